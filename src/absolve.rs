@@ -0,0 +1,396 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use colored::*;
+
+use crate::ark::Ark;
+use crate::forgiveness;
+use crate::lexer;
+use crate::{canon, sepulchre, walk};
+use crate::{DivinePLRuntime, Rule, SinSeverity};
+
+/// What blasphemous variables are renamed to.
+const ADVERSARY: &str = "adversary";
+
+/// The kinds of mechanical fix absolution makes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mend {
+    /// `var` becomes `let`.
+    Var,
+    /// `function name(...)` becomes `bless name(...)`.
+    Function,
+    /// A blasphemous variable becomes `adversary`, wherever it is used.
+    Rename,
+}
+
+impl Mend {
+    /// The fix for sins against `rule`, if they can be fixed mechanically.
+    fn for_rule(rule: &str) -> Option<Self> {
+        match rule {
+            "secular-var" => Some(Mend::Var),
+            "unblessed-function" => Some(Mend::Function),
+            "blasphemous-name" => Some(Mend::Rename),
+            _ => None,
+        }
+    }
+}
+
+/// One mechanical fix made to a line of a script.
+struct Absolution {
+    line: usize,
+    mend: Mend,
+    description: String,
+}
+
+/// Absolves every script at `path`, a script or a directory of them, of the
+/// venial sins that can be fixed mechanically, and renames blasphemous
+/// variables where that is safe. With `dry_run`, prints a unified diff of each
+/// fix instead of writing it. Returns how many sins remain.
+pub fn absolve(runtime: &DivinePLRuntime, path: &Path, dry_run: bool) -> Result<usize, String> {
+    if Ark::is_ark(path) {
+        return Err(format!("ArkError: {} is sealed; absolve the project it was built from", path.display()));
+    }
+    let scripts = if path.is_dir() {
+        let (found, skipped) = walk::canonical_scripts(path)?;
+        canon::report_skipped(skipped);
+        found
+    } else {
+        vec![PathBuf::from(path)]
+    };
+
+    let mut remaining = 0;
+    let mut absolved_files = 0;
+    for script in &scripts {
+        let content = fs::read_to_string(script)
+            .map_err(|e| format!("Failed to read {} for absolution: {}", script.display(), e))?;
+        let (absolved, absolutions) = absolve_source(&content, |_, _| true);
+        let (sins, _) = runtime.confess_source(&absolved, &runtime.standards_for(script)?)?;
+        remaining += sins.len();
+
+        if !absolutions.is_empty() {
+            absolved_files += 1;
+            if dry_run {
+                print!("{}", unified_diff(&script.display().to_string(), &content, &absolved));
+            } else {
+                sepulchre::entomb(script)?;
+                fs::write(script, &absolved).map_err(|e| format!("Failed to write {}: {}", script.display(), e))?;
+                sepulchre::seal(script)?;
+            }
+            let verb = if dry_run { "would be granted" } else { "granted" };
+            println!("{}", format!("🕊️ {}: {} absolution{} {}", script.display(), absolutions.len(),
+                if absolutions.len() == 1 { "" } else { "s" }, verb).green());
+            for absolution in &absolutions {
+                println!("  line {}: {}", absolution.line, absolution.description);
+            }
+        }
+
+        // Mortal sins are left for the sinner to repent of by hand
+        for sin in sins.iter().filter(|sin| sin.severity == SinSeverity::Mortal) {
+            println!("{}", format!("⚠️ {}:{}: {} cannot be absolved mechanically", script.display(), sin.line_num, sin.message).yellow());
+        }
+    }
+
+    if absolved_files == 0 {
+        println!("{}", "✝️ Nothing needed absolving.".green());
+    }
+    Ok(remaining)
+}
+
+/// What the sinner chose to do about one sin in an interactive confession.
+#[derive(Clone, Copy, PartialEq)]
+enum Choice {
+    Absolve,
+    Forgive,
+    Skip,
+}
+
+/// Walks through the sins of the script at `path` one by one, showing each in
+/// its context and asking whether to absolve it with the fix --absolve would
+/// make, forgive it with a `// forgiven:` comment, or skip it. The choices are
+/// written back together once every sin has been asked about, or the sinner
+/// quits. Returns how many sins remain.
+pub fn interactive(runtime: &DivinePLRuntime, path: &Path) -> Result<usize, String> {
+    if !io::stdin().is_terminal() {
+        return Err("--interactive asks about each sin at a terminal, but stdin is not one".to_string());
+    }
+    if path.is_dir() || Ark::is_ark(path) {
+        return Err(format!("--interactive confesses a single script, and {} is not one", path.display()));
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {} for confession: {}", path.display(), e))?;
+    let standards = runtime.standards_for(path)?;
+    let (sins, _) = runtime.confess_source(&content, &standards)?;
+    if sins.is_empty() {
+        println!("{}", "✝️ Your code is free from sin; there is nothing to confess.".green());
+        return Ok(0);
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let (_, possible) = absolve_source(&content, |_, _| true);
+    let mut choices = vec![Choice::Skip; sins.len()];
+    for (index, sin) in sins.iter().enumerate() {
+        let mend = Mend::for_rule(&sin.rule)
+            .filter(|mend| possible.iter().any(|absolution| absolution.mend == *mend && (absolution.line == sin.line_num || *mend == Mend::Rename)));
+        let forgivable = Rule::find(&sin.rule).is_some() && lines.get(sin.line_num - 1).and_then(|line| forgive_line(line, sin.rule_id())).is_some();
+
+        let label = match sin.severity {
+            SinSeverity::Venial => "Venial Sin".yellow(),
+            SinSeverity::Mortal => "Mortal Sin".bright_red(),
+        };
+        println!("\n{} {}[{}]: {} ({}:{})", format!("[{}/{}]", index + 1, sins.len()).bright_blue(), label, sin.rule_id(), sin.message,
+            path.display(), sin.line_num);
+        let first = sin.line_num.saturating_sub(3);
+        let last = (sin.line_num + 2).min(lines.len());
+        let width = last.to_string().len();
+        for number in first + 1..=last {
+            let marker = if number == sin.line_num { ">" } else { " " };
+            println!("{} {:>width$} {} {}", marker.bright_red(), number, "|".bright_blue(), lines[number - 1]);
+        }
+
+        let mut options = Vec::new();
+        if mend.is_some() {
+            options.push("[a]bsolve");
+        }
+        if forgivable {
+            options.push("[f]orgive");
+        }
+        options.extend(["[s]kip", "[q]uit"]);
+        let choice = loop {
+            print!("{} ", format!("{}?", options.join(", ")).bold());
+            io::stdout().flush().map_err(|e| format!("Failed to ask: {}", e))?;
+            let mut answer = String::new();
+            // The end of input is taken as quitting
+            if io::stdin().read_line(&mut answer).map_err(|e| format!("Failed to read the answer: {}", e))? == 0 {
+                break None;
+            }
+            match answer.trim().to_lowercase().as_str() {
+                "a" | "absolve" if mend.is_some() => break Some(Choice::Absolve),
+                "f" | "forgive" if forgivable => break Some(Choice::Forgive),
+                "s" | "skip" | "" => break Some(Choice::Skip),
+                "q" | "quit" => break None,
+                _ => println!("Answer with one of {}", options.join(", ")),
+            }
+        };
+        match choice {
+            Some(choice) => choices[index] = choice,
+            None => break,
+        }
+    }
+
+    // Absolutions first, as they never add or remove a line; forgiveness is then added to the lines absolved
+    let chosen = |line: usize, mend: Mend| sins.iter().zip(&choices).any(|(sin, choice)| {
+        *choice == Choice::Absolve && Mend::for_rule(&sin.rule) == Some(mend) && (sin.line_num == line || mend == Mend::Rename)
+    });
+    let (absolved, _) = absolve_source(&content, chosen);
+    let mut penitent = String::with_capacity(absolved.len());
+    for (index, raw) in absolved.split_inclusive('\n').enumerate() {
+        let ending_len = if raw.ends_with("\r\n") { 2 } else if raw.ends_with('\n') { 1 } else { 0 };
+        let (mut line, ending) = (raw[..raw.len() - ending_len].to_string(), &raw[raw.len() - ending_len..]);
+        for (sin, _) in sins.iter().zip(&choices).filter(|(sin, choice)| sin.line_num == index + 1 && **choice == Choice::Forgive) {
+            line = forgive_line(&line, sin.rule_id()).unwrap_or(line);
+        }
+        penitent.push_str(&line);
+        penitent.push_str(ending);
+    }
+
+    if penitent != content {
+        sepulchre::entomb(path)?;
+        fs::write(path, &penitent).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        sepulchre::seal(path)?;
+    }
+    let count = |wanted| choices.iter().filter(|choice| **choice == wanted).count();
+    println!("\n{}", format!("🕊️ {} absolved, {} forgiven, {} skipped", count(Choice::Absolve), count(Choice::Forgive), count(Choice::Skip)).green());
+    let (remaining, _) = runtime.confess_source(&penitent, &standards)?;
+    Ok(remaining.len())
+}
+
+/// `line` with sins against the rule `id` forgiven by its comment: a
+/// `// forgiven:` comment it already has names the rule too, and any other
+/// comment becomes the reason for forgiveness. `None` for a line that can't
+/// carry forgiveness, being a comment or prayer itself.
+fn forgive_line(line: &str, id: &str) -> Option<String> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with("//") || trimmed.starts_with("/*") || lexer::is_prayer(trimmed) {
+        return None;
+    }
+    let Some(comment) = forgiveness::line_comment(line) else {
+        return Some(format!("{} // forgiven: {}", line.trim_end(), id));
+    };
+    let code = &line[..line.len() - comment.len() - 2];
+    let comment = comment.trim();
+    Some(match comment.strip_prefix("forgiven:") {
+        Some(rules) => format!("{}// forgiven: {}, {}", code, id, rules.trim_start()),
+        None if comment.is_empty() => format!("{}// forgiven: {}", code, id),
+        None => format!("{}// forgiven: {} {}", code, id, comment),
+    })
+}
+
+/// The script with its fixable sins fixed, and the fixes made. Only the fixes
+/// `chosen` accepts, by line and kind, are made.
+fn absolve_source(content: &str, chosen: impl Fn(usize, Mend) -> bool) -> (String, Vec<Absolution>) {
+    let renames = blasphemous_renames(content);
+    let mut absolutions = Vec::new();
+    let mut absolved = String::with_capacity(content.len());
+    let mut in_block_comment = false;
+    let mut in_prayer = false;
+
+    for (index, raw) in content.split_inclusive('\n').enumerate() {
+        let ending_len = if raw.ends_with("\r\n") { 2 } else if raw.ends_with('\n') { 1 } else { 0 };
+        let (line, ending) = raw.split_at(raw.len() - ending_len);
+
+        // Prayers are the faithful's own words and are never rewritten
+        let trimmed = line.trim();
+        if in_prayer || lexer::is_prayer(trimmed) {
+            in_prayer = (in_prayer || lexer::is_begin_prayer(trimmed)) && !lexer::is_end_prayer(trimmed);
+            absolved.push_str(raw);
+            continue;
+        }
+
+        let mut fixes = BTreeMap::new();
+        let mut first_word = true;
+        let chosen = |mend| chosen(index + 1, mend);
+        let rewritten = rewrite_words(line, &mut in_block_comment, |word| {
+            let at_start = std::mem::replace(&mut first_word, false);
+            if word == "var" && chosen(Mend::Var) {
+                fixes.insert("var → let".to_string(), Mend::Var);
+                return Some("let".to_string());
+            }
+            if at_start && word == "function" && declares_function(trimmed) && chosen(Mend::Function) {
+                fixes.insert(format!("blessed {}", trimmed.trim_start_matches("function").trim_start().split('(').next().unwrap_or("").trim()), Mend::Function);
+                return Some("bless".to_string());
+            }
+            renames.iter().find(|name| **name == word).filter(|_| chosen(Mend::Rename)).map(|name| {
+                fixes.insert(format!("{} → {}", name, ADVERSARY), Mend::Rename);
+                ADVERSARY.to_string()
+            })
+        });
+
+        absolved.push_str(&rewritten);
+        absolved.push_str(ending);
+        absolutions.extend(fixes.into_iter().map(|(description, mend)| Absolution { line: index + 1, mend, description }));
+    }
+
+    (absolved, absolutions)
+}
+
+/// Whether a line declares a named function, as in `function name(...)`.
+fn declares_function(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix("function ") else { return false };
+    let name = rest.trim_start().split('(').next().unwrap_or("").trim();
+    rest.contains('(') && !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// The blasphemous variables that can safely become `adversary`: at most one of
+/// them may be declared, and only when `adversary` is not already in use.
+fn blasphemous_renames(content: &str) -> Vec<&'static str> {
+    let mut words = BTreeSet::new();
+    let mut declared = Vec::new();
+    let mut in_block_comment = false;
+    for line in content.lines() {
+        let mut previous = String::new();
+        rewrite_words(line, &mut in_block_comment, |word| {
+            for name in ["devil", "satan"] {
+                if word == name && matches!(previous.as_str(), "let" | "var" | "const") && !declared.contains(&name) {
+                    declared.push(name);
+                }
+            }
+            words.insert(word.to_string());
+            previous = word.to_string();
+            None
+        });
+    }
+    if declared.len() == 1 && !words.contains(ADVERSARY) { declared } else { Vec::new() }
+}
+
+/// Rewrites each word in the code of a line through `rewrite`, leaving string
+/// literals and comments untouched. `in_block_comment` carries an unterminated
+/// `/* */` comment over to the next line.
+fn rewrite_words(line: &str, in_block_comment: &mut bool, mut rewrite: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut word = String::new();
+    let mut quote = None;
+    let mut chars = line.chars().peekable();
+    let mut flush = |word: &mut String, out: &mut String| {
+        if !word.is_empty() {
+            out.push_str(&rewrite(word).unwrap_or_else(|| word.clone()));
+            word.clear();
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        if *in_block_comment {
+            out.push(c);
+            if c == '*' && chars.peek() == Some(&'/') {
+                out.extend(chars.next());
+                *in_block_comment = false;
+            }
+            continue;
+        }
+        match quote {
+            Some(open) => {
+                out.push(c);
+                if c == '\\' {
+                    out.extend(chars.next());
+                } else if c == open {
+                    quote = None;
+                }
+            }
+            None if c.is_alphanumeric() || c == '_' || c == '$' => word.push(c),
+            None => {
+                flush(&mut word, &mut out);
+                if c == '/' && chars.peek() == Some(&'/') {
+                    out.push(c);
+                    out.extend(chars.by_ref());
+                    break;
+                }
+                if c == '/' && chars.peek() == Some(&'*') {
+                    out.push(c);
+                    out.extend(chars.next());
+                    *in_block_comment = true;
+                    continue;
+                }
+                if matches!(c, '"' | '\'' | '`') {
+                    quote = Some(c);
+                }
+                out.push(c);
+            }
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+/// A unified diff between two versions of a script with the same lines, as
+/// absolution never adds or removes a line.
+fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let changed: Vec<usize> = (0..old.len()).filter(|&i| old[i] != new[i]).collect();
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut index = 0;
+    while index < changed.len() {
+        // A hunk takes in every change whose context touches the one before it
+        let start = changed[index].saturating_sub(CONTEXT);
+        let mut last = changed[index];
+        while index + 1 < changed.len() && changed[index + 1] <= last + 2 * CONTEXT + 1 {
+            index += 1;
+            last = changed[index];
+        }
+        let end = (last + CONTEXT + 1).min(old.len());
+
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", start + 1, end - start, start + 1, end - start));
+        for line in start..end {
+            if old[line] == new[line] {
+                diff.push_str(&format!(" {}\n", old[line]));
+            } else {
+                diff.push_str(&format!("-{}\n+{}\n", old[line], new[line]));
+            }
+        }
+        index += 1;
+    }
+    diff
+}
@@ -0,0 +1,169 @@
+use std::path::Path;
+
+use colored::*;
+
+use crate::calendar::Season;
+use crate::config::Commandments;
+use crate::environment::Environment;
+use crate::error::DivineError;
+use crate::glyphs;
+use serde::Serialize;
+use serde_json::json;
+use crate::sabbath;
+use crate::{DivinePLRuntime, OutputFormat};
+
+/// What one check of the environment found.
+#[derive(Serialize)]
+struct Anointing {
+    name: &'static str,
+    passed: bool,
+    /// Whether a failure keeps DivinePL from working at all, rather than
+    /// merely making it less pleasant.
+    blocking: bool,
+    detail: String,
+    /// What to do about a failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<&'static str>,
+}
+
+/// Checks the environment DivinePL runs in and reports what was found, along
+/// with the settings in effect and where each came from. Fails if anything
+/// found would stop DivinePL from working.
+pub fn anoint(runtime: &DivinePLRuntime, environment: &Environment, format: OutputFormat) -> Result<(), DivineError> {
+    let anointings = [
+        sabbath(runtime, environment.override_sabbath.value),
+        season(runtime),
+        color(),
+        emoji(),
+        commandments(),
+        Anointing {
+            name: "version",
+            passed: true,
+            blocking: false,
+            detail: format!("divine-pl {}", env!("CARGO_PKG_VERSION")),
+            hint: None,
+        },
+    ];
+
+    match format {
+        OutputFormat::Json => {
+            let blessed = anointings.iter().all(|anointing| anointing.passed || !anointing.blocking);
+            // Hints are only given for what failed
+            let checks = anointings.iter()
+                .map(|anointing| Anointing { hint: anointing.hint.filter(|_| !anointing.passed), detail: anointing.detail.clone(), ..*anointing })
+                .collect::<Vec<_>>();
+            println!("{}", json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "blessed": blessed,
+                "checks": checks,
+                "settings": environment.to_json(),
+            }));
+        }
+        OutputFormat::Text => {
+            println!("{}", "🫒 Anointing the environment... 🫒".bright_blue());
+            for anointing in &anointings {
+                let mark = if anointing.passed { "✓".green() } else if anointing.blocking { "✗".bright_red() } else { "✗".yellow() };
+                println!("  {} {:<12} {}", mark, anointing.name, anointing.detail);
+                if let Some(hint) = anointing.hint.filter(|_| !anointing.passed) {
+                    println!("{}", format!("      {}", hint).italic());
+                }
+            }
+            println!();
+            environment.report();
+        }
+    }
+
+    let blocking = anointings.iter().filter(|anointing| !anointing.passed && anointing.blocking).count();
+    if blocking > 0 {
+        return Err(DivineError::Other(format!(
+            "AnointError: {} blocking condition{} found", blocking, if blocking == 1 { " was" } else { "s were" }
+        )));
+    }
+    if format == OutputFormat::Text {
+        println!("{}", "✝️ This environment is anointed and ready for divine execution.".green());
+    }
+    Ok(())
+}
+
+fn sabbath(runtime: &DivinePLRuntime, override_sabbath: bool) -> Anointing {
+    let sabbath = &runtime.sabbath;
+    let now = runtime.clock.now();
+    let weekday = sabbath::day_name(sabbath.timezone.weekday_at(now));
+    let passed = !runtime.is_sabbath(override_sabbath);
+    let detail = match (sabbath.is_on(now) && runtime.sabbath_kept, passed) {
+        (false, _) => format!("Today is {} in {}; the Sabbath, {}, is not active", weekday, sabbath.timezone, sabbath),
+        (true, true) => format!("Today is the Sabbath, {}, but rest has been overridden in dev mode", sabbath),
+        (true, false) => format!("Today is the Sabbath, {}; commands that run or transform code must rest", sabbath),
+    };
+    Anointing {
+        name: "sabbath",
+        passed,
+        blocking: true,
+        detail,
+        hint: Some("Rest until tomorrow, or pass --dev --override-sabbath (or set DIVINEPL_DEV=1 and DIVINEPL_OVERRIDE_SABBATH=1)"),
+    }
+}
+
+fn season(runtime: &DivinePLRuntime) -> Anointing {
+    let detail = match &runtime.season {
+        Season::Feast(_) => format!("Today is {}; venial sins weigh nothing at judgment", runtime.season),
+        Season::Lent { .. } => format!("It is {}; judgment is sterner and long functions are a sin", runtime.season),
+        Season::Ordinary => "It is ordinary time".to_string(),
+    };
+    Anointing { name: "season", passed: true, blocking: false, detail, hint: None }
+}
+
+fn color() -> Anointing {
+    let passed = colored::control::SHOULD_COLORIZE.should_colorize();
+    Anointing {
+        name: "color",
+        passed,
+        blocking: false,
+        detail: if passed { "Output is colored".to_string() } else { "Output will be plain".to_string() },
+        hint: Some("Run in a terminal with NO_COLOR and DIVINEPL_NO_COLOR unset, or pass --color always"),
+    }
+}
+
+/// Whether the locale can show the 🙏 that open and close prayers.
+fn emoji() -> Anointing {
+    let locale = glyphs::locale().map(|(_, locale)| locale);
+    let passed = cfg!(windows) || locale.as_deref().is_some_and(glyphs::is_utf8);
+    Anointing {
+        name: "emoji",
+        passed,
+        blocking: false,
+        detail: match &locale {
+            Some(locale) => format!("The locale is {}", locale),
+            None => "No locale is set".to_string(),
+        },
+        hint: Some("Set LANG to a UTF-8 locale, e.g. en_US.UTF-8, so the 🙏 of prayers are shown and typed intact, or pass --plain-glyphs"),
+    }
+}
+
+fn commandments() -> Anointing {
+    let Some(path) = Commandments::discover(Path::new(".")) else {
+        return Anointing {
+            name: "commandments",
+            passed: false,
+            blocking: false,
+            detail: "No commandments.config was found here or in any parent directory".to_string(),
+            hint: Some("Run divine new to create a project, or work from inside one"),
+        };
+    };
+    match Commandments::load(&path) {
+        Ok(_) => Anointing {
+            name: "commandments",
+            passed: true,
+            blocking: true,
+            detail: format!("{} was found and parses", path.display()),
+            hint: None,
+        },
+        Err(e) => Anointing {
+            name: "commandments",
+            passed: false,
+            blocking: true,
+            detail: e,
+            hint: Some("Fix the JSON in commandments.config, or remove it"),
+        },
+    }
+}
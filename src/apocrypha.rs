@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Written in a template's file names and contents, and replaced with the name
+/// of the project created from it.
+const PROJECT_NAME: &str = "{{project_name}}";
+
+/// A project template kept outside the canon: a directory whose contents are
+/// copied into each project created from it.
+pub struct Apocryphon {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl Apocryphon {
+    /// The files the template holds, relative to it, in order.
+    pub fn files(&self) -> Result<Vec<PathBuf>, String> {
+        let mut files = Vec::new();
+        walk(&self.dir, Path::new(""), &mut files)?;
+        files.sort();
+        Ok(files)
+    }
+
+    /// Copies the template into `project_dir`, which must already exist, with
+    /// every `{{project_name}}` in file names and text replaced by `name`.
+    /// Returns the files written, relative to the project.
+    pub fn copy_into(&self, project_dir: &Path, name: &str) -> Result<Vec<PathBuf>, String> {
+        let mut written = Vec::new();
+        for file in self.files()? {
+            let source = self.dir.join(&file);
+            let relative = PathBuf::from(file.to_string_lossy().replace(PROJECT_NAME, name));
+            let target = project_dir.join(&relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            let bytes = fs::read(&source).map_err(|e| format!("Failed to read template file {}: {}", source.display(), e))?;
+            // Text has its placeholders filled; anything else is copied as it is
+            let bytes = match String::from_utf8(bytes) {
+                Ok(text) => text.replace(PROJECT_NAME, name).into_bytes(),
+                Err(e) => e.into_bytes(),
+            };
+            fs::write(&target, bytes).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+            written.push(relative);
+        }
+        Ok(written)
+    }
+}
+
+/// Where apocryphal templates are kept unless another directory is given:
+/// `~/.divinepl/templates`.
+pub fn templates_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".divinepl").join("templates"))
+}
+
+/// Every template in `dir`, one per subdirectory, by name. A directory that
+/// does not exist holds none.
+pub fn discover(dir: &Path) -> Vec<Apocryphon> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut found: Vec<Apocryphon> = entries.flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| Apocryphon { name: entry.file_name().to_string_lossy().into_owned(), dir: entry.path() })
+        .filter(|template| !template.name.starts_with('.'))
+        .collect();
+    found.sort_by(|a, b| a.name.cmp(&b.name));
+    found
+}
+
+/// Collects the files under `dir`, following symbolic links, as paths
+/// relative to the template.
+fn walk(dir: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read template directory {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read template directory {}: {}", dir.display(), e))?;
+        let path = entry.path();
+        let metadata = fs::metadata(&path).map_err(|e| match fs::symlink_metadata(&path) {
+            Ok(link) if link.file_type().is_symlink() => format!("Template file {} is a broken link", path.display()),
+            _ => format!("Failed to read template file {}: {}", path.display(), e),
+        })?;
+        if metadata.is_dir() {
+            walk(&path, &relative.join(entry.file_name()), files)?;
+        } else {
+            files.push(relative.join(entry.file_name()));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use colored::*;
+
+use crate::config::{Commandments, CONFIG_FILE_NAME};
+use crate::error::DivineError;
+use crate::modules::{self, Scriptorium};
+use crate::{parser, walk, DivinePLRuntime};
+
+/// The first word of every ark.
+const MAGIC: &str = "DIVINEPL-ARK";
+
+/// The newest ark format this build reads and the one it writes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A whole project sealed into one file, runnable without unpacking it.
+///
+/// The format is plain framed text:
+///
+/// ```text
+/// DIVINEPL-ARK 1
+/// created-by 0.1.0
+/// entry genesis.divine
+/// file genesis.divine 1234
+/// <exactly 1234 bytes>
+/// file commandments.config 210
+/// <exactly 210 bytes>
+/// end
+/// ```
+///
+/// The header names the format version, the version of the CLI that built the
+/// ark, and the entry script. Each `file` line gives a path relative to the
+/// project root and the byte length of the contents that follow it, which are
+/// themselves followed by a newline. Paths always use `/`.
+pub struct Ark {
+    pub format: u32,
+    pub created_by: String,
+    /// The path of the script `run` starts from.
+    pub entry: String,
+    /// Every file carried, as (path, contents), sorted by path.
+    pub files: Vec<(String, String)>,
+}
+
+impl Ark {
+    /// Whether `path` names an ark rather than a script.
+    pub fn is_ark(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "ark")
+    }
+
+    /// Gathers the project around `anchor`: every script in it and its
+    /// commandments. `entry` is relative to the project root.
+    pub fn gather(anchor: &Path, entry: &str) -> Result<Self, String> {
+        let root = match Commandments::discover(anchor) {
+            Some(config) => config.parent().map(Path::to_path_buf).unwrap_or_default(),
+            None => anchor.to_path_buf(),
+        };
+
+        let mut found = Vec::new();
+        walk::scripts(&root, &mut found);
+        let config = root.join(CONFIG_FILE_NAME);
+        if config.is_file() {
+            found.push(config);
+        }
+
+        let mut files = Vec::new();
+        for path in found {
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| format!("ArkError: cannot read {}: {}", path.display(), e))?;
+            let relative = path.strip_prefix(&root).unwrap_or(&path)
+                .components()
+                .filter(|part| !matches!(part, Component::CurDir))
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative, contents));
+        }
+        files.sort();
+
+        let entry = entry.trim_start_matches("./").replace('\\', "/");
+        if !files.iter().any(|(path, _)| *path == entry) {
+            return Err(format!("ArkError: {} has no entry script {}", root.display(), entry));
+        }
+
+        Ok(Self { format: FORMAT_VERSION, created_by: env!("CARGO_PKG_VERSION").to_string(), entry, files })
+    }
+
+    pub fn encode(&self) -> String {
+        let mut out = format!("{} {}\ncreated-by {}\nentry {}\n", MAGIC, self.format, self.created_by, self.entry);
+        for (path, contents) in &self.files {
+            out.push_str(&format!("file {} {}\n{}\n", path, contents.len(), contents));
+        }
+        out.push_str("end\n");
+        out
+    }
+
+    pub fn decode(text: &str) -> Result<Self, String> {
+        let corrupt = |what: &str| format!("ArkError: the ark is corrupted ({})", what);
+        let mut rest = text;
+        let header = next_line(&mut rest).ok_or_else(|| corrupt("no header"))?;
+        let format = header.strip_prefix(MAGIC)
+            .and_then(|version| version.trim().parse::<u32>().ok())
+            .ok_or_else(|| "ArkError: this is not an ark".to_string())?;
+        if format > FORMAT_VERSION {
+            return Err(format!(
+                "ArkError: the ark was built in format {}, but this divine-pl reads only format {} and older",
+                format, FORMAT_VERSION
+            ));
+        }
+
+        let created_by = next_line(&mut rest).as_deref().and_then(|line| line.strip_prefix("created-by "))
+            .map(str::to_string)
+            .ok_or_else(|| corrupt("no creator"))?;
+        let entry = next_line(&mut rest).as_deref().and_then(|line| line.strip_prefix("entry "))
+            .map(str::to_string)
+            .ok_or_else(|| corrupt("no entry"))?;
+
+        let mut files = Vec::new();
+        loop {
+            let line = next_line(&mut rest).ok_or_else(|| corrupt("no end"))?;
+            if line == "end" {
+                break;
+            }
+            let (path, length) = line.strip_prefix("file ")
+                .and_then(|frame| frame.rsplit_once(' '))
+                .and_then(|(path, length)| Some((path.to_string(), length.parse::<usize>().ok()?)))
+                .ok_or_else(|| corrupt(&format!("bad frame '{}'", line)))?;
+            let contents = rest.get(..length).ok_or_else(|| corrupt(&format!("{} is cut short", path)))?;
+            files.push((path, contents.to_string()));
+            rest = rest[length..].strip_prefix('\n').ok_or_else(|| corrupt("unterminated file"))?;
+        }
+
+        if !files.iter().any(|(path, _)| *path == entry) {
+            return Err(corrupt(&format!("entry {} is missing", entry)));
+        }
+        Ok(Self { format, created_by, entry, files })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to open the ark {}: {}", path.display(), e))?;
+        Self::decode(&text).map_err(|e| format!("{} ({})", e, path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.encode()).map_err(|e| format!("Failed to seal the ark {}: {}", path.display(), e))
+    }
+
+    /// The scripts carried, without the commandments.
+    pub fn scripts(&self) -> impl Iterator<Item = &(String, String)> {
+        self.files.iter().filter(|(path, _)| is_script(Path::new(path)))
+    }
+
+    pub fn file(&self, path: &str) -> Option<&str> {
+        self.files.iter().find(|(p, _)| p == path).map(|(_, contents)| contents.as_str())
+    }
+
+    pub fn entry_source(&self) -> &str {
+        self.file(&self.entry).unwrap_or_default()
+    }
+}
+
+/// Seals a project into an ark. Every verse and module a script imports must
+/// be known, since an ark carries no way to resolve them later.
+pub fn build(runtime: &DivinePLRuntime, project: &Path, output: Option<&Path>, entry_script: &str) -> Result<(), DivineError> {
+    let ark = Ark::gather(project, entry_script)?;
+    
+    for (path, source) in ark.scripts() {
+        for stmt in runtime.parse_script(source)? {
+            match &stmt.imported_verse {
+                Some(name) if !runtime.bible_verses.contains_key(name.as_str()) => {
+                    return Err(format!(
+                        "ArkError: {} imports the unknown verse \"{}\" (line {}); the ark cannot be sealed",
+                        path, name, stmt.line_num
+                    ).into());
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    // Modules resolve from the entry script, through whatever it imports
+    let mut program = parser::parse_program(ark.entry_source()).map_err(|e| format!("{} (in {})", e, ark.entry))?;
+    modules::load(&mut program, Path::new(&ark.entry), &Scriptorium::Ark(&ark), false)
+        .map_err(|e| format!("{}; the ark cannot be sealed", e))?;
+    
+    let output = match output {
+        Some(output) => output.to_path_buf(),
+        None => {
+            let root = fs::canonicalize(project).unwrap_or_else(|_| project.to_path_buf());
+            let name = root.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| "project".to_string());
+            PathBuf::from(format!("{}.ark", name))
+        }
+    };
+    ark.write(&output).map_err(DivineError::Io)?;
+    
+    println!("{}", format!("🚢 Sealed {} files into {} (entry: {})", ark.files.len(), output.display(), ark.entry).green());
+    Ok(())
+}
+
+/// Takes the next line off the front of `rest`.
+fn next_line(rest: &mut &str) -> Option<String> {
+    let (line, after) = rest.split_once('\n')?;
+    *rest = after;
+    Some(line.trim_end_matches('\r').to_string())
+}
+
+fn is_script(path: &Path) -> bool {
+    matches!(path.extension().and_then(|ext| ext.to_str()), Some("divine" | "dpl"))
+}
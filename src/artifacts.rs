@@ -0,0 +1,279 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use colored::*;
+
+use crate::config::Commandments;
+use crate::environment;
+use serde_json::Value;
+
+/// Where a project keeps run artifacts, relative to its root.
+const ARTIFACTS_DIR: &str = ".divinepl";
+const HEAVEN: &str = "heaven";
+const PURGATORY: &str = "purgatory";
+
+/// How many artifacts of each kind are kept per script unless the config says otherwise.
+pub const DEFAULT_KEEP: usize = 10;
+
+/// The root of the project `anchor` belongs to: the directory of the nearest
+/// commandments.config, or the one beside the script when it belongs to no project.
+pub fn project_root(anchor: &Path) -> PathBuf {
+    match Commandments::discover(anchor) {
+        Some(config) => config.parent().map(Path::to_path_buf).unwrap_or_default(),
+        None if anchor.is_dir() => anchor.to_path_buf(),
+        None => anchor.parent().map(Path::to_path_buf).unwrap_or_default(),
+    }
+}
+
+/// The directory a project's artifacts live under.
+pub fn artifacts_root(anchor: &Path) -> PathBuf {
+    project_root(anchor).join(ARTIFACTS_DIR)
+}
+
+/// Used in place of the project's directory when it cannot be written to.
+fn fallback_root(root: &Path) -> PathBuf {
+    let project = root.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let root = fs::canonicalize(project).unwrap_or_else(|_| root.to_path_buf());
+    std::env::temp_dir().join(format!("divinepl-artifacts-{:08x}", crate::fnv1a(root.to_string_lossy().as_bytes()) as u32))
+}
+
+/// Identifies which script an artifact belongs to, independent of when it was made.
+pub fn fingerprint(script: &Path) -> String {
+    let script = fs::canonicalize(script).unwrap_or_else(|_| script.to_path_buf());
+    format!("{:08x}", crate::fnv1a(script.to_string_lossy().as_bytes()) as u32)
+}
+
+/// `<timestamp>-<script>-<fingerprint>`, the part of an artifact's name before its kind.
+/// Timestamps come first so that names sort oldest first.
+pub fn stem(script: &Path) -> String {
+    let name = script.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_else(|| "script".to_string());
+    format!("{}-{}-{}", Local::now().format("%Y%m%dT%H%M%S"), name, fingerprint(script))
+}
+
+/// Saves an artifact produced by running `script`. Blessed runs go to heaven,
+/// fallen ones to purgatory. `kind` is the artifact's file extension, such as
+/// `report.json`. Older artifacts of the same kind for the same script are pruned
+/// beyond the project's `artifacts_keep` setting. Returns where the artifact was written.
+pub fn store(script: &Path, blessed: bool, kind: &str, contents: &str) -> Result<PathBuf, String> {
+    let root = artifacts_root(script);
+    let verdict = if blessed { HEAVEN } else { PURGATORY };
+
+    let written = match write_new(&root.join(verdict), &stem(script), kind, contents) {
+        Ok(path) => path,
+        Err(e) => {
+            let fallback = fallback_root(&root);
+            eprintln!("{}", environment::for_stderr(format!(
+                "⚠️ Cannot keep artifacts in {} ({}); using {} instead", root.display(), e, fallback.display()
+            ).yellow()));
+            write_new(&fallback.join(verdict), &stem(script), kind, contents)?
+        }
+    };
+
+    let keep = Commandments::for_script(script).ok().flatten()
+        .and_then(|commandments| commandments.settings.get("artifacts_keep").and_then(Value::as_f64))
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_KEEP);
+    if let Some(dir) = written.parent() {
+        prune(dir, &fingerprint(script), kind, keep)?;
+    }
+
+    Ok(written)
+}
+
+/// Writes a file that did not exist before, adding a counter to the name when
+/// another artifact was made in the same second. The counter goes past the
+/// highest already used, so a name freed by pruning is never reused for a newer
+/// artifact that would then sort before its elders.
+pub fn write_new(dir: &Path, stem: &str, kind: &str, contents: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+
+    let suffix = format!(".{}", kind);
+    let first = fs::read_dir(dir).map_err(|e| format!("cannot read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let counter = name.strip_prefix(stem)?.strip_suffix(&suffix)?;
+            match counter {
+                "" => Some(1),
+                _ => counter.strip_prefix('~')?.parse::<u32>().ok().map(|n| n + 1),
+            }
+        })
+        .max()
+        .unwrap_or(0);
+
+    for attempt in first.. {
+        let name = match attempt {
+            0 => format!("{}.{}", stem, kind),
+            // `~` sorts after `.`, keeping same-second artifacts in the order they were made
+            n => format!("{}~{:02}.{}", stem, n, kind),
+        };
+        let path = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(contents.as_bytes()).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("cannot create {}: {}", path.display(), e)),
+        }
+    }
+    unreachable!("artifact names are never exhausted")
+}
+
+/// Removes the oldest artifacts of `kind` for the script with `fingerprint` until `keep` remain.
+pub fn prune(dir: &Path, fingerprint: &str, kind: &str, keep: usize) -> Result<(), String> {
+    let marker = format!("-{}", fingerprint);
+    let suffix = format!(".{}", kind);
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| format!("cannot read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.contains(&marker) && name.ends_with(&suffix))
+        .collect();
+    names.sort();
+
+    let excess = names.len().saturating_sub(keep);
+    for name in &names[..excess] {
+        let path = dir.join(name);
+        fs::remove_file(&path).map_err(|e| format!("cannot prune {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// The artifacts kept for the project whose artifacts live under `root`, as
+/// (verdict, path) pairs, oldest first: those under `root`, then those `store`
+/// fell back to keeping in the temp dir.
+fn kept(root: &Path) -> Vec<(&'static str, PathBuf)> {
+    let mut artifacts = Vec::new();
+    for dir in [root.to_path_buf(), fallback_root(root)] {
+        for verdict in [HEAVEN, PURGATORY] {
+            let Ok(entries) = fs::read_dir(dir.join(verdict)) else { continue };
+            let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+            paths.sort();
+            artifacts.extend(paths.into_iter().map(|path| (verdict, path)));
+        }
+    }
+    artifacts
+}
+
+pub fn list(anchor: &Path) -> Result<(), String> {
+    let root = artifacts_root(anchor);
+    println!("{}", "🗄️ ARTIFACTS OF JUDGMENT 🗄️".underline().bright_blue());
+
+    let artifacts = kept(&root);
+    if artifacts.is_empty() {
+        println!("{}", format!("No artifacts are kept in {}, nor in {}.", root.display(), fallback_root(&root).display()).yellow());
+        return Ok(());
+    }
+
+    for (verdict, path) in &artifacts {
+        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let verdict = match *verdict {
+            HEAVEN => format!("{:<10}", verdict).green(),
+            _ => format!("{:<10}", verdict).red(),
+        };
+        println!("  {} {:>8} B  {}", verdict, size, path.display());
+    }
+    Ok(())
+}
+
+pub fn clean(anchor: &Path) -> Result<(), String> {
+    let root = artifacts_root(anchor);
+    let artifacts = kept(&root);
+    for (_, path) in &artifacts {
+        fs::remove_file(path).map_err(|e| format!("Failed to cleanse {}: {}", path.display(), e))?;
+    }
+    println!("{}", format!("🧹 Cleansed {} artifacts kept for {}", artifacts.len(), root.display()).green());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A directory of its own for one test, removed when it is done.
+    struct Dir(PathBuf);
+
+    impl Dir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("divinepl-artifacts-test-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn names(&self) -> Vec<String> {
+            let mut names: Vec<String> = fs::read_dir(&self.0).unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for Dir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn an_artifact_is_named_for_its_time_script_and_fingerprint() {
+        let dir = Dir::new("stem");
+        let script = dir.0.join("genesis.divine");
+        let stem = stem(&script);
+        let (time, rest) = stem.split_once('-').unwrap();
+        assert!(time.len() == 15 && time.as_bytes()[8] == b'T', "{}", stem);
+        assert_eq!(rest, format!("genesis-{}", fingerprint(&script)));
+        assert_eq!(fingerprint(&script).len(), 8);
+        assert_ne!(fingerprint(&script), fingerprint(&dir.0.join("exodus.divine")));
+    }
+
+    #[test]
+    fn artifacts_made_in_the_same_second_are_numbered_in_order() {
+        let dir = Dir::new("collisions");
+        let written: Vec<PathBuf> = (0..3)
+            .map(|n| write_new(&dir.0, "20260301T090000-s-0badcafe", "report.json", &n.to_string()).unwrap())
+            .collect();
+        assert_eq!(dir.names(), [
+            "20260301T090000-s-0badcafe.report.json",
+            "20260301T090000-s-0badcafe~01.report.json",
+            "20260301T090000-s-0badcafe~02.report.json",
+        ]);
+        assert_eq!(fs::read_to_string(&written[2]).unwrap(), "2");
+
+        // Once the first is pruned, its name is not handed to a newer artifact
+        fs::remove_file(&written[0]).unwrap();
+        let newest = write_new(&dir.0, "20260301T090000-s-0badcafe", "report.json", "3").unwrap();
+        assert_eq!(newest.file_name().unwrap(), "20260301T090000-s-0badcafe~03.report.json");
+    }
+
+    #[test]
+    fn pruning_keeps_the_newest_of_a_kind_for_a_script() {
+        let dir = Dir::new("prune");
+        for second in 0..4 {
+            write_new(&dir.0, &format!("20260301T09000{}-s-0badcafe", second), "report.json", "").unwrap();
+        }
+        write_new(&dir.0, "20260301T090000-s-0badcafe", "trace.txt", "").unwrap();
+        write_new(&dir.0, "20260301T090000-t-feedface", "report.json", "").unwrap();
+
+        prune(&dir.0, "0badcafe", "report.json", 2).unwrap();
+        assert_eq!(dir.names(), [
+            "20260301T090000-s-0badcafe.trace.txt",
+            "20260301T090000-t-feedface.report.json",
+            "20260301T090002-s-0badcafe.report.json",
+            "20260301T090003-s-0badcafe.report.json",
+        ]);
+    }
+
+    #[test]
+    fn the_fallback_is_the_same_for_a_project_however_it_is_named() {
+        let dir = Dir::new("fallback");
+        let absolute = dir.0.join(ARTIFACTS_DIR);
+        let relative = dir.0.join(".").join(ARTIFACTS_DIR);
+        assert_eq!(fallback_root(&absolute), fallback_root(&relative));
+        assert!(fallback_root(&absolute).starts_with(std::env::temp_dir()));
+    }
+}
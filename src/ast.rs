@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A parsed DivinePL script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    pub statements: Vec<Stmt>,
+    /// Every blessed function in the script by name, wherever it was declared.
+    pub functions: HashMap<String, Arc<Function>>,
+}
+
+/// A function declared with `bless name(params) { ... }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Vec<Stmt>,
+    pub line: usize,
+    /// The foretellings of `@prophesy("...")` annotations written just above the blessing.
+    pub prophecies: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stmt {
+    pub line: usize,
+    pub kind: StmtKind,
+    /// Comments, prayers and blank lines preceding the statement.
+    pub leading: Vec<Trivia>,
+    /// A `//` comment sharing the statement's last line.
+    pub trailing: Option<String>,
+}
+
+/// Source with no effect on execution that must still survive reformatting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trivia {
+    /// A `//` comment, slashes included.
+    Comment(String),
+    /// A single-line prayer, 🙏 markers included.
+    Prayer(String),
+    /// The lines of a `BEGIN PRAYER` ... `END PRAYER` block, markers included,
+    /// exactly as written.
+    PrayerBlock(Vec<String>),
+    /// One or more empty lines, collapsed to one.
+    BlankLine,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StmtKind {
+    /// `let name = value`
+    Let { name: String, value: Expr },
+    /// `covenant name = value`: a binding that can never be reassigned.
+    Covenant { name: String, value: Expr },
+    /// `name = value`
+    Assign { name: String, value: Expr },
+    /// `object.field = value`
+    AssignField { object: Expr, field: String, value: Expr },
+    Expr(Expr),
+    /// `judge (condition) { ... } else { ... }`
+    Judge { condition: Expr, then_branch: Vec<Stmt>, else_branch: Option<Vec<Stmt>> },
+    /// `preach (variable in iterable) { ... }`
+    Preach { variable: String, iterable: Expr, body: Vec<Stmt> },
+    /// `while (condition) { ... }`
+    While { condition: Expr, body: Vec<Stmt> },
+    /// `bless name(params) { ... }`; declares the function without running it.
+    Bless(Arc<Function>),
+    /// `ascend with value`, or a bare `ascend`: returns from the enclosing function.
+    Ascend(Option<Expr>),
+    /// `absolution { ... }`: every scroll opened inside is sealed when the block exits.
+    Absolution { body: Vec<Stmt> },
+    /// `confess { ... } forgive (sin) { ... }`: a sin raised in the body is bound to
+    /// `sin` in the forgive block instead of ending the run.
+    Confess { body: Vec<Stmt>, sin: String, forgive: Vec<Stmt> },
+    /// `import module "name"`: makes the blessed functions of `holy_trinity/name.divine`
+    /// callable. Modules are loaded before the script runs, so it does nothing itself.
+    Import { module: String },
+    /// `selah`: a pause to reflect on the state of the run, when it honors one.
+    Selah,
+    /// A braced block the runtime doesn't understand the header of, such as `bless Program {`.
+    Block { header: String, body: Vec<Stmt> },
+    /// Source the runtime can't interpret yet; it is announced but otherwise has no effect.
+    /// `binds` names the variable an opaque `let`/`const`/`var` declaration would create,
+    /// and `terminated` records whether the source ended it with a semicolon.
+    Opaque { text: String, binds: Option<String>, terminated: bool },
+    /// Trivia at the end of a block or script with no statement left to attach to.
+    Dangling,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Ident(String),
+    Array(Vec<Expr>),
+    /// `{ key: value, ... }`, with fields in the order they were written.
+    Object(Vec<(String, Expr)>),
+    /// `object.field`
+    Member { object: Box<Expr>, field: String },
+    /// `target[index]`
+    Index { target: Box<Expr>, index: Box<Expr> },
+    Unary { op: &'static str, operand: Box<Expr> },
+    Binary { op: &'static str, left: Box<Expr>, right: Box<Expr> },
+    Call { callee: String, args: Vec<Expr> },
+    /// `receiver.method(args)`
+    MethodCall { receiver: Box<Expr>, method: String, args: Vec<Expr> },
+}
+
+#[cfg(test)]
+impl Program {
+    /// A copy with every line number zeroed, so that two parses can be compared by
+    /// structure and trivia alone.
+    pub fn without_lines(&self) -> Program {
+        let statements = strip_lines(&self.statements);
+        let mut functions = HashMap::new();
+        collect_functions(&statements, &mut functions);
+        Program { statements, functions }
+    }
+}
+
+#[cfg(test)]
+fn collect_functions(statements: &[Stmt], functions: &mut HashMap<String, Arc<Function>>) {
+    for stmt in statements {
+        match &stmt.kind {
+            StmtKind::Bless(function) => {
+                functions.insert(function.name.clone(), function.clone());
+                collect_functions(&function.body, functions);
+            }
+            StmtKind::Judge { then_branch, else_branch, .. } => {
+                collect_functions(then_branch, functions);
+                collect_functions(else_branch.as_deref().unwrap_or_default(), functions);
+            }
+            StmtKind::Confess { body, forgive, .. } => {
+                collect_functions(body, functions);
+                collect_functions(forgive, functions);
+            }
+            StmtKind::Preach { body, .. }
+            | StmtKind::While { body, .. }
+            | StmtKind::Absolution { body }
+            | StmtKind::Block { body, .. } => collect_functions(body, functions),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+fn strip_lines(statements: &[Stmt]) -> Vec<Stmt> {
+    statements.iter().map(|stmt| {
+        let kind = match &stmt.kind {
+            StmtKind::Judge { condition, then_branch, else_branch } => StmtKind::Judge {
+                condition: condition.clone(),
+                then_branch: strip_lines(then_branch),
+                else_branch: else_branch.as_deref().map(strip_lines),
+            },
+            StmtKind::Preach { variable, iterable, body } => StmtKind::Preach {
+                variable: variable.clone(),
+                iterable: iterable.clone(),
+                body: strip_lines(body),
+            },
+            StmtKind::While { condition, body } => StmtKind::While { condition: condition.clone(), body: strip_lines(body) },
+            StmtKind::Bless(function) => StmtKind::Bless(Arc::new(Function {
+                line: 0,
+                body: strip_lines(&function.body),
+                ..(**function).clone()
+            })),
+            StmtKind::Absolution { body } => StmtKind::Absolution { body: strip_lines(body) },
+            StmtKind::Confess { body, sin, forgive } => StmtKind::Confess {
+                body: strip_lines(body),
+                sin: sin.clone(),
+                forgive: strip_lines(forgive),
+            },
+            StmtKind::Block { header, body } => StmtKind::Block { header: header.clone(), body: strip_lines(body) },
+            other => other.clone(),
+        };
+        Stmt { line: 0, kind, leading: stmt.leading.clone(), trailing: stmt.trailing.clone() }
+    }).collect()
+}
@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Commandments;
+use crate::{Sin, SinSeverity};
+
+/// Sins a project has accepted for now, so that confession reports only those
+/// found since: a JSON file of the sins `confess --update-baseline` found, each
+/// with the day it was first recorded. Where the commandments over the file
+/// give `baseline_max_age_days`, an entry older than that excuses nothing.
+pub struct Baseline {
+    path: PathBuf,
+    /// The directory files are named relative to: the baseline's own.
+    root: PathBuf,
+    /// How many days an entry excuses its sin, if not for ever.
+    max_age: Option<i64>,
+    entries: Vec<Entry>,
+    /// Whether each entry has matched a sin of this confession.
+    matched: Vec<bool>,
+    /// Every sin this confession found, as it would be recorded.
+    found: Vec<Entry>,
+}
+
+/// The baseline as it is written.
+#[derive(Default, Serialize, Deserialize)]
+struct Saved {
+    sins: Vec<Entry>,
+}
+
+/// A sin the baseline excuses.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Entry {
+    /// The script, relative to the baseline.
+    file: String,
+    id: String,
+    message: String,
+    /// When the sin was first recorded; an entry without a day never expires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created: Option<NaiveDate>,
+}
+
+impl Entry {
+    fn is(&self, other: &Entry) -> bool {
+        self.file == other.file && self.id == other.id && self.message == other.message
+    }
+}
+
+/// What the baseline made of a script's sins.
+pub struct Excused {
+    /// The sins it didn't excuse.
+    pub sins: Vec<Sin>,
+    /// A sin for each entry that matched but had expired.
+    pub stale: Vec<Sin>,
+    /// How many sins it excused.
+    pub excused: usize,
+}
+
+impl Baseline {
+    /// The baseline at `path`, which is empty when there is no file yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let saved: Saved = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("Malformed baseline {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Saved::default(),
+            Err(e) => return Err(format!("Failed to read the baseline {}: {}", path.display(), e)),
+        };
+        let max_age = Commandments::for_script(path)?
+            .and_then(|commandments| commandments.settings.get("baseline_max_age_days").and_then(Value::as_f64))
+            .map(|days| days as i64);
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        Ok(Self {
+            path: path.to_path_buf(),
+            root: fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf()),
+            max_age,
+            matched: vec![false; saved.sins.len()],
+            entries: saved.sins,
+            found: Vec::new(),
+        })
+    }
+
+    /// How the baseline names `script`: relative to itself where it can.
+    fn name(&self, script: &Path) -> String {
+        fs::canonicalize(script).ok()
+            .and_then(|script| script.strip_prefix(&self.root).ok().map(Path::to_path_buf))
+            .unwrap_or_else(|| script.to_path_buf())
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// Sets aside the sins of `script` the baseline records, each entry
+    /// excusing one sin. An entry older than `baseline_max_age_days` on
+    /// `today` excuses nothing, and is a stale absolution of its own.
+    pub fn excuse(&mut self, script: &Path, sins: Vec<Sin>, today: NaiveDate) -> Excused {
+        let file = self.name(script);
+        let mut excused = Excused { sins: Vec::new(), stale: Vec::new(), excused: 0 };
+        for sin in sins {
+            let found = Entry { file: file.clone(), id: sin.rule_id().to_string(), message: sin.message.clone(), created: None };
+            let entry = (0..self.entries.len()).find(|&i| !self.matched[i] && self.entries[i].is(&found));
+            self.found.push(found);
+            let Some(i) = entry else {
+                excused.sins.push(sin);
+                continue;
+            };
+            self.matched[i] = true;
+            match (self.entries[i].created, self.max_age) {
+                (Some(created), Some(max_age)) if (today - created).num_days() > max_age => {
+                    excused.stale.push(Sin {
+                        line_num: sin.line_num,
+                        severity: SinSeverity::Venial,
+                        rule: "stale-absolution".into(),
+                        message: format!(
+                            "The baseline has excused this {} sin since {}, more than {} days", sin.rule_id(), created, max_age
+                        ),
+                        culprit: None,
+                    });
+                    excused.sins.push(sin);
+                }
+                _ => excused.excused += 1,
+            }
+        }
+        excused
+    }
+
+    /// Records every sin this confession found, each keeping the day it was
+    /// first recorded, or `today` if it is new.
+    pub fn update(&self, today: NaiveDate) -> Result<usize, String> {
+        let mut taken = vec![false; self.entries.len()];
+        let sins: Vec<Entry> = self.found.iter().map(|found| {
+            let before = (0..self.entries.len()).find(|&i| !taken[i] && self.entries[i].is(found));
+            let created = match before {
+                Some(i) => {
+                    taken[i] = true;
+                    self.entries[i].created
+                }
+                None => Some(today),
+            };
+            Entry { created, ..found.clone() }
+        }).collect();
+        self.save(sins)
+    }
+
+    /// Drops the entries no sin of this confession matched, keeping the rest
+    /// as they were, expired or not.
+    pub fn prune(&self) -> Result<usize, String> {
+        let sins = self.entries.iter().zip(&self.matched).filter(|(_, matched)| **matched).map(|(entry, _)| entry.clone()).collect();
+        self.save(sins)
+    }
+
+    /// Writes `sins` as the baseline, returning how many there are.
+    fn save(&self, sins: Vec<Entry>) -> Result<usize, String> {
+        let count = sins.len();
+        let json = serde_json::to_string_pretty(&Saved { sins }).expect("a baseline is always serializable");
+        fs::write(&self.path, json + "\n")
+            .map_err(|e| format!("Failed to write the baseline {}: {}", self.path.display(), e))?;
+        Ok(count)
+    }
+}
@@ -0,0 +1,253 @@
+//! `divine bible`: the verses a topic finds, and the guidance that follows
+//! them.
+
+use colored::*;
+
+use crate::error::DivineError;
+use crate::{builtins, verses, DivinePLRuntime};
+
+/// Prints where the known verses came from, in the order they were merged.
+pub fn list_verse_sources() {
+    let sources = &verses::scripture().sources;
+    println!("{}", "📖 Sources of scripture:".underline().bright_blue());
+    let width = sources.iter().map(|source| source.name.len()).max().unwrap_or(0);
+    for source in sources {
+        let given = match (&source.trouble, source.overriding) {
+            (Some(trouble), _) => format!("not loaded: {}", trouble).red().to_string(),
+            (None, 0) => format!("{} {}", source.verses, if source.verses == 1 { "verse" } else { "verses" }),
+            (None, overriding) => format!(
+                "{} {} ({} replacing earlier ones)", source.verses, if source.verses == 1 { "verse" } else { "verses" }, overriding
+            ),
+        };
+        println!("  {:<width$}  {}", source.name, given, width = width);
+    }
+    println!("{}", format!("{} verses are known", verses::scripture().verses.len()).dimmed());
+}
+
+/// Prints every topic of scripture with its tags, in columns.
+pub fn list_verse_topics() {
+    let known = &verses::scripture().verses;
+    let mut topics: Vec<_> = known.iter().collect();
+    topics.sort_by_key(|(key, _)| key.as_str());
+    println!("{}", "📖 Topics of scripture:".underline().bright_blue());
+    let width = topics.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    for (key, verse) in topics {
+        println!("  {:<width$}  {}", key.bright_white(), verse.tags.join(", ").dimmed(), width = width);
+    }
+    println!("{}", format!("{} topics are known", known.len()).dimmed());
+}
+
+/// The programming guidance `divine bible` gives after its verses.
+#[derive(Clone, Copy, PartialEq)]
+enum Guidance {
+    Errors,
+    Loops,
+    Functions,
+    Variables,
+    Concurrency,
+    General,
+}
+
+impl Guidance {
+    fn for_topic(topic: &str) -> Self {
+        match topic.to_lowercase().as_str() {
+            "error" | "errors" | "bug" | "bugs" | "exception" => Guidance::Errors,
+            "loop" | "loops" | "iteration" => Guidance::Loops,
+            "function" | "functions" | "method" | "methods" => Guidance::Functions,
+            "variable" | "variables" | "let" | "const" => Guidance::Variables,
+            "concurrency" | "thread" | "threads" | "parallel" => Guidance::Concurrency,
+            _ => Guidance::General,
+        }
+    }
+    
+    fn preach(self) {
+        match self {
+            Guidance::Errors => {
+                println!("In DivinePL, errors are treated as sins to be confessed, not exceptions to be caught.");
+                println!("Use 'confess {{ ... }}' instead of 'try {{ ... }} catch {{ ... }}'");
+                println!("Remember: To err is human, to forgive divine, to handle errors properly, divine programming.");
+            },
+            Guidance::Loops => {
+                println!("Loops in DivinePL should be created with divine purpose and always include a path to termination.");
+                println!("For infinite is the kingdom of heaven, but finite should be thy loops.");
+                println!("Consider using 'blessing' loops that process each item with reverence.");
+                println!("Use 'preach (item in flock) {{ ... }}' to visit every soul in an array.");
+            },
+            Guidance::Functions => {
+                println!("Functions in DivinePL must be blessed to receive divine optimization.");
+                println!("Use 'bless functionName() {{ ... }}' for regular functions.");
+                println!("Use 'miracle functionName() {{ ... }}' for functions that perform extraordinary operations.");
+                println!("Use 'genesis() {{ ... }}' for program entry points.");
+                println!();
+                println!("{}", "These are blessed from the beginning and need no declaration:".bright_blue());
+                for builtin in builtins::BUILTINS {
+                    println!("  {:<24} {}", builtin.signature.bright_white(), builtin.doc);
+                }
+            },
+            Guidance::Variables => {
+                println!("Variables in DivinePL are vessels of divine data.");
+                println!("Use 'let' for mutable variables (as in 'Let there be light').");
+                println!("Use 'covenant' for constants that shall not be broken.");
+                println!("Avoid unholy variable names that invoke sin or blasphemy.");
+            },
+            Guidance::Concurrency => {
+                println!("Where two or three threads are gathered together, let them not quarrel over the same data.");
+                println!("Share nothing that one may change while another reads it, or guard it as the ark was guarded.");
+                println!("A DivinePL script is itself a single voice, and says one statement at a time.");
+            },
+            Guidance::General => {
+                println!("The path of righteous code is illuminated through clarity and purpose.");
+                println!("Seek to write your code as a testament to divine order and comprehension.");
+                println!("Remember that all DivinePL code must rest on the Sabbath (unless overridden in dev mode).");
+            }
+        }
+    }
+}
+
+/// Prints the verses matching `topic` followed by programming guidance, and
+/// returns how many verses matched. Under --quiet, only the verses are printed.
+pub fn search_bible(runtime: &DivinePLRuntime, topic: &str) -> Result<usize, DivineError> {
+    if !runtime.quiet() {
+        println!("{}", "📖 Searching for divine guidance on...".bright_blue());
+        println!("{}", format!("Topic: \"{}\"", topic).underline().bright_blue());
+        println!();
+    }
+    
+    let (found, guidance) = verses_for(runtime, topic);
+    if let [exact @ verses::Found { reason: verses::Reason::Exact, .. }] = found.as_slice() {
+        println!("{}", format!("📜 {}", exact.verse.text).green());
+    } else {
+        for verse in &found {
+            println!("{}", format!("📜 [{}] {}", verse.key, verse.verse.text).green());
+            match verse.explanation(&topic.to_lowercase()) {
+                Some(explanation) if !runtime.quiet() => println!("{}", format!("   {}", explanation).dimmed()),
+                _ => {}
+            }
+        }
+    }
+    
+    if runtime.quiet() {
+        return Ok(found.len());
+    }
+    if found.is_empty() {
+        lament_topic(runtime, topic);
+    }
+    
+    // Programming connection
+    println!("\n{}", "Divine Programming Guidance:".underline().bright_blue());
+    guidance.preach();
+    
+    Ok(found.len())
+}
+
+/// Prints the verses matching each of `topics` under a heading of its own, a
+/// verse several topics found only once, then the guidance for every kind of
+/// topic once. Returns how many verses matched.
+pub fn search_bible_topics(runtime: &DivinePLRuntime, topics: &[String]) -> Result<usize, DivineError> {
+    if !runtime.quiet() {
+        println!("{}", "📖 Searching for divine guidance on...".bright_blue());
+        let named: Vec<String> = topics.iter().map(|topic| format!("\"{}\"", topic)).collect();
+        println!("{}", format!("Topics: {}", named.join(", ")).underline().bright_blue());
+    }
+    
+    let searched: Vec<(&str, Vec<verses::Found>, Guidance)> = topics.iter()
+        .map(|topic| {
+            let (found, guidance) = verses_for(runtime, topic);
+            (topic.as_str(), found, guidance)
+        })
+        .collect();
+    let finders = |key: &str| -> Vec<&str> {
+        searched.iter()
+            .filter(|(_, found, _)| found.iter().any(|verse| verse.key == key))
+            .map(|(topic, _, _)| *topic)
+            .collect()
+    };
+    
+    let mut shown: Vec<&str> = Vec::new();
+    for (topic, found, _) in &searched {
+        if !runtime.quiet() {
+            println!();
+            println!("{}", format!("Topic: \"{}\"", topic).bright_blue());
+        }
+        let unseen: Vec<&verses::Found> = found.iter().filter(|verse| !shown.contains(&verse.key)).collect();
+        for verse in &unseen {
+            let finders = finders(verse.key);
+            let label = match finders.as_slice() {
+                [_] => verse.key.to_string(),
+                [earlier @ .., last] => {
+                    let earlier: Vec<String> = earlier.iter().map(|topic| format!("\"{}\"", topic)).collect();
+                    format!("{}, for {} and \"{}\"", verse.key, earlier.join(", "), last)
+                }
+                [] => verse.key.to_string(),
+            };
+            println!("{}", format!("📜 [{}] {}", label, verse.verse.text).green());
+            shown.push(verse.key);
+            if runtime.quiet() {
+                continue;
+            }
+            for (finder, found, _) in searched.iter().filter(|(finder, _, _)| finders.contains(finder)) {
+                let explanation = found.iter().find(|found| found.key == verse.key).and_then(|found| found.explanation(&finder.to_lowercase()));
+                match explanation {
+                    Some(explanation) if finders.len() > 1 => println!("{}", format!("   \"{}\": {}", finder, explanation).dimmed()),
+                    Some(explanation) => println!("{}", format!("   {}", explanation).dimmed()),
+                    None => {}
+                }
+            }
+        }
+        if runtime.quiet() {
+            continue;
+        }
+        if found.is_empty() {
+            lament_topic(runtime, topic);
+        } else if unseen.is_empty() {
+            println!("{}", "   Every verse for this topic is above.".dimmed());
+        }
+    }
+    
+    if runtime.quiet() {
+        return Ok(shown.len());
+    }
+    
+    let mut preached: Vec<Guidance> = Vec::new();
+    println!("\n{}", "Divine Programming Guidance:".underline().bright_blue());
+    for (_, _, guidance) in searched {
+        if !preached.contains(&guidance) {
+            if !preached.is_empty() {
+                println!();
+            }
+            guidance.preach();
+            preached.push(guidance);
+        }
+    }
+    
+    Ok(shown.len())
+}
+
+/// The verses `topic` finds, best first, and the guidance it calls for. An
+/// exact match stands alone.
+fn verses_for(runtime: &DivinePLRuntime, topic: &str) -> (Vec<verses::Found>, Guidance) {
+    let mut found = verses::search(runtime.bible_verses, topic);
+    if matches!(found.first().map(|found| &found.reason), Some(verses::Reason::Exact)) {
+        found.truncate(1);
+    }
+    // Guidance follows the topic a misspelt one was taken for, not the
+    // misspelling, nor the tag it nearly spelt
+    let guidance = match found.first() {
+        Some(found @ verses::Found { reason: verses::Reason::Similar { .. }, .. }) => Guidance::for_topic(found.key),
+        _ => Guidance::for_topic(topic),
+    };
+    (found, guidance)
+}
+
+/// Says that `topic` found nothing, with what it might have meant.
+fn lament_topic(runtime: &DivinePLRuntime, topic: &str) {
+    println!("{}", "No direct verse found for this topic.".yellow());
+    match verses::suggestions(runtime.bible_verses, topic).as_slice() {
+        [] => println!("{}", "Consider broadening your search or consulting the Good Book directly.".yellow()),
+        suggestions => {
+            for suggestion in suggestions {
+                println!("{}", format!("Did you mean \"{}\"?", suggestion).yellow());
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use colored::*;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::interpreter::Value;
+
+/// A function every script may call without blessing it.
+pub struct Builtin {
+    pub name: &'static str,
+    /// How a call is written, as shown by `bible function`.
+    pub signature: &'static str,
+    pub doc: &'static str,
+}
+
+/// The standard library, in the order `bible function` lists it.
+pub const BUILTINS: &[Builtin] = &[
+    Builtin { name: "print", signature: "print(values...)", doc: "Writes its arguments to the output, separated by spaces" },
+    Builtin { name: "revelation", signature: "revelation(values...)", doc: "Proclaims its arguments as a revelation" },
+    Builtin { name: "covenant", signature: "covenant(promise)", doc: "Makes a promise that must be fulfilled before judgment day" },
+    Builtin { name: "fulfill", signature: "fulfill(promise)", doc: "Keeps a promise made with covenant" },
+    Builtin { name: "transgress", signature: "transgress(message)", doc: "Raises a sin, which a confess block may forgive" },
+    Builtin { name: "affirm", signature: "affirm(condition, message)", doc: "Records whether condition holds, for testament to report" },
+    Builtin { name: "affirm_equal", signature: "affirm_equal(actual, expected)", doc: "Records whether two values are equal, for testament to report" },
+    Builtin { name: "createLight", signature: "createLight()", doc: "Let there be light: returns true, blessed from the beginning" },
+    Builtin { name: "castLots", signature: "castLots(n)", doc: "Casts lots for a whole number from 1 to n; run --seed makes the lots fall the same way each time" },
+    Builtin { name: "amen", signature: "amen()", doc: "Flushes everything printed so far" },
+    Builtin { name: "scribe", signature: "scribe(path, text)", doc: "Writes text to the file at path (dev mode only)" },
+    Builtin { name: "len", signature: "len(x)", doc: "How many items an array holds, or characters a string" },
+    Builtin { name: "listen", signature: "listen(prompt)", doc: "Reads a line from stdin, prompting only when a person is there to answer; void at end of input" },
+    Builtin { name: "number", signature: "number(x)", doc: "Converts a string such as manna[0] to a number" },
+];
+
+/// Calls the builtin `name` that needs nothing from the interpreter beyond the
+/// lots and whether this is dev mode. `None` if there is no such builtin.
+pub fn call(name: &str, args: &[Value], line: usize, lots: &mut StdRng, dev_mode: bool) -> Option<Result<Value, String>> {
+    let result = match name {
+        "createLight" => arity(name, args, 0, line).map(|_| Value::Bool(true)),
+        "castLots" => arity(name, args, 1, line).and_then(|_| match &args[0] {
+            Value::Number(n) if *n >= 1.0 && n.fract() == 0.0 => Ok(Value::Number(lots.gen_range(1..=*n as i64) as f64)),
+            other => Err(format!("DiscernmentError: castLots requires a whole number of at least 1, but received {} (line {})", describe(other), line)),
+        }),
+        "amen" => arity(name, args, 0, line).map(|_| {
+            let _ = io::stdout().flush();
+            Value::Void
+        }),
+        // Scrolls are open to every run, being sealed whatever happens and linted by
+        // DPL005; scribe writes with no such care, so only dev mode may use it
+        "scribe" => arity(name, args, 2, line).and_then(|_| {
+            let Value::Str(path) = &args[0] else {
+                return Err(format!("DiscernmentError: scribe requires a path, but received {} (line {})", args[0].type_name(), line));
+            };
+            if !dev_mode {
+                return Err(format!("ScribeError: only in dev mode may the scribe write '{}' (line {})", path, line));
+            }
+            fs::write(path, args[1].to_string())
+                .map(|_| Value::Void)
+                .map_err(|e| format!("ScribeError: could not write '{}': {} (line {})", path, e, line))
+        }),
+        "len" => arity(name, args, 1, line).and_then(|_| match &args[0] {
+            Value::Array(items) => Ok(Value::Number(items.len() as f64)),
+            Value::Str(text) => Ok(Value::Number(text.chars().count() as f64)),
+            other => Err(format!("DiscernmentError: len requires an array or a string, but received {} (line {})", other.type_name(), line)),
+        }),
+        "listen" => arity(name, args, 1, line).and_then(|_| listen(&args[0].to_string(), line)),
+        "number" => arity(name, args, 1, line).and_then(|_| match &args[0] {
+            Value::Number(n) => Ok(Value::Number(*n)),
+            Value::Str(text) => text.trim().parse::<f64>().ok().filter(|n| n.is_finite()).map(Value::Number).ok_or_else(|| {
+                format!("DiscernmentError: number cannot count \"{}\" (line {})", text, line)
+            }),
+            other => Err(format!("DiscernmentError: number requires a string or a number, but received {} (line {})", other.type_name(), line)),
+        }),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// The builtin or blessed function whose name is closest to `name`, if any is
+/// close enough to be what was meant.
+pub fn nearest<'a>(name: &str, blessed: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    BUILTINS.iter().map(|builtin| builtin.name)
+        .chain(blessed)
+        .map(|candidate| (crate::edit_distance(&candidate.to_lowercase(), &name.to_lowercase()), candidate))
+        .filter(|(distance, candidate)| *distance <= candidate.len() / 2)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Reads the next line of stdin, trimmed. The prompt is only shown to a
+/// terminal, so piped input is read silently.
+fn listen(prompt: &str, line: usize) -> Result<Value, String> {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        print!("{} ", prompt.bright_blue());
+        let _ = io::stdout().flush();
+    }
+    let mut answer = String::new();
+    match stdin.lock().read_line(&mut answer) {
+        Ok(0) => Ok(Value::Void),
+        Ok(_) => Ok(Value::Str(answer.trim().to_string())),
+        Err(e) => Err(format!("SilenceError: could not listen: {} (line {})", e, line)),
+    }
+}
+
+fn arity(name: &str, args: &[Value], expected: usize, line: usize) -> Result<(), String> {
+    if args.len() == expected {
+        return Ok(());
+    }
+    Err(format!(
+        "HeresyError: '{}' expects {} argument{} but received {} (line {})",
+        name, expected, if expected == 1 { "" } else { "s" }, args.len(), line
+    ))
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        other => other.type_name().to_string(),
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::config::Commandments;
+use serde_json::Value;
+
+/// The feasts kept whatever the commandments say, by month and day. Easter,
+/// which moves, is reckoned for each year.
+const FEASTS: &[(u32, u32, &str)] = &[(1, 6, "Epiphany"), (11, 1, "All Saints"), (12, 25, "Christmas")];
+
+/// Where in the liturgical year a day falls.
+#[derive(Clone, Default)]
+pub enum Season {
+    Feast(String),
+    /// Lent, which lasts until the day given, inclusive.
+    Lent { until: NaiveDate },
+    #[default]
+    Ordinary,
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Season::Feast(name) => write!(f, "the feast of {}", name),
+            Season::Lent { until } => write!(f, "Lent, until {}", until.format("%-d %B")),
+            Season::Ordinary => f.write_str("ordinary time"),
+        }
+    }
+}
+
+/// A day of the calendar: one that comes every year, or in one year only.
+#[derive(Clone, Copy)]
+enum Day {
+    Yearly { month: u32, day: u32 },
+    Once(NaiveDate),
+}
+
+impl Day {
+    /// A day written MM-DD, or YYYY-MM-DD for one year only.
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+            return Some(Day::Once(date));
+        }
+        let (month, day) = text.split_once('-')?;
+        let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+        // A leap year, so that 02-29 is a day
+        NaiveDate::from_ymd_opt(2024, month, day).map(|_| Day::Yearly { month, day })
+    }
+
+    /// The date of this day in `year`, if it falls in it.
+    fn in_year(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            Day::Yearly { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+            Day::Once(date) => (date.year() == year).then_some(date),
+        }
+    }
+}
+
+/// The feasts a project keeps, and when its Lent is.
+pub struct Calendar {
+    feasts: Vec<(Day, String)>,
+    /// The first and last days of Lent, when the commandments give them.
+    lent: Option<(Day, Day)>,
+}
+
+impl Calendar {
+    /// The calendar the commandments keep: their `feast_days`, then the feasts
+    /// every project keeps, and their `lent`, else the Lent before Easter.
+    pub fn new(commandments: Option<&Commandments>) -> Result<Self, String> {
+        let setting = |key: &str| commandments.and_then(|commandments| commandments.settings.get(key));
+        let undated = |key: &str, text: &str| format!(
+            "{} has \"{}\", which is no date; write MM-DD, or YYYY-MM-DD for a single year", key, text
+        );
+
+        let mut feasts = Vec::new();
+        if let Some(days) = setting("feast_days") {
+            let days = days.as_object().ok_or_else(|| "feast_days must map dates to the names of their feasts".to_string())?;
+            for (date, name) in days {
+                let day = Day::parse(date).ok_or_else(|| undated("feast_days", date))?;
+                let name = name.as_str().ok_or_else(|| format!("feast_days has {} for \"{}\"; name the feast", name, date))?;
+                feasts.push((day, name.to_string()));
+            }
+        }
+        feasts.extend(FEASTS.iter().map(|&(month, day, name)| (Day::Yearly { month, day }, name.to_string())));
+
+        let lent = match setting("lent") {
+            Some(lent) => {
+                let bound = |key: &str| {
+                    let text = lent.get(key).and_then(Value::as_str)
+                        .ok_or_else(|| "lent must give the days it is \"from\" and \"to\", as in { \"from\": \"02-18\", \"to\": \"04-04\" }".to_string())?;
+                    Day::parse(text).ok_or_else(|| undated(&format!("lent.{}", key), text))
+                };
+                Some((bound("from")?, bound("to")?))
+            }
+            None => None,
+        };
+        Ok(Self { feasts, lent })
+    }
+
+    /// The season `date` falls in. A feast outranks Lent.
+    pub fn season(&self, date: NaiveDate) -> Season {
+        let easter = easter(date.year());
+        let feast = self.feasts.iter()
+            .find(|(day, _)| day.in_year(date.year()) == Some(date))
+            .map(|(_, name)| name.clone())
+            .or_else(|| (date == easter).then(|| "Easter".to_string()));
+        if let Some(name) = feast {
+            return Season::Feast(name);
+        }
+
+        match self.lent {
+            // Lent runs from Ash Wednesday to Holy Saturday
+            None => (easter - Duration::days(46) <= date && date < easter).then(|| Season::Lent { until: easter - Duration::days(1) }),
+            Some((from, to)) => [date.year() - 1, date.year()].into_iter().find_map(|year| {
+                let from = from.in_year(year)?;
+                // A yearly Lent whose end comes before its start ends the next year
+                let to = match to {
+                    Day::Yearly { .. } => to.in_year(year).filter(|to| *to >= from).or_else(|| to.in_year(year + 1))?,
+                    Day::Once(to) => to,
+                };
+                (from <= date && date <= to).then_some(Season::Lent { until: to })
+            }),
+        }
+        .unwrap_or(Season::Ordinary)
+    }
+}
+
+/// The date of Easter Sunday in `year` of the Gregorian calendar.
+fn easter(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let (b, c) = (year / 100, year % 100);
+    let (d, e) = (b / 4, b % 4);
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let (i, k) = (c / 4, c % 4);
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap_or_default()
+}
+
+/// Whether `text` is a day the calendar can keep, as `feast_days` and `lent` write them.
+pub fn is_day(text: &str) -> bool {
+    Day::parse(text).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    fn calendar(commandments: &str) -> Result<Calendar, String> {
+        Calendar::new(Some(&Commandments::parse(Path::new("commandments.config"), commandments)?))
+    }
+
+    #[test]
+    fn easter_is_reckoned_for_each_year() {
+        assert_eq!(easter(2024), date("2024-03-31"));
+        assert_eq!(easter(2025), date("2025-04-20"));
+        assert_eq!(easter(2038), date("2038-04-25"));
+    }
+
+    #[test]
+    fn without_commandments_lent_leads_up_to_easter() {
+        let calendar = Calendar::new(None).unwrap();
+        assert!(matches!(calendar.season(date("2024-03-31")), Season::Feast(name) if name == "Easter"));
+        assert!(matches!(calendar.season(date("2024-12-25")), Season::Feast(name) if name == "Christmas"));
+        // Ash Wednesday to Holy Saturday
+        assert!(matches!(calendar.season(date("2024-02-13")), Season::Ordinary));
+        assert!(matches!(calendar.season(date("2024-02-14")), Season::Lent { until } if until == date("2024-03-30")));
+        assert!(matches!(calendar.season(date("2024-03-30")), Season::Lent { .. }));
+        assert!(matches!(calendar.season(date("2024-04-01")), Season::Ordinary));
+    }
+
+    #[test]
+    fn the_commandments_add_feasts_and_move_lent() {
+        let calendar = calendar(r#"{"feast_days": {"07-04": "the Founding", "2024-08-01": "the Release"}, "lent": {"from": "12-20", "to": "01-02"}}"#).unwrap();
+        assert!(matches!(calendar.season(date("2031-07-04")), Season::Feast(name) if name == "the Founding"));
+        assert!(matches!(calendar.season(date("2024-08-01")), Season::Feast(name) if name == "the Release"));
+        assert!(matches!(calendar.season(date("2025-08-01")), Season::Ordinary));
+        // A Lent over the new year, which Christmas still outranks
+        assert!(matches!(calendar.season(date("2024-12-24")), Season::Lent { until } if until == date("2025-01-02")));
+        assert!(matches!(calendar.season(date("2024-12-25")), Season::Feast(name) if name == "Christmas"));
+        assert!(matches!(calendar.season(date("2025-01-02")), Season::Lent { until } if until == date("2025-01-02")));
+        assert!(matches!(calendar.season(date("2025-01-03")), Season::Ordinary));
+        // The Lent before Easter is no longer kept
+        assert!(matches!(calendar.season(date("2024-03-01")), Season::Ordinary));
+    }
+
+    #[test]
+    fn a_day_that_is_no_date_is_refused() {
+        let error = calendar(r#"{"feast_days": {"13-01": "Nowhen"}}"#).err().unwrap();
+        assert!(error.contains("feast_days has \"13-01\", which is no date"), "{}", error);
+        let error = calendar(r#"{"lent": {"from": "02-18"}}"#).err().unwrap();
+        assert!(error.starts_with("lent must give the days it is \"from\" and \"to\""), "{}", error);
+        assert!(is_day("02-29") && is_day("2025-04-20") && !is_day("02-30"));
+    }
+}
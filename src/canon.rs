@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use colored::*;
+
+use crate::config::Commandments;
+use crate::environment;
+use serde_json::Value;
+
+/// The file, beside the commandments, whose lines are apocrypha too.
+const IGNORE_FILE_NAME: &str = ".divineignore";
+
+/// Set by --no-apocrypha, after which every script is canonical.
+static FORGONE: AtomicBool = AtomicBool::new(false);
+
+/// Stops the apocrypha from being heeded for the rest of the process, so that
+/// directory operations take in every script.
+pub fn forgo() {
+    FORGONE.store(true, Ordering::Relaxed);
+}
+
+/// Which scripts of a project are canonical: all but those its apocrypha
+/// name. The apocrypha are the globs of the commandments' `apocrypha` list,
+/// then the lines of `.divineignore`, written as in a .gitignore and matched
+/// relative to the directory the commandments are in. A later glob overrules
+/// an earlier one, so `!` can return a script to the canon.
+#[derive(Default)]
+pub struct Canon {
+    root: PathBuf,
+    globs: Vec<Glob>,
+}
+
+struct Glob {
+    pattern: Vec<char>,
+    /// Whether the glob returns what it matches to the canon.
+    negated: bool,
+    /// Whether the glob matches only directories, as in `build/`.
+    dir_only: bool,
+}
+
+impl Canon {
+    /// The canon of the project `dir` belongs to, or of `dir` itself when it
+    /// belongs to none.
+    pub fn for_dir(dir: &Path) -> Result<Self, String> {
+        if FORGONE.load(Ordering::Relaxed) {
+            return Ok(Self::default());
+        }
+        let commandments = Commandments::for_script(dir)?;
+        let root = match &commandments {
+            Some(commandments) => commandments.path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            None => fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf()),
+        };
+
+        let listed = commandments.as_ref()
+            .and_then(|commandments| commandments.settings.get("apocrypha")?.as_array().cloned())
+            .unwrap_or_default();
+        let ignored = fs::read_to_string(root.join(IGNORE_FILE_NAME)).unwrap_or_default();
+        let globs = listed.iter().filter_map(Value::as_str)
+            .chain(ignored.lines())
+            .filter_map(Glob::new)
+            .collect();
+        Ok(Self { root, globs })
+    }
+
+    /// Whether `script` is apocryphal: whether the last glob to match it, or a
+    /// directory it is in, doesn't return it to the canon.
+    pub fn is_apocryphal(&self, script: &Path) -> bool {
+        if self.globs.is_empty() {
+            return false;
+        }
+        let script = fs::canonicalize(script).unwrap_or_else(|_| script.to_path_buf());
+        let Ok(relative) = script.strip_prefix(&self.root) else { return false };
+        let relative: Vec<char> = relative.to_string_lossy().replace('\\', "/").chars().collect();
+
+        // The script itself, and each directory it is in
+        let dirs = relative.iter().enumerate().filter(|(_, &c)| c == '/').map(|(at, _)| (&relative[..at], true));
+        let candidates: Vec<(&[char], bool)> = dirs.chain([(&relative[..], false)]).collect();
+        self.globs.iter()
+            .rev()
+            .find(|glob| candidates.iter().any(|(path, is_dir)| (*is_dir || !glob.dir_only) && matches(&glob.pattern, path)))
+            .is_some_and(|glob| !glob.negated)
+    }
+
+    /// `scripts` without the apocryphal ones, and how many those were.
+    pub fn sift(&self, mut scripts: Vec<PathBuf>) -> (Vec<PathBuf>, usize) {
+        let found = scripts.len();
+        scripts.retain(|script| !self.is_apocryphal(script));
+        let skipped = found - scripts.len();
+        (scripts, skipped)
+    }
+}
+
+impl Glob {
+    /// The glob a line of apocrypha writes, if it writes one.
+    fn new(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (line, negated) = match line.strip_prefix('!') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let (line, dir_only) = match line.strip_suffix('/') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        // A glob with no slash but at its end matches at any depth
+        let pattern = match line.strip_prefix('/') {
+            Some(line) => line.to_string(),
+            None if line.contains('/') => line.to_string(),
+            None => format!("**/{}", line),
+        };
+        Some(Self { pattern: pattern.chars().collect(), negated, dir_only })
+    }
+}
+
+/// Whether `pattern` matches all of `path`: `*` matches within a directory,
+/// `**` across them and `?` any one character but `/`.
+fn matches(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // "**/" may match no directories at all
+            rest.first() == Some(&'/') && matches(&rest[1..], path) || (0..=path.len()).any(|at| matches(rest, &path[at..]))
+        }
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|&at| at == 0 || path[at - 1] != '/')
+            .any(|at| matches(rest, &path[at..])),
+        ['?', rest @ ..] => path.first().is_some_and(|&c| c != '/') && matches(rest, &path[1..]),
+        [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..]),
+    }
+}
+
+/// Tells how many scripts were passed over as apocryphal, if any were.
+pub fn report_skipped(skipped: usize) {
+    if skipped > 0 {
+        eprintln!("{}", environment::for_stderr(format!(
+            "📕 {} {} skipped as apocryphal (--no-apocrypha takes them in)",
+            skipped, if skipped == 1 { "script was" } else { "scripts were" }
+        ).dimmed()));
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::Local;
+use colored::*;
+
+use crate::config::Commandments;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Chronicles rotate once they grow past this many bytes unless the config says otherwise.
+pub const DEFAULT_MAX_BYTES: u64 = 256 * 1024;
+
+const LOG_NAME: &str = "chronicles.log";
+const ROTATED_NAME: &str = "chronicles.log.1";
+const DEBUG_NAME: &str = "chronicles.debug.log";
+
+/// Where chronicles are kept: `$DIVINEPL_HOME`, else the user's data directory.
+/// Nothing recorded here ever leaves the machine.
+pub fn chronicles_dir() -> PathBuf {
+    if let Some(home) = std::env::var_os("DIVINEPL_HOME") {
+        return PathBuf::from(home);
+    }
+    if let Some(data) = std::env::var_os("XDG_DATA_HOME") {
+        return PathBuf::from(data).join("divinepl");
+    }
+    match std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        Some(home) => PathBuf::from(home).join(".local").join("share").join("divinepl"),
+        None => std::env::temp_dir().join("divinepl"),
+    }
+}
+
+/// One recorded command invocation.
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    pub at: String,
+    pub command: String,
+    pub duration_ms: u64,
+    #[serde(rename = "outcome", with = "outcome")]
+    pub blessed: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sins: Option<usize>,
+}
+
+/// Outcomes are written as words, "blessed" or "fallen", so the chronicles read as they are.
+mod outcome {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(blessed: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(if *blessed { "blessed" } else { "fallen" })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        Ok(String::deserialize(deserializer)? == "blessed")
+    }
+}
+
+impl Entry {
+    /// The calendar day the entry was recorded on.
+    fn day(&self) -> &str {
+        self.at.get(..10).unwrap_or(&self.at)
+    }
+}
+
+/// Appends an entry when the governing commandments opt in with `keep_chronicles`.
+///
+/// Best-effort by design: this must never slow down or fail the command being
+/// chronicled, so every error is swallowed into the debug log.
+pub fn record(anchor: &Path, command: &str, duration: Duration, blessed: bool, sins: Option<usize>) {
+    let commandments = match Commandments::for_script(anchor) {
+        Ok(Some(commandments)) => commandments,
+        _ => return,
+    };
+    if commandments.settings.get("keep_chronicles").and_then(Value::as_bool) != Some(true) {
+        return;
+    }
+    let max_bytes = commandments.settings.get("chronicles_max_bytes")
+        .and_then(Value::as_f64)
+        .map(|n| n as u64)
+        .unwrap_or(DEFAULT_MAX_BYTES);
+
+    let entry = Entry {
+        at: Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+        command: command.to_string(),
+        duration_ms: duration.as_millis() as u64,
+        blessed,
+        sins,
+    };
+
+    let dir = chronicles_dir();
+    if let Err(e) = append(&dir, &entry, max_bytes) {
+        debug_log(&dir, &e);
+    }
+}
+
+fn append(dir: &Path, entry: &Entry, max_bytes: u64) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+    let line = format!("{}\n", serde_json::to_string(entry).map_err(|e| e.to_string())?);
+    rotate_if_full(dir, line.len() as u64, max_bytes)?;
+
+    let path = dir.join(LOG_NAME);
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)
+        .map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+    file.write_all(line.as_bytes()).map_err(|e| format!("cannot write {}: {}", path.display(), e))
+}
+
+/// Moves the current log aside when appending `incoming` bytes would exceed the cap.
+/// Only one generation is kept, so chronicles never use more than about twice the cap.
+fn rotate_if_full(dir: &Path, incoming: u64, max_bytes: u64) -> Result<(), String> {
+    let path = dir.join(LOG_NAME);
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    if size == 0 || size + incoming <= max_bytes {
+        return Ok(());
+    }
+    fs::rename(&path, dir.join(ROTATED_NAME)).map_err(|e| format!("cannot rotate {}: {}", path.display(), e))
+}
+
+fn debug_log(dir: &Path, message: &str) {
+    let _ = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(DEBUG_NAME))
+        .and_then(|mut file| writeln!(file, "{} {}", Local::now().format("%Y-%m-%dT%H:%M:%S"), message));
+}
+
+/// Reads every entry still kept, oldest first. Unreadable lines are skipped.
+pub fn load(dir: &Path) -> Vec<Entry> {
+    [ROTATED_NAME, LOG_NAME]
+        .iter()
+        .filter_map(|name| fs::read_to_string(dir.join(name)).ok())
+        .flat_map(|content| {
+            content.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+pub fn clear(dir: &Path) -> Result<(), String> {
+    for name in [LOG_NAME, ROTATED_NAME, DEBUG_NAME] {
+        let path = dir.join(name);
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to burn {}: {}", path.display(), e))?;
+        }
+    }
+    println!("{}", "🔥 The chronicles have been burned. Your habits are known to no one.".green());
+    Ok(())
+}
+
+/// Aggregates computed from a set of entries.
+pub struct Summary {
+    /// Invocation counts, most used first.
+    pub usage: Vec<(String, usize)>,
+    /// Average duration in milliseconds per command.
+    pub average_ms: BTreeMap<String, f64>,
+    /// Fraction of blessed runs per day.
+    pub salvation_by_day: BTreeMap<String, f64>,
+    /// Average sins found per confession per day.
+    pub sins_by_day: BTreeMap<String, f64>,
+}
+
+pub fn summarize(entries: &[Entry]) -> Summary {
+    let mut counts: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+    let mut runs: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    let mut confessions: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+
+    for entry in entries {
+        let (count, total_ms) = counts.entry(entry.command.clone()).or_default();
+        *count += 1;
+        *total_ms += entry.duration_ms;
+
+        if entry.command == "run" {
+            let (blessed, total) = runs.entry(entry.day().to_string()).or_default();
+            *blessed += entry.blessed as usize;
+            *total += 1;
+        }
+        if let (true, Some(sins)) = (entry.command == "confess", entry.sins) {
+            let (sum, total) = confessions.entry(entry.day().to_string()).or_default();
+            *sum += sins;
+            *total += 1;
+        }
+    }
+
+    let mut usage: Vec<(String, usize)> = counts.iter().map(|(command, (count, _))| (command.clone(), *count)).collect();
+    usage.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let ratio = |(part, total): &(usize, usize)| *part as f64 / *total as f64;
+    Summary {
+        usage,
+        average_ms: counts.iter().map(|(command, (count, ms))| (command.clone(), *ms as f64 / *count as f64)).collect(),
+        salvation_by_day: runs.iter().map(|(day, tally)| (day.clone(), ratio(tally))).collect(),
+        sins_by_day: confessions.iter().map(|(day, tally)| (day.clone(), ratio(tally))).collect(),
+    }
+}
+
+pub fn show(dir: &Path) -> Result<(), String> {
+    let entries = load(dir);
+    println!("{}", "📚 THE CHRONICLES 📚".underline().bright_blue());
+
+    if entries.is_empty() {
+        println!("{}", "The chronicles are empty. Set \"keep_chronicles\": true in commandments.config to begin recording.".yellow());
+        return Ok(());
+    }
+
+    let summary = summarize(&entries);
+    println!("{} invocations recorded since {}", entries.len(), entries[0].day());
+
+    println!("\n{}", "Most-used commands:".underline().bright_blue());
+    for (command, count) in &summary.usage {
+        println!("  {:<10} {:>5}", command, count);
+    }
+
+    println!("\n{}", "Average durations:".underline().bright_blue());
+    for (command, ms) in &summary.average_ms {
+        println!("  {:<10} {:>8.0} ms", command, ms);
+    }
+
+    if !summary.salvation_by_day.is_empty() {
+        println!("\n{}", "Salvation rate over time:".underline().bright_blue());
+        for (day, rate) in &summary.salvation_by_day {
+            println!("  {}  {:>5.1}%", day, rate * 100.0);
+        }
+    }
+
+    if !summary.sins_by_day.is_empty() {
+        println!("\n{}", "Sins per confession:".underline().bright_blue());
+        for (day, sins) in &summary.sins_by_day {
+            println!("  {}  {:>5.1}", day, sins);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A chronicles directory of its own for one test, removed when it is done.
+    struct Chronicles(PathBuf);
+
+    impl Chronicles {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("divinepl-chronicles-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            Self(dir)
+        }
+    }
+
+    impl Drop for Chronicles {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn entry(at: &str, command: &str, duration_ms: u64, blessed: bool, sins: Option<usize>) -> Entry {
+        Entry { at: at.to_string(), command: command.to_string(), duration_ms, blessed, sins }
+    }
+
+    #[test]
+    fn the_summary_counts_averages_and_rates_by_day() {
+        let summary = summarize(&[
+            entry("2026-03-01T09:00:00", "run", 100, true, None),
+            entry("2026-03-01T10:00:00", "run", 300, false, None),
+            entry("2026-03-01T11:00:00", "confess", 40, false, Some(3)),
+            entry("2026-03-02T09:00:00", "run", 200, true, None),
+            entry("2026-03-02T10:00:00", "confess", 20, true, Some(0)),
+            entry("2026-03-02T11:00:00", "confess", 30, false, Some(4)),
+            entry("2026-03-02T12:00:00", "prophesy", 10, true, None),
+        ]);
+
+        assert_eq!(summary.usage, [("confess".to_string(), 3), ("run".to_string(), 3), ("prophesy".to_string(), 1)]);
+        assert_eq!(summary.average_ms.get("run"), Some(&200.0));
+        assert_eq!(summary.average_ms.get("confess"), Some(&30.0));
+        assert_eq!(summary.average_ms.get("prophesy"), Some(&10.0));
+        assert_eq!(summary.salvation_by_day.into_iter().collect::<Vec<_>>(), [("2026-03-01".to_string(), 0.5), ("2026-03-02".to_string(), 1.0)]);
+        assert_eq!(summary.sins_by_day.into_iter().collect::<Vec<_>>(), [("2026-03-01".to_string(), 3.0), ("2026-03-02".to_string(), 2.0)]);
+    }
+
+    #[test]
+    fn a_confession_without_a_count_of_sins_weighs_on_no_trend() {
+        let summary = summarize(&[entry("2026-03-01T09:00:00", "confess", 10, false, None)]);
+        assert_eq!(summary.usage, [("confess".to_string(), 1)]);
+        assert!(summary.sins_by_day.is_empty() && summary.salvation_by_day.is_empty());
+    }
+
+    #[test]
+    fn the_log_is_rotated_once_it_would_outgrow_its_cap() {
+        let chronicles = Chronicles::new("rotation");
+        let dir = &chronicles.0;
+        let line = |command: &str| entry("2026-03-01T09:00:00", command, 1, true, None);
+        let size = serde_json::to_string(&line("aaa")).unwrap().len() as u64 + 1;
+
+        // Two lines fit under a cap of two lines and a half; the third moves them aside
+        append(dir, &line("aaa"), size * 5 / 2).unwrap();
+        append(dir, &line("bbb"), size * 5 / 2).unwrap();
+        assert!(!dir.join(ROTATED_NAME).exists());
+        append(dir, &line("ccc"), size * 5 / 2).unwrap();
+        assert_eq!(fs::read_to_string(dir.join(LOG_NAME)).unwrap().lines().count(), 1);
+        assert_eq!(fs::read_to_string(dir.join(ROTATED_NAME)).unwrap().lines().count(), 2);
+        let commands: Vec<String> = load(dir).into_iter().map(|entry| entry.command).collect();
+        assert_eq!(commands, ["aaa", "bbb", "ccc"]);
+
+        // Only one generation is kept, so the oldest entries are forgotten
+        append(dir, &line("ddd"), size * 5 / 2).unwrap();
+        append(dir, &line("eee"), size * 5 / 2).unwrap();
+        let commands: Vec<String> = load(dir).into_iter().map(|entry| entry.command).collect();
+        assert_eq!(commands, ["ccc", "ddd", "eee"]);
+    }
+
+    #[test]
+    fn an_entry_larger_than_the_cap_is_still_written() {
+        let chronicles = Chronicles::new("oversized");
+        append(&chronicles.0, &entry("2026-03-01T09:00:00", "run", 1, true, None), 8).unwrap();
+        assert_eq!(load(&chronicles.0).len(), 1);
+        assert!(!chronicles.0.join(ROTATED_NAME).exists());
+    }
+}
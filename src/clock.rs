@@ -0,0 +1,39 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::sabbath::Timezone;
+
+/// Tells the time. The runtime asks its clock rather than the system, so that
+/// whatever depends on the day can be run as if it were another.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system's own clock.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at one instant.
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+impl FixedClock {
+    /// Stopped in `timezone` at the time `text` gives, written YYYY-MM-DDTHH:MM,
+    /// or at noon on the day it gives, written YYYY-MM-DD, as --pretend-today asks.
+    pub fn pretending(text: &str, timezone: &Timezone) -> Result<Self, String> {
+        let text = text.trim();
+        let time = NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M")
+            .or_else(|_| NaiveDate::parse_from_str(text, "%Y-%m-%d").map(|day| day.and_hms_opt(12, 0, 0).unwrap_or_default()))
+            .map_err(|_| format!("--pretend-today must be a date written YYYY-MM-DD, or a time written YYYY-MM-DDTHH:MM, but is '{}'", text))?;
+        Ok(Self(timezone.at(time)))
+    }
+}
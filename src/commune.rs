@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use colored::*;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use crate::ast::Program;
+use crate::diagnostic;
+use crate::error::{DivineError, Span};
+use crate::forgiveness::Forgiveness;
+use crate::interpreter::{Interpreter, Value};
+use crate::modules::{self, Scriptorium};
+use crate::{bible, confession, parser, tribulation, DivinePLRuntime, Verbosity};
+
+/// What entries are called in diagnostics, and where their imports are found from.
+const SESSION: &str = "<commune>";
+
+/// Opens an interactive session: each entry is judged and run as soon as its
+/// braces balance, on top of everything entered before it. Lines are kept in
+/// the session's history, recalled with the up arrow; Ctrl-C abandons the
+/// entry being written, or stops the one being run.
+pub fn commune(runtime: &DivinePLRuntime) -> Result<(), String> {
+    println!("{}", "✝️ Commune with DivinePL. Speak :amen to depart, :sins to confess the session, :verse <topic> for scripture.".bright_cyan());
+
+    let mut interpreter = Interpreter::new(runtime, &Program { statements: Vec::new(), functions: Default::default() }, &[]);
+    // Every entry that ran, so :sins can judge the session as one script
+    let mut session = String::new();
+    let mut entry = String::new();
+    let mut editor = DefaultEditor::new().map_err(|e| format!("Failed to open the session: {}", e))?;
+
+    loop {
+        let prompt = if entry.is_empty() { "✝ > " } else { "✝ | " }.bright_blue().to_string();
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                entry.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(format!("Failed to hear the faithful: {}", e)),
+        };
+        if !line.trim().is_empty() {
+            // Only a terminal has history to keep
+            let _ = editor.add_history_entry(line.as_str());
+        }
+
+        if entry.is_empty() {
+            match line.trim().split_once(' ').unwrap_or((line.trim(), "")) {
+                ("", _) => continue,
+                (":amen", _) => break,
+                (":sins", _) => {
+                    let (sins, forgiven) = runtime.confess_source(&session, &runtime.standards_for(Path::new(SESSION))?)?;
+                    confession::report_sins(runtime, &sins, forgiven, SESSION, &session);
+                    continue;
+                }
+                (":verse", topic) => {
+                    if let Err(e) = bible::search_bible(runtime, topic.trim()) {
+                        diagnostic::report_error(&e.to_string(), None);
+                    }
+                    continue;
+                }
+                (command, _) if command.starts_with(':') => {
+                    println!("{}", format!("⚠️ Unknown command {}; try :sins, :verse <topic> or :amen", command).yellow());
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        entry.push_str(&line);
+        entry.push('\n');
+        if unbalanced(&entry) {
+            continue;
+        }
+
+        match tribulation::endure(|| evaluate(runtime, &mut interpreter, &entry)) {
+            Ok(Value::Void) => session.push_str(&entry),
+            Ok(value) => {
+                println!("{}", value.to_string().bright_white());
+                session.push_str(&entry);
+            }
+            Err(e) => diagnostic::report_error(&e.to_string(), e.span(&entry).map(|span| (SESSION, entry.as_str(), span))),
+        }
+        entry.clear();
+    }
+
+    println!("{}", "🕊️ Go in peace.".bright_cyan());
+    Ok(())
+}
+
+/// Judges one entry by the commandments, as run would, then runs it.
+fn evaluate(runtime: &DivinePLRuntime, interpreter: &mut Interpreter, entry: &str) -> Result<Value, DivineError> {
+    let (statements, liturgical_error) = runtime.scan_script(entry);
+    if let Some((line, message)) = liturgical_error {
+        return Err(DivineError::Parse { message, span: Span::find(entry, line, None) });
+    }
+    let standards = runtime.standards_for(Path::new(SESSION))?;
+    runtime.check_commandments(entry, &statements, &Forgiveness::read(entry, runtime.today()), &standards)?;
+    let mut program = parser::parse_program(entry)?;
+    modules::load(&mut program, Path::new(SESSION), &Scriptorium::Disk, runtime.verbosity >= Verbosity::Internals)
+        .map_err(|fault| DivineError::parse(fault, entry))?;
+    interpreter.continue_with(&program).map_err(|fault| DivineError::judgment(fault, entry))
+}
+
+/// Whether an entry has opened more braces than it has closed, outside strings.
+fn unbalanced(entry: &str) -> bool {
+    let depth: i64 = entry.lines()
+        .map(DivinePLRuntime::without_strings)
+        .map(|code| code.matches('{').count() as i64 - code.matches('}').count() as i64)
+        .sum();
+    depth > 0
+}
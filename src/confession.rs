@@ -0,0 +1,571 @@
+//! `divine confess`: the sins found in a script, a directory, an ark or the
+//! blocks of a document, reported as text or JSON, and the sins a range of
+//! commits introduced and resolved.
+
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::ark::Ark;
+use crate::config::{Commandments, CONFIG_FILE_NAME};
+use crate::error::DivineError;
+use crate::git::Repository;
+use crate::{canon, daemon, embedded, environment, walk};
+use crate::{fnv1a, read_scripture, scripture_name, truncate_left, ConfessionFormat, DivinePLRuntime, Embedded, OutputFormat, Rule, Sin, SinSeverity, RULES};
+
+/// The least grave sin that fails a confession.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PenanceThreshold {
+    /// No sin fails it
+    None,
+    /// Any sin fails it
+    Venial,
+    /// Only mortal sins fail it
+    Mortal,
+}
+
+/// The status a confession earns: 2 if a mortal sin was found, 1 if only
+/// venial ones were, and 0 if none were or the threshold forgives them.
+pub fn penance(venial: usize, mortal: usize, threshold: PenanceThreshold) -> i32 {
+    match threshold {
+        PenanceThreshold::Venial | PenanceThreshold::Mortal if mortal > 0 => 2,
+        PenanceThreshold::Venial if venial > 0 => 1,
+        _ => 0,
+    }
+}
+
+/// Prints the explanation of the rule `key` names, or with `list`, a line for
+/// every rule.
+pub fn explain_rules(key: Option<&str>, list: bool) -> Result<(), DivineError> {
+    if list {
+        let width = RULES.iter().map(|rule| rule.name.len()).max().unwrap_or(0);
+        for rule in RULES {
+            println!("{}  {:<width$}  {:<6}  {}", rule.id, rule.name, rule.severity.as_str(), rule.description);
+        }
+        return Ok(());
+    }
+    let Some(key) = key else {
+        return Err(DivineError::Other("ExplainError: name a rule to explain, or pass --list to see them all".to_string()));
+    };
+    let rule = Rule::find(key).ok_or_else(|| DivineError::Other(format!(
+        "ExplainError: no rule is named '{}'; divine confess --explain --list shows them all", key
+    )))?;
+    rule.explain();
+    Ok(())
+}
+
+/// The sins a confession found in one script.
+pub struct Confession {
+    /// The script, as it is named in reports.
+    pub file: String,
+    pub sins: Vec<Sin>,
+    /// How many sins its `// forgiven:` comments set aside.
+    pub forgiven: usize,
+}
+
+impl Confession {
+    /// How many venial and how many mortal sins were found between them.
+    pub fn count(confessions: &[Confession]) -> (usize, usize) {
+        let sins = || confessions.iter().flat_map(|confession| &confession.sins);
+        let venial = sins().filter(|sin| sin.severity == SinSeverity::Venial).count();
+        (venial, sins().count() - venial)
+    }
+    
+    /// How many sins were forgiven between them.
+    pub fn forgiven(confessions: &[Confession]) -> usize {
+        confessions.iter().map(|confession| confession.forgiven).sum()
+    }
+    
+    /// The report of `confess --format json`: a diagnostic for every sin, with
+    /// the fields `file`, `line`, `id`, `rule`, `severity`, `message` and `suggestion`
+    /// (and `culprit`, when one was found), followed by a summary of the counts,
+    /// the counts of each script, and the three rules most often broken.
+    pub fn report(confessions: &[Confession]) -> ConfessionReport {
+        let diagnostics = confessions.iter()
+            .flat_map(|confession| confession.sins.iter().map(|sin| ReportedSin {
+                file: confession.file.clone(),
+                line: sin.line_num,
+                id: sin.rule_id().to_string(),
+                rule: sin.rule.to_string(),
+                severity: sin.severity,
+                message: sin.message.clone(),
+                suggestion: sin.suggestion().to_string(),
+                culprit: sin.culprit.clone(),
+            }))
+            .collect();
+        let rule = |&(id, rule, count): &(&str, &str, usize)| RuleCount { id: id.to_string(), rule: rule.to_string(), count };
+        let scripts = confessions.iter().map(|confession| {
+            let (venial, mortal) = Self::count(std::slice::from_ref(confession));
+            ScriptSummary {
+                file: confession.file.clone(),
+                venial,
+                mortal,
+                forgiven: confession.forgiven,
+                most_common: Sin::most_frequent(&confession.sins).first().map(rule),
+            }
+        }).collect();
+        let (venial, mortal) = Self::count(confessions);
+        ConfessionReport {
+            diagnostics,
+            summary: ConfessionSummary {
+                files: confessions.len(),
+                total: venial + mortal,
+                venial,
+                mortal,
+                forgiven: Self::forgiven(confessions),
+                scripts,
+                most_frequent: Sin::most_frequent(confessions.iter().flat_map(|confession| &confession.sins)).iter().take(3).map(rule).collect(),
+            },
+        }
+    }
+}
+
+/// What `confess --format json` prints.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfessionReport {
+    diagnostics: Vec<ReportedSin>,
+    summary: ConfessionSummary,
+}
+
+/// A sin as `confess --format json` reports it.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ReportedSin {
+    file: String,
+    line: usize,
+    id: String,
+    rule: String,
+    severity: SinSeverity,
+    message: String,
+    suggestion: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    culprit: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ConfessionSummary {
+    files: usize,
+    total: usize,
+    venial: usize,
+    mortal: usize,
+    forgiven: usize,
+    scripts: Vec<ScriptSummary>,
+    most_frequent: Vec<RuleCount>,
+}
+
+/// The counts of one script's confession.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ScriptSummary {
+    file: String,
+    venial: usize,
+    mortal: usize,
+    forgiven: usize,
+    most_common: Option<RuleCount>,
+}
+
+/// How many times a rule was broken.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct RuleCount {
+    id: String,
+    rule: String,
+    count: usize,
+}
+
+/// A sin located in a particular file, with a fingerprint that survives line shifts.
+struct TracedSin {
+    file: String,
+    sin: Sin,
+    fingerprint: u64,
+}
+
+impl TracedSin {
+    fn reported(&self) -> TracedReport {
+        TracedReport {
+            file: self.file.clone(),
+            line: self.sin.line_num,
+            severity: self.sin.severity,
+            message: self.sin.message.clone(),
+            fingerprint: format!("{:016x}", self.fingerprint),
+        }
+    }
+}
+
+/// A sin as `confess --between --format json` reports it.
+#[derive(Serialize)]
+struct TracedReport {
+    file: String,
+    line: usize,
+    severity: SinSeverity,
+    message: String,
+    fingerprint: String,
+}
+
+/// What `confess --between --format json` prints.
+#[derive(Serialize)]
+struct BetweenReport<'a> {
+    from: &'a str,
+    to: &'a str,
+    introduced: Vec<TracedReport>,
+    resolved: Vec<TracedReport>,
+    persisting: Vec<TracedReport>,
+    summary: BetweenSummary,
+}
+
+#[derive(Serialize)]
+struct BetweenSummary {
+    introduced: usize,
+    resolved: usize,
+    persisting: usize,
+}
+
+/// Confesses each DivinePL block embedded in a document as a script of its
+/// own, reporting sins at the document's lines. Returns how many sins were found.
+pub fn confess_embedded(runtime: &DivinePLRuntime, path: &Path, embedded: Embedded, format: ConfessionFormat) -> Result<Vec<Confession>, DivineError> {
+    let document = fs::read_to_string(path)
+        .map_err(|e| DivineError::Io(format!("Failed to read the script for confession: {}", e)))?;
+    if let Some(commandments) = Commandments::for_script(path)? {
+        if !commandments.allows_confession() {
+            return Err(format!("Confession is forbidden by {}", commandments.path.display()).into());
+        }
+    }
+    
+    let snippets = match embedded {
+        Embedded::Markdown => embedded::markdown_snippets(&document),
+    };
+    let standards = runtime.standards_for(path)?;
+    let mut sins = Vec::new();
+    let mut forgiven = 0;
+    for snippet in &snippets {
+        let (found, forgiven_here) = runtime.confess_source(&snippet.source, &standards)?;
+        forgiven += forgiven_here;
+        sins.extend(found.into_iter().map(|sin| Sin {
+            line_num: snippet.document_line(sin.line_num),
+            message: snippet.map_message(&sin.message),
+            ..sin
+        }));
+    }
+    
+    if format == ConfessionFormat::Text {
+        if !runtime.quiet() {
+            println!("{}", format!("🙏 Beginning confession ritual for {} embedded blocks... 🙏", snippets.len()).bright_blue());
+        }
+        report_sins(runtime, &sins, forgiven, &path.display().to_string(), &document);
+    }
+    
+    Ok(vec![Confession { file: path.display().to_string(), sins, forgiven }])
+}
+
+/// Confesses every script carried in an ark, returning the sins found.
+fn confess_ark(runtime: &DivinePLRuntime, path: &Path, format: ConfessionFormat) -> Result<Vec<Confession>, DivineError> {
+    let ark = Ark::read(path)?;
+    let origin = PathBuf::from(format!("{}:{}", path.display(), CONFIG_FILE_NAME));
+    let commandments = ark.file(CONFIG_FILE_NAME).map(|config| Commandments::parse(&origin, config)).transpose()?;
+    let standards = runtime.standards(commandments.as_ref());
+    let mut found = Vec::new();
+    for (script, source) in ark.scripts() {
+        let file = format!("{}:{}", path.display(), script);
+        let (sins, forgiven) = runtime.confess_source(source, &standards)?;
+        if format == ConfessionFormat::Text {
+            if !runtime.quiet() {
+                println!("{}", format!("📜 {}", file).underline().bright_blue());
+                println!("{}", "🙏 Beginning confession ritual... 🙏".bright_blue());
+            }
+            report_sins(runtime, &sins, forgiven, &file, source);
+        }
+        found.push(Confession { file, sins, forgiven });
+    }
+    Ok(found)
+}
+
+/// Confesses a script, or each script in an ark, returning the sins found.
+/// Only the text format is reported here.
+pub fn confess_script(runtime: &DivinePLRuntime, path: &Path, daemon_auto: bool, format: ConfessionFormat) -> Result<Vec<Confession>, DivineError> {
+    if Ark::is_ark(path) {
+        return confess_ark(runtime, path, format);
+    }
+    
+    let content = read_scripture(path)
+        .map_err(|e| DivineError::Io(format!("Failed to read the script for confession: {}", e)))?;
+    
+    // Prefer a warm daemon when asked, falling back to confessing in-process. The daemon
+    // judges by the commandments alone, so rules decreed on the command line, or
+    // forgiveness refused, are confessed here.
+    let daemon_confession = if daemon_auto && runtime.decrees.is_empty() && runtime.forgiving {
+        daemon::request_confession(&daemon::default_socket_path(), path, &content)
+    } else {
+        None
+    };
+    
+    let (sins, forgiven) = match daemon_confession {
+        Some(confession) => confession?,
+        None => {
+            let commandments = Commandments::for_script(path)?;
+            if let Some(commandments) = &commandments {
+                if !commandments.allows_confession() {
+                    return Err(format!("Confession is forbidden by {}", commandments.path.display()).into());
+                }
+            }
+            runtime.confess_source(&content, &runtime.standards(commandments.as_ref()))?
+        }
+    };
+    let (sins, excused) = runtime.excuse(path, sins)?;
+    
+    if format == ConfessionFormat::Text {
+        if !runtime.quiet() {
+            println!("{}", "🙏 Beginning confession ritual... 🙏".bright_blue());
+        }
+        report_sins(runtime, &sins, forgiven, &scripture_name(path), &content);
+        if excused > 0 && !runtime.quiet() && !runtime.porcelain {
+            println!("{}", format!("{} sin{} excused by the baseline.",
+                excused, if excused == 1 { " was" } else { "s were" }).italic().bright_cyan());
+        }
+    }
+    
+    Ok(vec![Confession { file: scripture_name(path), sins, forgiven }])
+}
+
+/// Confesses every script under a directory, except those ignored on the
+/// command line or by the commandments' `confess_ignore` list, then tallies
+/// the sins of each and of them all. Returns the sins found.
+pub fn confess_directory(runtime: &DivinePLRuntime, dir: &Path, daemon_auto: bool, ignore: &[String], format: ConfessionFormat) -> Result<Vec<Confession>, DivineError> {
+    let (scripts, skipped) = walk::scripts_heeding(dir, "confess_ignore", ignore)?;
+    if scripts.is_empty() {
+        canon::report_skipped(skipped);
+        return Err(format!("No scripts (*.divine, *.dpl) were found in {}", dir.display()).into());
+    }
+    
+    let mut found = Vec::new();
+    for script in &scripts {
+        if format == ConfessionFormat::Text && !runtime.quiet() {
+            println!("\n{}", format!("📜 {}", script.display()).bright_blue());
+        }
+        found.extend(confess_script(runtime, script, daemon_auto, format)?);
+    }
+    // Quietly, the findings and the tally of them all are enough
+    if format != ConfessionFormat::Text || runtime.quiet() {
+        canon::report_skipped(skipped);
+        return Ok(found);
+    }
+    
+    let tally: Vec<_> = found.iter().map(|confession| {
+        let (venial, mortal) = Confession::count(std::slice::from_ref(confession));
+        let most_common = Sin::most_frequent(&confession.sins).first()
+            .map_or("-".to_string(), |(id, rule, _)| format!("{} {}", id, rule));
+        (confession.file.trim_start_matches("./"), venial, mortal, most_common)
+    }).collect();
+    
+    let common_width = tally.iter().map(|(_, _, _, common)| common.len()).max().unwrap_or(0).max("Most common".len());
+    let mut width = tally.iter().map(|(script, _, _, _)| script.chars().count()).max().unwrap_or(0).max("Total".len());
+    // On a narrow terminal, paths give way from the left, keeping the names of the scripts
+    if let Some((terminal_size::Width(columns), _)) = terminal_size::terminal_size().filter(|_| io::stdout().is_terminal()) {
+        width = width.min((columns as usize).saturating_sub(2 + 6 + 2 + 6 + 2 + common_width).max(12));
+    }
+    let (venial, mortal) = Confession::count(&found);
+    println!("\n{}", "⚖️ THE CONFESSIONS ⚖️".bright_yellow());
+    println!("{}", format!("{:<width$}  {:>6}  {:>6}  {:<common_width$}", "Script", "Venial", "Mortal", "Most common").underline().bright_blue());
+    for (script, v, m, common) in &tally {
+        let row = format!("{:<width$}  {:>6}  {:>6}  {}", truncate_left(script, width), v, m, common);
+        match (v, m) {
+            (0, 0) => println!("{}", row.green()),
+            (_, 0) => println!("{}", row.yellow()),
+            _ => println!("{}", row.bright_red()),
+        }
+    }
+    println!("{}", format!("{:<width$}  {:>6}  {:>6}", "Total", venial, mortal).bold());
+    
+    let frequent = Sin::most_frequent(found.iter().flat_map(|confession| &confession.sins));
+    if !frequent.is_empty() {
+        println!("\n{}", "Repent of these first:".underline().bright_blue());
+        for (id, rule, count) in frequent.iter().take(3) {
+            println!("  {:>4}  {} {}", count, id, rule);
+        }
+    }
+    canon::report_skipped(skipped);
+    
+    Ok(found)
+}
+
+/// Reports every sin found in `source`, each pinned to its line of `path`,
+/// and how many more were forgiven.
+pub fn report_sins(runtime: &DivinePLRuntime, sins: &[Sin], forgiven: usize, path: &str, source: &str) {
+    if runtime.porcelain {
+        for sin in sins {
+            println!("sin {} {} {}:{}", sin.severity.as_str(), sin.rule_id(), path, sin.line_num);
+        }
+        return;
+    }
+    let color = environment::stdout_colored();
+    for sin in sins {
+        match sin.diagnostic(path, source) {
+            Some(diagnostic) => println!("{}\n", diagnostic.render(color)),
+            None => {
+                let label = match sin.severity {
+                    SinSeverity::Venial => "Venial Sin".yellow(),
+                    SinSeverity::Mortal => "Mortal Sin".bright_red(),
+                };
+                println!("{}[{}]: {} - {}", label, sin.rule_id(), sin.line_num, sin.message);
+            }
+        }
+    }
+    
+    if runtime.quiet() {
+        return;
+    }
+    
+    let venial_sins = sins.iter().filter(|s| s.severity == SinSeverity::Venial).count();
+    let mortal_sins = sins.len() - venial_sins;
+    let sins_found = sins.len();
+    
+    // Final judgment
+    if sins_found == 0 {
+        println!("{}", "✝️ Your code is free from sin and ready for divine execution! ✝️".green());
+    } else {
+        let most_often = Sin::most_frequent(sins).first()
+            .map_or(String::new(), |(id, rule, _)| format!(", most often {} {}", id, rule));
+        println!("{}", format!("Found {} sins in your code ({} venial, {} mortal){}.", 
+                            sins_found, venial_sins, mortal_sins, most_often).yellow());
+        
+        if mortal_sins > 0 {
+            println!("{}", "Mortal sins require immediate repentance before execution.".bright_red());
+        } else {
+            println!("{}", "Venial sins can be forgiven with minor modifications.".yellow());
+        }
+        
+        // Suggest penance
+        println!("\n{}", "Suggested Penance:".underline().bright_blue());
+        if venial_sins > 0 {
+            println!("- Replace 'var' with 'let' and add proper blessings to functions");
+            println!("- Avoid infinite loops by adding faithful termination conditions");
+        }
+        if mortal_sins > 0 {
+            println!("- Rename blasphemous variables to virtuous alternatives");
+            println!("- Replace 'try/catch' with 'confess' for proper error handling");
+            println!("- Remove all 'kill' statements and implement graceful process lifecycle");
+        }
+    }
+    if forgiven > 0 {
+        println!("{}", format!("{} sin{} forgiven by // forgiven: comments.",
+            forgiven, if forgiven == 1 { " was" } else { "s were" }).italic().bright_cyan());
+    }
+}
+
+pub fn confess_between(runtime: &DivinePLRuntime, range: &str, pathspec: Option<&Path>, format: OutputFormat) -> Result<(), DivineError> {
+    let (from_rev, to_rev) = range.split_once("..")
+        .ok_or_else(|| format!("'{}' is not a revision range; use REV1..REV2", range))?;
+    let from_rev = if from_rev.is_empty() { "HEAD" } else { from_rev };
+    let to_rev = if to_rev.is_empty() { "HEAD" } else { to_rev };
+    
+    let repo = Repository::discover()?;
+    let from = repo.resolve(from_rev)?;
+    let to = repo.resolve(to_rev)?;
+    
+    let mut files = repo.scripts_at(&from, pathspec)?;
+    files.extend(repo.scripts_at(&to, pathspec)?);
+    files.sort();
+    files.dedup();
+    
+    let mut introduced = Vec::new();
+    let mut resolved = Vec::new();
+    let mut persisting = Vec::new();
+    
+    for file in &files {
+        // A file missing on one side contributes no sins there
+        let before = fingerprinted_sins(runtime, file, repo.show(&from, file).ok())?;
+        let mut after = fingerprinted_sins(runtime, file, repo.show(&to, file).ok())?;
+        
+        // Match findings by rule and content, so unrelated edits that shift lines don't count
+        for old in before {
+            match after.iter().position(|new| new.fingerprint == old.fingerprint) {
+                Some(index) => persisting.push(after.remove(index)),
+                None => resolved.push(old),
+            }
+        }
+        introduced.extend(after);
+    }
+    
+    match format {
+        OutputFormat::Json => {
+            let list = |sins: &[TracedSin]| sins.iter().map(TracedSin::reported).collect();
+            let report = BetweenReport {
+                from: from_rev,
+                to: to_rev,
+                introduced: list(&introduced),
+                resolved: list(&resolved),
+                persisting: list(&persisting),
+                summary: BetweenSummary { introduced: introduced.len(), resolved: resolved.len(), persisting: persisting.len() },
+            };
+            println!("{}", serde_json::to_string(&report).expect("a confession is always serializable"));
+        }
+        OutputFormat::Text => {
+            println!("{}", format!("⚖️ Weighing the sins of {}..{} ⚖️", from_rev, to_rev).bright_blue());
+            
+            let sections = [
+                ("Introduced sins", "+", &introduced),
+                ("Resolved sins", "-", &resolved),
+                ("Persisting sins", "=", &persisting),
+            ];
+            for (title, marker, sins) in sections {
+                println!("\n{}", format!("{} ({}):", title, sins.len()).underline().bright_blue());
+                for traced in sins.iter() {
+                    let label = match traced.sin.severity {
+                        SinSeverity::Venial => "Venial Sin".yellow(),
+                        SinSeverity::Mortal => "Mortal Sin".bright_red(),
+                    };
+                    println!("  {} {}:{} {}[{}] - {}", marker, traced.file, traced.sin.line_num, label, traced.sin.rule_id(), traced.sin.message);
+                }
+            }
+            
+            println!("\nintroduced={} resolved={} persisting={}", introduced.len(), resolved.len(), persisting.len());
+            if introduced.is_empty() {
+                println!("{}", "No new sins were committed in this release. ✝️".green());
+            } else {
+                println!("{}", "This release has strayed; repent of the introduced sins.".yellow());
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Confesses one revision of a file, tagging each sin with a line-independent fingerprint.
+fn fingerprinted_sins(runtime: &DivinePLRuntime, file: &str, content: Option<String>) -> Result<Vec<TracedSin>, DivineError> {
+    let Some(content) = content else {
+        return Ok(Vec::new());
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    
+    Ok(runtime.confess_source(&content, &runtime.standards_for(Path::new(file))?)?.0
+        .into_iter()
+        .map(|sin| {
+            let source_line = lines.get(sin.line_num - 1).map(|l| l.trim()).unwrap_or("");
+            let fingerprint = fnv1a(format!("{}\0{}\0{}", file, sin.message, source_line).as_bytes());
+            TracedSin { file: file.to_string(), sin, fingerprint }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sin(line_num: usize, rule: &str, severity: SinSeverity) -> Sin {
+        Sin { line_num, severity, rule: rule.into(), message: format!("broke {}", rule), culprit: line_num.is_multiple_of(2).then(|| "console.log(\"🙏\")".to_string()) }
+    }
+
+    #[test]
+    fn a_confession_report_round_trips_through_json() {
+        let confessions = [
+            Confession { file: "a.divine".to_string(), sins: vec![sin(1, RULES[0].name, SinSeverity::Venial), sin(2, RULES[1].name, SinSeverity::Mortal)], forgiven: 1 },
+            Confession { file: "dir/b \"quoted\".divine".to_string(), sins: vec![sin(4, RULES[0].name, SinSeverity::Venial)], forgiven: 0 },
+            Confession { file: "clean.divine".to_string(), sins: Vec::new(), forgiven: 0 },
+        ];
+        let report = Confession::report(&confessions);
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<ConfessionReport>(&json).unwrap(), report);
+        assert_eq!(report.summary.total, 3);
+        assert_eq!(report.summary.scripts[2].most_common, None);
+    }
+}
@@ -0,0 +1,343 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde_json::Value;
+use crate::pattern::Pattern;
+use crate::{Rule, SinSeverity};
+
+/// The name of the project configuration file written by `new`.
+pub const CONFIG_FILE_NAME: &str = "commandments.config";
+
+/// Set by --no-config, after which no commandments are discovered.
+static FORGONE: AtomicBool = AtomicBool::new(false);
+
+/// Stops commandments from being discovered for the rest of the process, so
+/// every script is treated as though it were in no project.
+pub fn forgo() {
+    FORGONE.store(true, Ordering::Relaxed);
+}
+
+/// Whether --no-config was given.
+pub fn forgone() -> bool {
+    FORGONE.load(Ordering::Relaxed)
+}
+
+/// A parsed commandments.config file.
+pub struct Commandments {
+    pub path: PathBuf,
+    pub settings: Value,
+    /// The project's own commandments, from `custom_commandments`.
+    pub custom: Vec<CustomCommandment>,
+}
+
+/// A commandment a project writes for itself: a pattern no line may match, or
+/// text every script must contain.
+#[derive(Clone)]
+pub struct CustomCommandment {
+    /// How sins against it are named, in place of a rule identifier.
+    pub id: Arc<str>,
+    pub test: Test,
+    pub severity: SinSeverity,
+    pub message: Arc<str>,
+}
+
+#[derive(Clone)]
+pub enum Test {
+    /// A line matching the pattern is a sin.
+    Forbids(Pattern),
+    /// A script without the text is a sin.
+    MustContain(String),
+}
+
+impl CustomCommandment {
+    /// Reads the custom commandment `entry`, the `index`th of its list.
+    fn read(entry: &Value, index: usize) -> Result<Self, String> {
+        let text = |key: &str| entry.get(key).and_then(Value::as_str);
+        let id = text("id").filter(|id| !id.trim().is_empty())
+            .ok_or_else(|| format!("custom commandment {} has no \"id\"", index + 1))?;
+        if Rule::find(id).is_some() {
+            return Err(format!("custom commandment \"{}\" takes the name of a rule", id));
+        }
+        let test = match (text("pattern"), text("must_contain")) {
+            (Some(pattern), None) => Test::Forbids(Pattern::new(pattern)
+                .map_err(|e| format!("custom commandment \"{}\" has the invalid pattern \"{}\": {}", id, pattern, e))?),
+            (None, Some(needed)) => Test::MustContain(needed.to_string()),
+            _ => return Err(format!("custom commandment \"{}\" needs either a \"pattern\" or \"must_contain\", but not both", id)),
+        };
+        let severity = match text("severity") {
+            None => SinSeverity::Venial,
+            Some(severity) => SinSeverity::from_str(severity)
+                .ok_or_else(|| format!("custom commandment \"{}\" is \"{}\"; it can be \"venial\" or \"mortal\"", id, severity))?,
+        };
+        let message = text("message").map(str::to_string).unwrap_or_else(|| match &test {
+            Test::Forbids(pattern) => format!("Matches the forbidden pattern {}", pattern),
+            Test::MustContain(needed) => format!("Must contain \"{}\"", needed),
+        });
+        Ok(Self { id: id.into(), test, severity, message: message.into() })
+    }
+
+    /// Where `source` breaks the commandment: the line and the text on it to
+    /// blame, or line 1 and nothing for text that is missing.
+    pub fn broken(&self, source: &str) -> Vec<(usize, Option<String>)> {
+        match &self.test {
+            Test::Forbids(pattern) => source.lines().enumerate()
+                .filter_map(|(index, line)| pattern.find(line).map(|(start, end)| (index + 1, Some(line[start..end].to_string()))))
+                .collect(),
+            Test::MustContain(needed) if !source.contains(needed.as_str()) => vec![(1, None)],
+            Test::MustContain(_) => Vec::new(),
+        }
+    }
+}
+
+impl Commandments {
+    /// Walks up from a script's directory looking for the nearest commandments.config.
+    pub fn discover(script_path: &Path) -> Option<PathBuf> {
+        if forgone() {
+            return None;
+        }
+        let start = if script_path.is_dir() {
+            script_path.to_path_buf()
+        } else {
+            script_path.parent().map(Path::to_path_buf).unwrap_or_default()
+        };
+        let start = fs::canonicalize(&start).unwrap_or(start);
+
+        start.ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Loads the commandments at `path`, warning of whatever in them can't
+    /// be heeded: a key that is no commandment, or one of the wrong type.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let commandments = Self::read(path)?;
+        // However the path was reached, each problem is told once
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        for problem in crate::examine::problems(&commandments.settings) {
+            crate::warn_once(format!("⚠️ Warning: {} (in {}); it is not heeded", problem, path.display()));
+        }
+        Ok(commandments)
+    }
+
+    /// Loads the commandments at `path` without a word about them.
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read the commandments at {}: {}", path.display(), e))?;
+        Self::parse(path, &content)
+    }
+
+    /// Parses the commandments in `content`, which were found at `path`.
+    pub fn parse(path: &Path, content: &str) -> Result<Self, String> {
+        let settings: Value = serde_json::from_str(content)
+            .map_err(|e| format!("The commandments are corrupted: {} (in {})", e, path.display()))?;
+        let custom = match settings.get("custom_commandments") {
+            Some(entries) => entries.as_array()
+                .ok_or_else(|| "custom_commandments must be a list".to_string())
+                .and_then(|entries| entries.iter().enumerate().map(|(index, entry)| CustomCommandment::read(entry, index)).collect())
+                .map_err(|e| format!("The commandments are corrupted: {} (in {})", e, path.display()))?,
+            None => Vec::new(),
+        };
+
+        Ok(Self { path: path.to_path_buf(), settings, custom })
+    }
+
+    /// Loads the commandments governing a script, if the script lives in a project.
+    pub fn for_script(script_path: &Path) -> Result<Option<Self>, String> {
+        Self::discover(script_path).map(|path| Self::load(&path)).transpose()
+    }
+
+    fn flag(&self, key: &str, default: bool) -> bool {
+        self.settings.get(key).and_then(Value::as_bool).unwrap_or(default)
+    }
+
+    pub fn allows_confession(&self) -> bool {
+        self.flag("allow_confession", true)
+    }
+
+    /// Whether the project keeps the Sabbath; `"sabbath_mode": false` lets it
+    /// run on Sundays.
+    pub fn keeps_sabbath(&self) -> bool {
+        self.flag("sabbath_mode", true)
+    }
+
+    pub fn allows_resurrection(&self) -> bool {
+        self.flag("resurrection_enabled", true)
+    }
+
+    pub fn allows_miracles(&self) -> bool {
+        self.flag("miracles_enabled", true)
+    }
+
+    /// The verse packs `verse_packs` names, relative to the commandments.
+    pub fn verse_packs(&self) -> Vec<PathBuf> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        self.settings.get("verse_packs").and_then(Value::as_array).into_iter().flatten()
+            .filter_map(Value::as_str)
+            .map(|pack| dir.join(pack))
+            .collect()
+    }
+
+    /// Whether `"revelation_level": "deep"` asks for more divine insight.
+    pub fn deep_revelation(&self) -> bool {
+        self.settings.get("revelation_level").and_then(Value::as_str) == Some("deep")
+    }
+}
+
+/// What the value of a commandment must be.
+pub enum Kind {
+    Bool,
+    Number,
+    Text,
+    /// One of these strings.
+    Choice(&'static [&'static str]),
+    /// A list of strings.
+    List,
+    /// An object holding these commandments.
+    Table(&'static [Commandment]),
+    /// An object mapping rule identifiers, or names, to one of these strings.
+    Rules(&'static [&'static str]),
+    /// A list of custom commandments.
+    Custom,
+    /// An object mapping days, written MM-DD or YYYY-MM-DD, to names.
+    Dates,
+}
+
+/// What a commandment is taken to be when it isn't given.
+pub enum Fallback {
+    Bool(bool),
+    Number(f64),
+    Text(&'static str),
+    EmptyList,
+    EmptyTable,
+}
+
+/// A setting commandments.config may hold.
+pub struct Commandment {
+    pub key: &'static str,
+    pub kind: Kind,
+    /// `None` when a missing commandment means there is nothing to heed.
+    pub fallback: Option<Fallback>,
+    pub description: &'static str,
+    /// Whether the table holding the commandment must give it.
+    pub required: bool,
+}
+
+const fn commandment(key: &'static str, kind: Kind, fallback: Option<Fallback>, description: &'static str) -> Commandment {
+    Commandment { key, kind, fallback, description, required: false }
+}
+
+const TRINITY: &[Commandment] = &[
+    Commandment { key: "father", kind: Kind::Text, fallback: None, description: "The alias of holy_trinity/father.divine", required: true },
+    Commandment { key: "son", kind: Kind::Text, fallback: None, description: "The alias of holy_trinity/son.divine", required: true },
+    Commandment { key: "holy_ghost", kind: Kind::Text, fallback: None, description: "The alias of holy_trinity/holy_ghost.divine", required: true },
+];
+
+const DIVINE_INSIGHTS: &[Commandment] = &[
+    commandment("enabled", Kind::Bool, None, "Whether divine insights are offered"),
+    commandment("frequency", Kind::Text, None, "How often divine insights are offered"),
+];
+
+const BLESSING_REQUIREMENTS: &[Commandment] = &[
+    commandment("functions", Kind::Bool, None, "Whether functions must be blessed"),
+    commandment("classes", Kind::Bool, None, "Whether classes must be blessed"),
+    commandment("modules", Kind::Bool, None, "Whether modules must be blessed"),
+];
+
+const LENT: &[Commandment] = &[
+    Commandment { key: "from", kind: Kind::Text, fallback: None, description: "The first day of Lent, MM-DD or YYYY-MM-DD", required: true },
+    Commandment { key: "to", kind: Kind::Text, fallback: None, description: "The last day of Lent, MM-DD or YYYY-MM-DD", required: true },
+];
+
+const PROPHECY: &[Commandment] = &[
+    commandment("max_statements", Kind::Number, Some(Fallback::Number(crate::omens::DEFAULT_MAX_STATEMENTS as f64)),
+        "How many statements a function may span before a refactoring is foretold"),
+    commandment("max_nesting", Kind::Number, Some(Fallback::Number(crate::omens::DEFAULT_MAX_NESTING as f64)),
+        "How many blocks deep a function may nest before a prophecy is made about it"),
+    commandment("max_params", Kind::Number, Some(Fallback::Number(crate::omens::DEFAULT_MAX_PARAMS as f64)),
+        "How many parameters a function may take before a prophecy is made about it"),
+    commandment("max_callees", Kind::Number, Some(Fallback::Number(crate::omens::DEFAULT_MAX_CALLEES as f64)),
+        "How many different functions a function may call before a prophecy is made about it"),
+    commandment("stale_days", Kind::Number, Some(Fallback::Number(crate::omens::DEFAULT_STALE_DAYS as f64)),
+        "How many days a declared TODO or prophecy may stand in the ledger before it adds to the doom"),
+    commandment("doom", Kind::Table(DOOM), None, "How much doom each kind of prophecy adds, for --max-doom"),
+];
+
+const DOOM: &[Commandment] = &[
+    commandment("unvalidated_data", Kind::Number, Some(Fallback::Number(3.0)), "Data that is never validated"),
+    commandment("endless_loop", Kind::Number, Some(Fallback::Number(2.0)), "A while with no break"),
+    commandment("statements", Kind::Number, Some(Fallback::Number(2.0)), "A function spanning more than max_statements statements"),
+    commandment("nesting", Kind::Number, Some(Fallback::Number(2.0)), "A function nesting deeper than max_nesting"),
+    commandment("params", Kind::Number, Some(Fallback::Number(1.0)), "A function taking more than max_params parameters"),
+    commandment("callees", Kind::Number, Some(Fallback::Number(1.0)), "A function calling more than max_callees functions"),
+    commandment("uncovenanted_let", Kind::Number, Some(Fallback::Number(1.0)), "A let of a literal that is never assigned again"),
+    commandment("unmodularized", Kind::Number, Some(Fallback::Number(1.0)), "A script of over 100 lines with no module"),
+    commandment("stale_declaration", Kind::Number, Some(Fallback::Number(1.0)), "A declared TODO or prophecy pending past stale_days"),
+];
+
+/// Every setting commandments.config may hold, in the order they are shown.
+pub const COMMANDMENTS: &[Commandment] = &[
+    commandment("trinity", Kind::Table(TRINITY), None,
+        "Aliases for the modules of the trinity by role, so that import module \"main\" loads holy_trinity/father.divine when father is \"main\""),
+    commandment("sabbath_mode", Kind::Bool, Some(Fallback::Bool(true)), "Whether the project keeps the Sabbath; false lets it run on Sundays"),
+    commandment("sabbath_day", Kind::Choice(crate::sabbath::DAYS), Some(Fallback::Text("sun")), "The day kept as the Sabbath"),
+    commandment("sabbath_timezone", Kind::Text, Some(Fallback::Text("local")),
+        "The timezone the Sabbath is reckoned in: \"local\", \"utc\" or an IANA name such as \"Europe/London\""),
+    commandment("allow_confession", Kind::Bool, Some(Fallback::Bool(true)), "Whether the project's scripts may be confessed"),
+    commandment("miracles_enabled", Kind::Bool, Some(Fallback::Bool(true)), "Whether runs may perform miracles"),
+    commandment("revelation_level", Kind::Choice(&["normal", "deep"]), Some(Fallback::Text("normal")),
+        "How deep revelation mode goes; deep offers divine insight twice as often"),
+    commandment("feast_days", Kind::Dates, Some(Fallback::EmptyTable),
+        "Feasts kept besides Epiphany, Easter, All Saints and Christmas, by day; venial sins weigh nothing at judgment on a feast"),
+    commandment("lent", Kind::Table(LENT), None,
+        "When Lent is, if not from Ash Wednesday to Holy Saturday; runs in Lent are judged more sternly"),
+    commandment("resurrection_enabled", Kind::Bool, Some(Fallback::Bool(true)),
+        "Whether files are entombed in .sepulchre before a command rewrites them, so divine resurrect can restore them"),
+    commandment("resurrection_keep", Kind::Number, Some(Fallback::Number(crate::sepulchre::DEFAULT_KEEP as f64)),
+        "How many relics of each file the sepulchre keeps"),
+    commandment("prophecy_enabled", Kind::Bool, Some(Fallback::Bool(true)), "Whether prophecies may be made (accepted, but not yet heeded)"),
+    commandment("prophecy", Kind::Table(PROPHECY), None, "How far a function may go before divine prophesy foretells trouble with it"),
+    commandment("divine_insights", Kind::Table(DIVINE_INSIGHTS), None, "How divine insights are offered (accepted, but not yet heeded)"),
+    commandment("allowed_sins", Kind::List, Some(Fallback::EmptyList), "Practices forgiven in the project (accepted, but not yet heeded)"),
+    commandment("forbidden_practices", Kind::List, Some(Fallback::EmptyList), "Practices forbidden in the project (accepted, but not yet heeded)"),
+    commandment("blessing_requirements", Kind::Table(BLESSING_REQUIREMENTS), None, "What must be blessed (accepted, but not yet heeded)"),
+    commandment("sins", Kind::Rules(&["off", "venial", "mortal"]), Some(Fallback::EmptyTable), "How gravely each confession rule is judged, by rule identifier"),
+    commandment("custom_commandments", Kind::Custom, Some(Fallback::EmptyList),
+        "The project's own commandments: each has an id, a pattern no line may match or text every script must_contain, a severity and a message"),
+    commandment("tithe_ignore", Kind::List, Some(Fallback::EmptyList), "Paths, relative to the directory counted, that divine tithe passes over"),
+    commandment("apocrypha", Kind::List, Some(Fallback::EmptyList),
+        "Globs, relative to the commandments, of scripts that directory operations pass over; ! returns one to the canon"),
+    commandment("confess_ignore", Kind::List, Some(Fallback::EmptyList), "Paths, relative to the directory confessed, that divine confess passes over"),
+    commandment("baseline_max_age_days", Kind::Number, None,
+        "How many days a baseline entry excuses its sin before it goes stale; entries never expire without it"),
+    commandment("keep_chronicles", Kind::Bool, Some(Fallback::Bool(false)), "Whether each command is recorded in the chronicles"),
+    commandment("chronicles_max_bytes", Kind::Number, Some(Fallback::Number(crate::chronicles::DEFAULT_MAX_BYTES as f64)),
+        "How many bytes the chronicles may hold before they are rotated"),
+    commandment("verse_packs", Kind::List, Some(Fallback::EmptyList),
+        "Verse packs, relative to the commandments, merged in order over the built-in verses and ~/.divinepl/verses.toml"),
+    commandment("artifacts_keep", Kind::Number, Some(Fallback::Number(crate::artifacts::DEFAULT_KEEP as f64)),
+        "How many artifacts of each kind are kept for each script"),    commandment("dev", Kind::Bool, Some(Fallback::Bool(false)), "Whether runs are in development mode, where neither flag nor DIVINEPL_DEV says"),
+    commandment("override_sabbath", Kind::Bool, Some(Fallback::Bool(false)),
+        "Whether development mode may labor on the Sabbath, where neither flag nor DIVINEPL_OVERRIDE_SABBATH says"),
+    commandment("seed", Kind::Number, None, "The seed of castLots, where neither --seed nor DIVINEPL_SEED gives one"),
+    commandment("fast", Kind::Bool, Some(Fallback::Bool(false)), "Whether dramatic pauses are skipped, where neither --fast nor DIVINEPL_FAST says"),
+    commandment("color", Kind::Choice(&["auto", "always", "never"]), Some(Fallback::Text("auto")),
+        "When output is colored, where no flag or variable says"),
+    commandment("plain_glyphs", Kind::Bool, Some(Fallback::Bool(false)),
+        "Whether emoji are printed as ASCII markers, where no flag, variable or locale says"),
+];
+
+impl Fallback {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Fallback::Bool(value) => Value::Bool(*value),
+            // Whole numbers are written as such, not as 1048576.0
+            Fallback::Number(value) if value.fract() == 0.0 => Value::from(*value as i64),
+            Fallback::Number(value) => Value::from(*value),
+            Fallback::Text(value) => Value::from(*value),
+            Fallback::EmptyList => Value::Array(Vec::new()),
+            Fallback::EmptyTable => Value::Object(Default::default()),
+        }
+    }
+}
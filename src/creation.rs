@@ -0,0 +1,617 @@
+//! How projects come to be: created from a template by `new`, or renamed by
+//! `baptize`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+
+use crate::config::CONFIG_FILE_NAME;
+use crate::error::DivineError;
+use crate::{apocrypha, edit_distance, sepulchre};
+
+/// A starting point for `new`.
+pub struct Template {
+    pub name: &'static str,
+    description: &'static str,
+    /// The files the template writes, with what each is for.
+    files: &'static [(&'static str, &'static str)],
+    pub generate: fn(&str, &Path) -> Result<(), DivineError>,
+}
+
+/// Every template `new` can create a project from; the first is the default.
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "default",
+        description: "A single genesis script with an example testament",
+        files: &[
+            ("genesis.divine", "Main script"),
+            ("genesis_testament.divine", "Testaments"),
+            ("commandments.config", "Configuration"),
+        ],
+        generate: create_default_template,
+    },
+    Template {
+        name: "miracle",
+        description: "Miracles and imported verses, with the trinity of modules",
+        files: &[
+            ("genesis.divine", "Main script"),
+            ("commandments.config", "Configuration"),
+            ("holy_trinity/father.divine", "Module"),
+            ("holy_trinity/son.divine", "Module"),
+            ("holy_trinity/holy_ghost.divine", "Module"),
+        ],
+        generate: create_miracle_template,
+    },
+    Template {
+        name: "prophet",
+        description: "Prophecies and covenants, with the trinity of modules",
+        files: &[
+            ("genesis.divine", "Main script"),
+            ("commandments.config", "Configuration"),
+            ("holy_trinity/father.divine", "Module"),
+            ("holy_trinity/son.divine", "Module"),
+            ("holy_trinity/holy_ghost.divine", "Module"),
+        ],
+        generate: create_prophet_template,
+    },
+];
+
+/// The user's own templates: those in `template_dir` if it was given, else those
+/// in the user's templates directory.
+fn apocryphal_templates(template_dir: Option<&Path>) -> Vec<apocrypha::Apocryphon> {
+    template_dir.map(Path::to_path_buf)
+        .or_else(apocrypha::templates_dir)
+        .map(|dir| apocrypha::discover(&dir))
+        .unwrap_or_default()
+}
+
+pub fn list_templates(template_dir: Option<&Path>) -> Result<(), DivineError> {
+    println!("{}", "📖 Templates for new projects:".bright_blue());
+    for template in TEMPLATES {
+        println!("\n  {}  {}", template.name.bright_white().bold(), template.description);
+        for (file, purpose) in template.files {
+            println!("    |- {}  ({})", file, purpose);
+        }
+    }
+    for template in apocryphal_templates(template_dir) {
+        println!("\n  {}  {}", template.name.bright_white().bold(), format!("(apocryphal) {}", template.dir.display()).italic());
+        for file in template.files().map_err(DivineError::Io)? {
+            println!("    |- {}", file.display());
+        }
+    }
+    Ok(())
+}
+
+/// Replaces each occurrence of `word` in `text` that isn't part of a longer
+/// name, and counts them.
+fn replace_word(text: &str, word: &str, replacement: &str) -> (String, usize) {
+    let is_name = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut count = 0;
+    let mut rest = text;
+    while let Some(at) = rest.find(word) {
+        let (before, after) = (&rest[..at], &rest[at + word.len()..]);
+        let standalone = !before.ends_with(is_name) && !after.starts_with(is_name);
+        out.push_str(before);
+        out.push_str(if standalone { replacement } else { word });
+        count += standalone as usize;
+        rest = after;
+    }
+    out.push_str(rest);
+    (out, count)
+}
+
+/// Creates a project from a template: one of the canon's, or an apocryphal
+/// one from `template_dir` or the user's templates directory. The canon wins
+/// a clash of names unless `template_dir` was given.
+pub fn create_project(name: &str, template: &str, template_dir: Option<&Path>) -> Result<(), DivineError> {
+    let apocrypha = apocryphal_templates(template_dir);
+    let canon = TEMPLATES.iter().find(|t| t.name == template);
+    let apocryphon = apocrypha.iter().find(|t| t.name == template);
+    let template = match (canon, apocryphon) {
+        (_, Some(apocryphon)) if template_dir.is_some() || canon.is_none() => {
+            return create_apocryphal_project(name, apocryphon);
+        }
+        (Some(canon), _) => canon,
+        (None, _) => {
+            let names = TEMPLATES.iter().map(|t| t.name).chain(apocrypha.iter().map(|t| t.name.as_str())).collect::<Vec<_>>();
+            let suggestion = names.iter()
+                .min_by_key(|name| edit_distance(name, template))
+                .filter(|name| edit_distance(name, template) <= name.len() / 2)
+                .map(|name| format!("did you mean '{}'? ", name))
+                .unwrap_or_default();
+            return Err(format!(
+                "Unknown template '{}'; {}The templates are: {} (see new --list-templates)", template, suggestion, names.join(", ")
+            ).into());
+        }
+    };
+    let project_dir = PathBuf::from(name);
+    
+    if project_dir.exists() {
+        return Err(format!("Project '{}' already exists. Creation is sacred, duplication is heresy.", name).into());
+    }
+    
+    // Create project directory
+    fs::create_dir(&project_dir).map_err(|e| DivineError::Io(format!("Failed to create project: {}", e)))?;
+    
+    (template.generate)(name, &project_dir)?;
+    
+    println!("{}", format!("🕊️ New DivinePL project '{}' has been blessed with creation!", name).green());
+    println!("Structure:");
+    println!("- {}/", name);
+    for (file, purpose) in template.files {
+        println!("  |- {}  ({})", file, purpose);
+    }
+    
+    Ok(())
+}
+
+fn create_apocryphal_project(name: &str, template: &apocrypha::Apocryphon) -> Result<(), DivineError> {
+    let project_dir = PathBuf::from(name);
+    
+    if project_dir.exists() {
+        return Err(format!("Project '{}' already exists. Creation is sacred, duplication is heresy.", name).into());
+    }
+    
+    fs::create_dir(&project_dir).map_err(|e| DivineError::Io(format!("Failed to create project: {}", e)))?;
+    let files = template.copy_into(&project_dir, name).map_err(|e| {
+        // A half-copied project is no creation at all
+        let _ = fs::remove_dir_all(&project_dir);
+        DivineError::Io(e)
+    })?;
+    
+    println!("{}", format!("🕊️ New DivinePL project '{}' has been blessed with creation from the apocryphal '{}'!", name, template.name).green());
+    println!("Structure:");
+    println!("- {}/", name);
+    for file in files {
+        println!("  |- {}", file.display());
+    }
+    
+    Ok(())
+}
+
+/// Renames a project's directory and rewrites its old name wherever it appears
+/// in the header comments of genesis.divine and in commandments.config.
+pub fn baptize_project(old_path: &Path, new_name: &str, dry_run: bool) -> Result<(), DivineError> {
+    if new_name.is_empty() || new_name.contains(['/', '\\']) || new_name == "." || new_name == ".." {
+        return Err(format!("BaptismError: '{}' is not a name a project can be given", new_name).into());
+    }
+    let old_dir = fs::canonicalize(old_path)
+        .map_err(|e| DivineError::Io(format!("Failed to find project {}: {}", old_path.display(), e)))?;
+    let genesis = old_dir.join("genesis.divine");
+    let config = old_dir.join(CONFIG_FILE_NAME);
+    if !old_dir.is_dir() || !(genesis.is_file() || config.is_file()) {
+        return Err(format!(
+            "BaptismError: {} is not a DivinePL project; it has neither genesis.divine nor {}", old_path.display(), CONFIG_FILE_NAME
+        ).into());
+    }
+    let old_name = old_dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let new_dir = old_dir.with_file_name(new_name);
+    if new_dir.exists() {
+        return Err(format!("Project '{}' already exists. Creation is sacred, duplication is heresy.", new_name).into());
+    }
+    
+    // The old name is rewritten in the header of genesis and anywhere in the commandments
+    let mut rewrites = Vec::new();
+    if let Ok(source) = fs::read_to_string(&genesis) {
+        let header_len: usize = source.split_inclusive('\n')
+            .take_while(|line| line.trim_start().starts_with("//"))
+            .map(str::len)
+            .sum();
+        let (header, count) = replace_word(&source[..header_len], &old_name, new_name);
+        if count > 0 {
+            rewrites.push(("genesis.divine", format!("{}{}", header, &source[header_len..]), count));
+        }
+    }
+    if let Ok(source) = fs::read_to_string(&config) {
+        let (rewritten, count) = replace_word(&source, &old_name, new_name);
+        if count > 0 {
+            rewrites.push((CONFIG_FILE_NAME, rewritten, count));
+        }
+    }
+    
+    let verb = if dry_run { "Would baptize" } else { "Baptizing" };
+    println!("{}", format!("💧 {} '{}' as '{}'", verb, old_name, new_name).bright_blue());
+    println!("  rename {} → {}", old_dir.display(), new_dir.display());
+    for (file, _, count) in &rewrites {
+        println!("  rewrite {} ({} mention{} of '{}')", file, count, if *count == 1 { "" } else { "s" }, old_name);
+    }
+    if rewrites.is_empty() {
+        println!("  no file mentions '{}'", old_name);
+    }
+    if dry_run {
+        return Ok(());
+    }
+    
+    fs::rename(&old_dir, &new_dir).map_err(|e| DivineError::Io(format!("Failed to rename project: {}", e)))?;
+    for (file, rewritten, _) in &rewrites {
+        sepulchre::entomb(&new_dir.join(file)).map_err(DivineError::Io)?;
+        fs::write(new_dir.join(file), rewritten)
+            .map_err(|e| DivineError::Io(format!("Failed to rewrite {}: {}", file, e)))?;
+        sepulchre::seal(&new_dir.join(file)).map_err(DivineError::Io)?;
+    }
+    println!("{}", format!("🕊️ Project '{}' has been baptized '{}'. Go forth in newness of life.", old_name, new_name).green());
+    Ok(())
+}
+
+fn create_default_template(_name: &str, project_dir: &Path) -> Result<(), DivineError> {
+    // Create main divine file
+    let main_file_path = project_dir.join("genesis.divine");
+    let main_content = r#"// DivinePL - The Holy Programming Experience
+bless Program {
+  genesis() {
+    🙏 Lord, guide this program to righteousness 🙏
+    
+    let light = createLight();
+    let world = new Creation();
+    
+    world.populate(light);
+    
+    // Arguments after `--` fall as manna: divine run genesis.divine -- Moses
+    judge (manna.length > 0) {
+      revelation("Manna has fallen for " + manna[0]);
+    }
+    
+    let faithful = true;
+    judge (faithful) {
+      revelation("The faithful shall see the light");
+    } else {
+      revelation("Even the doubters are loved");
+    }
+    
+    let disciples = createChildProcesses(12);
+    disciples.forEach(disciple => {
+      disciple.spread_gospel();
+    });
+    
+    return salvation;
+  }
+}
+
+bless createLight() {
+  ascend with "light";
+}
+
+bless createChildProcesses(count) {
+  revelation("Sending forth " + count + " disciples");
+  ascend with count;
+}
+"#;
+    fs::write(main_file_path, main_content).map_err(|e| DivineError::Io(format!("Failed to write genesis file: {}", e)))?;
+    
+    // Create an example testament, run with `divine testament`
+    let testament_path = project_dir.join("genesis_testament.divine");
+    let testament_content = r#"// Testaments are verified with: divine testament
+bless tithe(income) {
+  ascend with income / 10;
+}
+
+bless test_tithe_is_a_tenth() {
+  affirm_equal(tithe(500), 50);
+  affirm(tithe(0) == 0, "nothing is owed on nothing");
+}
+
+bless test_light_is_good() {
+  affirm(createLight(), "God saw the light, that it was good");
+}
+"#;
+    fs::write(testament_path, testament_content).map_err(|e| DivineError::Io(format!("Failed to write testament: {}", e)))?;
+    
+    // Create commandments (config) file
+    let config_path = project_dir.join("commandments.config");
+    let config_content = r#"{
+  "trinity": {
+    "father": "main",
+    "son": "child_processes",
+    "holy_ghost": "background_services"
+  },
+  "sabbath_mode": true,
+  "resurrection_enabled": true,
+  "allow_confession": true
+}
+"#;
+    fs::write(config_path, config_content).map_err(|e| DivineError::Io(format!("Failed to write commandments: {}", e)))?;
+    
+    Ok(())
+}
+
+fn create_miracle_template(_name: &str, project_dir: &Path) -> Result<(), DivineError> {
+    // Create main miracle file
+    let main_file_path = project_dir.join("genesis.divine");
+    let main_content = r#"// DivinePL - Divine Miracle Template
+import verse "creation";
+import verse "light";
+
+🙏 BEGIN PRAYER 🙏
+Lord, grant this code the power to transform and heal
+Guide my keystrokes with divine wisdom
+Let miracles flow through these functions
+🙏 END PRAYER 🙏
+
+miracle Program {
+  genesis() {
+    let light = createDivineLight();
+    
+    // This miracle transforms simple data into revelation
+    miracle transform(data) {
+      return data.map(item => {
+        item.blessed = true;
+        item.purified = removeImpurities(item);
+        return item;
+      });
+    }
+    
+    // Healing miracles for corrupted data
+    miracle heal(brokenSystem) {
+      covenant("This system shall be restored");
+      
+      brokenSystem.restoreFromBackup();
+      brokenSystem.cleanse();
+      fulfill("This system shall be restored");
+      
+      revelation("System has been restored through divine intervention");
+      return brokenSystem;
+    }
+    
+    return salvation;
+  }
+}
+
+bless createDivineLight() {
+  ascend with "divine light";
+}
+"#;
+    fs::write(main_file_path, main_content).map_err(|e| DivineError::Io(format!("Failed to write genesis file: {}", e)))?;
+    
+    // Create commandments (config) file
+    let config_path = project_dir.join("commandments.config");
+    let config_content = r#"{
+  "trinity": {
+    "father": "main",
+    "son": "child_processes",
+    "holy_ghost": "background_services"
+  },
+  "sabbath_mode": true,
+  "resurrection_enabled": true,
+  "allow_confession": true,
+  "miracles_enabled": true
+}
+"#;
+    fs::write(config_path, config_content).map_err(|e| DivineError::Io(format!("Failed to write commandments: {}", e)))?;
+    
+    // Create Holy Trinity directory structure
+    let trinity_dir = project_dir.join("holy_trinity");
+    fs::create_dir(&trinity_dir).map_err(|e| DivineError::Io(format!("Failed to create holy trinity directory: {}", e)))?;
+    
+    // Create Father module
+    let father_path = trinity_dir.join("father.divine");
+    let father_content = r#"// The Father - Source of all creation
+bless FatherModule {
+  createAll() {
+    return {
+      light: true,
+      earth: true,
+      heaven: true,
+      life: true
+    };
+  }
+  
+  miracle resurrection(deadCode) {
+    // Only the Father can resurrect dead code
+    return deadCode.restore();
+  }
+}
+"#;
+    fs::write(father_path, father_content).map_err(|e| DivineError::Io(format!("Failed to write father module: {}", e)))?;
+    
+    // Create Son module
+    let son_path = trinity_dir.join("son.divine");
+    let son_content = r#"// The Son - Salvation for humanity
+bless SonModule {
+  saveBrokenCode(code) {
+    // Takes the sins of the code upon itself
+    let errors = code.findAllErrors();
+    return this.redeemErrors(errors, code);
+  }
+  
+  redeemErrors(errors, code) {
+    errors.forEach(error => {
+      confession(error);
+      forgive(error);
+    });
+    return code.purified();
+  }
+  
+  miracle healProcess(process) {
+    if (process.isDying) {
+      process.resurrect();
+      return true;
+    }
+    return false;
+  }
+}
+"#;
+    fs::write(son_path, son_content).map_err(|e| DivineError::Io(format!("Failed to write son module: {}", e)))?;
+    
+    // Create Holy Ghost module
+    let holy_ghost_path = trinity_dir.join("holy_ghost.divine");
+    let holy_ghost_content = r#"// The Holy Ghost - Divine guidance and inspiration
+bless HolyGhostModule {
+  inspire(developer) {
+    // Fill the developer with divine inspiration
+    developer.productivity *= 3;
+    developer.errors /= 2;
+    developer.creativity += 10;
+  }
+  
+  guideCoding(codebase) {
+    // Analyze and provide divine guidance
+    revelation(codebase.analyze());
+    
+    return this.offerInsights(codebase);
+  }
+  
+  miracle tongues(code) {
+    // Translate code between programming languages
+    return code.translateTo("DivinePL");
+  }
+}
+"#;
+    fs::write(holy_ghost_path, holy_ghost_content).map_err(|e| DivineError::Io(format!("Failed to write holy ghost module: {}", e)))?;
+    
+    Ok(())
+}
+
+fn create_prophet_template(_name: &str, project_dir: &Path) -> Result<(), DivineError> {
+    // Create main prophet file
+    let main_file_path = project_dir.join("genesis.divine");
+    let main_content = r#"// DivinePL - Divine Prophet Template
+import verse "wisdom";
+import verse "promise";
+
+🙏 BEGIN PRAYER 🙏
+Grant me the vision to see beyond the present code
+Let future bugs be revealed before they manifest
+Guide this project through the fog of development
+🙏 END PRAYER 🙏
+
+bless Program {
+  genesis() {
+    let vision = seekVision();
+    let prophecies = analyze(vision);
+    
+    @prophesy("Future optimization required")
+    bless dataProcessor(data) {
+      covenant("Every record shall be marked as processed");
+      let processed = data + ", processed";
+      fulfill("Every record shall be marked as processed");
+      ascend with processed;
+    }
+    revelation(dataProcessor(prophecies));
+    
+    // Predict future errors and provide guidance
+    revelation("Security vulnerabilities shall arise in v1.2");
+    covenant("Input validation shall be added before release");
+    
+    let roadmap = prophesy(3); // Look 3 versions ahead
+    revelation("The roadmap holds " + roadmap + " releases");
+    
+    // Every covenant must be fulfilled before judgment day
+    fulfill("Input validation shall be added before release");
+    return roadmap;
+  }
+}
+
+bless seekVision() {
+  ascend with "a vision of version 2.0";
+}
+
+bless analyze(vision) {
+  ascend with "Prophecies drawn from " + vision;
+}
+
+// Each version is foretold before the versions that follow it
+bless prophesy(versions) {
+  judge (versions == 0) {
+    ascend with 0;
+  }
+  revelation("Version " + versions + " is foretold");
+  ascend with 1 + prophesy(versions - 1);
+}
+"#;
+    fs::write(main_file_path, main_content).map_err(|e| DivineError::Io(format!("Failed to write genesis file: {}", e)))?;
+    
+    // Create commandments (config) file
+    let config_path = project_dir.join("commandments.config");
+    let config_content = r#"{
+  "trinity": {
+    "father": "main",
+    "son": "child_processes",
+    "holy_ghost": "background_services"
+  },
+  "sabbath_mode": true,
+  "resurrection_enabled": true,
+  "allow_confession": true,
+  "prophecy_enabled": true,
+  "revelation_level": "deep"
+}
+"#;
+    fs::write(config_path, config_content).map_err(|e| DivineError::Io(format!("Failed to write commandments: {}", e)))?;
+    
+    // Create Holy Trinity directory structure
+    let trinity_dir = project_dir.join("holy_trinity");
+    fs::create_dir(&trinity_dir).map_err(|e| DivineError::Io(format!("Failed to create holy trinity directory: {}", e)))?;
+    
+    // Create Father module
+    let father_path = trinity_dir.join("father.divine");
+    let father_content = r#"// The Father - Eternal vision and wisdom
+bless FatherModule {
+  providePlan() {
+    return {
+      version1: "Foundation",
+      version2: "Growth",
+      version3: "Enlightenment"
+    };
+  }
+  
+  @prophesy("Will need to update dependencies")
+  revelation(message) {
+    // Record divine insights for future generations
+    log.divineInsight(message);
+  }
+}
+"#;
+    fs::write(father_path, father_content).map_err(|e| DivineError::Io(format!("Failed to write father module: {}", e)))?;
+    
+    // Create Son module
+    let son_path = trinity_dir.join("son.divine");
+    let son_content = r#"// The Son - Implementation of the divine plan
+bless SonModule {
+  implementPlan(plan) {
+    covenant("This plan shall be fulfilled");
+    
+    @prophesy("Will require refactoring in version 2")
+    bless executePhase(phase) {
+      // Implementation details
+      return phase.complete();
+    }
+    
+    revelation("Testing will reveal hidden bugs");
+    return plan.fulfilled();
+  }
+}
+"#;
+    fs::write(son_path, son_content).map_err(|e| DivineError::Io(format!("Failed to write son module: {}", e)))?;
+    
+    // Create Holy Ghost module
+    let holy_ghost_path = trinity_dir.join("holy_ghost.divine");
+    let holy_ghost_content = r#"// The Holy Ghost - Guidance and future insights
+bless HolyGhostModule {
+  revealFuture(project) {
+    // Prophetic insights into the future of the codebase
+    let prophecies = [];
+    
+    revelation("Technical debt will accumulate in module X");
+    revelation("New requirements will conflict with current architecture");
+    revelation("A more efficient algorithm will be discovered");
+    
+    @prophesy("Will need more comprehensive documentation")
+    return prophecies;
+  }
+  
+  guideDevelopment(team) {
+    // Provide spiritual guidance to the development team
+    team.forEach(developer => {
+      developer.inspireWithVision();
+      developer.grantWisdom();
+    });
+    
+    covenant("The team shall be guided to righteous development practices");
+  }
+}
+"#;
+    fs::write(holy_ghost_path, holy_ghost_content).map_err(|e| DivineError::Io(format!("Failed to write holy ghost module: {}", e)))?;
+    
+    Ok(())
+}
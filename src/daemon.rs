@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Commandments;
+use crate::{DivinePLRuntime, Sin, Standards};
+
+/// Clients and daemons only talk to each other when built from the same version.
+pub const PROTOCOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The socket used when neither `--socket` nor `DIVINEPL_SOCKET` says otherwise;
+/// on Windows, a named pipe.
+pub fn default_socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("DIVINEPL_SOCKET") {
+        return PathBuf::from(path);
+    }
+    let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_else(|_| "pilgrim".to_string());
+    if cfg!(windows) {
+        return PathBuf::from(format!(r"\\.\pipe\divinepl-{}", user));
+    }
+    std::env::temp_dir().join(format!("divinepl-{}.sock", user))
+}
+
+/// How long a pilgrim may stay silent mid-conversation before the daemon stops listening to it.
+#[cfg(unix)]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Warm state kept alive between requests, shared by every pilgrim's thread.
+struct Sanctuary<'a> {
+    runtime: &'a DivinePLRuntime,
+    commandments: Mutex<HashMap<PathBuf, (Option<SystemTime>, Commandments)>>,
+}
+
+/// A line a pilgrim sends the daemon.
+#[derive(Serialize, Deserialize)]
+struct Request {
+    cmd: String,
+    /// The version of the pilgrim, which must be the daemon's own.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    version: Option<String>,
+    /// The script the source was read from, whose commandments judge it.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    source: Option<String>,
+}
+
+/// The line the daemon answers a request with.
+#[derive(Serialize, Deserialize)]
+struct Response {
+    ok: bool,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    findings: Option<Vec<Sin>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    forgiven: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    error: Option<String>,
+}
+
+impl Response {
+    fn ok() -> Self {
+        Self { ok: true, version: PROTOCOL_VERSION.to_string(), findings: None, forgiven: None, error: None }
+    }
+
+    fn error(error: impl Into<String>) -> Self {
+        Self { ok: false, error: Some(error.into()), ..Self::ok() }
+    }
+}
+
+impl std::fmt::Display for Response {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&serde_json::to_string(self).map_err(|_| std::fmt::Error)?)
+    }
+}
+
+/// What the daemon should do after answering a request.
+enum Next {
+    Continue,
+    Depart,
+}
+
+impl Sanctuary<'_> {
+    /// Returns the standards a script is judged by, reloading its commandments
+    /// whenever the file on disk changes.
+    fn standards_for(&self, script_path: &Path) -> Result<Standards, String> {
+        let Some(config_path) = Commandments::discover(script_path) else {
+            return Ok(self.runtime.standards(None));
+        };
+        let modified = std::fs::metadata(&config_path).and_then(|m| m.modified()).ok();
+
+        let mut cache = self.commandments.lock().unwrap_or_else(PoisonError::into_inner);
+        let stale = match cache.get(&config_path) {
+            Some((cached, _)) => *cached != modified || modified.is_none(),
+            None => true,
+        };
+        if stale {
+            let commandments = Commandments::load(&config_path)?;
+            cache.insert(config_path.clone(), (modified, commandments));
+        }
+
+        let (_, commandments) = &cache[&config_path];
+        if !commandments.allows_confession() {
+            return Err(format!("Confession is forbidden by {}", commandments.path.display()));
+        }
+        Ok(self.runtime.standards(Some(commandments)))
+    }
+
+    /// Confesses a request's source by its script's standards, forgiving what
+    /// the script forgives. Returns the sins left and how many were forgiven.
+    fn confess(&self, request: &Request) -> Result<(Vec<Sin>, usize), String> {
+        let source = request.source.as_deref().ok_or("request is missing its \"source\"")?;
+
+        let standards = match &request.path {
+            Some(path) => self.standards_for(Path::new(path))?,
+            None => self.runtime.standards(None),
+        };
+        self.runtime.confess_source(source, &standards)
+    }
+
+    fn answer(&self, line: &str) -> (Response, Next) {
+        let request: Request = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return (Response::error(format!("malformed request: {}", e)), Next::Continue),
+        };
+
+        if request.version.as_deref().is_some_and(|version| version != PROTOCOL_VERSION) {
+            return (Response::error("version mismatch"), Next::Depart);
+        }
+
+        match request.cmd.as_str() {
+            "ping" => (Response::ok(), Next::Continue),
+            "shutdown" => (Response::ok(), Next::Depart),
+            "check" | "confess" => match self.confess(&request) {
+                Ok((sins, forgiven)) => (Response { findings: Some(sins), forgiven: Some(forgiven), ..Response::ok() }, Next::Continue),
+                Err(e) => (Response::error(e), Next::Continue),
+            },
+            other => (Response::error(format!("unknown command '{}'", other)), Next::Continue),
+        }
+    }
+}
+
+#[cfg(any(unix, windows))]
+pub fn serve(runtime: &DivinePLRuntime, socket: &Path, idle_timeout: Duration) -> Result<(), String> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Instant;
+
+    let door = door::Door::open(socket)?;
+
+    println!("{}", format!("🕯️ DivinePL daemon keeping vigil at {}", door.name()).bright_blue());
+
+    let sanctuary = Sanctuary { runtime, commandments: Mutex::new(HashMap::new()) };
+    let last_activity = Mutex::new(Instant::now());
+    let pilgrims = AtomicUsize::new(0);
+    let departing = AtomicBool::new(false);
+    let touch = || *last_activity.lock().unwrap_or_else(PoisonError::into_inner) = Instant::now();
+
+    let vigil = std::thread::scope(|scope| {
+        while !departing.load(Ordering::SeqCst) {
+            let Some((stream, mut writer)) = door.admit()? else {
+                let idle = last_activity.lock().unwrap_or_else(PoisonError::into_inner).elapsed();
+                if pilgrims.load(Ordering::SeqCst) == 0 && idle >= idle_timeout {
+                    println!("{}", "The daemon has rested long enough and departs in peace.".bright_blue());
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+                continue;
+            };
+
+            // Each pilgrim is heard on its own thread, so a slow one keeps no other waiting
+            pilgrims.fetch_add(1, Ordering::SeqCst);
+            touch();
+            let (sanctuary, door, pilgrims, departing, touch) = (&sanctuary, &door, &pilgrims, &departing, &touch);
+            scope.spawn(move || {
+                for line in BufReader::new(stream).lines() {
+                    let Ok(line) = line else { break };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    let (response, next) = sanctuary.answer(&line);
+                    touch();
+                    if matches!(next, Next::Depart) {
+                        // Release the door first so a replacement daemon can open it immediately
+                        door.release();
+                        departing.store(true, Ordering::SeqCst);
+                    }
+                    if writeln!(writer, "{}", response).is_err() || matches!(next, Next::Depart) {
+                        break;
+                    }
+                }
+                touch();
+                pilgrims.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        Ok(())
+    });
+
+    // A departure already released the door, which a replacement may hold by now
+    if !departing.load(Ordering::SeqCst) {
+        door.release();
+    }
+    vigil
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn serve(_runtime: &DivinePLRuntime, _socket: &Path, _idle_timeout: Duration) -> Result<(), String> {
+    Err("The DivinePL daemon is not yet available on this platform".to_string())
+}
+
+/// Where pilgrims reach the daemon: a Unix socket.
+#[cfg(unix)]
+mod door {
+    use std::io::ErrorKind;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::time::Duration;
+
+    use super::REQUEST_TIMEOUT;
+
+    pub struct Door<'a> {
+        listener: UnixListener,
+        socket: &'a Path,
+    }
+
+    impl<'a> Door<'a> {
+        pub fn open(socket: &'a Path) -> Result<Self, String> {
+            if socket.exists() {
+                if UnixStream::connect(socket).is_ok() {
+                    return Err(format!("A daemon already keeps vigil at {}", socket.display()));
+                }
+                // Nobody is listening; the socket was left behind by a departed daemon
+                std::fs::remove_file(socket).map_err(|e| format!("Failed to clear stale socket: {}", e))?;
+            }
+
+            let listener = UnixListener::bind(socket)
+                .map_err(|e| format!("Failed to bind the daemon socket: {}", e))?;
+            listener.set_nonblocking(true)
+                .map_err(|e| format!("Failed to configure the daemon socket: {}", e))?;
+            Ok(Self { listener, socket })
+        }
+
+        pub fn name(&self) -> String {
+            self.socket.display().to_string()
+        }
+
+        /// The next pilgrim waiting to be heard, as a stream to read and one to
+        /// answer on, or `None` when nobody is.
+        pub fn admit(&self) -> Result<Option<(UnixStream, UnixStream)>, String> {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(None),
+                Err(e) => return Err(format!("Failed to accept a pilgrim: {}", e)),
+            };
+            // A pilgrim that goes silent is let go rather than heard forever
+            let _ = stream.set_nonblocking(false);
+            let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+            let Ok(writer) = stream.try_clone() else { return Ok(None) };
+            Ok(Some((stream, writer)))
+        }
+
+        /// Gives up the socket, so that a replacement daemon can bind it.
+        pub fn release(&self) {
+            let _ = std::fs::remove_file(self.socket);
+        }
+    }
+
+    pub fn knock(socket: &Path) -> Option<UnixStream> {
+        let stream = UnixStream::connect(socket).ok()?;
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(30)));
+        Some(stream)
+    }
+}
+
+/// Where pilgrims reach the daemon: a named pipe, with a fresh instance kept
+/// listening for the next pilgrim while the others are heard.
+#[cfg(windows)]
+mod door {
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::{AsRawHandle, FromRawHandle};
+    use std::path::{Path, PathBuf};
+    use std::sync::{Mutex, PoisonError};
+
+    use windows_sys::Win32::Foundation::{ERROR_ACCESS_DENIED, ERROR_NO_DATA, ERROR_PIPE_CONNECTED, ERROR_PIPE_LISTENING, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX};
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, SetNamedPipeHandleState, PIPE_NOWAIT, PIPE_READMODE_BYTE,
+        PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    pub struct Door {
+        pipe: PathBuf,
+        /// The pipe's name as Windows wants it: wide and NUL-terminated.
+        wide: Vec<u16>,
+        /// The instance listening for the next pilgrim, until the door is released.
+        waiting: Mutex<Option<File>>,
+    }
+
+    /// The pipe a daemon at `socket` listens on: `socket` itself when it names a
+    /// pipe, or else one named after it.
+    fn pipe_name(socket: &Path) -> PathBuf {
+        let path = socket.to_string_lossy();
+        if path.starts_with(r"\\.\pipe\") {
+            return socket.to_path_buf();
+        }
+        PathBuf::from(format!(r"\\.\pipe\{}", path.replace(['\\', '/', ':'], "-")))
+    }
+
+    /// Opens a new instance of the pipe, in non-blocking mode so that the
+    /// vigil can wait for a pilgrim the way it waits on a Unix socket.
+    fn instance(wide: &[u16], first: bool) -> io::Result<File> {
+        let flags = PIPE_ACCESS_DUPLEX | if first { FILE_FLAG_FIRST_PIPE_INSTANCE } else { 0 };
+        // SAFETY: `wide` is NUL-terminated, and the handle returned is owned by the file alone
+        unsafe {
+            let handle = CreateNamedPipeW(
+                wide.as_ptr(),
+                flags,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_NOWAIT,
+                PIPE_UNLIMITED_INSTANCES,
+                4096,
+                4096,
+                0,
+                std::ptr::null(),
+            );
+            if handle == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(File::from_raw_handle(handle))
+        }
+    }
+
+    impl Door {
+        pub fn open(socket: &Path) -> Result<Self, String> {
+            let pipe = pipe_name(socket);
+            let wide: Vec<u16> = pipe.as_os_str().encode_wide().chain(Some(0)).collect();
+            // Only the first instance may claim the name, so another daemon already holds it
+            let first = instance(&wide, true).map_err(|e| match e.raw_os_error() {
+                Some(code) if code as u32 == ERROR_ACCESS_DENIED => format!("A daemon already keeps vigil at {}", pipe.display()),
+                _ => format!("Failed to open the daemon pipe: {}", e),
+            })?;
+            Ok(Self { pipe, wide, waiting: Mutex::new(Some(first)) })
+        }
+
+        pub fn name(&self) -> String {
+            self.pipe.display().to_string()
+        }
+
+        /// The next pilgrim waiting to be heard, as a stream to read and one to
+        /// answer on, or `None` when nobody is.
+        ///
+        /// Windows has no read timeout for a pipe, so a pilgrim that goes silent
+        /// is heard until it leaves.
+        pub fn admit(&self) -> Result<Option<(File, File)>, String> {
+            let mut waiting = self.waiting.lock().unwrap_or_else(PoisonError::into_inner);
+            let Some(handle) = waiting.as_ref().map(|pipe| pipe.as_raw_handle()) else { return Ok(None) };
+
+            // SAFETY: `handle` belongs to the waiting instance, which stays open while locked
+            if unsafe { ConnectNamedPipe(handle, std::ptr::null_mut()) } != 0 {
+                // The instance has just begun to listen
+                return Ok(None);
+            }
+            let error = io::Error::last_os_error();
+            match error.raw_os_error().map(|code| code as u32) {
+                Some(ERROR_PIPE_CONNECTED) => {}
+                Some(ERROR_PIPE_LISTENING) => return Ok(None),
+                Some(ERROR_NO_DATA) => {
+                    // The pilgrim left before it was heard; listen again for the next
+                    // SAFETY: as above
+                    unsafe { DisconnectNamedPipe(handle) };
+                    return Ok(None);
+                }
+                _ => return Err(format!("Failed to accept a pilgrim: {}", error)),
+            }
+
+            // The pilgrim is heard in blocking mode, while a fresh instance listens in its place
+            let mode = PIPE_READMODE_BYTE | PIPE_WAIT;
+            // SAFETY: as above
+            unsafe { SetNamedPipeHandleState(handle, &mode, std::ptr::null(), std::ptr::null()) };
+            let next = instance(&self.wide, false).map_err(|e| format!("Failed to open the daemon pipe: {}", e))?;
+            let Some(stream) = waiting.replace(next) else { return Ok(None) };
+            let Ok(writer) = stream.try_clone() else { return Ok(None) };
+            Ok(Some((stream, writer)))
+        }
+
+        /// Closes the listening instance, so that the name passes to a
+        /// replacement daemon once the pilgrims still here have been heard.
+        pub fn release(&self) {
+            self.waiting.lock().unwrap_or_else(PoisonError::into_inner).take();
+        }
+    }
+
+    pub fn knock(socket: &Path) -> Option<File> {
+        OpenOptions::new().read(true).write(true).open(pipe_name(socket)).ok()
+    }
+}
+
+/// Asks a running daemon to confess `source`, judged by the standards of the
+/// script at `path` and forgiving what it forgives. Returns the sins left and
+/// how many were forgiven.
+///
+/// Returns `None` when no compatible daemon could be reached, in which case the
+/// caller should confess in-process. A daemon from another version is told to
+/// depart and a fresh one is started in the background for next time.
+#[cfg(any(unix, windows))]
+pub fn request_confession(socket: &Path, path: &Path, source: &str) -> Option<Result<(Vec<Sin>, usize), String>> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut stream = door::knock(socket)?;
+
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let request = Request {
+        cmd: "check".to_string(),
+        version: Some(PROTOCOL_VERSION.to_string()),
+        path: Some(path.to_string_lossy().into_owned()),
+        source: Some(source.to_string()),
+    };
+    writeln!(stream, "{}", serde_json::to_string(&request).ok()?).ok()?;
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line).ok()?;
+    let response: Response = serde_json::from_str(line.trim()).ok()?;
+
+    if response.ok {
+        return Some(Ok((response.findings?, response.forgiven.unwrap_or(0))));
+    }
+
+    let error = response.error.unwrap_or_else(|| "unknown daemon error".to_string());
+    if error == "version mismatch" {
+        restart_daemon(socket);
+        return None;
+    }
+    Some(Err(error))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn request_confession(_socket: &Path, _path: &Path, _source: &str) -> Option<Result<(Vec<Sin>, usize), String>> {
+    None
+}
+
+#[cfg(any(unix, windows))]
+fn restart_daemon(socket: &Path) {
+    use std::process::{Command, Stdio};
+
+    if let Ok(exe) = std::env::current_exe() {
+        let _ = Command::new(exe)
+            .arg("daemon")
+            .arg("--socket")
+            .arg(socket)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+    }
+}
@@ -0,0 +1,142 @@
+use colored::*;
+
+use crate::environment;
+use crate::ast::Expr;
+use crate::error::Span;
+use crate::interpreter;
+use crate::lexer::without_bom;
+use crate::parser;
+
+/// How grave a diagnostic is, which decides its label and color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Venial,
+    Mortal,
+}
+
+impl Level {
+    fn label(&self) -> &'static str {
+        match self {
+            Level::Error => "Divine Error",
+            Level::Venial => "Venial Sin",
+            Level::Mortal => "Mortal Sin",
+        }
+    }
+
+    fn paint(&self, text: &str) -> ColoredString {
+        match self {
+            Level::Venial => text.yellow(),
+            Level::Error | Level::Mortal => text.bright_red(),
+        }
+    }
+}
+
+/// An error or sin pinned to the place in a script that caused it, rendered
+/// with the offending line and a caret under the problem:
+///
+/// ```text
+/// Divine Error: HeresyError: no such blessed function 'speak' (line 4)
+///  --> genesis.divine:4:1
+///   |
+/// 4 | speak(greeting)
+///   | ^^^^^
+/// ```
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub level: Level,
+    /// The identifier of the rule broken, shown after the label.
+    pub code: Option<String>,
+    pub message: String,
+    pub path: String,
+    pub line: usize,
+    /// 1-based, counted in characters.
+    pub column: usize,
+    /// How many characters the caret underlines.
+    pub width: usize,
+    pub source_line: String,
+    /// The value of the constant the caret underlines, shown after it.
+    pub value: Option<String>,
+}
+
+impl Diagnostic {
+    /// Pins `message` to `line` of `source`. The caret goes under the first
+    /// occurrence of `culprit` in the line, or under the whole line when there is
+    /// no culprit or it can't be found. `None` if the source has no such line.
+    pub fn new(level: Level, message: &str, path: &str, source: &str, line: usize, culprit: Option<&str>) -> Option<Self> {
+        Self::at(level, message, path, source, Span::find(source, line, culprit)?)
+    }
+
+    /// Pins `message` to `span` of `source`. `None` if the source has no such line.
+    pub fn at(level: Level, message: &str, path: &str, source: &str, span: Span) -> Option<Self> {
+        let source_line = without_bom(source).lines().nth(span.line.checked_sub(1)?)?.trim_end_matches('\r').to_string();
+        Some(Self {
+            level,
+            code: None,
+            message: message.to_string(),
+            path: path.to_string(),
+            line: span.line,
+            column: span.column,
+            width: span.len,
+            source_line,
+            value: None,
+        })
+    }
+
+    /// The text the caret underlines.
+    pub fn spanned(&self) -> String {
+        self.source_line.chars().skip(self.column - 1).take(self.width).collect()
+    }
+
+    /// Shows the value of the expression the caret underlines after it, when
+    /// that is a constant that folds to something other than itself, as
+    /// `0.1 + 0.2 == 0.3` folds to false. Nothing is run to find it.
+    pub fn with_folded_value(self) -> Self {
+        let value = parser::parse_expression(&self.spanned())
+            .filter(|expr| !matches!(expr, Expr::Number(_) | Expr::Str(_) | Expr::Bool(_)))
+            .and_then(|expr| interpreter::fold(&expr))
+            .map(|value| interpreter::quoted(&value));
+        Self { value, ..self }
+    }
+
+    pub fn render(&self, color: bool) -> String {
+        let paint = |text: &str, painted: ColoredString| if color { painted.to_string() } else { text.to_string() };
+        let gutter = " ".repeat(self.line.to_string().len());
+        let bar = paint("|", "|".bright_blue());
+        let header = match &self.code {
+            Some(code) => format!("{}[{}]: {}", self.level.label(), code, self.message),
+            None => format!("{}: {}", self.level.label(), self.message),
+        };
+        // Pad and underline by how wide the text looks, so carets line up under emoji
+        let before: String = self.source_line.chars().take(self.column - 1).collect();
+        let caret = "^".repeat(display_width(&self.spanned()).max(1));
+        let value = self.value.as_ref().map(|value| format!(" = {}", value)).unwrap_or_default();
+
+        format!(
+            "{}\n{}{} {}:{}:{}\n{} {}\n{} {} {}\n{} {} {}{}{}",
+            paint(&header, self.level.paint(&header)),
+            gutter, paint("-->", "-->".bright_blue()), self.path, self.line, self.column,
+            gutter, bar,
+            paint(&self.line.to_string(), self.line.to_string().bright_blue()), bar, self.source_line,
+            gutter, bar, " ".repeat(display_width(&before)), paint(&caret, self.level.paint(&caret)), value,
+        )
+    }
+}
+
+/// How many terminal columns `text` takes, counting emoji such as 🙏 as two.
+fn display_width(text: &str) -> usize {
+    text.chars().filter(|c| !matches!(c, '\u{FE00}'..='\u{FE0F}' | '\u{200D}')).map(|c| if c >= '\u{1F300}' { 2 } else { 1 }).sum()
+}
+
+/// Prints a command's error to stderr, pinned to `place`, the (path, source)
+/// of the script it came from and the span of it at fault, when it has one.
+/// Colors are only used when stderr is colored, as --color decides.
+pub fn report_error(message: &str, place: Option<(&str, &str, Span)>) {
+    let color = environment::stderr_colored();
+    let pinned = place.and_then(|(path, source, span)| Diagnostic::at(Level::Error, message, path, source, span));
+    match pinned {
+        Some(diagnostic) => eprintln!("{}", diagnostic.render(color)),
+        None if color => eprintln!("{}", environment::for_stderr(format!("{}: {}", Level::Error.label(), message).bright_red())),
+        None => eprintln!("{}: {}", Level::Error.label(), message),
+    }
+}
@@ -0,0 +1,117 @@
+use colored::*;
+
+/// How many unchanged lines a hunk shows on either side of its changes.
+const CONTEXT: usize = 3;
+
+/// A line of the diff, with where it stands in the old and new text.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Same { old: usize, new: usize },
+    Removed { old: usize, new: usize },
+    Added { old: usize, new: usize },
+}
+
+impl Op {
+    fn at(&self) -> (usize, usize) {
+        match *self {
+            Op::Same { old, new } | Op::Removed { old, new } | Op::Added { old, new } => (old, new),
+        }
+    }
+}
+
+/// A unified diff from `old` to `new`, or nothing if they are the same. Lines
+/// are matched by their longest common subsequence, after the lines the two
+/// begin and end with alike are set aside.
+pub fn unified(old_name: &str, new_name: &str, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (middle_old, middle_new) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    // lengths[i][j]: the longest common subsequence of what follows i and j
+    let width = middle_new.len() + 1;
+    let mut lengths = vec![0u32; (middle_old.len() + 1) * width];
+    for i in (0..middle_old.len()).rev() {
+        for j in (0..middle_new.len()).rev() {
+            lengths[i * width + j] = if middle_old[i] == middle_new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Same { old: i, new: i }).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < middle_old.len() || j < middle_new.len() {
+        let (old, new) = (prefix + i, prefix + j);
+        if i < middle_old.len() && j < middle_new.len() && middle_old[i] == middle_new[j] {
+            ops.push(Op::Same { old, new });
+            i += 1;
+            j += 1;
+        } else if j == middle_new.len() || (i < middle_old.len() && lengths[(i + 1) * width + j] >= lengths[i * width + j + 1]) {
+            ops.push(Op::Removed { old, new });
+            i += 1;
+        } else {
+            ops.push(Op::Added { old, new });
+            j += 1;
+        }
+    }
+    ops.extend((0..suffix).map(|k| Op::Same { old: old.len() - suffix + k, new: new.len() - suffix + k }));
+
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| !matches!(ops[k], Op::Same { .. })).collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", old_name, new_name);
+    let mut index = 0;
+    while index < changed.len() {
+        // A hunk takes in every change whose context touches the one before it
+        let start = changed[index].saturating_sub(CONTEXT);
+        let mut last = changed[index];
+        while index + 1 < changed.len() && changed[index + 1] <= last + 2 * CONTEXT + 1 {
+            index += 1;
+            last = changed[index];
+        }
+        let end = (last + CONTEXT + 1).min(ops.len());
+
+        let hunk = &ops[start..end];
+        let old_lines = hunk.iter().filter(|op| !matches!(op, Op::Added { .. })).count();
+        let new_lines = hunk.iter().filter(|op| !matches!(op, Op::Removed { .. })).count();
+        let (old_at, new_at) = hunk[0].at();
+        // An empty side is numbered by the line before it, as diff does
+        let from = |at: usize, lines: usize| if lines == 0 { at } else { at + 1 };
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", from(old_at, old_lines), old_lines, from(new_at, new_lines), new_lines));
+        for op in hunk {
+            match *op {
+                Op::Same { old: at, .. } => diff.push_str(&format!(" {}\n", old[at])),
+                Op::Removed { old: at, .. } => diff.push_str(&format!("-{}\n", old[at])),
+                Op::Added { new: at, .. } => diff.push_str(&format!("+{}\n", new[at])),
+            }
+        }
+        index += 1;
+    }
+    diff
+}
+
+/// A unified diff with removals in red, additions in green and hunk headers
+/// in cyan.
+pub fn paint(diff: &str) -> String {
+    diff.lines()
+        .map(|line| {
+            if line.starts_with("---") || line.starts_with("+++") {
+                line.bold().to_string()
+            } else if line.starts_with("@@") {
+                line.cyan().to_string()
+            } else if line.starts_with('-') {
+                line.red().to_string()
+            } else if line.starts_with('+') {
+                line.green().to_string()
+            } else {
+                line.to_string()
+            }
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
@@ -0,0 +1,139 @@
+/// Languages whose fenced blocks hold DivinePL.
+const LANGUAGES: &[&str] = &["divine", "dpl", "divinepl"];
+
+/// A DivinePL block found inside another document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    /// The line of the document holding the opening fence. Line `n` of the
+    /// snippet is line `fence_line + n` of the document.
+    pub fence_line: usize,
+    pub source: String,
+    /// How many spaces of the fence's indentation were removed from each line.
+    pub dedented: Vec<usize>,
+}
+
+impl Snippet {
+    /// The document line corresponding to line `line` of the snippet.
+    pub fn document_line(&self, line: usize) -> usize {
+        self.fence_line + line
+    }
+
+    /// The column on the document's line of `column` on line `line` of the snippet.
+    pub fn document_column(&self, line: usize, column: usize) -> usize {
+        column + line.checked_sub(1).and_then(|index| self.dedented.get(index)).copied().unwrap_or(0)
+    }
+
+    /// Rewrites every "line N" (and "lines N, M") in a message about the snippet
+    /// so that it refers to the document's lines instead.
+    pub fn map_message(&self, message: &str) -> String {
+        let mut out = String::with_capacity(message.len());
+        let mut rest = message;
+        while let Some(found) = rest.find("line") {
+            let (before, after) = rest.split_at(found);
+            out.push_str(before);
+            let word = if after.starts_with("lines ") { "lines " } else if after.starts_with("line ") { "line " } else { "line" };
+            out.push_str(word);
+            rest = &after[word.len()..];
+            if word == "line" {
+                continue;
+            }
+
+            // One number after "line", a comma-separated list after "lines"
+            loop {
+                let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                if digits == 0 {
+                    break;
+                }
+                let line: usize = rest[..digits].parse().unwrap_or(0);
+                out.push_str(&self.document_line(line).to_string());
+                rest = &rest[digits..];
+                if word == "lines " && rest.starts_with(", ") && rest[2..].starts_with(|c: char| c.is_ascii_digit()) {
+                    out.push_str(", ");
+                    rest = &rest[2..];
+                } else {
+                    break;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Extracts the ```divine fenced blocks of a Markdown document, in order.
+///
+/// Fences may be indented, as inside list items; the fence's indentation is
+/// removed from each line of its block. A block whose info string carries the
+/// `ignore` attribute, as in ```divine,ignore, is skipped. A fence left open runs
+/// to the end of the document.
+pub fn markdown_snippets(document: &str) -> Vec<Snippet> {
+    let mut snippets = Vec::new();
+    let mut open: Option<(Fence, Option<Snippet>)> = None;
+
+    for (index, raw) in document.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw.strip_suffix('\r').unwrap_or(raw);
+
+        match &mut open {
+            None => {
+                if let Some(fence) = Fence::opening(line) {
+                    let snippet = fence.holds_divine().then(|| Snippet { fence_line: line_number, source: String::new(), dedented: Vec::new() });
+                    open = Some((fence, snippet));
+                }
+            }
+            Some((fence, snippet)) => {
+                if fence.closes(line) {
+                    snippets.extend(open.take().and_then(|(_, snippet)| snippet));
+                } else if let Some(snippet) = snippet {
+                    let dedented = fence.dedent(line);
+                    snippet.source.push_str(dedented);
+                    snippet.source.push('\n');
+                    snippet.dedented.push(line.len() - dedented.len());
+                }
+            }
+        }
+    }
+
+    snippets.extend(open.and_then(|(_, snippet)| snippet));
+    snippets
+}
+
+struct Fence {
+    marker: char,
+    length: usize,
+    indent: usize,
+    info: String,
+}
+
+impl Fence {
+    fn opening(line: &str) -> Option<Self> {
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+        let length = trimmed.len() - trimmed.trim_start_matches(marker).len();
+        let info = trimmed[length..].trim();
+        // A backtick fence's info string may not itself contain backticks
+        if length < 3 || (marker == '`' && info.contains('`')) {
+            return None;
+        }
+        Some(Self { marker, length, indent, info: info.to_string() })
+    }
+
+    fn closes(&self, line: &str) -> bool {
+        let trimmed = line.trim();
+        trimmed.len() >= self.length && trimmed.chars().all(|c| c == self.marker)
+    }
+
+    /// Whether the info string names DivinePL without asking for the block to be ignored.
+    fn holds_divine(&self) -> bool {
+        let mut words = self.info.split(|c: char| c == ',' || c.is_whitespace()).filter(|word| !word.is_empty());
+        let language = words.next().unwrap_or_default().to_lowercase();
+        LANGUAGES.contains(&language.as_str()) && !words.any(|word| word == "ignore")
+    }
+
+    /// Removes up to the fence's own indentation from a line of its block.
+    fn dedent<'a>(&self, line: &'a str) -> &'a str {
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        &line[spaces.min(self.indent)..]
+    }
+}
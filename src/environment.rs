@@ -0,0 +1,260 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use colored::*;
+
+use crate::config::Commandments;
+use crate::glyphs;
+use serde_json::{json, Value};
+use crate::ColorChoice;
+use clap::ValueEnum;
+
+/// Whether what is printed to stdout is colored.
+static STDOUT_COLORED: AtomicBool = AtomicBool::new(false);
+
+/// Whether what is printed to stderr is colored. Diagnostics go there, so it is
+/// decided apart from stdout: `confess > report.txt` still colors its errors.
+static STDERR_COLORED: AtomicBool = AtomicBool::new(false);
+
+/// Whether what is printed to stdout is colored.
+pub fn stdout_colored() -> bool {
+    STDOUT_COLORED.load(Ordering::Relaxed)
+}
+
+/// Whether what is printed to stderr is colored.
+pub fn stderr_colored() -> bool {
+    STDERR_COLORED.load(Ordering::Relaxed)
+}
+
+/// Renders `text` to be printed to stderr, colored only if stderr is, whatever
+/// stdout is.
+pub fn for_stderr(text: ColoredString) -> String {
+    colored::control::set_override(stderr_colored());
+    let rendered = text.to_string();
+    colored::control::set_override(stdout_colored());
+    rendered
+}
+
+/// Where a setting's value came from. A flag on the command line wins over
+/// the environment, which wins over commandments.config, which wins over the
+/// default.
+#[derive(Clone, Copy)]
+pub enum Source {
+    Flag(&'static str),
+    Environment(&'static str),
+    /// The key of commandments.config that gave it.
+    Commandments(&'static str),
+    Default,
+}
+
+impl Source {
+    fn describe(&self) -> String {
+        match self {
+            Source::Flag(flag) => format!("from {}", flag),
+            Source::Environment(variable) => format!("from ${}", variable),
+            Source::Commandments(key) => format!("from \"{}\" in commandments.config", key),
+            Source::Default => "by default".to_string(),
+        }
+    }
+}
+
+/// A setting, with where its value came from.
+#[derive(Clone, Copy)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+/// The settings as the command line gives them: `false` or `None` for each
+/// flag that wasn't given.
+pub struct Flags {
+    pub dev: bool,
+    pub override_sabbath: bool,
+    pub seed: Option<u64>,
+    pub fast: bool,
+    pub color: Option<ColorChoice>,
+    pub no_color: bool,
+    pub plain_glyphs: bool,
+}
+
+/// The settings of a run that CI can give through the environment as well as
+/// on the command line: DIVINEPL_DEV, DIVINEPL_OVERRIDE_SABBATH, DIVINEPL_SEED,
+/// DIVINEPL_FAST, DIVINEPL_NO_COLOR and DIVINEPL_PLAIN_GLYPHS, or NO_COLOR,
+/// CLICOLOR_FORCE and the locale. A project can give them too, under the
+/// same names as `settings` reports them, in its commandments.
+pub struct Environment {
+    pub dev: Setting<bool>,
+    pub override_sabbath: Setting<bool>,
+    pub seed: Setting<Option<u64>>,
+    pub fast: Setting<bool>,
+    pub color: Setting<ColorChoice>,
+    pub plain_glyphs: Setting<bool>,
+}
+
+impl Environment {
+    /// Resolves each setting from its flag, when given, else from its
+    /// variable, when set, else from the `commandments`. A variable whose
+    /// value can't be understood is an error naming it, rather than being
+    /// ignored; a commandment of the wrong type is warned of when the
+    /// commandments are loaded, and passed over here. Colors and glyphs are
+    /// settled first, so even that error is printed as they ask.
+    pub fn resolve(flags: Flags, commandments: Option<&Commandments>) -> Result<Self, String> {
+        let commanded = |key: &str| commandments.and_then(|commandments| commandments.settings.get(key));
+        let plain_glyphs = glyphs_from(flags.plain_glyphs, commanded("plain_glyphs"));
+        glyphs::choose(plain_glyphs.as_ref().is_ok_and(|plain| plain.value));
+        let color = colors(flags.color, flags.no_color, commanded("color"));
+        let (stdout, stderr) = match color.as_ref().map_or(ColorChoice::Auto, |color| color.value) {
+            ColorChoice::Always => (true, true),
+            ColorChoice::Never => (false, false),
+            ColorChoice::Auto => (io::stdout().is_terminal(), io::stderr().is_terminal()),
+        };
+        STDOUT_COLORED.store(stdout, Ordering::Relaxed);
+        STDERR_COLORED.store(stderr, Ordering::Relaxed);
+        colored::control::set_override(stdout);
+        
+        Ok(Self {
+            dev: switch(flags.dev, "--dev", "DIVINEPL_DEV")?.or_commanded("dev", commanded("dev")),
+            override_sabbath: switch(flags.override_sabbath, "--override-sabbath", "DIVINEPL_OVERRIDE_SABBATH")?
+                .or_commanded("override_sabbath", commanded("override_sabbath")),
+            seed: match flags.seed {
+                Some(seed) => Setting { value: Some(seed), source: Source::Flag("--seed") },
+                None => match variable("DIVINEPL_SEED") {
+                    Some(value) => Setting {
+                        value: Some(value.trim().parse().map_err(|_| format!(
+                            "DIVINEPL_SEED must be a whole number from 0 to {}, but is '{}'", u64::MAX, value
+                        ))?),
+                        source: Source::Environment("DIVINEPL_SEED"),
+                    },
+                    None => match commanded("seed").and_then(Value::as_u64) {
+                        Some(seed) => Setting { value: Some(seed), source: Source::Commandments("seed") },
+                        None => Setting { value: None, source: Source::Default },
+                    },
+                },
+            },
+            fast: switch(flags.fast, "--fast", "DIVINEPL_FAST")?.or_commanded("fast", commanded("fast")),
+            color: color?,
+            plain_glyphs: plain_glyphs?,
+        })
+    }
+
+    /// Each setting by name, with its value and where it came from. A seed is
+    /// given as a string, since JSON numbers can't hold every one.
+    fn settings(&self) -> [(&'static str, Value, Source); 6] {
+        let seed = self.seed.value.map_or(Value::Null, |seed| Value::from(seed.to_string()));
+        [
+            ("dev", Value::from(self.dev.value), self.dev.source),
+            ("override_sabbath", Value::from(self.override_sabbath.value), self.override_sabbath.source),
+            ("seed", seed, self.seed.source),
+            ("fast", Value::from(self.fast.value), self.fast.source),
+            ("color", Value::from(self.color.value.as_str()), self.color.source),
+            ("plain_glyphs", Value::from(self.plain_glyphs.value), self.plain_glyphs.source),
+        ]
+    }
+
+    /// Prints every setting and where its value came from, so a setting that is
+    /// active unexpectedly can be traced.
+    pub fn report(&self) {
+        println!("{}", "⚙️ Settings (flags, then DIVINEPL_* variables, then commandments.config, then defaults):".bright_blue());
+        for (name, value, source) in self.settings() {
+            let value = match value {
+                Value::String(text) => text,
+                Value::Null => "random".to_string(),
+                value => value.to_string(),
+            };
+            let line = format!("  {:<17} {:<20} {}", name, value, source.describe());
+            match source {
+                Source::Default => println!("{}", line.dimmed()),
+                _ => println!("{}", line),
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let settings = self.settings().into_iter().map(|(name, value, source)| {
+            let (kind, from) = match source {
+                Source::Flag(flag) => ("flag", Some(flag)),
+                Source::Environment(variable) => ("environment", Some(variable)),
+                Source::Commandments(key) => ("commandments", Some(key)),
+                Source::Default => ("default", None),
+            };
+            let mut setting = json!({ "value": value, "source": kind });
+            if let Some(from) = from {
+                setting["from"] = Value::from(from);
+            }
+            (name.to_string(), setting)
+        });
+        Value::Object(settings.collect())
+    }
+}
+
+impl Setting<bool> {
+    /// This setting, or where it fell to its default, what the commandment
+    /// `key` says of it, if anything.
+    fn or_commanded(self, key: &'static str, commanded: Option<&Value>) -> Self {
+        match (self.source, commanded.and_then(Value::as_bool)) {
+            (Source::Default, Some(value)) => Setting { value, source: Source::Commandments(key) },
+            _ => self,
+        }
+    }
+}
+
+/// An on-or-off setting: on when its flag was given, else as its variable says.
+fn switch(flag_given: bool, flag: &'static str, name: &'static str) -> Result<Setting<bool>, String> {
+    if flag_given {
+        return Ok(Setting { value: true, source: Source::Flag(flag) });
+    }
+    let Some(value) = variable(name) else {
+        return Ok(Setting { value: false, source: Source::Default });
+    };
+    let value = match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => true,
+        "0" | "false" | "no" | "off" => false,
+        _ => return Err(format!("{} must be 1 or 0 (or true or false), but is '{}'", name, value)),
+    };
+    Ok(Setting { value, source: Source::Environment(name) })
+}
+
+/// When to color output: as --color or --no-color says, else as
+/// DIVINEPL_NO_COLOR says, else never if NO_COLOR is set, always if
+/// CLICOLOR_FORCE is, else as the `color` commandment says, and otherwise
+/// when printing to a terminal.
+fn colors(flag: Option<ColorChoice>, no_color: bool, commanded: Option<&Value>) -> Result<Setting<ColorChoice>, String> {
+    if let Some(value) = flag {
+        return Ok(Setting { value, source: Source::Flag("--color") });
+    }
+    let no_color = switch(no_color, "--no-color", "DIVINEPL_NO_COLOR")?;
+    if !matches!(no_color.source, Source::Default) {
+        let value = if no_color.value { ColorChoice::Never } else { ColorChoice::Auto };
+        return Ok(Setting { value, source: no_color.source });
+    }
+    if variable("NO_COLOR").is_some() {
+        return Ok(Setting { value: ColorChoice::Never, source: Source::Environment("NO_COLOR") });
+    }
+    if variable("CLICOLOR_FORCE").is_some_and(|value| value.trim() != "0") {
+        return Ok(Setting { value: ColorChoice::Always, source: Source::Environment("CLICOLOR_FORCE") });
+    }
+    match commanded.and_then(Value::as_str).and_then(|color| ColorChoice::from_str(color, true).ok()) {
+        Some(value) => Ok(Setting { value, source: Source::Commandments("color") }),
+        None => Ok(Setting { value: ColorChoice::Auto, source: Source::Default }),
+    }
+}
+
+/// Whether to print emoji as ASCII markers: as --plain-glyphs or
+/// DIVINEPL_PLAIN_GLYPHS says, else when a locale is set that isn't UTF-8,
+/// else as the `plain_glyphs` commandment says.
+fn glyphs_from(flag: bool, commanded: Option<&Value>) -> Result<Setting<bool>, String> {
+    let plain = switch(flag, "--plain-glyphs", "DIVINEPL_PLAIN_GLYPHS")?;
+    if !matches!(plain.source, Source::Default) {
+        return Ok(plain);
+    }
+    match glyphs::locale() {
+        Some((name, locale)) if !cfg!(windows) && !glyphs::is_utf8(&locale) => Ok(Setting { value: true, source: Source::Environment(name) }),
+        _ => Ok(plain.or_commanded("plain_glyphs", commanded)),
+    }
+}
+
+/// The value of the variable `name`, unless it is unset or empty.
+fn variable(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::lexer::without_bom;
+use crate::tribulation;
+
+/// Why a command failed. Each kind of failure leaves the process with its own
+/// exit status, so scripts and CI can tell a sinful script from a missing one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DivineError {
+    /// A commandment broken by the script, found before it runs.
+    Sin { line: usize, kind: SinKind },
+    /// An unholy name given to a variable.
+    Blasphemy { line: usize },
+    /// The Sabbath was not kept.
+    Rest(String),
+    /// A file could not be read or written.
+    Io(String),
+    /// The script, or a module it imports, could not be understood; `span` is
+    /// where in the script, when the fault lies in the script itself.
+    Parse { message: String, span: Option<Span> },
+    /// The script ran and was not found worthy: a runtime error, a broken
+    /// covenant or a trip to purgatory.
+    Judgment { message: String, span: Option<Span> },
+    /// The script ran past the time --rapture-after appointed, and was stopped
+    /// before the statement after `line`.
+    Rapture { seconds: u64, line: usize },
+    /// The run was interrupted by Ctrl-C after `executed` statements.
+    Tribulation { executed: usize },
+    /// Anything else, such as a bad flag or a project that already exists.
+    Other(String),
+}
+
+/// The commandments whose breaking is a [`DivineError::Sin`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinKind {
+    /// A function declared without bless, genesis or miracle.
+    UnblessedFunction,
+    /// A child process killed outside dev mode.
+    KilledChild,
+    /// A miracle performed where the commandments disable miracles.
+    ForbiddenMiracle,
+    /// A line breaking one of the project's custom commandments, judged mortal.
+    Custom { id: Arc<str>, message: Arc<str> },
+}
+
+impl DivineError {
+    /// The status the process exits with when a command fails this way.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            DivineError::Other(_) => 1,
+            DivineError::Sin { .. } | DivineError::Blasphemy { .. } => 2,
+            DivineError::Rest(_) => 3,
+            DivineError::Io(_) => 4,
+            DivineError::Parse { .. } => 5,
+            DivineError::Judgment { .. } => 6,
+            DivineError::Rapture { .. } => 7,
+            DivineError::Tribulation { .. } => tribulation::EXIT_CODE,
+        }
+    }
+
+    /// A parse error pinned to where `fault` lies in `source`.
+    pub fn parse(fault: Fault, source: &str) -> Self {
+        DivineError::Parse { span: fault.span(source), message: fault.message }
+    }
+
+    /// A judgment pinned to where `fault` lies in `source`.
+    pub fn judgment(fault: Fault, source: &str) -> Self {
+        DivineError::Judgment { span: fault.span(source), message: fault.message }
+    }
+
+    /// Where in `source`, the script the command was given, the error lies.
+    pub fn span(&self, source: &str) -> Option<Span> {
+        match self {
+            DivineError::Parse { span, .. } | DivineError::Judgment { span, .. } => *span,
+            DivineError::Sin { line, .. } | DivineError::Blasphemy { line } | DivineError::Rapture { line, .. } => {
+                Span::find(source, *line, None)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DivineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DivineError::Sin { line, kind: SinKind::UnblessedFunction } => {
+                write!(f, "SinError: Function at line {} lacks divine blessing", line)
+            }
+            DivineError::Sin { line, kind: SinKind::KilledChild } => {
+                write!(f, "MoralError: Thou shalt not kill child processes at line {}", line)
+            }
+            DivineError::Sin { line, kind: SinKind::ForbiddenMiracle } => {
+                write!(f, "MoralError: Miracles are disabled by the commandments, but one is performed at line {}", line)
+            }
+            DivineError::Sin { line, kind: SinKind::Custom { id, message } } => {
+                write!(f, "CommandmentError: {} at line {} ({})", message, line, id)
+            }
+            DivineError::Blasphemy { line } => write!(f, "BlasphemyError: Unholy variable names at line {}", line),
+            DivineError::Rapture { seconds, line } => write!(
+                f, "⏰ The appointed time has come: the run was raptured after {} second{} (last executed line {})",
+                seconds, if *seconds == 1 { "" } else { "s" }, line
+            ),
+            DivineError::Tribulation { executed } => write!(
+                f, "InterruptedError: the run was stopped by Ctrl-C after {} statement{}",
+                executed, if *executed == 1 { "" } else { "s" }
+            ),
+            DivineError::Rest(message)
+            | DivineError::Io(message)
+            | DivineError::Parse { message, .. }
+            | DivineError::Judgment { message, .. }
+            | DivineError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for DivineError {}
+
+/// Errors from the rest of the crate are still plain messages; those that reach
+/// a command unclassified are [`DivineError::Other`].
+impl From<String> for DivineError {
+    fn from(message: String) -> Self {
+        DivineError::Other(message)
+    }
+}
+
+/// Where in a script an error lies: `len` characters from `column` of `line`,
+/// both counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+impl Span {
+    /// The first `culprit` on `line` of `source`, or the whole line less its
+    /// indentation when there is no culprit or it can't be found. `None` if the
+    /// source has no such line.
+    pub fn find(source: &str, line: usize, culprit: Option<&str>) -> Option<Self> {
+        let text = without_bom(source).lines().nth(line.checked_sub(1)?)?.trim_end_matches('\r');
+        let (column, len) = match culprit.filter(|culprit| !culprit.is_empty()).and_then(|culprit| Some((text.find(culprit)?, culprit))) {
+            Some((offset, culprit)) => (text[..offset].chars().count() + 1, culprit.chars().count()),
+            None => {
+                let indent = text.len() - text.trim_start().len();
+                (text[..indent].chars().count() + 1, text.trim().chars().count().max(1))
+            }
+        };
+        Some(Self { line, column, len })
+    }
+}
+
+/// An error in a script, raised by a part of the runtime that knows the line
+/// at fault and perhaps the name to blame, but not the script's text: the
+/// interpreter and the module loader. Whoever holds the text pins it with
+/// [`Fault::span`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fault {
+    pub message: String,
+    pub line: Option<usize>,
+    pub culprit: Option<String>,
+}
+
+impl Fault {
+    /// A fault that blames `culprit`, on whichever line it is found.
+    pub fn blaming(culprit: &str, message: String) -> Self {
+        Self { message, line: None, culprit: Some(culprit.to_string()) }
+    }
+
+    /// The fault, on `line` unless it already knew its line.
+    pub fn on(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    pub fn span(&self, source: &str) -> Option<Span> {
+        Span::find(source, self.line?, self.culprit.as_deref())
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// A fault known only by its message, such as one from a built-in, is pinned
+/// to the statement it was raised in.
+impl From<String> for Fault {
+    fn from(message: String) -> Self {
+        Self { message, line: None, culprit: None }
+    }
+}
@@ -0,0 +1,229 @@
+use std::path::Path;
+
+use colored::*;
+
+use crate::calendar;
+use crate::config::{Commandment, Commandments, Kind, COMMANDMENTS, CONFIG_FILE_NAME};
+use crate::error::DivineError;
+use serde_json::{json, Map, Value};
+use crate::{edit_distance, Rule, RULES};
+
+/// Finds the commandments governing `path`, reports everything in them that
+/// can't be heeded, then prints every commandment in effect, defaults
+/// included. Fails if anything was wrong with them.
+pub fn examine(path: &Path) -> Result<(), DivineError> {
+    let found = Commandments::discover(path).ok_or_else(|| DivineError::Other(format!(
+        "ExamineError: no {} was found for {} or in any directory above it", CONFIG_FILE_NAME, path.display()
+    )))?;
+    let commandments = Commandments::read(&found)?;
+    println!("{}", format!("📜 Examining {}", found.display()).bright_blue());
+
+    let problems = problems(&commandments.settings);
+    for problem in &problems {
+        println!("  {} {}", "✗".bright_red(), problem);
+    }
+
+    println!("\n{}", "The commandments in effect:".underline().bright_blue());
+    let width = COMMANDMENTS.iter().map(|commandment| commandment.key.len()).max().unwrap_or(0);
+    for commandment in COMMANDMENTS {
+        match (commandments.settings.get(commandment.key), &commandment.fallback) {
+            (Some(value), _) => println!("  {:<width$}  {}", commandment.key, value),
+            (None, Some(fallback)) => println!("  {:<width$}  {} {}", commandment.key, fallback.to_json(), "(default)".dimmed()),
+            (None, None) => println!("  {:<width$}  {}", commandment.key, "unset".dimmed()),
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(DivineError::Other(format!(
+            "ExamineError: {} problem{} found in {}", problems.len(), if problems.len() == 1 { " was" } else { "s were" }, found.display()
+        )));
+    }
+    println!("\n{}", "✓ The commandments are sound".green());
+    Ok(())
+}
+
+/// Everything in `settings`, the whole of a commandments.config, that can't
+/// be heeded.
+pub fn problems(settings: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    inspect(settings, COMMANDMENTS, None, &mut problems);
+    problems
+}
+
+/// A JSON Schema of every commandment, for editors to check and complete
+/// commandments.config with.
+pub fn schema() -> Value {
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": CONFIG_FILE_NAME,
+        "description": "The commandments of a DivinePL project",
+        "type": "object",
+        "properties": properties(COMMANDMENTS),
+        "additionalProperties": false,
+    })
+}
+
+fn properties(commandments: &[Commandment]) -> Value {
+    Value::Object(commandments.iter().map(|commandment| {
+        let mut property = kind_schema(&commandment.kind);
+        property["description"] = Value::from(commandment.description);
+        if let Some(fallback) = &commandment.fallback {
+            property["default"] = fallback.to_json();
+        }
+        (commandment.key.to_string(), property)
+    }).collect::<Map<_, _>>())
+}
+
+fn kind_schema(kind: &Kind) -> Value {
+    match kind {
+        Kind::Bool => json!({ "type": "boolean" }),
+        Kind::Number => json!({ "type": "number", "minimum": 0.0 }),
+        Kind::Text => json!({ "type": "string" }),
+        Kind::Choice(choices) => json!({ "type": "string", "enum": choices }),
+        Kind::List => json!({ "type": "array", "items": { "type": "string" } }),
+        Kind::Table(commandments) => {
+            let required = commandments.iter().filter(|commandment| commandment.required).map(|commandment| commandment.key).collect::<Vec<_>>();
+            json!({
+                "type": "object",
+                "properties": properties(commandments),
+                "required": required,
+                "additionalProperties": false,
+            })
+        }
+        Kind::Dates => json!({
+            "type": "object",
+            "propertyNames": { "pattern": "^([0-9]{4}-)?[0-9]{2}-[0-9]{2}$" },
+            "additionalProperties": { "type": "string" },
+        }),
+        Kind::Rules(judgments) => {
+            let rules = RULES.iter().flat_map(|rule| [rule.id, rule.name]).collect::<Vec<_>>();
+            json!({
+                "type": "object",
+                "propertyNames": { "enum": rules },
+                "additionalProperties": { "enum": judgments },
+            })
+        }
+        Kind::Custom => json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "How sins against the commandment are named" },
+                    "pattern": { "type": "string", "description": "A regular expression no line may match" },
+                    "must_contain": { "type": "string", "description": "Text every script must contain" },
+                    "severity": { "type": "string", "enum": ["venial", "mortal"], "default": "venial" },
+                    "message": { "type": "string", "description": "What a sin against the commandment is reported as" },
+                },
+                "required": ["id"],
+                "oneOf": [
+                    { "required": ["pattern"] },
+                    { "required": ["must_contain"] },
+                ],
+                "additionalProperties": false,
+            },
+        }),
+    }
+}
+
+/// Collects what is wrong with `settings`, which should hold `commandments`:
+/// those of the table named `table`, or the whole config when `None`.
+fn inspect(settings: &Value, commandments: &[Commandment], table: Option<&str>, problems: &mut Vec<String>) {
+    let Some(entries) = settings.as_object() else {
+        problems.push(format!("{} should be an object, but is {}", table.unwrap_or("The commandments"), settings));
+        return;
+    };
+    let named = |key: &str| match table {
+        Some(table) => format!("{}.{}", table, key),
+        None => key.to_string(),
+    };
+
+    for (key, value) in entries {
+        match commandments.iter().find(|commandment| commandment.key == key) {
+            Some(commandment) => check(&named(key), value, &commandment.kind, problems),
+            None => {
+                let suggestion = commandments.iter()
+                    .map(|commandment| commandment.key)
+                    .min_by_key(|candidate| edit_distance(candidate, key))
+                    .filter(|candidate| edit_distance(candidate, key) <= candidate.len() / 2)
+                    .map(|candidate| format!("; did you mean \"{}\"?", named(candidate)))
+                    .unwrap_or_default();
+                problems.push(format!("\"{}\" is no commandment{}", named(key), suggestion));
+            }
+        }
+    }
+    for commandment in commandments.iter().filter(|commandment| commandment.required) {
+        if settings.get(commandment.key).is_none() {
+            problems.push(format!("{} is missing \"{}\"", table.unwrap_or("The commandments"), commandment.key));
+        }
+    }
+}
+
+fn check(name: &str, value: &Value, kind: &Kind, problems: &mut Vec<String>) {
+    let mut mismatch = |expected: &str| problems.push(format!("{} should be {}, but is {}", name, expected, value));
+    match kind {
+        Kind::Bool if value.as_bool().is_none() => mismatch("true or false"),
+        Kind::Number if value.as_f64().is_none_or(|number| number < 0.0) => mismatch("a number of 0 or more"),
+        Kind::Text if value.as_str().is_none() => mismatch("a string"),
+        Kind::Choice(choices) if value.as_str().is_none_or(|text| !choices.contains(&text)) => {
+            mismatch(&format!("one of {}", quoted(choices)));
+        }
+        Kind::List if value.as_array().is_none_or(|items| items.iter().any(|item| item.as_str().is_none())) => {
+            mismatch("a list of strings");
+        }
+        Kind::Table(commandments) => inspect(value, commandments, Some(name), problems),
+        Kind::Custom => match value.as_array() {
+            None => mismatch("a list"),
+            Some(entries) => {
+                for (index, entry) in entries.iter().enumerate() {
+                    let unknown = entry.as_object().into_iter().flatten()
+                        .map(|(key, _)| key)
+                        .filter(|key| !CUSTOM_KEYS.contains(&key.as_str()));
+                    for key in unknown {
+                        problems.push(format!("{}[{}] has the unknown key \"{}\"; it may have {}", name, index, key, quoted(CUSTOM_KEYS)));
+                    }
+                }
+            }
+        },
+        Kind::Dates => match value.as_object() {
+            None => mismatch("an object"),
+            Some(entries) => {
+                for (day, feast) in entries {
+                    if !calendar::is_day(day) {
+                        problems.push(format!("{} has \"{}\", which is no date; write MM-DD, or YYYY-MM-DD for a single year", name, day));
+                    }
+                    if feast.as_str().is_none() {
+                        problems.push(format!("{}.{} should be the name of a feast, but is {}", name, day, feast));
+                    }
+                }
+            }
+        },
+        Kind::Rules(judgments) => match value.as_object() {
+            None => mismatch("an object"),
+            Some(entries) => {
+                for (rule, judgment) in entries {
+                    if Rule::find(rule).is_none() {
+                        problems.push(format!("{} judges the unknown rule \"{}\"; the rules are {}",
+                            name, rule, RULES.iter().map(|rule| rule.id).collect::<Vec<_>>().join(", ")));
+                    }
+                    if judgment.as_str().is_none_or(|text| !judgments.contains(&text)) {
+                        problems.push(format!("{}.{} should be one of {}, but is {}", name, rule, quoted(judgments), judgment));
+                    }
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+/// What a custom commandment may hold; anything else in one is a problem,
+/// since loading the commandments checks the rest.
+const CUSTOM_KEYS: &[&str] = &["id", "pattern", "must_contain", "severity", "message"];
+
+/// `"a", "b" or "c"`.
+fn quoted(choices: &[&str]) -> String {
+    let quoted = choices.iter().map(|choice| format!("\"{}\"", choice)).collect::<Vec<_>>();
+    match quoted.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        _ => quoted.concat(),
+    }
+}
@@ -0,0 +1,219 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate};
+use colored::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::environment;
+
+/// The ledger kept beside a script unless --ledger names another.
+pub const DEFAULT_LEDGER: &str = ".prophecies.json";
+
+/// How dates are written in the ledger.
+const DATE: &str = "%Y-%m-%d";
+
+/// Whether a prophecy in the ledger is still awaited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pending,
+    Fulfilled,
+}
+
+/// Whether a prophecy was declared in the script or divined from its code.
+/// A vision that divines nothing leaves divined prophecies as they stand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Declared,
+    Divined,
+}
+
+impl Kind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Declared => "declared",
+            Kind::Divined => "divined",
+        }
+    }
+}
+
+/// A prophecy made in a vision, as the ledger knows it.
+pub struct Sign {
+    /// The script, relative to the ledger.
+    pub script: String,
+    pub kind: Kind,
+    /// What the prophecy is grounded in, which stays the same while it holds
+    /// even as the lines around it move.
+    pub omen: String,
+    pub text: String,
+}
+
+/// A prophecy recorded in the ledger. Dates are written YYYY-MM-DD.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    /// A hash of the script, kind and omen, by which the prophecy is known again.
+    pub hash: String,
+    pub script: String,
+    pub kind: Kind,
+    pub text: String,
+    pub foretold: NaiveDate,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub fulfilled: Option<NaiveDate>,
+}
+
+impl Entry {
+    /// How many days the prophecy has stood, or stood until it was fulfilled.
+    pub fn age(&self, today: NaiveDate) -> i64 {
+        (self.fulfilled.unwrap_or(today) - self.foretold).num_days()
+    }
+}
+
+/// Every prophecy made about a project, so that each vision can tell which of
+/// the last came true.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Ledger {
+    #[serde(rename = "prophecies")]
+    pub entries: Vec<Entry>,
+}
+
+impl Ledger {
+    /// Where the ledger of `path` is kept unless --ledger names another: beside
+    /// the script, or in the directory.
+    pub fn beside(path: &Path) -> PathBuf {
+        let dir = if path.is_dir() { Some(path) } else { path.parent() };
+        dir.filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new(".")).join(DEFAULT_LEDGER)
+    }
+
+    /// `script` as the ledger at `ledger` names it: relative to the ledger's
+    /// directory when it lies under it, so the name doesn't change with where
+    /// `prophesy` is run from.
+    pub fn name(ledger: &Path, script: &Path) -> String {
+        let dir = ledger.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        match (fs::canonicalize(dir), fs::canonicalize(script)) {
+            (Ok(dir), Ok(script)) => script.strip_prefix(&dir).unwrap_or(&script).display().to_string(),
+            _ => script.display().to_string(),
+        }
+    }
+
+    /// The ledger at `path`, or an empty one if none was kept there yet. A
+    /// ledger that can't be understood is set aside, so it can be looked into,
+    /// and begun anew rather than lost or fatal.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("Failed to read the prophecy ledger {}: {}", path.display(), e)),
+        };
+        if let Ok(ledger) = serde_json::from_str(&text) {
+            return Ok(ledger);
+        }
+
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(format!(".corrupt-{}", Local::now().format("%Y%m%dT%H%M%S")));
+        let backup = PathBuf::from(backup);
+        fs::rename(path, &backup)
+            .map_err(|e| format!("Failed to set aside the unreadable prophecy ledger {}: {}", path.display(), e))?;
+        eprintln!("{}", environment::for_stderr(format!(
+            "⚠️ The prophecy ledger {} could not be understood; it was kept as {} and begun anew",
+            path.display(), backup.display()
+        ).yellow()));
+        Ok(Self::default())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| format!("Failed to write the prophecy ledger {}: {}", path.display(), e))?;
+        fs::write(path, format!("{}\n", json))
+            .map_err(|e| format!("Failed to write the prophecy ledger {}: {}", path.display(), e))
+    }
+
+    /// Weighs the prophecies of a vision against the ledger. Each one still
+    /// pending is kept, each new one is recorded, and each pending one of the
+    /// `scripts` it looked at that the vision no longer makes is fulfilled today.
+    /// Divined prophecies are weighed only when the vision `divined`. Returns
+    /// how many days each sign has stood, in order, and what was fulfilled.
+    pub fn reckon(&mut self, scripts: &[String], divined: bool, signs: &[Sign], today: NaiveDate) -> (Vec<i64>, Vec<Entry>) {
+        let weighed = |entry: &Entry| entry.status == Status::Pending
+            && (divined || entry.kind == Kind::Declared)
+            && scripts.contains(&entry.script);
+        let mut unclaimed: Vec<usize> = (0..self.entries.len()).filter(|&i| weighed(&self.entries[i])).collect();
+
+        let mut ages = Vec::with_capacity(signs.len());
+        let mut recorded = Vec::new();
+        for sign in signs {
+            let hash = hash(sign);
+            // The same TODO written twice is two prophecies, each claiming its own entry
+            match unclaimed.iter().position(|&i| self.entries[i].hash == hash) {
+                Some(at) => {
+                    let entry = &mut self.entries[unclaimed.remove(at)];
+                    entry.text = sign.text.clone();
+                    ages.push(entry.age(today));
+                }
+                None => {
+                    recorded.push(Entry {
+                        hash,
+                        script: sign.script.clone(),
+                        kind: sign.kind,
+                        text: sign.text.clone(),
+                        foretold: today,
+                        status: Status::Pending,
+                        fulfilled: None,
+                    });
+                    ages.push(0);
+                }
+            }
+        }
+
+        let mut fulfilled = Vec::new();
+        for i in unclaimed {
+            let entry = &mut self.entries[i];
+            entry.status = Status::Fulfilled;
+            entry.fulfilled = Some(today);
+            fulfilled.push(entry.clone());
+        }
+        self.entries.extend(recorded);
+        (ages, fulfilled)
+    }
+}
+
+/// The hash a sign is known by in the ledger.
+fn hash(sign: &Sign) -> String {
+    format!("{:016x}", crate::fnv1a(format!("{}\0{}\0{}", sign.script, sign.kind.as_str(), sign.omen).as_bytes()))
+}
+
+/// Prints the ledger at `path`: the prophecies still pending with how long
+/// they have stood, then those fulfilled.
+pub fn report(path: &Path, today: NaiveDate) -> Result<(), String> {
+    let ledger = Ledger::load(path)?;
+    println!("{}", format!("📜 Prophecy ledger {}", path.display()).bright_magenta());
+    if ledger.entries.is_empty() {
+        println!("{}", "No prophecies have been recorded yet.".dimmed());
+        return Ok(());
+    }
+
+    let pending: Vec<&Entry> = ledger.entries.iter().filter(|entry| entry.status == Status::Pending).collect();
+    println!("{}", format!("\n⏳ Pending ({})", pending.len()).underline().bright_yellow());
+    for entry in pending {
+        println!("  {} {} {}", entry.script, entry.text.bright_cyan(), format!("({})", days(entry.age(today))).dimmed());
+    }
+
+    let fulfilled: Vec<&Entry> = ledger.entries.iter().filter(|entry| entry.status == Status::Fulfilled).collect();
+    println!("{}", format!("\n🕊️ Fulfilled ({})", fulfilled.len()).underline().bright_green());
+    for entry in fulfilled {
+        let on = entry.fulfilled.map_or_else(String::new, |on| on.format(DATE).to_string());
+        println!("  {} {} {}", entry.script, entry.text, format!("(foretold {}, fulfilled {})", entry.foretold.format(DATE), on).dimmed());
+    }
+    Ok(())
+}
+
+/// An age, as `pending 3 days`.
+pub fn days(age: i64) -> String {
+    match age {
+        0 => "foretold today".to_string(),
+        1 => "pending 1 day".to_string(),
+        age => format!("pending {} days", age),
+    }
+}
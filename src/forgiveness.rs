@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::lexer::{self, without_bom};
+use crate::RULES;
+
+const FORGIVEN: &str = "forgiven:";
+const FORGIVEN_FILE: &str = "forgiven-file:";
+const UNTIL: &str = "until:";
+
+/// The sins a script's comments forgive. `// forgiven: DPL010 reason` at the
+/// end of a line forgives sins against the rules it names on that line, and
+/// `// forgiven-file: DPL003` among the comments at the top of a script
+/// forgives them in the whole script. Either may give `until: 2025-06-01`
+/// after its rules, and forgives nothing after that day.
+#[derive(Default)]
+pub struct Forgiveness {
+    /// The identifiers of the rules forgiven on each line, by line number.
+    lines: HashMap<usize, Vec<&'static str>>,
+    /// The identifiers of the rules forgiven in the whole script.
+    file: Vec<&'static str>,
+    /// The lines whose forgiveness names no rule, or one that doesn't exist,
+    /// with what is wrong with each.
+    pub vague: Vec<(usize, String)>,
+    /// The lines whose forgiveness expired before `today`, with when.
+    pub expired: Vec<(usize, String)>,
+    /// The lines whose forgiveness gives a day that isn't one, which are
+    /// taken as unexpired, with what was given.
+    pub undated: Vec<(usize, String)>,
+}
+
+impl Forgiveness {
+    /// The forgiveness of `content` as it stands on `today`.
+    pub fn read(content: &str, today: NaiveDate) -> Self {
+        let mut forgiveness = Self::default();
+        let mut at_top = true;
+        for (index, line) in without_bom(content).lines().enumerate() {
+            let line = line.trim();
+            let whole_line = line.is_empty() || line.starts_with("//") || lexer::is_prayer(line);
+            at_top &= whole_line;
+            let Some(comment) = line_comment(line).map(str::trim_start) else { continue };
+
+            let (rules, file) = if let Some(rules) = comment.strip_prefix(FORGIVEN_FILE) {
+                if !at_top {
+                    forgiveness.vague.push((index + 1, "Whole-script forgiveness must come before any code".to_string()));
+                    continue;
+                }
+                (rules, true)
+            } else if let Some(rules) = comment.strip_prefix(FORGIVEN) {
+                if whole_line {
+                    forgiveness.vague.push((index + 1, "Forgiveness must follow the code it forgives, on the same line".to_string()));
+                    continue;
+                }
+                (rules, false)
+            } else {
+                continue;
+            };
+
+            let (ids, until) = match named_rules(rules) {
+                Ok(named) => named,
+                Err(message) => {
+                    forgiveness.vague.push((index + 1, message));
+                    continue;
+                }
+            };
+            match until.map(|until| NaiveDate::parse_from_str(until, "%Y-%m-%d").map_err(|_| until)) {
+                Some(Ok(until)) if until < today => {
+                    forgiveness.expired.push((index + 1, format!("Forgiveness of {} expired on {}", ids.join(", "), until)));
+                    continue;
+                }
+                Some(Err(until)) => forgiveness.undated.push((index + 1, until.to_string())),
+                _ => {}
+            }
+            if file {
+                forgiveness.file.extend(ids);
+            } else {
+                forgiveness.lines.entry(index + 1).or_default().extend(ids);
+            }
+        }
+        forgiveness
+    }
+
+    /// Whether sins against the rule `id` are forgiven on line `line_num`.
+    pub fn forgives(&self, line_num: usize, id: &str) -> bool {
+        self.file.contains(&id) || self.lines.get(&line_num).is_some_and(|ids| ids.contains(&id))
+    }
+}
+
+/// The identifiers of the rules a forgiveness names before giving its reason,
+/// as in `DPL010, DPL011 the cleanup must end its children`, and the day it
+/// lasts until when `until:` follows them, as it is written.
+fn named_rules(text: &str) -> Result<(Vec<&'static str>, Option<&str>), String> {
+    let mut ids = Vec::new();
+    let mut words = text.split(|c: char| c.is_whitespace() || c == ',').filter(|word| !word.is_empty());
+    let mut until = None;
+    while let Some(word) = words.next() {
+        let looks_like_id = word.len() > 3 && word[..3].eq_ignore_ascii_case("DPL") && word[3..].chars().all(|c| c.is_ascii_digit());
+        if !looks_like_id {
+            // The day may be written straight after `until:` or apart from it
+            until = word.strip_prefix(UNTIL).map(|day| if day.is_empty() { words.next().unwrap_or_default() } else { day });
+            break;
+        }
+        let rule = RULES.iter().find(|rule| rule.id.eq_ignore_ascii_case(word))
+            .ok_or_else(|| format!("Forgiveness names {}, which is no rule", word))?;
+        ids.push(rule.id);
+    }
+    if ids.is_empty() {
+        return Err("Forgiveness must name the rules it forgives, as in // forgiven: DPL010".to_string());
+    }
+    Ok((ids, until))
+}
+
+/// The text of a line's `//` comment, if it has one outside its strings.
+pub fn line_comment(line: &str) -> Option<&str> {
+    let mut quote = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((at, c)) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '/' && chars.peek().is_some_and(|(_, next)| *next == '/') => return Some(&line[at + 2..]),
+            None if matches!(c, '"' | '\'' | '`') => quote = Some(c),
+            None => {}
+        }
+    }
+    None
+}
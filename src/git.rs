@@ -0,0 +1,66 @@
+use std::path::Path;
+use std::process::Command;
+
+/// A thin wrapper over the git plumbing commands DivinePL needs. Nothing here
+/// touches the working tree; file contents are read straight from the object store.
+pub struct Repository;
+
+impl Repository {
+    /// Confirms that the current directory is inside a git repository.
+    pub fn discover() -> Result<Self, String> {
+        let inside = git(&["rev-parse", "--is-inside-work-tree"])
+            .map_err(|_| "Not inside a git repository; there is no history to judge".to_string())?;
+        if inside.trim() != "true" {
+            return Err("Not inside a git work tree; there is no history to judge".to_string());
+        }
+        Ok(Self)
+    }
+
+    /// Resolves a revision to a full commit hash.
+    pub fn resolve(&self, rev: &str) -> Result<String, String> {
+        git(&["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", rev)])
+            .map(|hash| hash.trim().to_string())
+            .map_err(|_| format!("Revision '{}' does not exist", rev))
+    }
+
+    /// Lists the DivinePL scripts present at a commit, optionally limited to a pathspec.
+    pub fn scripts_at(&self, commit: &str, pathspec: Option<&Path>) -> Result<Vec<String>, String> {
+        let mut args = vec!["ls-tree", "-r", "--name-only", "--full-name", commit];
+        let pathspec = pathspec.map(|p| p.to_string_lossy().into_owned());
+        if let Some(pathspec) = &pathspec {
+            args.push("--");
+            args.push(pathspec);
+        }
+
+        Ok(git(&args)?
+            .lines()
+            .filter(|name| name.ends_with(".divine") || name.ends_with(".dpl"))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Reads a file's content at a commit. `path` is relative to the repository root.
+    pub fn show(&self, commit: &str, path: &str) -> Result<String, String> {
+        git(&["show", &format!("{}:{}", commit, path)])
+    }
+}
+
+/// Reads a file's content as last committed, from whichever repository holds
+/// it. Fails when the file is in none, or has never been committed.
+pub fn committed(file: &Path) -> Result<String, String> {
+    let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = file.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    git(&["-C", &dir.to_string_lossy(), "show", &format!("HEAD:./{}", name)])
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to summon git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
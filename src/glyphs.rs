@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether emoji are printed as ASCII markers, for terminals that can't show them.
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// The marker each glyph is printed as when glyphs are plain.
+const MARKERS: &[(char, &str)] = &[
+    ('🙏', "[PRAY]"),
+    ('🙌', "[PRAISE]"),
+    ('🕊', "[DOVE]"),
+    ('✨', "[STAR]"),
+    ('🌟', "[STAR]"),
+    ('⚠', "[WARN]"),
+    ('📜', "[SCROLL]"),
+    ('✝', "[CROSS]"),
+    ('⚖', "[SCALES]"),
+    ('📖', "[BOOK]"),
+    ('📕', "[BOOK]"),
+    ('📚', "[BOOKS]"),
+    ('🕯', "[CANDLE]"),
+    ('⚰', "[COFFIN]"),
+    ('⚡', "[BOLT]"),
+    ('🔔', "[BELL]"),
+    ('🔮', "[VISION]"),
+    ('🤝', "[COVENANT]"),
+    ('💰', "[TITHE]"),
+    ('🗄', "[ARCHIVE]"),
+    ('🫒', "[OIL]"),
+    ('🚢', "[ARK]"),
+    ('🔥', "[FIRE]"),
+    ('📋', "[LIST]"),
+    ('📂', "[FOLDER]"),
+    ('📄', "[PAGE]"),
+    ('⚙', "[SETTINGS]"),
+    ('🌅', "[DAWN]"),
+    ('🧹', "[SWEEP]"),
+    ('🎲', "[DICE]"),
+    ('🎉', "[JOY]"),
+    ('💔', "[BROKEN]"),
+    ('💧', "[WATER]"),
+    ('🍂', "[LEAF]"),
+    ('⏳', "[WAIT]"),
+    ('⏰', "[TIME]"),
+    ('⏸', "[PAUSE]"),
+    ('🔄', "[AGAIN]"),
+    ('🌀', "[SPIRAL]"),
+    ('🕸', "[WEB]"),
+    ('📊', "[CHART]"),
+    ('☠', "[DOOM]"),
+    ('🐑', "[SHEEP]"),
+    ('📢', "[PROCLAIM]"),
+    ('👀', "[WATCH]"),
+    ('✓', "[OK]"),
+    ('✗', "[X]"),
+    ('→', "->"),
+    ('×', "x"),
+    ('—', "--"),
+];
+
+/// Prints emoji as ASCII markers from now on, or not.
+pub fn choose(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+/// Whether emoji are printed as ASCII markers.
+pub fn plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// The variable the locale is taken from, and its value: LC_ALL, else
+/// LC_CTYPE, else LANG, whichever is set first.
+pub fn locale() -> Option<(&'static str, String)> {
+    ["LC_ALL", "LC_CTYPE", "LANG"].into_iter()
+        .find_map(|key| env::var(key).ok().filter(|value| !value.is_empty()).map(|value| (key, value)))
+}
+
+/// Whether a locale encodes text as UTF-8, and so can show emoji.
+pub fn is_utf8(locale: &str) -> bool {
+    let locale = locale.to_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+/// `text` as it is printed: unchanged, or with every emoji as its ASCII marker
+/// when glyphs are plain. Emoji with no marker of their own are printed as [*].
+pub fn render(text: &str) -> Cow<'_, str> {
+    if !plain() || text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut rendered = String::with_capacity(text.len());
+    for c in text.chars() {
+        match MARKERS.iter().find(|(glyph, _)| *glyph == c) {
+            Some((_, marker)) => rendered.push_str(marker),
+            // Variation selectors and joiners only shape the emoji beside them
+            None if matches!(c, '\u{FE00}'..='\u{FE0F}' | '\u{200D}') => {}
+            None if matches!(c, '\u{2600}'..='\u{27BF}' | '\u{1F000}'..='\u{1FAFF}') => rendered.push_str("[*]"),
+            None => rendered.push(c),
+        }
+    }
+    Cow::Owned(rendered)
+}
@@ -0,0 +1,1001 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use colored::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::ast::{Expr, Function, Program, Stmt, StmtKind};
+use crate::builtins;
+use crate::error::Fault;
+use crate::ledger::Ledger;
+use crate::printer;
+use crate::scroll::Scroll;
+use crate::stewardship::Stewardship;
+use crate::trace::Event;
+use crate::tribulation;
+use crate::{DivinePLRuntime, Verbosity};
+
+/// A runtime value.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Void,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Array(Vec<Value>),
+    /// Objects are shared: every binding holding one sees changes to its fields.
+    Object(Rc<RefCell<Fields>>),
+    Scroll(Rc<RefCell<Scroll>>),
+}
+
+/// An object's fields, in the order they were first set.
+pub type Fields = Vec<(String, Value)>;
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Void, Value::Void) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => Rc::ptr_eq(a, b),
+            (Value::Scroll(a), Value::Scroll(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Void => "void",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::Str(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+            Value::Scroll(_) => "a scroll",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Void => write!(f, "void"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Array(items) => {
+                let items = items.iter().map(Value::to_string).collect::<Vec<_>>();
+                write!(f, "[{}]", items.join(", "))
+            }
+            Value::Object(fields) => {
+                let fields = fields.borrow().iter().map(|(key, value)| format!("{}: {}", key, value)).collect::<Vec<_>>();
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+            Value::Scroll(scroll) => write!(f, "<scroll {}>", scroll.borrow().path),
+        }
+    }
+}
+
+/// A value bound to a name in some scope.
+#[derive(Debug, Clone)]
+struct Binding {
+    value: Value,
+    /// The line a covenant was sworn on; covenants can never be rebound.
+    covenant: Option<usize>,
+}
+
+type Scope = HashMap<String, Binding>;
+
+/// How control leaves a statement.
+enum Flow {
+    Next,
+    /// An `ascend` is unwinding to the function being called.
+    Ascend(Value),
+}
+
+/// Walks a parsed program, executing only the statements control flow reaches.
+pub struct Interpreter<'a> {
+    runtime: &'a DivinePLRuntime,
+    scopes: Vec<Scope>,
+    /// Covenants sworn at the top level of the script, visible inside every function.
+    covenants: Scope,
+    /// Every scroll opened during the run, so absolution can find them again.
+    scrolls: Vec<Weak<RefCell<Scroll>>>,
+    functions: HashMap<String, Arc<Function>>,
+    /// How many blessed function calls are in progress.
+    depth: usize,
+    /// Covenants made during the run, and whether they were fulfilled.
+    ledger: Ledger,
+    /// How many sins `forgive` blocks have taken in.
+    forgiven: usize,
+    /// Topics of the verses the script imported.
+    verses: Vec<&'static str>,
+    /// Where castLots draws from, seeded as the runtime is.
+    lots: StdRng,
+    /// What `affirm` and `affirm_equal` found, in the order they were called.
+    affirmations: Vec<Affirmation>,
+    /// Where the time went, when the run is profiled.
+    stewardship: Option<Stewardship>,
+    /// Whether to pause before each statement for a command, as --smite-step asks.
+    stepping: bool,
+    /// The venial and mortal sins found against the script before it ran.
+    sins: (usize, usize),
+    /// When the run is raptured, as --rapture-after appoints. Time spent
+    /// waiting on the terminal pushes it back.
+    deadline: Option<Instant>,
+    /// The line of the statement executed last.
+    line: usize,
+    /// How many statements have been executed.
+    executed: usize,
+    /// Why the run was stopped before its end, once it has been.
+    halted: Option<Halt>,
+    /// Whether a sin told in porcelain is still unwinding.
+    unwinding: bool,
+}
+
+/// Why a run was stopped by the runtime rather than by the script.
+#[derive(Debug, Clone, Copy)]
+pub enum Halt {
+    /// The deadline --rapture-after appointed passed after the statement on this line.
+    Rapture(usize),
+    /// Ctrl-C was pressed.
+    Tribulation,
+}
+
+/// The outcome of one call to `affirm` or `affirm_equal`.
+#[derive(Debug, Clone)]
+pub struct Affirmation {
+    pub line: usize,
+    pub passed: bool,
+    pub message: String,
+}
+
+/// What a run leaves behind for judgment day.
+pub struct Testimony {
+    pub ledger: Ledger,
+    /// How many sins were forgiven by `confess ... forgive` blocks.
+    pub forgiven: usize,
+    pub affirmations: Vec<Affirmation>,
+    pub stewardship: Option<Stewardship>,
+    /// How many statements were executed.
+    pub executed: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(runtime: &'a DivinePLRuntime, program: &Program, verses: &[&'static str]) -> Self {
+        // The script's arguments are given to every scope, as manna fell for all of Israel
+        let manna = runtime.manna.iter().cloned().map(Value::Str).collect();
+        let covenants = HashMap::from([("manna".to_string(), Binding { value: Value::Array(manna), covenant: None })]);
+        Self {
+            runtime,
+            scopes: vec![covenants.clone()],
+            covenants,
+            scrolls: Vec::new(),
+            functions: program.functions.clone(),
+            depth: 0,
+            ledger: Ledger::new(runtime.revelation_mode),
+            forgiven: 0,
+            verses: verses.to_vec(),
+            lots: StdRng::seed_from_u64(runtime.seed),
+            affirmations: Vec::new(),
+            stewardship: runtime.profile.map(|_| Stewardship::new(runtime.lingered())),
+            stepping: runtime.stepping,
+            sins: (0, 0),
+            deadline: (runtime.rapture_after > 0).then(|| Instant::now() + Duration::from_secs(runtime.rapture_after)),
+            line: 0,
+            executed: 0,
+            halted: None,
+            unwinding: false,
+        }
+    }
+
+    /// Why the run was stopped before its end, if it was.
+    pub fn halted(&self) -> Option<Halt> {
+        self.halted
+    }
+
+    /// Remembers how many venial and mortal sins were found against the script,
+    /// for --smite-step to show.
+    pub fn with_sins(mut self, venial: usize, mortal: usize) -> Self {
+        self.sins = (venial, mortal);
+        self
+    }
+
+    /// What the run leaves behind for judgment day.
+    pub fn into_testimony(mut self) -> Testimony {
+        if let Some(stewardship) = &mut self.stewardship {
+            stewardship.close(self.runtime.lingered());
+        }
+        Testimony {
+            ledger: self.ledger,
+            forgiven: self.forgiven,
+            affirmations: self.affirmations,
+            stewardship: self.stewardship,
+            executed: self.executed,
+        }
+    }
+
+    /// Runs a program from its entry point: the function named by `entry`, or
+    /// `genesis` when none is given. A program with no genesis is executed top to
+    /// bottom instead.
+    pub fn run(&mut self, program: &Program, entry: Option<&str>) -> Result<(), Fault> {
+        let name = entry.unwrap_or("genesis");
+        let mut candidates = entry_points(program, name);
+
+        match (candidates.len(), entry) {
+            (0, Some(name)) => Err(Fault::from(format!("HeresyError: no such blessed function '{}' to enter", name))),
+            (0, None) => {
+                if self.runtime.verbosity > Verbosity::Quiet {
+                    println!("{}", "⚠️ No genesis found; executing in the wilderness".yellow());
+                }
+                self.execute_all(&program.statements).map(|_| ())
+            }
+            (1, _) => {
+                // Top-level covenants are sworn before the entry point begins
+                for stmt in &program.statements {
+                    if matches!(&stmt.kind, StmtKind::Covenant { .. })
+                        || matches!(&stmt.kind, StmtKind::Opaque { text, binds: Some(_), .. } if text.starts_with("covenant ")) {
+                        self.execute(stmt)?;
+                    }
+                }
+                let function = candidates.remove(0);
+                self.call_blessed(&function, Vec::new(), function.line).map(|_| ()).map_err(|fault| fault.on(function.line))
+            }
+            _ => {
+                let lines = candidates.iter().map(|f| f.line.to_string()).collect::<Vec<_>>();
+                Err(Fault::from(format!("SchismError: {} is defined more than once (lines {})", name, lines.join(", "))))
+            }
+        }
+    }
+
+    /// Runs more of a script on top of everything run before, as `commune` does
+    /// with each entry: earlier variables and blessed functions are still there.
+    /// Gives the value of a trailing expression statement, so it can be echoed.
+    pub fn continue_with(&mut self, program: &Program) -> Result<Value, Fault> {
+        // An earlier entry that was stopped doesn't stop this one
+        self.halted = None;
+        self.functions.extend(program.functions.iter().map(|(name, function)| (name.clone(), function.clone())));
+        let Some((last, rest)) = program.statements.split_last() else {
+            return Ok(Value::Void);
+        };
+        self.execute_all(rest)?;
+        match &last.kind {
+            StmtKind::Expr(expr) => self.evaluate(expr, last.line).map_err(|fault| fault.on(last.line)),
+            _ => self.execute(last).map(|_| Value::Void),
+        }
+    }
+
+    /// Executes statements in a fresh scope that is dropped afterwards.
+    fn execute_block(&mut self, statements: &[Stmt]) -> Result<Flow, Fault> {
+        self.scopes.push(HashMap::new());
+        let result = self.execute_all(statements);
+        self.scopes.pop();
+        result
+    }
+
+    /// Executes statements in the current scope until one ascends.
+    fn execute_all(&mut self, statements: &[Stmt]) -> Result<Flow, Fault> {
+        for stmt in statements {
+            if let Flow::Ascend(value) = self.execute(stmt)? {
+                return Ok(Flow::Ascend(value));
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn execute(&mut self, stmt: &Stmt) -> Result<Flow, Fault> {
+        // Selah is passed over in silence unless the run honors it
+        if matches!(stmt.kind, StmtKind::Dangling) || (matches!(stmt.kind, StmtKind::Selah) && !self.runtime.honor_selah) {
+            return Ok(Flow::Next);
+        }
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.halted = Some(Halt::Rapture(self.line));
+            return Err(Fault::from(format!("RaptureError: the appointed time passed after line {}", self.line)));
+        }
+        if tribulation::interrupted() {
+            self.halted = Some(Halt::Tribulation);
+            return Err(Fault::from("TribulationError: execution interrupted by mortal hands".to_string()));
+        }
+        self.line = stmt.line;
+        self.executed += 1;
+        let headline = printer::headline(stmt);
+        if self.stepping {
+            let waiting = Instant::now();
+            self.pause(stmt.line, &headline).map_err(|fault| fault.on(stmt.line))?;
+            self.waited(waiting);
+        }
+        let flow = match &mut self.stewardship {
+            None => self.perform(stmt, &headline),
+            Some(stewardship) => {
+                stewardship.begin_line(stmt.line, &headline);
+                let (started, lingered) = (Instant::now(), self.runtime.lingered());
+                let flow = self.perform(stmt, &headline);
+                let spent = started.elapsed().saturating_sub(self.runtime.lingered() - lingered);
+                if let Some(stewardship) = &mut self.stewardship {
+                    stewardship.end_line(stmt.line, spent);
+                }
+                flow
+            }
+        };
+        // A fault that doesn't know its line lies in the innermost statement it was raised in
+        let flow = flow.map_err(|fault| fault.on(stmt.line));
+        if self.runtime.porcelain {
+            self.testify(stmt.line, &flow);
+        }
+        flow
+    }
+
+    /// Prints the porcelain line for the statement on `line` that just ended
+    /// with `flow`. A sin is told once, at the statement it was raised in,
+    /// rather than again at each statement it unwinds through.
+    fn testify(&mut self, line: usize, flow: &Result<Flow, Fault>) {
+        match flow {
+            Ok(_) => {
+                self.unwinding = false;
+                println!("stmt {} ok", line);
+            }
+            Err(_) if self.unwinding => {}
+            Err(sin) => {
+                self.unwinding = true;
+                let code = sin.message.split(':').next().filter(|code| !code.contains(char::is_whitespace)).unwrap_or("Error");
+                println!("stmt {} sin {}", line, code);
+            }
+        }
+    }
+
+    fn perform(&mut self, stmt: &Stmt, headline: &str) -> Result<Flow, Fault> {
+        self.runtime.announce_statement(headline);
+        self.runtime.recall_verses(&self.verses, headline);
+        // A let is traced once its value is known
+        if !matches!(stmt.kind, StmtKind::Let { .. }) {
+            self.trace(stmt, headline, None);
+        }
+
+        match &stmt.kind {
+            StmtKind::Let { name, value } => {
+                let value = self.evaluate(value, stmt.line)?;
+                self.trace(stmt, headline, Some(quoted(&value)));
+                self.declare(name, value, None, stmt.line)?;
+            }
+            StmtKind::Covenant { name, value } => {
+                let value = self.evaluate(value, stmt.line)?;
+                self.declare(name, value, Some(stmt.line), stmt.line)?;
+            }
+            StmtKind::Assign { name, value } => {
+                let value = self.evaluate(value, stmt.line)?;
+                let binding = self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(name))
+                    .ok_or_else(|| Fault::blaming(name, format!("HeresyError: '{}' was never declared (line {})", name, stmt.line)))?;
+                if let Some(sworn) = binding.covenant {
+                    return Err(broken_covenant(name, sworn, stmt.line));
+                }
+                binding.value = value;
+            }
+            StmtKind::AssignField { object, field, value } => {
+                let object = self.evaluate(object, stmt.line)?;
+                let value = self.evaluate(value, stmt.line)?;
+                let Value::Object(fields) = object else {
+                    return Err(Fault::blaming(field, format!(
+                        "FormlessError: cannot set '{}' on {}, which has no fields (line {})",
+                        field, object.type_name(), stmt.line
+                    )));
+                };
+                let mut fields = fields.borrow_mut();
+                match fields.iter_mut().find(|(key, _)| key == field) {
+                    Some((_, slot)) => *slot = value,
+                    None => fields.push((field.clone(), value)),
+                }
+            }
+            StmtKind::Expr(expr) => {
+                self.evaluate(expr, stmt.line)?;
+            }
+            StmtKind::Judge { condition, then_branch, else_branch } => {
+                match self.evaluate(condition, stmt.line)? {
+                    Value::Bool(true) => return self.execute_block(then_branch),
+                    Value::Bool(false) => {
+                        if let Some(else_branch) = else_branch {
+                            return self.execute_block(else_branch);
+                        }
+                    }
+                    other => {
+                        return Err(Fault::from(format!(
+                            "DiscernmentError: judgment at line {} requires true or false, but the condition was {}",
+                            stmt.line, other.type_name()
+                        )));
+                    }
+                }
+            }
+            StmtKind::Preach { variable, iterable, body } => {
+                let flock = match self.evaluate(iterable, stmt.line)? {
+                    Value::Array(items) => items,
+                    other => {
+                        return Err(Fault::from(format!(
+                            "ShepherdError: preach at line {} needs an array to shepherd, but found {}",
+                            stmt.line, other.type_name()
+                        )));
+                    }
+                };
+
+                for soul in &flock {
+                    if let Some(stewardship) = &mut self.stewardship {
+                        stewardship.iterated(stmt.line);
+                    }
+                    let soul = Binding { value: soul.clone(), covenant: None };
+                    self.scopes.push(HashMap::from([(variable.clone(), soul)]));
+                    let result = self.execute_block(body);
+                    self.scopes.pop();
+                    if let Flow::Ascend(value) = result? {
+                        return Ok(Flow::Ascend(value));
+                    }
+                }
+
+                if self.runtime.verbosity >= Verbosity::Statements {
+                    println!("{}", format!("🐑 Preached to {} souls (line {})", flock.len(), stmt.line).italic().bright_blue());
+                }
+            }
+            StmtKind::While { condition, body } => {
+                let limit = self.runtime.tribulation_limit;
+                let mut iterations = 0;
+
+                loop {
+                    match self.evaluate(condition, stmt.line)? {
+                        Value::Bool(true) => {}
+                        Value::Bool(false) => break,
+                        other => {
+                            return Err(Fault::from(format!(
+                                "DiscernmentError: while at line {} requires true or false, but the condition was {}",
+                                stmt.line, other.type_name()
+                            )));
+                        }
+                    }
+
+                    if limit != 0 && iterations >= limit {
+                        return Err(Fault::from(format!(
+                            "TribulationError: loop exceeded its appointed time at line {} ({} iterations)",
+                            stmt.line, limit
+                        )));
+                    }
+                    iterations += 1;
+                    if let Some(stewardship) = &mut self.stewardship {
+                        stewardship.iterated(stmt.line);
+                    }
+                    if let Flow::Ascend(value) = self.execute_block(body)? {
+                        return Ok(Flow::Ascend(value));
+                    }
+                }
+            }
+            StmtKind::Absolution { body } => {
+                let opened_before = self.scrolls.len();
+                self.scopes.push(HashMap::new());
+                let result = self.execute_all(body);
+
+                // Seal whatever was opened inside, whether or not a sin unwound the block
+                for scroll in self.scrolls.drain(opened_before..).filter_map(|weak| weak.upgrade()) {
+                    let mut scroll = scroll.borrow_mut();
+                    if !scroll.is_sealed() {
+                        scroll.seal()?;
+                        if self.runtime.verbosity >= Verbosity::Statements {
+                            println!("{}", format!("📜 The scroll {} was sealed by absolution", scroll.path).italic().bright_blue());
+                        }
+                    }
+                }
+                self.scopes.pop();
+                return result;
+            }
+            StmtKind::Confess { body, sin, forgive } => {
+                let confession = match self.execute_block(body) {
+                    Ok(flow) => return Ok(flow),
+                    // Neither the appointed time nor mortal hands are a sin to be forgiven
+                    Err(confession) if self.halted.is_some() => return Err(confession),
+                    Err(confession) => confession,
+                };
+                self.forgiven += 1;
+                if let Some(trace) = &self.runtime.trace {
+                    trace.record(Event::Forgiven { line: stmt.line, sin: &confession.message });
+                }
+                if self.runtime.verbosity >= Verbosity::Statements || self.runtime.revelation_mode {
+                    println!("{}", format!("🙏 Forgiven: {}", confession).italic().bright_blue());
+                }
+
+                let confession = Binding { value: Value::Str(confession.message), covenant: None };
+                self.scopes.push(HashMap::from([(sin.clone(), confession)]));
+                let result = self.execute_block(forgive);
+                self.scopes.pop();
+                return result;
+            }
+            StmtKind::Block { body, .. } => return self.execute_block(body),
+            // Functions were registered when the script was parsed, and modules loaded before it ran
+            StmtKind::Bless(_) | StmtKind::Import { .. } => {}
+            StmtKind::Ascend(value) => {
+                if self.depth == 0 {
+                    return Err(Fault::from(format!(
+                        "HeresyError: there is nothing to ascend from outside a blessed function (line {})", stmt.line
+                    )));
+                }
+                let value = match value {
+                    Some(value) => self.evaluate(value, stmt.line)?,
+                    None => Value::Void,
+                };
+                return Ok(Flow::Ascend(value));
+            }
+            StmtKind::Opaque { text, binds, .. } => {
+                // Declarations we can't evaluate still bring their name into being
+                if let Some(name) = binds {
+                    let covenant = text.starts_with("covenant ").then_some(stmt.line);
+                    self.declare(name, Value::Void, covenant, stmt.line)?;
+                }
+            }
+            StmtKind::Selah => {
+                println!("{}", format!("🕊️ Selah (line {})", stmt.line).bright_blue());
+                print!("{}", self.reveal_state());
+                print!("{}", "Press Enter to continue... ".dimmed());
+                let _ = io::stdout().flush();
+                let waiting = Instant::now();
+                io::stdin().lock().read_line(&mut String::new())
+                    .map_err(|e| format!("SilenceError: could not wait at selah: {} (line {})", e, stmt.line))?;
+                self.waited(waiting);
+            }
+            StmtKind::Dangling => {}
+        }
+
+        Ok(Flow::Next)
+    }
+
+    /// The variables in scope, innermost first where names are shadowed, and
+    /// the covenants made so far, one per line.
+    pub fn reveal_state(&self) -> String {
+        let mut seen = HashMap::new();
+        for scope in self.scopes.iter().rev() {
+            for (name, binding) in scope {
+                seen.entry(name.as_str()).or_insert(binding);
+            }
+        }
+        let mut names: Vec<_> = seen.into_iter().collect();
+        names.sort_by_key(|(name, _)| *name);
+
+        let mut state = String::from("  Variables:\n");
+        for (name, binding) in names {
+            let sworn = binding.covenant.map(|line| format!(" (covenant, line {})", line)).unwrap_or_default();
+            state.push_str(&format!("    {} = {}{}\n", name, quoted(&binding.value), sworn));
+        }
+        state.push_str(&format!("  Covenants: {}\n", self.ledger.tally().trim_matches(['(', ')'])));
+        for promise in self.ledger.promises() {
+            let kept = match promise.fulfilled {
+                Some(line) => format!("fulfilled at line {}", line),
+                None => "open".to_string(),
+            };
+            state.push_str(&format!("    line {}: \"{}\" {}\n", promise.line, promise.text, kept));
+        }
+        state
+    }
+
+    /// Binds `name` in the innermost scope. A name sworn as a covenant can't be
+    /// bound again, even by shadowing it.
+    fn declare(&mut self, name: &str, value: Value, covenant: Option<usize>, line: usize) -> Result<(), Fault> {
+        if let Some(sworn) = self.binding(name).and_then(|binding| binding.covenant) {
+            return Err(broken_covenant(name, sworn, line));
+        }
+
+        let binding = Binding { value, covenant };
+        if covenant.is_some() && self.depth == 0 && self.scopes.len() == 1 {
+            self.covenants.insert(name.to_string(), binding.clone());
+        }
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), binding);
+        }
+        Ok(())
+    }
+
+    fn binding(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Value> {
+        self.binding(name).map(|binding| &binding.value)
+    }
+
+    fn evaluate(&mut self, expr: &Expr, line: usize) -> Result<Value, Fault> {
+        match expr {
+            Expr::Number(n) => Ok(Value::Number(*n)),
+            Expr::Str(s) => Ok(Value::Str(s.clone())),
+            Expr::Bool(b) => Ok(Value::Bool(*b)),
+            Expr::Array(items) => Ok(Value::Array(
+                items.iter().map(|item| self.evaluate(item, line)).collect::<Result<_, _>>()?,
+            )),
+            Expr::Object(fields) => {
+                let fields = fields.iter()
+                    .map(|(key, value)| Ok((key.clone(), self.evaluate(value, line)?)))
+                    .collect::<Result<Fields, Fault>>()?;
+                Ok(Value::Object(Rc::new(RefCell::new(fields))))
+            }
+            Expr::Member { object, field } => {
+                let object = match object.as_ref() {
+                    // Fields of objects that were never declared are read on faith
+                    Expr::Ident(name) if self.lookup(name).is_none() => return Ok(Value::Void),
+                    other => self.evaluate(other, line)?,
+                };
+                Ok(member_of(object, field))
+            }
+            Expr::Index { target, index } => {
+                let target = self.evaluate(target, line)?;
+                let index = self.evaluate(index, line)?;
+                index_of(target, index, line)
+            }
+            Expr::Ident(name) => self.lookup(name).cloned()
+                .ok_or_else(|| Fault::blaming(name, format!("HeresyError: '{}' was never declared (line {})", name, line))),
+            Expr::Unary { op, operand } => {
+                let operand = self.evaluate(operand, line)?;
+                unary(op, operand, line)
+            }
+            Expr::Binary { op, left, right } => self.binary(op, left, right, line),
+            Expr::Call { callee, args } => {
+                let args = args.iter()
+                    .map(|arg| self.evaluate(arg, line))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call(callee, args, line)
+            }
+            Expr::MethodCall { receiver, method, args } => {
+                // `scroll` names the scroll namespace unless a script binds it itself
+                let receiver = match receiver.as_ref() {
+                    Expr::Ident(name) if name == "scroll" && self.lookup(name).is_none() => None,
+                    // Methods on objects that were never declared are performed on faith
+                    Expr::Ident(name) if self.lookup(name).is_none() => return Ok(Value::Void),
+                    other => Some(self.evaluate(other, line)?),
+                };
+                let args = args.iter()
+                    .map(|arg| self.evaluate(arg, line))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call_method(receiver, method, args, line)
+            }
+        }
+    }
+
+    fn binary(&mut self, op: &str, left: &Expr, right: &Expr, line: usize) -> Result<Value, Fault> {
+        // Logical operators short-circuit, so the right side may never be evaluated
+        if op == "&&" || op == "||" {
+            let left = self.evaluate(left, line)?;
+            let left = expect_bool(op, left, line)?;
+            if (op == "&&" && !left) || (op == "||" && left) {
+                return Ok(Value::Bool(left));
+            }
+            let right = self.evaluate(right, line)?;
+            let right = expect_bool(op, right, line)?;
+            return Ok(Value::Bool(right));
+        }
+
+        let left = self.evaluate(left, line)?;
+        let right = self.evaluate(right, line)?;
+        binary(op, left, right, line)
+    }
+
+    fn call_method(&mut self, receiver: Option<Value>, method: &str, args: Vec<Value>, line: usize) -> Result<Value, Fault> {
+        match (receiver, method) {
+            (None, "open") => {
+                let Some(Value::Str(path)) = args.first() else {
+                    return Err(Fault::from(format!("ScrollError: scroll.open at line {} needs a path", line)));
+                };
+                let scroll = Rc::new(RefCell::new(Scroll::open(path, self.runtime.verbosity >= Verbosity::Statements)?));
+                self.scrolls.push(Rc::downgrade(&scroll));
+                Ok(Value::Scroll(scroll))
+            }
+            (None, other) => Err(Fault::blaming(other, format!("ScrollError: scrolls know nothing of '{}' (line {})", other, line))),
+            (Some(Value::Scroll(scroll)), "inscribe") => {
+                let text = args.iter().map(Value::to_string).collect::<Vec<_>>().join(" ");
+                scroll.borrow_mut().inscribe(&text).map_err(|e| format!("{} (line {})", e, line))?;
+                Ok(Value::Void)
+            }
+            (Some(Value::Scroll(scroll)), "seal") => {
+                scroll.borrow_mut().seal()?;
+                Ok(Value::Void)
+            }
+            (Some(Value::Scroll(_)), other) => {
+                Err(Fault::blaming(other, format!("ScrollError: a scroll cannot '{}' (line {})", other, line)))
+            }
+            // Secular methods on other values are performed on faith
+            _ => Ok(Value::Void),
+        }
+    }
+
+    fn call(&mut self, callee: &str, args: Vec<Value>, line: usize) -> Result<Value, Fault> {
+        // Scripts may bless functions that share a name with a builtin
+        if let Some(function) = self.functions.get(callee).cloned() {
+            return self.call_blessed(&function, args, line);
+        }
+
+        let message = args.iter().map(Value::to_string).collect::<Vec<_>>().join(" ");
+        match callee {
+            "print" => println!("{}", message),
+            "revelation" if self.runtime.porcelain => println!("{}", message),
+            "revelation" => println!("{}", format!("📢 {}", message).bright_cyan()),
+            "covenant" => self.ledger.make(&message, line),
+            "fulfill" => self.ledger.fulfill(&message, line),
+            "transgress" => return Err(Fault::from(format!("TransgressionError: {} (line {})", message, line))),
+            // Affirmations are recorded rather than raised, so one failure doesn't hide the next
+            "affirm" if matches!(args.len(), 1 | 2) => {
+                let (passed, message) = match &args[0] {
+                    Value::Bool(passed) => (*passed, args.get(1).map_or("affirmation failed".to_string(), Value::to_string)),
+                    other => (false, format!("affirm requires true or false, but received {}", other.type_name())),
+                };
+                self.affirmations.push(Affirmation { line, passed, message });
+            }
+            "affirm_equal" if args.len() == 2 => {
+                let passed = args[0] == args[1];
+                let message = format!("expected {}, but found {}", quoted(&args[1]), quoted(&args[0]));
+                self.affirmations.push(Affirmation { line, passed, message });
+            }
+            "affirm" => return Err(Fault::blaming("affirm", format!("HeresyError: 'affirm' expects 1 or 2 arguments but received {} (line {})", args.len(), line))),
+            "affirm_equal" => return Err(Fault::blaming("affirm_equal", format!("HeresyError: 'affirm_equal' expects 2 arguments but received {} (line {})", args.len(), line))),
+            _ => {
+                if let Some(result) = builtins::call(callee, &args, line, &mut self.lots, self.runtime.dev_mode) {
+                    return result.map_err(|e| Fault::blaming(callee, e));
+                }
+                let suggestion = builtins::nearest(callee, self.functions.keys().map(String::as_str))
+                    .map(|name| format!("; did you mean '{}'?", name))
+                    .unwrap_or_default();
+                return Err(Fault::blaming(callee, format!("HeresyError: no such blessed function '{}' (line {}){}", callee, line, suggestion)));
+            }
+        }
+        Ok(Value::Void)
+    }
+
+    /// Pushes the deadline back by the time spent waiting on the terminal since `waiting`.
+    fn waited(&mut self, waiting: Instant) {
+        if let Some(deadline) = &mut self.deadline {
+            *deadline += waiting.elapsed();
+        }
+    }
+
+    /// Waits before the statement on `line` for a command from the terminal:
+    /// n (or nothing) for the next statement, c to continue to the end, p NAME
+    /// to print a variable, s for the tallies of sins and covenants, and q to
+    /// abandon the run.
+    fn pause(&mut self, line: usize, headline: &str) -> Result<(), Fault> {
+        println!("{}", format!("⏸️ line {}: {}", line, headline).bright_yellow());
+        loop {
+            print!("{} ", "smite>".bright_blue());
+            let _ = io::stdout().flush();
+            let mut command = String::new();
+            if io::stdin().lock().read_line(&mut command).map_err(|e| format!("SmiteError: could not read a command: {}", e))? == 0 {
+                return Err(Fault::from(format!("SmiteError: the run was abandoned at line {}", line)));
+            }
+            let mut words = command.split_whitespace();
+            match (words.next(), words.next()) {
+                (None | Some("n"), None) => return Ok(()),
+                (Some("c"), None) => {
+                    self.stepping = false;
+                    return Ok(());
+                }
+                (Some("q"), None) => return Err(Fault::from(format!("SmiteError: the run was abandoned at line {}", line))),
+                (Some("p"), Some(name)) => match self.lookup(name) {
+                    Some(value) => println!("  {} = {}", name, quoted(value)),
+                    None => println!("{}", format!("  '{}' is not in scope here", name).yellow()),
+                },
+                (Some("s"), None) => {
+                    let (venial, mortal) = self.sins;
+                    println!("  Sins: {} venial, {} mortal, {} forgiven", venial, mortal, self.forgiven);
+                    println!("  Covenants: {}", self.ledger.tally().trim_matches(['(', ')']));
+                }
+                _ => println!("{}", "  n: next, c: continue, p NAME: print a variable, s: sins and covenants, q: quit".dimmed()),
+            }
+        }
+    }
+
+    /// Records the execution of `stmt` in the trace, if one is kept.
+    fn trace(&self, stmt: &Stmt, headline: &str, value: Option<String>) {
+        let Some(trace) = &self.runtime.trace else {
+            return;
+        };
+        let kind = match &stmt.kind {
+            StmtKind::Covenant { .. } => "covenant",
+            _ if headline.starts_with("covenant ") => "covenant",
+            _ if headline.starts_with("miracle") => "miracle",
+            _ => "plain",
+        };
+        trace.record(Event::Statement { line: stmt.line, kind, text: headline, value });
+    }
+
+    fn call_blessed(&mut self, function: &Function, args: Vec<Value>, line: usize) -> Result<Value, Fault> {
+        if args.len() != function.params.len() {
+            return Err(Fault::blaming(&function.name, format!(
+                "HeresyError: '{}' expects {} argument{} but received {} (line {})",
+                function.name,
+                function.params.len(),
+                if function.params.len() == 1 { "" } else { "s" },
+                args.len(),
+                line
+            )));
+        }
+        if self.depth >= self.runtime.recursion_limit {
+            return Err(Fault::blaming(&function.name, format!(
+                "TowerOfBabelError: calls to '{}' reached higher than {} levels (line {})",
+                function.name, self.runtime.recursion_limit, line
+            )));
+        }
+
+        if self.runtime.revelation_mode {
+            for prophecy in &function.prophecies {
+                println!("{}", format!("🔮 It was foretold of {}: \"{}\"", function.name, prophecy).italic().bright_magenta());
+            }
+        }
+
+        // The body sees only top-level covenants and its parameters, never the caller's variables
+        let parameters = function.params.iter().cloned()
+            .zip(args.into_iter().map(|value| Binding { value, covenant: None }))
+            .collect();
+        let caller_scopes = std::mem::replace(&mut self.scopes, vec![self.covenants.clone(), parameters]);
+        self.depth += 1;
+        if let Some(stewardship) = &mut self.stewardship {
+            stewardship.begin_call(&function.name);
+        }
+        let (started, lingered) = (Instant::now(), self.runtime.lingered());
+        let result = self.execute_block(&function.body);
+        if let Some(stewardship) = &mut self.stewardship {
+            stewardship.end_call(&function.name, started.elapsed().saturating_sub(self.runtime.lingered() - lingered));
+        }
+        self.depth -= 1;
+        self.scopes = caller_scopes;
+
+        match result? {
+            Flow::Ascend(value) => Ok(value),
+            Flow::Next => Ok(Value::Void),
+        }
+    }
+}
+
+/// A value as an affirmation shows it, with strings quoted so "1" and 1 differ.
+pub fn quoted(value: &Value) -> String {
+    match value {
+        Value::Str(text) => format!("\"{}\"", text),
+        other => other.to_string(),
+    }
+}
+
+fn broken_covenant(name: &str, sworn: usize, line: usize) -> Fault {
+    Fault::blaming(name, format!(
+        "BrokenCovenantError: '{}' was sworn as a covenant at line {} and cannot be rebound at line {}", name, sworn, line
+    ))
+}
+
+/// Every function a run could start from under `name`: blessed functions, and
+/// methods such as `genesis() { ... }` inside a top-level `bless` or `miracle` block.
+fn entry_points(program: &Program, name: &str) -> Vec<Arc<Function>> {
+    let methods = program.statements.iter()
+        .filter_map(|stmt| match &stmt.kind {
+            StmtKind::Block { header, body } if header.starts_with("bless ") || header.starts_with("miracle ") => Some(body),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|stmt| match &stmt.kind {
+            StmtKind::Block { header, body }
+                if header.strip_prefix(name).is_some_and(|rest| rest.trim_start().starts_with('(')) =>
+            {
+                Some(Arc::new(Function { name: name.to_string(), params: Vec::new(), body: body.clone(), line: stmt.line, prophecies: Vec::new() }))
+            }
+            _ => None,
+        });
+
+    let mut candidates: Vec<Arc<Function>> = methods.chain(program.functions.get(name).cloned()).collect();
+    candidates.sort_by_key(|function| function.line);
+    candidates
+}
+
+/// The value of `expr` if it is a constant, found without running anything:
+/// literals fold, and so do the operators between them, but names, calls and
+/// methods, builtins among them, are refused. An expression that would fault
+/// has no value.
+pub fn fold(expr: &Expr) -> Option<Value> {
+    match expr {
+        Expr::Number(n) => Some(Value::Number(*n)),
+        Expr::Str(s) => Some(Value::Str(s.clone())),
+        Expr::Bool(b) => Some(Value::Bool(*b)),
+        Expr::Array(items) => items.iter().map(fold).collect::<Option<_>>().map(Value::Array),
+        Expr::Object(fields) => fields.iter()
+            .map(|(key, value)| Some((key.clone(), fold(value)?)))
+            .collect::<Option<Fields>>()
+            .map(|fields| Value::Object(Rc::new(RefCell::new(fields)))),
+        Expr::Member { object, field } => Some(member_of(fold(object)?, field)),
+        Expr::Index { target, index } => index_of(fold(target)?, fold(index)?, 0).ok(),
+        Expr::Unary { op, operand } => unary(op, fold(operand)?, 0).ok(),
+        // Logical operators short-circuit here as they do in a run
+        Expr::Binary { op: op @ ("&&" | "||"), left, right } => {
+            let Value::Bool(left) = fold(left)? else { return None };
+            if (*op == "&&" && !left) || (*op == "||" && left) {
+                return Some(Value::Bool(left));
+            }
+            fold(right).filter(|right| matches!(right, Value::Bool(_)))
+        }
+        Expr::Binary { op, left, right } => binary(op, fold(left)?, fold(right)?, 0).ok(),
+        Expr::Ident(_) | Expr::Call { .. } | Expr::MethodCall { .. } => None,
+    }
+}
+
+fn unary(op: &'static str, operand: Value, line: usize) -> Result<Value, Fault> {
+    match (op, operand) {
+        ("!", Value::Bool(b)) => Ok(Value::Bool(!b)),
+        ("-", Value::Number(n)) => Ok(Value::Number(-n)),
+        (op, other) => Err(Fault::blaming(op, format!(
+            "DiscernmentError: cannot apply '{}' to {} at line {}", op, other.type_name(), line
+        ))),
+    }
+}
+
+/// Applies an operator other than `&&` and `||`, which short-circuit.
+fn binary(op: &str, left: Value, right: Value, line: usize) -> Result<Value, Fault> {
+    match (op, &left, &right) {
+        ("==", _, _) => Ok(Value::Bool(left == right)),
+        ("!=", _, _) => Ok(Value::Bool(left != right)),
+        ("+", Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+        ("+", Value::Str(_), _) | ("+", _, Value::Str(_)) => Ok(Value::Str(format!("{}{}", left, right))),
+        ("-", Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
+        ("*", Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
+        ("/", Value::Number(_), Value::Number(b)) | ("%", Value::Number(_), Value::Number(b)) if *b == 0.0 => {
+            Err(Fault::from(format!("DivisionError: cannot divide by zero at line {}", line)))
+        }
+        ("/", Value::Number(a), Value::Number(b)) => Ok(Value::Number(a / b)),
+        ("%", Value::Number(a), Value::Number(b)) => Ok(Value::Number(a % b)),
+        ("<", Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a < b)),
+        (">", Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a > b)),
+        ("<=", Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a <= b)),
+        (">=", Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a >= b)),
+        ("<", Value::Str(a), Value::Str(b)) => Ok(Value::Bool(a < b)),
+        (">", Value::Str(a), Value::Str(b)) => Ok(Value::Bool(a > b)),
+        ("<=", Value::Str(a), Value::Str(b)) => Ok(Value::Bool(a <= b)),
+        (">=", Value::Str(a), Value::Str(b)) => Ok(Value::Bool(a >= b)),
+        _ => Err(Fault::blaming(op, format!(
+            "DiscernmentError: cannot apply '{}' to {} and {} at line {}",
+            op, left.type_name(), right.type_name(), line
+        ))),
+    }
+}
+
+/// `object.field`. Missing fields, and fields of values that have none, are void.
+fn member_of(object: Value, field: &str) -> Value {
+    match object {
+        Value::Object(fields) => field_of(&fields.borrow(), field),
+        Value::Array(items) if field == "length" => Value::Number(items.len() as f64),
+        Value::Str(text) if field == "length" => Value::Number(text.chars().count() as f64),
+        _ => Value::Void,
+    }
+}
+
+fn field_of(fields: &Fields, field: &str) -> Value {
+    fields.iter().find(|(key, _)| key == field).map(|(_, value)| value.clone()).unwrap_or(Value::Void)
+}
+
+/// `target[index]`: arrays and strings are indexed by position from 0, objects by
+/// field name. Positions past the end and missing fields are void.
+fn index_of(target: Value, index: Value, line: usize) -> Result<Value, Fault> {
+    let position = |n: f64, len: usize| (n >= 0.0 && n.fract() == 0.0 && (n as usize) < len).then_some(n as usize);
+    match (&target, &index) {
+        (Value::Array(items), Value::Number(n)) => {
+            Ok(position(*n, items.len()).map(|i| items[i].clone()).unwrap_or(Value::Void))
+        }
+        (Value::Str(s), Value::Number(n)) => {
+            Ok(position(*n, s.chars().count()).and_then(|i| s.chars().nth(i)).map(|c| Value::Str(c.to_string())).unwrap_or(Value::Void))
+        }
+        (Value::Object(fields), Value::Str(key)) => Ok(field_of(&fields.borrow(), key)),
+        (Value::Void, _) => Ok(Value::Void),
+        _ => Err(Fault::from(format!(
+            "DiscernmentError: cannot index {} with {} at line {}", target.type_name(), index.type_name(), line
+        ))),
+    }
+}
+
+fn expect_bool(op: &str, value: Value, line: usize) -> Result<bool, Fault> {
+    match value {
+        Value::Bool(b) => Ok(b),
+        other => Err(Fault::blaming(op, format!(
+            "DiscernmentError: '{}' requires true or false, but found {} at line {}", op, other.type_name(), line
+        ))),
+    }
+}
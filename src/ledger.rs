@@ -0,0 +1,127 @@
+use colored::*;
+
+/// A promise made through `covenant("...")`.
+#[derive(Debug, Clone)]
+pub struct Promise {
+    pub text: String,
+    pub line: usize,
+    /// The line of the `fulfill("...")` that kept the promise.
+    pub fulfilled: Option<usize>,
+}
+
+/// Every covenant made during a run, kept for judgment day.
+#[derive(Debug)]
+pub struct Ledger {
+    promises: Vec<Promise>,
+    /// Whether to show a running tally as covenants are made and fulfilled.
+    announce: bool,
+}
+
+impl Ledger {
+    pub fn new(announce: bool) -> Self {
+        Self { promises: Vec::new(), announce }
+    }
+
+    pub fn make(&mut self, text: &str, line: usize) {
+        self.promises.push(Promise { text: text.to_string(), line, fulfilled: None });
+        if self.announce {
+            println!("{}", format!("📜 Covenant made at line {}: \"{}\" {}", line, text, self.tally()).bright_cyan());
+        }
+    }
+
+    /// Keeps the oldest open promise whose text is exactly `text`. A fulfill that
+    /// matches nothing is not a sin, but it is pointed out along with any promises
+    /// it was probably meant for.
+    pub fn fulfill(&mut self, text: &str, line: usize) {
+        if let Some(promise) = self.promises.iter_mut().find(|p| p.fulfilled.is_none() && p.text == text) {
+            promise.fulfilled = Some(line);
+            if self.announce {
+                let made = promise.line;
+                println!("{}", format!("🤝 Covenant from line {} fulfilled at line {}: \"{}\" {}", made, line, text, self.tally()).bright_green());
+            }
+            return;
+        }
+
+        if let Some(kept) = self.promises.iter().find(|p| p.text == text) {
+            println!("{}", format!(
+                "⚠️ fulfill(\"{}\") at line {}: that covenant was already fulfilled at line {}",
+                text, line, kept.fulfilled.unwrap_or(kept.line)
+            ).yellow());
+            return;
+        }
+
+        let suggestions: Vec<String> = self.unfulfilled()
+            .filter(|p| close(&p.text, text))
+            .map(|p| format!("\"{}\" (line {})", p.text, p.line))
+            .collect();
+        let note = if suggestions.is_empty() {
+            String::new()
+        } else {
+            format!("; did you mean {}?", suggestions.join(" or "))
+        };
+        println!("{}", format!("⚠️ fulfill(\"{}\") at line {} matches no covenant{}", text, line, note).yellow());
+    }
+
+    pub fn promises(&self) -> &[Promise] {
+        &self.promises
+    }
+
+    pub fn unfulfilled(&self) -> impl Iterator<Item = &Promise> {
+        self.promises.iter().filter(|p| p.fulfilled.is_none())
+    }
+
+    /// How many covenants are still open and how many were fulfilled.
+    pub fn tally(&self) -> String {
+        let open = self.unfulfilled().count();
+        format!("({} open, {} fulfilled)", open, self.promises.len() - open)
+    }
+}
+
+/// Whether `a` and `b` differ by few enough edits to be the same promise mistyped.
+fn close(a: &str, b: &str) -> bool {
+    let (a, b) = (a.to_lowercase(), b.to_lowercase());
+    let allowed = (a.chars().count().max(b.chars().count()) / 4).max(2);
+    a.trim() == b.trim() || crate::edit_distance(&a, &b) <= allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kept(ledger: &Ledger) -> Vec<(&str, usize, Option<usize>)> {
+        ledger.promises().iter().map(|p| (p.text.as_str(), p.line, p.fulfilled)).collect()
+    }
+
+    #[test]
+    fn a_fulfill_keeps_the_oldest_open_promise_of_its_text() {
+        let mut ledger = Ledger::new(false);
+        ledger.make("rest", 1);
+        ledger.make("return", 2);
+        ledger.make("rest", 3);
+        ledger.fulfill("rest", 4);
+        assert_eq!(kept(&ledger), [("rest", 1, Some(4)), ("return", 2, None), ("rest", 3, None)]);
+        ledger.fulfill("rest", 5);
+        assert_eq!(ledger.tally(), "(1 open, 2 fulfilled)");
+    }
+
+    #[test]
+    fn a_fulfill_that_matches_nothing_keeps_nothing() {
+        let mut ledger = Ledger::new(false);
+        ledger.make("return", 1);
+        ledger.fulfill("retrun", 2);
+        ledger.fulfill("Return", 3);
+        ledger.fulfill("return", 4);
+        // Already kept, so a second fulfill changes nothing
+        ledger.fulfill("return", 5);
+        assert_eq!(kept(&ledger), [("return", 1, Some(4))]);
+        assert_eq!(ledger.unfulfilled().count(), 0);
+    }
+
+    #[test]
+    fn promises_a_few_edits_apart_are_close() {
+        assert!(close("return", "retrun"));
+        assert!(close("Return", "return "));
+        assert!(close("deliver the scroll", "delivr the scrol"));
+        assert!(!close("return", "rest"));
+    }
+}
@@ -0,0 +1,261 @@
+/// The kinds of token the DivinePL lexer produces.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    /// A backtick template literal, kept raw; interpolation is not understood yet.
+    Template(String),
+    Symbol(&'static str),
+    /// A single-line prayer, markers included.
+    Prayer(String),
+    /// The lines of a whole BEGIN/END PRAYER block, verbatim, in either delimiter style.
+    PrayerBlock(Vec<String>),
+    /// A `//` comment, slashes included, or a whole `/* ... */` comment.
+    Comment(String),
+    Newline,
+    Unknown(char),
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub line: usize,
+    /// Byte offsets of the token within the source.
+    pub start: usize,
+    pub end: usize,
+}
+
+pub const BEGIN_PRAYER: &str = "🙏 BEGIN PRAYER 🙏";
+pub const END_PRAYER: &str = "🙏 END PRAYER 🙏";
+
+/// The same delimiters for keyboards and terminals without emoji.
+pub const PLAIN_BEGIN_PRAYER: &str = "BEGIN PRAYER";
+pub const PLAIN_END_PRAYER: &str = "END PRAYER";
+/// Begins a single-line prayer in place of 🙏.
+pub const PLAIN_PRAYER: &str = "::pray::";
+
+/// Whether a line, trimmed, begins a prayer block, in either delimiter style.
+pub fn is_begin_prayer(line: &str) -> bool {
+    matches!(line.trim(), BEGIN_PRAYER | PLAIN_BEGIN_PRAYER)
+}
+
+/// Whether a line, trimmed, ends a prayer block, in either delimiter style.
+pub fn is_end_prayer(line: &str) -> bool {
+    matches!(line.trim(), END_PRAYER | PLAIN_END_PRAYER)
+}
+
+/// Whether a line is a prayer, or a prayer block's delimiter, from its start.
+pub fn is_prayer(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('🙏') || line.starts_with(PLAIN_PRAYER) || is_begin_prayer(line) || is_end_prayer(line)
+}
+
+// Longest symbols first so that `==` wins over `=`
+const SYMBOLS: &[&str] = &[
+    "===", "!==", "==", "!=", "<=", ">=", "&&", "||", "=>", "++", "--", "+=", "-=", "*=", "/=",
+    "(", ")", "{", "}", "[", "]", ",", ";", ".", ":", "=", "<", ">", "+", "-", "*", "/", "%", "!", "?", "@",
+];
+
+/// Strips the byte order mark some editors, mostly on Windows, put at the start of a file.
+pub fn without_bom(source: &str) -> &str {
+    source.strip_prefix('\u{FEFF}').unwrap_or(source)
+}
+
+pub fn tokenize(source: &str) -> Vec<Token> {
+    Lexer { source, pos: 0, line: 1, line_start: 0, tokens: Vec::new() }.run()
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    tokens: Vec<Token>,
+}
+
+impl Lexer<'_> {
+    fn rest(&self) -> &str {
+        &self.source[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.source[self.line_start..self.pos].trim().is_empty()
+    }
+
+    fn current_line(&self) -> &str {
+        let rest = &self.source[self.line_start..];
+        rest.split('\n').next().unwrap_or("")
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize, line: usize) {
+        self.tokens.push(Token { kind, line, start, end: self.pos });
+    }
+
+    fn newline(&mut self) {
+        self.line += 1;
+        self.line_start = self.pos;
+    }
+
+    fn run(mut self) -> Vec<Token> {
+        while let Some(c) = self.peek() {
+            let start = self.pos;
+            let line = self.line;
+
+            if c == '\n' {
+                self.pos += 1;
+                self.push(TokenKind::Newline, start, line);
+                self.newline();
+            } else if c.is_whitespace() {
+                self.pos += c.len_utf8();
+            } else if self.at_line_start() && is_prayer(self.current_line()) {
+                let prayer = self.prayer();
+                self.push(prayer, start, line);
+            } else if self.rest().starts_with("/*") {
+                // Block comments run through the closing `*/`, or to the end of the script
+                let len = self.rest().find("*/").map_or(self.rest().len(), |end| end + 2);
+                let comment = self.rest()[..len].to_string();
+                for (offset, _) in comment.match_indices('\n') {
+                    self.line += 1;
+                    self.line_start = start + offset + 1;
+                }
+                self.pos += len;
+                self.push(TokenKind::Comment(comment), start, line);
+            } else if self.rest().starts_with("//") {
+                // Line comments run until (but not including) the newline
+                let len = self.rest().find('\n').unwrap_or(self.rest().len());
+                let comment = self.rest()[..len].trim_end().to_string();
+                self.pos += len;
+                self.push(TokenKind::Comment(comment), start, line);
+            } else if c == '"' || c == '\'' {
+                let value = self.string(c);
+                self.push(TokenKind::Str(value), start, line);
+            } else if c == '`' {
+                let raw = self.template();
+                self.push(TokenKind::Template(raw), start, line);
+            } else if c.is_ascii_digit() {
+                let number = self.number();
+                self.push(TokenKind::Number(number), start, line);
+            } else if c.is_alphabetic() || c == '_' || c == '$' {
+                let len = self.rest()
+                    .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+                    .unwrap_or(self.rest().len());
+                let ident = self.rest()[..len].to_string();
+                self.pos += len;
+                self.push(TokenKind::Ident(ident), start, line);
+            } else if let Some(symbol) = SYMBOLS.iter().find(|s| self.rest().starts_with(**s)) {
+                self.pos += symbol.len();
+                self.push(TokenKind::Symbol(symbol), start, line);
+            } else {
+                self.pos += c.len_utf8();
+                self.push(TokenKind::Unknown(c), start, line);
+            }
+        }
+
+        let (start, line) = (self.pos, self.line);
+        self.push(TokenKind::Eof, start, line);
+        self.tokens
+    }
+
+    /// Consumes a single-line prayer, or a whole prayer block when the line opens one.
+    fn prayer(&mut self) -> TokenKind {
+        let opening = self.current_line().trim().to_string();
+        if !is_begin_prayer(&opening) {
+            let len = self.rest().find('\n').unwrap_or(self.rest().len());
+            self.pos += len;
+            return TokenKind::Prayer(opening);
+        }
+
+        // Prayers are kept exactly as they were written, markers and indentation included
+        let mut text = Vec::new();
+        loop {
+            let len = self.rest().find('\n').unwrap_or(self.rest().len());
+            let line = self.current_line().trim_end_matches('\r').to_string();
+            self.pos += len;
+
+            let ended = is_end_prayer(&line);
+            text.push(line);
+            if ended {
+                return TokenKind::PrayerBlock(text);
+            }
+            if self.pos >= self.source.len() {
+                return TokenKind::PrayerBlock(text);
+            }
+            // Step over the newline; the block is a single token spanning many lines
+            self.pos += 1;
+            self.newline();
+        }
+    }
+
+    fn string(&mut self, quote: char) -> String {
+        self.pos += 1;
+        let mut value = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
+            match c {
+                '\\' => {
+                    let Some(escaped) = self.peek() else { break };
+                    self.pos += escaped.len_utf8();
+                    match escaped {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        other => value.push(other),
+                    }
+                }
+                c if c == quote => break,
+                '\n' => {
+                    // Unterminated literal; stop at the end of the line
+                    self.pos -= 1;
+                    break;
+                }
+                other => value.push(other),
+            }
+        }
+        value
+    }
+
+    fn template(&mut self) -> String {
+        self.pos += 1;
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
+            match c {
+                '\\' => {
+                    if let Some(escaped) = self.peek() {
+                        self.pos += escaped.len_utf8();
+                    }
+                }
+                '`' => return self.source[start..self.pos - 1].to_string(),
+                '\n' => self.newline(),
+                _ => {}
+            }
+        }
+        self.source[start..].to_string()
+    }
+
+    fn number(&mut self) -> f64 {
+        let digits = |text: &str| text.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(text.len());
+        let mut len = digits(self.rest());
+        let after = &self.rest()[len..];
+        if after.starts_with('.') && after[1..].starts_with(|ch: char| ch.is_ascii_digit()) {
+            len += 1 + digits(&after[1..]);
+        }
+        // An exponent, as in 1e5 or 2.5E-3, only when digits follow it
+        let after = &self.rest()[len..];
+        if after.starts_with(['e', 'E']) {
+            let sign = usize::from(after[1..].starts_with(['+', '-']));
+            let exponent = digits(&after[1 + sign..]);
+            if exponent > 0 {
+                len += 1 + sign + exponent;
+            }
+        }
+        let number = self.rest()[..len].parse().unwrap_or(0.0);
+        self.pos += len;
+        number
+    }
+}
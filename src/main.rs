@@ -1,14 +1,104 @@
-use chrono::{Datelike, Local};
-use clap::{Parser, Subcommand};
+use chrono::{Local, NaiveDate};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use colored::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
 use std::time::{Duration, Instant};
-use std::collections::HashMap;
-use rand::thread_rng;
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Everything printed goes through `glyphs::render`, so --plain-glyphs reaches
+// every emoji of every command. Defined before the modules so they print so too.
+macro_rules! print {
+    ($($arg:tt)*) => { ::std::print!("{}", $crate::glyphs::render(&::std::format!($($arg)*))) };
+}
+macro_rules! println {
+    () => { ::std::println!() };
+    ($($arg:tt)*) => { ::std::println!("{}", $crate::glyphs::render(&::std::format!($($arg)*))) };
+}
+macro_rules! eprint {
+    ($($arg:tt)*) => { ::std::eprint!("{}", $crate::glyphs::render(&::std::format!($($arg)*))) };
+}
+macro_rules! eprintln {
+    () => { ::std::eprintln!() };
+    ($($arg:tt)*) => { ::std::eprintln!("{}", $crate::glyphs::render(&::std::format!($($arg)*))) };
+}
+
+mod absolve;
+mod anoint;
+mod apocrypha;
+mod ark;
+mod artifacts;
+mod ast;
+mod baseline;
+mod bible;
+mod builtins;
+mod calendar;
+mod canon;
+mod chronicles;
+mod clock;
+mod commune;
+mod config;
+mod confession;
+mod creation;
+mod daemon;
+mod diff;
+mod diagnostic;
+mod embedded;
+mod environment;
+mod error;
+mod examine;
+mod forgiveness;
+mod foretold;
+mod git;
+mod glyphs;
+mod interpreter;
+mod ledger;
+mod lexer;
+mod miracle;
+mod modules;
+mod omens;
+mod parser;
+mod pattern;
+mod printer;
+mod prophesy;
+mod sarif;
+mod sabbath;
+mod scroll;
+mod sepulchre;
+mod status;
+mod stewardship;
+mod testament;
+mod tithe;
+mod trace;
+mod transfiguration;
+mod tribulation;
+mod verses;
+mod vision;
+mod walk;
+mod watch;
+
+use ark::Ark;
+use config::{Commandments, CustomCommandment, CONFIG_FILE_NAME};
+use diagnostic::{Diagnostic, Level};
+use environment::{Environment, Flags};
+use error::{DivineError, SinKind, Span};
+use forgiveness::Forgiveness;
+use baseline::Baseline;
+use ast::Program;
+use calendar::{Calendar, Season};
+use confession::{Confession, PenanceThreshold};
+use clock::{Clock, FixedClock, SystemClock};
+use interpreter::{Halt, Interpreter, Testimony};
+use modules::Scriptorium;
+use sabbath::Sabbath;
+use trace::{Event, Trace};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -17,99 +107,796 @@ struct Cli {
     #[command(subcommand)]
     command: Commands,
     
-    /// Force compilation on Sunday (only available in development mode with --dev flag)
+    /// Say more: -v echoes each statement, -vv adds divine insights, -vvv adds how scripts are read
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    
+    /// Say only what the script prints, the findings and the errors, without ceremony
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    
+    /// Force compilation on Sunday (only available in development mode with --dev flag; also set by DIVINEPL_OVERRIDE_SABBATH)
     #[arg(long, default_value_t = false)]
     override_sabbath: bool,
     
-    /// Enable development mode (unlocks sinful operations)
+    /// The day kept as the Sabbath, mon to sun, over the commandments' sabbath_day
+    #[arg(long, value_name = "DAY")]
+    sabbath_day: Option<String>,
+    
+    /// The timezone the Sabbath is reckoned in: local, utc or an IANA name, over the commandments' sabbath_timezone
+    #[arg(long, value_name = "TZ")]
+    sabbath_timezone: Option<String>,
+    
+    /// Run as if today were this day, or this time of it, to reproduce what happens then (requires --dev)
+    #[arg(long, value_name = "YYYY-MM-DD[THH:MM]", hide = true)]
+    pretend_today: Option<String>,
+    
+    /// Enable development mode (unlocks sinful operations; also set by DIVINEPL_DEV)
     #[arg(long, default_value_t = false)]
     dev: bool,
+    
+    /// Skip the ritual pauses, printing the same lines at once (also set by DIVINEPL_FAST)
+    #[arg(long, default_value_t = false)]
+    fast: bool,
+    
+    /// When to color output: auto colors stdout and stderr each when it is a terminal
+    #[arg(long, value_name = "WHEN")]
+    color: Option<ColorChoice>,
+    
+    /// Print without colors, as --color never (also set by DIVINEPL_NO_COLOR or NO_COLOR)
+    #[arg(long, default_value_t = false, conflicts_with = "color")]
+    no_color: bool,
+    
+    /// Print a stable, line-oriented form of run, confess and prophesy for other programs to read
+    ///
+    /// Porcelain output has no colors, no emoji and no pauses, and keeps this
+    /// form from version to version:
+    ///
+    ///   run       stmt <line> ok, or stmt <line> sin <code>, as each statement
+    ///             ends, then verdict saved or verdict purgatory at judgment
+    ///   confess   sin <venial|mortal> <rule> <file>:<line> for each sin
+    ///   prophesy  declared <marker> <file>:<line> <text> for each TODO, FIXME,
+    ///             HACK or @prophesy, then prophecy <n> <text> for each prophecy,
+    ///             for a directory script <file> <prophecies> <doom> for each
+    ///             script and observation <text> for each observation, then
+    ///             doom <score>
+    ///
+    /// What the script prints is printed as it is, between the stmt lines.
+    /// Errors go to stderr as always.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["verbose", "color"], verbatim_doc_comment)]
+    porcelain: bool,
+    
+    /// Print emoji as ASCII markers such as [PRAY] and [DOVE], and have miracles write
+    /// ASCII prayers (also set by DIVINEPL_PLAIN_GLYPHS, and on when the locale isn't UTF-8)
+    #[arg(long, default_value_t = false)]
+    plain_glyphs: bool,
+    
+    /// Run, confess or prophesy about a script that isn't a .divine or .dpl file
+    #[arg(long, default_value_t = false)]
+    unorthodox: bool,
+    
+    /// Ignore every commandments.config, as though no script were in a project
+    #[arg(long, default_value_t = false)]
+    no_config: bool,
+    
+    /// Take in the scripts the apocrypha and .divineignore pass over, for a full audit
+    #[arg(long, default_value_t = false)]
+    no_apocrypha: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Run a DivinePL script with divine interpretation
+    #[command(visible_alias = "exec")]
     Run {
-        /// Path to the DivinePL script (.divine or .dpl file)
+        /// Path to the DivinePL script (.divine or .dpl file), or a directory
+        /// holding exactly one script with the entry point
         #[arg(required = true)]
         path: PathBuf,
         
-        /// Enable verbose output for debugging
-        #[arg(short, long, default_value_t = false)]
-        verbose: bool,
-        
         /// Enable Revelation Mode for deep divine insight
         #[arg(short, long, default_value_t = false)]
         revelation: bool,
+        
+        /// Maximum iterations of a while loop before it is judged runaway (0 disables, dev mode only)
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_TRIBULATION_LIMIT)]
+        tribulation_limit: usize,
+        
+        /// Maximum depth of nested blessed function calls
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_RECURSION_LIMIT)]
+        recursion_limit: usize,
+        
+        /// Seed every chance the run takes, castLots included, so it can be repeated
+        #[arg(long, value_name = "N")]
+        seed: Option<u64>,
+        
+        /// Run this blessed function instead of genesis
+        #[arg(long, value_name = "NAME")]
+        entry: Option<String>,
+        
+        /// Arguments for the script, given after --, which it reads from the manna array
+        #[arg(last = true, value_name = "ARGS")]
+        manna: Vec<String>,
+        
+        /// Write a JSON report of the run, to PATH or else into the project's artifacts
+        #[arg(long, value_name = "PATH", num_args = 0..=1)]
+        report: Option<Option<PathBuf>>,
+        
+        /// Run each DivinePL block embedded in a document; every block must be saved
+        #[arg(long, value_enum, value_name = "FORMAT", conflicts_with = "report")]
+        embedded: Option<Embedded>,
+        
+        /// Run again whenever the script or a module it imports changes
+        #[arg(long, default_value_t = false, conflicts_with_all = ["report", "embedded"])]
+        watch: bool,
+        
+        /// Only check that the script parses and keeps the commandments, without running it
+        #[arg(long, default_value_t = false, conflicts_with_all = ["report", "embedded", "watch"])]
+        check: bool,
+        
+        /// Ignore every // forgiven: comment, judging each sin as if none were forgiven
+        #[arg(long, default_value_t = false)]
+        no_forgiveness: bool,
+        
+        /// Judge sins against this rule as mortal, whatever the commandments say (may be repeated)
+        #[arg(long, value_name = "RULE")]
+        deny: Vec<String>,
+        
+        /// Turn this rule off, whatever the commandments say (may be repeated)
+        #[arg(long, value_name = "RULE")]
+        allow: Vec<String>,
+        
+        /// Time each line, blessed function and loop, and report on it after judgment
+        #[arg(long, default_value_t = false, conflicts_with_all = ["check", "embedded"])]
+        profile: bool,
+        
+        /// How the --profile report is written
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text, requires = "profile")]
+        format: OutputFormat,
+        
+        /// Append a line for each statement executed, and for what befalls the run,
+        /// to PATH or else into the project's artifacts
+        #[arg(long, value_name = "PATH", num_args = 0..=1, conflicts_with_all = ["check", "embedded"])]
+        trace: Option<Option<PathBuf>>,
+        
+        /// How --trace lines are written: plain text, or a JSON object per line
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text, requires = "trace")]
+        trace_format: OutputFormat,
+        
+        /// Pause before each statement for a command: n(ext), c(ontinue), p NAME, s(ins), q(uit)
+        #[arg(long, visible_alias = "debug", default_value_t = false, conflicts_with_all = ["check", "embedded", "watch"])]
+        smite_step: bool,
+        
+        /// Pause at each selah to show the variables and covenants, until Enter is pressed
+        #[arg(long, default_value_t = false, conflicts_with_all = ["check", "embedded", "watch"])]
+        honor_selah: bool,
+        
+        /// Stop a run still going after SECONDS, exiting 7 (0 never stops it)
+        #[arg(long, value_name = "SECONDS", default_value_t = 0, conflicts_with = "check")]
+        rapture_after: u64,
     },
     
     /// Create a new DivinePL project with basic structure
+    #[command(visible_alias = "init")]
     New {
         /// Name of the project
-        #[arg(required = true)]
-        name: String,
+        #[arg(required_unless_present = "list_templates")]
+        name: Option<String>,
         
-        /// Project template (default, miracle, or prophet)
+        /// Project template (see --list-templates)
         #[arg(short, long, default_value = "default")]
         template: String,
+        
+        /// List the templates a project can be created from, and exit
+        #[arg(long, default_value_t = false, conflicts_with = "name")]
+        list_templates: bool,
+        
+        /// Directory of your own templates, used instead of ~/.divinepl/templates;
+        /// its templates win over built-in ones of the same name
+        #[arg(long)]
+        template_dir: Option<PathBuf>,
+    },
+    
+    /// Rename a DivinePL project, along with the mentions of its name
+    Baptize {
+        /// Path to the project to rename
+        #[arg(required = true)]
+        old_path: PathBuf,
+        
+        /// The project's new name
+        #[arg(required = true)]
+        new_name: String,
+        
+        /// List the changes without making them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
     },
     
     /// Check if a DivinePL script is free from sin (linting)
+    #[command(visible_alias = "lint", alias = "check")]
     Confess {
-        /// Path to the DivinePL script to confess
-        #[arg(required = true)]
-        path: PathBuf,
+        /// Path to the DivinePL script, or directory of scripts, to confess
+        /// (limits the scripts compared with --between)
+        #[arg(required_unless_present_any = ["between", "explain"])]
+        path: Option<PathBuf>,
+        
+        /// Confess through a running daemon if one is available
+        #[arg(long, default_value_t = false)]
+        daemon_auto: bool,
+        
+        /// Compare the sins of two git revisions, e.g. v1.0..HEAD
+        #[arg(long, value_name = "REV1..REV2")]
+        between: Option<String>,
+        
+        /// Output format for the confession report
+        #[arg(long, value_enum, default_value_t = ConfessionFormat::Text)]
+        format: ConfessionFormat,
+        
+        /// Confess each DivinePL block embedded in a document
+        #[arg(long, value_enum, value_name = "FORMAT", conflicts_with_all = ["between", "daemon_auto"])]
+        embedded: Option<Embedded>,
+        
+        /// Fix the sins that can be fixed mechanically, in a script or every script in a directory
+        #[arg(long, default_value_t = false, conflicts_with_all = ["between", "daemon_auto", "embedded"])]
+        absolve: bool,
+        
+        /// Walk through the sins one by one, absolving, forgiving or skipping each
+        #[arg(long, default_value_t = false, conflicts_with_all = ["between", "daemon_auto", "embedded", "absolve", "format", "watch"])]
+        interactive: bool,
+        
+        /// With --absolve, print a diff of the fixes instead of writing them
+        #[arg(long, default_value_t = false, requires = "absolve")]
+        dry_run: bool,
+        
+        /// Confess again whenever the script or a module it imports changes
+        #[arg(long, default_value_t = false, conflicts_with_all = ["between", "embedded", "absolve"])]
+        watch: bool,
+        
+        /// When confessing a directory, pass over scripts under this path, relative to it (may be repeated)
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["between", "embedded", "absolve", "watch"])]
+        ignore: Vec<String>,
+        
+        /// The least grave sin that makes confession exit non-zero
+        #[arg(long, value_enum, default_value_t = PenanceThreshold::Venial)]
+        penance_threshold: PenanceThreshold,
+        
+        /// Explain a rule, by identifier (e.g. DPL010) or name, instead of confessing
+        #[arg(long, value_name = "RULE", num_args = 0..=1, conflicts_with_all = ["path", "between", "embedded", "absolve", "watch"])]
+        explain: Option<Option<String>>,
+        
+        /// With --explain, list every rule
+        #[arg(long, default_value_t = false, requires = "explain")]
+        list: bool,
+        
+        /// Ignore every // forgiven: comment, for audits
+        #[arg(long, default_value_t = false)]
+        no_forgiveness: bool,
+        
+        /// Excuse the sins recorded in this baseline file, reporting only those found since
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["between", "embedded", "absolve", "interactive", "watch"])]
+        baseline: Option<PathBuf>,
+        
+        /// Record every sin found in the --baseline file, keeping when each was first recorded
+        #[arg(long, default_value_t = false, requires = "baseline")]
+        update_baseline: bool,
+        
+        /// Drop the entries of the --baseline file that no sin matches any more
+        #[arg(long, default_value_t = false, requires = "baseline", conflicts_with = "update_baseline")]
+        prune_baseline: bool,
+        
+        /// Judge sins against this rule as mortal, whatever the commandments say (may be repeated)
+        #[arg(long, value_name = "RULE")]
+        deny: Vec<String>,
+        
+        /// Turn this rule off, whatever the commandments say (may be repeated)
+        #[arg(long, value_name = "RULE")]
+        allow: Vec<String>,
     },
     
     /// Find scriptural inspirations for your code
     Bible {
-        /// Topic to search for inspiration
-        #[arg(required = true)]
-        topic: String,
+        /// Topics to search for inspiration; the verses of each are shown
+        /// under a heading of its own
+        #[arg(required_unless_present_any = ["list", "list_sources"])]
+        topics: Vec<String>,
+        
+        /// List every topic of scripture with its tags
+        #[arg(long, default_value_t = false, conflicts_with_all = ["topics", "list_sources"])]
+        list: bool,
+        
+        /// List where the known verses came from: the built-in ones, and
+        /// each verse pack with how many verses it gave
+        #[arg(long, default_value_t = false, conflicts_with = "topics")]
+        list_sources: bool,
     },
     
     /// Perform a miracle transformation on a secular code file
     Miracle {
-        /// Path to secular code to be transformed
+        /// Path to secular code to be transformed, or a directory of it
+        #[arg(required = true)]
+        input_path: PathBuf,
+        
+        /// Path for the miraculous output, or the directory a directory's
+        /// miracles are written under
+        #[arg(required_unless_present_any = ["in_place", "preview", "stat"])]
+        output_path: Option<PathBuf>,
+        
+        /// Rewrite the secular code where it lies, keeping the original in the
+        /// sepulchre, or beside it as <name>.secular.bak
+        #[arg(long, default_value_t = false, conflicts_with = "output_path")]
+        in_place: bool,
+        
+        /// Perform the miracle again on code that already bears its mark
+        #[arg(long, default_value_t = false)]
+        retransform: bool,
+        
+        /// The language of the secular code, when its extension doesn't tell
+        /// (javascript, typescript, python or rust)
+        #[arg(long, value_name = "NAME")]
+        language: Option<String>,
+        
+        /// Print a diff of what the miracle would change, and how often each
+        /// substitution would be made, without writing anything
+        #[arg(long, visible_alias = "dry-run", default_value_t = false)]
+        preview: bool,
+        
+        /// Print only how often each substitution would be made, without
+        /// writing anything
+        #[arg(long, default_value_t = false, conflicts_with = "preview")]
+        stat: bool,
+        
+        /// Transform only the files of a directory with these extensions, as js,ts
+        #[arg(long, value_name = "EXT", value_delimiter = ',')]
+        extensions: Vec<String>,
+        
+        /// A JSON or TOML (.toml) file of rules to apply after the miracle's
+        /// own, and of its own to disable
+        #[arg(long, value_name = "PATH")]
+        liturgy: Option<PathBuf>,
+        
+        /// Write a JSON source map of where each line of the output came from
+        #[arg(long, value_name = "PATH")]
+        map: Option<PathBuf>,
+    },
+    
+    /// Run the bless test_* functions of testament scripts and report their affirmations
+    Testament {
+        /// A testament script, or a directory searched for *_testament.divine
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        
+        /// Run only the testaments whose names contain this
+        #[arg(long, value_name = "SUBSTRING")]
+        filter: Option<String>,
+    },
+    
+    /// Count what a project's scripts give: lines, blessings, prayers, covenants and devotion
+    Tithe {
+        /// A script, or a directory whose scripts are counted (defaults to the current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        
+        /// Output format for the report
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        
+        /// Shorthand for --format json
+        #[arg(long, default_value_t = false, conflicts_with = "format")]
+        json: bool,
+        
+        /// Pass over scripts under this path, relative to the directory counted (may be repeated)
+        #[arg(long, value_name = "PATH")]
+        ignore: Vec<String>,
+    },
+    
+    /// Tidy a DivinePL script's indentation and spacing, in place
+    #[command(visible_alias = "fmt")]
+    Sanctify {
+        /// Path to the DivinePL script to sanctify
+        #[arg(required = true)]
+        path: PathBuf,
+        
+        /// Change nothing; fail if the script is not already sanctified
+        #[arg(long, default_value_t = false)]
+        check: bool,
+        
+        /// Print the sanctified script instead of writing it back
+        #[arg(long, default_value_t = false, conflicts_with = "check")]
+        stdout: bool,
+    },
+    
+    /// Undo a miracle, returning sanctified code to its secular form
+    Secularize {
+        /// Path to code transformed by a miracle
         #[arg(required = true)]
         input_path: PathBuf,
         
-        /// Path for the miraculous output
+        /// Path for the secular output
         #[arg(required = true)]
         output_path: PathBuf,
+        
+        /// List how many times each substitution was reversed
+        #[arg(long, default_value_t = false)]
+        report: bool,
+        
+        /// The language to return the code to, when the output's extension
+        /// doesn't tell (javascript, typescript, python or rust)
+        #[arg(long, value_name = "NAME")]
+        language: Option<String>,
+        
+        /// The liturgy the miracle was performed with, whose rules are undone too
+        #[arg(long, value_name = "PATH")]
+        liturgy: Option<PathBuf>,
+        
+        /// The source map the miracle wrote, to put each line back where it was
+        #[arg(long, value_name = "PATH")]
+        map: Option<PathBuf>,
+    },
+    
+    /// Tell which line of the secular code a line of a miracle came from
+    MapLookup {
+        /// A source map written by miracle --map
+        map: PathBuf,
+        
+        /// A line of the transformed code, counted from 1
+        generated_line: usize,
     },
     
     /// Prophesy future TODOs and potential bugs in your DivinePL script
+    #[command(visible_alias = "todo")]
     Prophesy {
-        /// Path to the DivinePL script to prophesy about
+        /// Path to the DivinePL script to prophesy about, or a directory of them
         #[arg(required = true)]
         path: PathBuf,
+        
+        /// Exit with status 1 if any prophecy of this category is foretold (repeatable)
+        #[arg(long, value_enum, value_name = "CATEGORY")]
+        fail_on: Vec<prophesy::ProphecyCategory>,
+        
+        /// Seed the prophecies foretold, so they can be foretold again
+        #[arg(long, value_name = "N")]
+        seed: Option<u64>,
+        
+        /// Write the vision to this file too, as Markdown (.md) or JSON (.json)
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+        
+        /// Divine nothing: only harvest the @prophesy annotations and TODO, FIXME
+        /// and HACK comments, from every script when PATH is a directory
+        #[arg(long, conflicts_with = "fail_on")]
+        todos_only: bool,
+        
+        /// Keep the ledger of prophecies here [default: .prophecies.json beside the script]
+        #[arg(long, value_name = "PATH")]
+        ledger: Option<PathBuf>,
+        
+        /// Print the ledger's pending and fulfilled prophecies without making new ones
+        #[arg(long, conflicts_with_all = ["fail_on", "seed", "output", "todos_only", "max_doom"])]
+        ledger_report: bool,
+        
+        /// Exit with status 1 if the doom of the prophecies exceeds N
+        #[arg(long, value_name = "N")]
+        max_doom: Option<usize>,
+    },
+    
+    /// Summarize your own locally kept usage chronicles
+    Chronicles {
+        #[command(subcommand)]
+        action: Option<ChroniclesAction>,
+    },
+    
+    /// Seal a whole project into a single runnable .ark file
+    Ark {
+        #[command(subcommand)]
+        action: ArkAction,
+    },
+    
+    /// List the relics of a file kept in .sepulchre, and restore one of them
+    Resurrect {
+        /// The file to resurrect
+        file: PathBuf,
+        
+        /// Which relic to restore, counting from 1 for the newest
+        #[arg(long, value_name = "N", default_value_t = 1)]
+        relic: usize,
+        
+        /// Only list the relics
+        #[arg(long)]
+        list: bool,
+        
+        /// Overwrite changes to the file that neither a relic, the last commit nor a command's own write holds
+        #[arg(long)]
+        force: bool,
+    },
+    
+    /// Manage the artifacts kept from past runs in .divinepl/heaven and .divinepl/purgatory
+    Artifacts {
+        #[command(subcommand)]
+        action: ArtifactsAction,
+    },
+    
+    /// Count the project's sins, compactly enough for a shell prompt
+    Status {
+        /// Print a machine-readable form instead of the summary
+        #[arg(long, value_enum)]
+        porcelain: Option<Porcelain>,
+        
+        /// Print a snippet that adds the status to your shell prompt
+        #[arg(long, value_enum, conflicts_with = "porcelain")]
+        init: Option<Shell>,
+        
+        /// Recount and cache without printing (used by prompts that ran out of time)
+        #[arg(long, hide = true)]
+        refresh: bool,
+    },
+    
+    /// Keep a warm confession daemon running for editor tooling
+    Daemon {
+        /// Socket to listen on (defaults to a per-user socket in the temp directory)
+        #[arg(long)]
+        socket: Option<PathBuf>,
+        
+        /// Seconds without requests before the daemon departs in peace
+        #[arg(long, default_value_t = 600)]
+        idle_timeout: u64,
+    },
+    
+    /// Commune with DivinePL at an interactive prompt, without writing a script
+    Commune,
+    
+    /// Check the environment DivinePL runs in: the Sabbath, the terminal and the commandments
+    Anoint {
+        /// Output format for the report
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        
+        /// Shorthand for --format json
+        #[arg(long, default_value_t = false, conflicts_with = "format")]
+        json: bool,
+    },
+    
+    /// Check the commandments.config governing a path and show every commandment in effect
+    Examine {
+        /// A script or directory of the project (defaults to the current directory)
+        path: Option<PathBuf>,
+        
+        /// Print a JSON Schema of every commandment instead, for editors
+        #[arg(long, default_value_t = false, conflicts_with = "path")]
+        schema: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ChroniclesAction {
+    /// Burn every recorded chronicle
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum ArkAction {
+    /// Package the project's scripts and commandments into one file
+    Build {
+        /// The project to package (defaults to the current directory)
+        project: Option<PathBuf>,
+        
+        /// Where to write the ark (defaults to <project>.ark)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// The script runs start from, relative to the project root
+        #[arg(long, value_name = "SCRIPT", default_value = "genesis.divine")]
+        entry_script: String,
     },
 }
 
+#[derive(Subcommand)]
+enum ArtifactsAction {
+    /// List the artifacts kept for this project
+    List,
+    /// Remove every artifact kept for this project
+    Clean,
+}
+
+impl Commands {
+    /// The name recorded in the chronicles.
+    fn name(&self) -> &'static str {
+        match self {
+            Commands::Run { .. } => "run",
+            Commands::New { .. } => "new",
+            Commands::Baptize { .. } => "baptize",
+            Commands::Confess { .. } => "confess",
+            Commands::Bible { .. } => "bible",
+            Commands::Miracle { .. } => "miracle",
+            Commands::Secularize { .. } => "secularize",
+            Commands::MapLookup { .. } => "map-lookup",
+            Commands::Sanctify { .. } => "sanctify",
+            Commands::Testament { .. } => "testament",
+            Commands::Tithe { .. } => "tithe",
+            Commands::Prophesy { .. } => "prophesy",
+            Commands::Chronicles { .. } => "chronicles",
+            Commands::Ark { .. } => "ark",
+            Commands::Resurrect { .. } => "resurrect",
+            Commands::Artifacts { .. } => "artifacts",
+            Commands::Status { .. } => "status",
+            Commands::Daemon { .. } => "daemon",
+            Commands::Commune => "commune",
+            Commands::Anoint { .. } => "anoint",
+            Commands::Examine { .. } => "examine",
+        }
+    }
+    
+    /// Whether the command executes or transforms code, and so is labor the
+    /// Sabbath forbids. Reading, linting and reporting are not.
+    fn is_labor(&self) -> bool {
+        matches!(self,
+            Commands::Run { .. } | Commands::Miracle { .. } | Commands::Secularize { .. } | Commands::Sanctify { .. }
+            | Commands::Testament { .. } | Commands::Commune | Commands::Confess { absolve: true, dry_run: false, .. }
+            | Commands::Confess { interactive: true, .. })
+    }
+    
+    /// The path whose project commandments govern this invocation.
+    fn anchor(&self) -> PathBuf {
+        let path = match self {
+            Commands::Run { path, .. } | Commands::Prophesy { path, .. } | Commands::Sanctify { path, .. }
+            | Commands::Testament { path, .. } | Commands::Tithe { path, .. } => Some(path.as_path()),
+            Commands::Confess { path, .. } | Commands::Examine { path, .. } => path.as_deref(),
+            Commands::Miracle { input_path, .. } => Some(input_path.as_path()),
+            Commands::Resurrect { file, .. } => Some(file.as_path()),
+            _ => None,
+        };
+        path.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."))
+    }
+}
+
+/// How many times a while loop may iterate before it is judged runaway.
+const DEFAULT_TRIBULATION_LIMIT: usize = 10_000;
+
+/// How deeply blessed functions may call one another before the tower falls.
+const DEFAULT_RECURSION_LIMIT: usize = 256;
+
+/// How much a command says beyond what it must: less under --quiet, and more
+/// with each -v.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Verbosity {
+    /// Only what the script prints, the findings and the errors.
+    Quiet,
+    Normal,
+    /// Each statement is echoed as it executes.
+    Statements,
+    /// Divine insights and answered prayers too.
+    Insights,
+    /// How the script was read and its modules loaded too.
+    Internals,
+}
+
+impl Verbosity {
+    fn new(verbose: u8, quiet: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Statements,
+            (false, 2) => Verbosity::Insights,
+            (false, _) => Verbosity::Internals,
+        }
+    }
+}
+
+/// When output is colored, as --color asks.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Color stdout and stderr each when it is a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// How a confession is reported.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ConfessionFormat {
+    Text,
+    Json,
+    /// A SARIF 2.1.0 log, for code scanning
+    Sarif,
+}
+
+/// Documents that DivinePL can be embedded in.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Embedded {
+    /// ```divine fenced code blocks in Markdown; ```divine,ignore blocks are skipped
+    Markdown,
+}
+
+/// Machine-readable forms of `status`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Porcelain {
+    /// A single token such as `✝3|1` for 3 venial and 1 mortal sin
+    Prompt,
+}
+
+/// Shells whose prompts `status --init` can extend.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Shell {
+    Zsh,
+    Bash,
+}
+
 struct DivinePLRuntime {
-    start_time: Instant,
     dev_mode: bool,
-    verbose: bool,
+    /// How much is said beyond what must be, as -v and --quiet ask.
+    verbosity: Verbosity,
     revelation_mode: bool,
+    tribulation_limit: usize,
+    recursion_limit: usize,
+    /// Seeds every chance taken in a run, and the lots cast by scripts: given
+    /// by --seed, or else chosen at random.
+    seed: u64,
+    /// Whether the seed was given, so the run must repeat exactly.
+    seeded: bool,
+    /// Where the runtime's chances are drawn from.
+    fortune: Mutex<StdRng>,
+    /// Arguments for the script, bound to `manna`.
+    manna: Vec<String>,
+    /// Whether runs repeat under --watch, when the creation sequence is skipped.
+    watching: bool,
+    /// Whether the ritual pauses are skipped.
+    fast: bool,
+    /// Whether output keeps to the stable form of --porcelain.
+    porcelain: bool,
+    /// How long the ritual pauses have lasted, so profiling can set them apart.
+    lingered: Mutex<Duration>,
+    /// Whether runs are profiled with --profile, and how the report is written.
+    profile: Option<OutputFormat>,
+    /// Where runs are traced with --trace.
+    trace: Option<Trace>,
+    /// Whether runs pause before each statement, under --smite-step.
+    stepping: bool,
+    /// Whether `selah` pauses the run, under --honor-selah.
+    honor_selah: bool,
+    /// How many seconds a run may go on before it is raptured, or 0 for ever.
+    rapture_after: u64,
+    /// Whether `// forgiven:` comments are heeded.
+    forgiving: bool,
+    /// The sins confession excuses, under --baseline.
+    baseline: Option<Mutex<Baseline>>,
+    /// How rules are judged by --deny and --allow, over what the commandments say.
+    decrees: Vec<(&'static str, Option<SinSeverity>)>,
+    /// Whether the Sabbath is kept, as it is unless the commandments say otherwise.
+    sabbath_kept: bool,
+    /// Which day the Sabbath is, and in which timezone.
+    sabbath: Sabbath,
+    /// What the Sabbath, and anything else that depends on the day, asks the time of.
+    clock: Box<dyn Clock>,
+    /// Where in the liturgical year today falls.
+    season: Season,
+    /// Whether miracles may be performed.
+    miracles_allowed: bool,
+    /// Whether revelation mode offers divine insight more often.
+    deep_revelation: bool,
     prayer_answers: Vec<&'static str>,
-    bible_verses: HashMap<&'static str, &'static str>,
+    /// The built-in verses, with any verse packs merged over them.
+    bible_verses: &'static HashMap<String, verses::Verse>,
     miracles: Vec<&'static str>,
     divine_inspirations: HashMap<&'static str, Vec<&'static str>>,
 }
 
 impl DivinePLRuntime {
-    fn new(dev_mode: bool, verbose: bool, revelation_mode: bool) -> Self {
-        let mut bible_verses = HashMap::new();
-        bible_verses.insert("creation", "In the beginning God created the heaven and the earth. (Genesis 1:1)");
-        bible_verses.insert("light", "And God said, Let there be light: and there was light. (Genesis 1:3)");
-        bible_verses.insert("error", "For all have sinned, and come short of the glory of God. (Romans 3:23)");
-        bible_verses.insert("wisdom", "The fear of the LORD is the beginning of wisdom. (Proverbs 9:10)");
-        bible_verses.insert("debug", "Prove all things; hold fast that which is good. (1 Thessalonians 5:21)");
-        bible_verses.insert("loop", "And let us not be weary in well doing: for in due season we shall reap, if we faint not. (Galatians 6:9)");
-        bible_verses.insert("concurrency", "For where two or three are gathered together in my name, there am I in the midst of them. (Matthew 18:20)");
-        bible_verses.insert("promise", "For I know the thoughts that I think toward you, saith the LORD, thoughts of peace, and not of evil, to give you an expected future. (Jeremiah 29:11)");
-
+    fn new(dev_mode: bool, verbosity: Verbosity, revelation_mode: bool) -> Self {
         let mut divine_inspirations = HashMap::new();
         divine_inspirations.insert("error_handling", vec![
             "Try using 'confess' instead of 'catch'",
@@ -127,11 +914,35 @@ impl DivinePLRuntime {
             "Secure thy systems against the temptations of injection"
         ]);
 
+        let seed = rand::thread_rng().gen();
         Self {
-            start_time: Instant::now(),
             dev_mode,
-            verbose,
+            verbosity,
             revelation_mode,
+            tribulation_limit: DEFAULT_TRIBULATION_LIMIT,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+            seed,
+            seeded: false,
+            fortune: Mutex::new(StdRng::seed_from_u64(seed)),
+            manna: Vec::new(),
+            watching: false,
+            fast: false,
+            porcelain: false,
+            lingered: Mutex::new(Duration::ZERO),
+            profile: None,
+            trace: None,
+            stepping: false,
+            honor_selah: false,
+            rapture_after: 0,
+            forgiving: true,
+            baseline: None,
+            decrees: Vec::new(),
+            sabbath_kept: true,
+            sabbath: Sabbath::default(),
+            clock: Box::new(SystemClock),
+            season: Season::Ordinary,
+            miracles_allowed: true,
+            deep_revelation: false,
             prayer_answers: vec![
                 "Your prayer has been heard.",
                 "The Lord works in mysterious ways.",
@@ -141,7 +952,7 @@ impl DivinePLRuntime {
                 "Ask, and it shall be given you; seek, and ye shall find; optimize, and your code shall perform.",
                 "The Lord sees all variables, even those hidden in closures.",
             ],
-            bible_verses,
+            bible_verses: &verses::scripture().verses,
             miracles: vec![
                 "Water to Wine: Transformed mundane code into elegant expressions",
                 "Healing the Lame: Fixed runtime errors without modifying source",
@@ -153,30 +964,241 @@ impl DivinePLRuntime {
         }
     }
     
-    fn check_sabbath(&self, override_sabbath: bool) -> Result<(), String> {
-        let today = Local::now();
-        let is_sunday = today.weekday().num_days_from_monday() == 6;
-        
-        if is_sunday && !(override_sabbath && self.dev_mode) {
-            return Err("RestError: Remember the Sabbath day, to keep it holy (Exodus 20:8)".to_string());
+    fn with_tribulation_limit(mut self, limit: usize) -> Result<Self, String> {
+        if limit == 0 && !self.dev_mode {
+            return Err("Only in dev mode may loops wander without limit (--tribulation-limit 0 requires --dev)".to_string());
         }
-        
+        self.tribulation_limit = limit;
+        Ok(self)
+    }
+    
+    fn with_recursion_limit(mut self, limit: usize) -> Result<Self, String> {
+        if limit == 0 {
+            return Err("A tower must have at least one level (--recursion-limit must be 1 or more)".to_string());
+        }
+        self.recursion_limit = limit;
+        Ok(self)
+    }
+    
+    fn with_seed(mut self, seed: Option<u64>) -> Self {
+        if let Some(seed) = seed {
+            self.seed = seed;
+            self.seeded = true;
+            self.fortune = Mutex::new(StdRng::seed_from_u64(seed));
+        }
+        self
+    }
+    
+    /// Draws on the runtime's fortune, which --seed makes repeatable.
+    fn fortune(&self) -> MutexGuard<'_, StdRng> {
+        self.fortune.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    
+    fn with_manna(mut self, manna: Vec<String>) -> Self {
+        self.manna = manna;
+        self
+    }
+    
+    fn with_watching(mut self, watching: bool) -> Self {
+        self.watching = watching;
+        self
+    }
+    
+    fn with_fast(mut self, fast: bool) -> Self {
+        self.fast = fast;
+        self
+    }
+    
+    fn with_porcelain(mut self, porcelain: bool) -> Self {
+        self.porcelain = porcelain;
+        self
+    }
+    
+    fn with_profile(mut self, profile: Option<OutputFormat>) -> Self {
+        self.profile = profile;
+        self
+    }
+    
+    /// Traces runs to the file given, or into the project's artifacts when
+    /// --trace was given without one.
+    fn with_trace(mut self, destination: Option<Option<&Path>>, format: OutputFormat) -> Result<Self, String> {
+        self.trace = match destination {
+            Some(Some(path)) => Some(Trace::open(path, format)?),
+            Some(None) => Some(Trace::kept(format)),
+            None => None,
+        };
+        Ok(self)
+    }
+    
+    fn with_stepping(mut self, stepping: bool) -> Result<Self, String> {
+        if stepping && !io::stdin().is_terminal() {
+            return Err("--smite-step takes its commands from a terminal, but standard input is not one".to_string());
+        }
+        self.stepping = stepping;
+        Ok(self)
+    }
+    
+    fn with_selah(mut self, honored: bool) -> Self {
+        self.honor_selah = honored;
+        self
+    }
+    
+    fn with_rapture_after(mut self, seconds: u64) -> Self {
+        self.rapture_after = seconds;
+        self
+    }
+    
+    fn with_forgiveness(mut self, forgiving: bool) -> Self {
+        self.forgiving = forgiving;
+        self
+    }
+    
+    fn with_baseline(mut self, path: Option<&Path>) -> Result<Self, String> {
+        self.baseline = path.map(Baseline::load).transpose()?.map(Mutex::new);
+        Ok(self)
+    }
+    
+    /// Judges the rules in `deny` mortal and turns those in `allow` off; a rule
+    /// in both is off.
+    fn with_decrees(mut self, deny: &[String], allow: &[String]) -> Result<Self, String> {
+        let decreed = |key: &String| Rule::find(key).map(|rule| rule.id).ok_or_else(|| format!(
+            "Unknown rule '{}'; the rules are {}", key, RULES.iter().map(|rule| rule.id).collect::<Vec<_>>().join(", ")
+        ));
+        for key in deny {
+            self.decrees.push((decreed(key)?, Some(SinSeverity::Mortal)));
+        }
+        for key in allow {
+            self.decrees.push((decreed(key)?, None));
+        }
+        Ok(self)
+    }
+    
+    /// Keeps the Sabbath on the day and in the timezone given, else as the
+    /// commandments governing `anchor` say.
+    fn with_sabbath(mut self, day: Option<&str>, timezone: Option<&str>, anchor: &Path) -> Result<Self, String> {
+        self.sabbath = Sabbath::resolve(Commandments::for_script(anchor)?.as_ref(), day, timezone)?;
+        Ok(self)
+    }
+    
+    /// Runs as if today were `day`, written YYYY-MM-DD, or as if it were the
+    /// time written YYYY-MM-DDTHH:MM. Only development mode may pretend.
+    fn with_pretend_today(mut self, day: Option<&str>) -> Result<Self, String> {
+        let Some(day) = day else { return Ok(self) };
+        if !self.dev_mode {
+            return Err("--pretend-today is only available in development mode (--dev)".to_string());
+        }
+        self.clock = Box::new(FixedClock::pretending(day, &self.sabbath.timezone)?);
+        Ok(self)
+    }
+    
+    /// Keeps the calendar of the commandments governing `anchor`, reckoning
+    /// today in the Sabbath's timezone.
+    fn with_calendar(mut self, anchor: &Path) -> Result<Self, String> {
+        self.season = Calendar::new(Commandments::for_script(anchor)?.as_ref())?.season(self.today());
+        Ok(self)
+    }
+    
+    /// Heeds the commandments governing `script`, or carried in it if it is an
+    /// ark. Commandments that can't be parsed are an error, not ignored.
+    fn with_commandments(mut self, script: &Path) -> Result<Self, String> {
+        let commandments = if config::forgone() {
+            None
+        } else if Ark::is_ark(script) {
+            let ark = Ark::read(script)?;
+            let origin = PathBuf::from(format!("{}:{}", script.display(), CONFIG_FILE_NAME));
+            ark.file(CONFIG_FILE_NAME).map(|content| Commandments::parse(&origin, content)).transpose()?
+        } else {
+            Commandments::for_script(script)?
+        };
+        if let Some(commandments) = commandments {
+            self.sabbath_kept = commandments.keeps_sabbath();
+            self.miracles_allowed = commandments.allows_miracles();
+            self.deep_revelation = commandments.deep_revelation();
+        }
+        Ok(self)
+    }
+    
+    /// The standards sins are judged by: those of `commandments`, as overruled
+    /// by --deny and --allow. Entries of the commandments that can't be heeded
+    /// are warned about, once each.
+    fn standards(&self, commandments: Option<&Commandments>) -> Standards {
+        let mut standards = Standards::default();
+        if let Some(commandments) = commandments {
+            standards.heed(&commandments.settings);
+            standards.custom = commandments.custom.clone();
+        }
+        standards.judged.extend(self.decrees.iter().copied());
+        standards
+    }
+    
+    /// The standards a script on disk is judged by.
+    fn standards_for(&self, script: &Path) -> Result<Standards, String> {
+        Ok(self.standards(Commandments::for_script(script)?.as_ref()))
+    }
+    
+    /// The day it is by the runtime's clock, in the Sabbath's timezone.
+    fn today(&self) -> NaiveDate {
+        self.sabbath.timezone.date_at(self.clock.now())
+    }
+    
+    /// Pauses for ritual effect, unless running fast or quietly, with nothing to pause over.
+    fn linger(&self, millis: u64) {
+        if !self.fast && !self.quiet() {
+            std::thread::sleep(Duration::from_millis(millis));
+            *self.lingered.lock().unwrap_or_else(PoisonError::into_inner) += Duration::from_millis(millis);
+        }
+    }
+    
+    /// Whether only what the script prints, the findings and the errors are to be said.
+    fn quiet(&self) -> bool {
+        self.verbosity == Verbosity::Quiet
+    }
+    
+    /// How long the ritual pauses have lasted so far.
+    fn lingered(&self) -> Duration {
+        *self.lingered.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    
+    /// Whether today is a Sabbath the project keeps and the override doesn't lift.
+    fn is_sabbath(&self, override_sabbath: bool) -> bool {
+        self.sabbath_kept && self.sabbath.is_on(self.clock.now()) && !(override_sabbath && self.dev_mode)
+    }
+    
+    /// Forbids labor on the Sabbath. Anything else may go on, with a reminder.
+    fn check_sabbath(&self, command: &Commands, override_sabbath: bool) -> Result<(), DivineError> {
+        if !self.is_sabbath(override_sabbath) {
+            return Ok(());
+        }
+        if command.is_labor() {
+            let remaining = self.sabbath.remaining(self.clock.now());
+            return Err(DivineError::Rest(format!(
+                "RestError: Remember the Sabbath day, to keep it holy (Exodus 20:8); today is the Sabbath, {}. The Sabbath ends in {}",
+                self.sabbath, sabbath::spoken(remaining)
+            )));
+        }
+        eprintln!("{}", environment::for_stderr(format!("🕯️ It is the Sabbath, {}. Reading and reflection are welcome; running and transforming code must wait for tomorrow.", self.sabbath).yellow()));
         Ok(())
     }
 
-    fn extract_between(s: &str, start_delimiter: &str, end_delimiter: &str) -> Option<String> {
-        if let Some(start_idx) = s.find(start_delimiter) {
-            let start = start_idx + start_delimiter.len();
-            if let Some(end_idx) = s[start..].find(end_delimiter) {
-                return Some(s[start..start+end_idx].to_string());
-            }
+    fn parse_script(&self, content: &str) -> Result<Vec<DivinePLStatement>, String> {
+        let (statements, liturgical_error) = self.scan_script(content);
+        match liturgical_error {
+            Some((_, error)) => Err(error),
+            None => Ok(statements),
         }
-        None
     }
     
-    fn parse_script(&self, content: &str) -> Result<Vec<DivinePLStatement>, String> {
+    /// Splits a script into statements, skipping prayers and comments. Also
+    /// returns the first prayer block left open or begun again inside another,
+    /// with the line to blame; everything after such a block is taken as prayer.
+    fn scan_script(&self, content: &str) -> (Vec<DivinePLStatement>, Option<(usize, String)>) {
+        let content = lexer::without_bom(content);
         let mut statements = Vec::new();
         let mut in_multiline_prayer = false;
+        // The line the open prayer block was begun on
+        let mut prayer_begun = 0;
+        let mut liturgical_error = None;
+        let mut in_block_comment = false;
         
         // Split the content by lines for basic parsing
         for (line_num, line) in content.lines().enumerate() {
@@ -187,137 +1209,372 @@ impl DivinePLRuntime {
                 continue;
             }
             
+            // The rest of a block comment is neither prayer nor code
+            let after_comment;
+            let line = if in_block_comment {
+                after_comment = Self::without_comments(line, &mut in_block_comment);
+                if after_comment.trim().is_empty() {
+                    continue;
+                }
+                after_comment.trim()
+            } else {
+                line
+            };
+            
             // Handle multiline prayer blocks
-            if line == "🙏 BEGIN PRAYER 🙏" {
+            if lexer::is_begin_prayer(line) {
+                if in_multiline_prayer {
+                    liturgical_error.get_or_insert((line_num + 1, format!(
+                        "LiturgicalError: prayer begun at line {} is begun again at line {} before it was concluded with Amen",
+                        prayer_begun, line_num + 1
+                    )));
+                    continue;
+                }
                 in_multiline_prayer = true;
-                if self.verbose || self.revelation_mode {
+                prayer_begun = line_num + 1;
+                if self.verbosity >= Verbosity::Internals || self.revelation_mode {
                     println!("{}", "Entering sacred prayer block...".italic().bright_blue());
                 }
                 continue;
             }
             
-            if line == "🙏 END PRAYER 🙏" {
+            if lexer::is_end_prayer(line) {
                 in_multiline_prayer = false;
-                if self.verbose || self.revelation_mode {
+                if self.verbosity >= Verbosity::Internals || self.revelation_mode {
                     println!("{}", "Leaving sacred prayer block. Amen.".italic().bright_blue());
                 }
                 continue;
             }
             
             if in_multiline_prayer {
-                if self.verbose || self.revelation_mode {
+                if self.verbosity >= Verbosity::Internals || self.revelation_mode {
                     println!("{}", format!("  Prayer: {}", line).italic().blue());
                 }
                 continue;
             }
             
             // Handle single line prayer comments
-            if line.starts_with("🙏") {
-                if self.verbose || self.revelation_mode {
-                    let mut rng = rand::thread_rng();
-                    let answer = self.prayer_answers[rng.gen_range(0..self.prayer_answers.len())];
+            if lexer::is_prayer(line) {
+                if self.verbosity >= Verbosity::Insights || self.revelation_mode {
+                    let answer = self.prayer_answers[self.fortune().gen_range(0..self.prayer_answers.len())];
                     println!("{}", answer.italic().bright_blue());
                 }
                 continue;
             }
             
-            // Handle regular comments
-            if line.starts_with("//") {
+            // Comments, whole-line or trailing, are never judged as code
+            let code = Self::without_comments(line, &mut in_block_comment);
+            let line = code.trim();
+            if line.is_empty() {
                 continue;
             }
             
-            // NEW CODE: Handle revelation function calls - always print them
-            if line.contains("revelation(") {
-                // Extract the message between quotes
-                if let Some(message) = Self::extract_between(line, "revelation(\"", "\")") {
-                    println!("{}", format!("📢 {}", message).bright_cyan());
-                }
-            }
-            
-            // NEW CODE: Handle print function calls - always print them
-            if line.contains("print(") {
-                // Extract the message between quotes
-                if let Some(message) = Self::extract_between(line, "print(\"", "\")") {
-                    println!("{}", message);
-                }
-            }
-            
             // Handle Bible verse imports
-            if line.starts_with("import verse") {
-                // Existing code...
-            }
+            let imported_verse = line.strip_prefix("import verse").map(|name| {
+                name.trim().trim_end_matches(';').trim().trim_matches(|c| c == '"' || c == '\'').to_lowercase()
+            });
             
             // Process actual code statements
+            let code = Self::without_strings(line);
             statements.push(DivinePLStatement {
                 line_num: line_num + 1,
                 content: line.to_string(),
-                has_revelation: line.contains("revelation"),
-                is_miracle: line.starts_with("miracle"),
-                is_covenant: line.contains("covenant") || line.contains("promise"),
+                is_miracle: code.starts_with("miracle"),
+                is_covenant: code.contains("covenant") || code.contains("promise"),
+                code,
+                imported_verse,
+                prophecy: Self::prophecy_annotation(line),
             });
         }
         
-        Ok(statements)
+        if in_multiline_prayer {
+            liturgical_error.get_or_insert((prayer_begun, format!(
+                "LiturgicalError: prayer begun at line {} was never concluded with Amen", prayer_begun
+            )));
+        }
+        (statements, liturgical_error)
     }
     
-    fn run_script(&self, path: &Path) -> Result<(), String> {
-        // Read file content
-        let content = fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read the scripture: {}", e))?;
-        
-        println!("{}", "🕊️ DivinePL script loaded. Beginning divine interpretation...".green());
-        
-        // Parse the script
-        let statements = self.parse_script(&content)?;
-        
-        // Check for potential sins in the code
-        self.check_commandments(&statements)?;
-        
-        // Check for covenants (promises) in the code
-        self.check_covenants(&statements)?;
+    fn run_script(&self, path: &Path, entry: Option<&str>) -> Result<(), DivineError> {
+        if path.is_dir() {
+            return self.run_script(&self.entry_script(path, entry)?, entry);
+        }
         
-        // Simulate execution with divine timing
-        self.execute_with_faith(&statements)?;
+        // Arks run straight from the bundle, without unpacking
+        if Ark::is_ark(path) {
+            let ark = Ark::read(path)?;
+            if !self.quiet() {
+                println!("{}", format!("🚢 Ark {} opened (built by divine-pl {}). Beginning divine interpretation of {}...",
+                    path.display(), ark.created_by, ark.entry).green());
+            }
+            return self.run_source(ark.entry_source(), Path::new(&ark.entry), &Scriptorium::Ark(&ark), entry);
+        }
         
-        // Perform judgment day validation
-        self.judgment_day()?;
+        // Read file content
+        let content = read_scripture(path)
+            .map_err(|e| DivineError::Io(format!("Failed to read the scripture: {}", e)))?;
         
-        Ok(())
+        if !self.quiet() {
+            println!("{}", "🕊️ DivinePL script loaded. Beginning divine interpretation...".green());
+        }
+        let shown = if is_stdio(path) { Path::new("<stdin>") } else { path };
+        self.run_source(&content, shown, &Scriptorium::Disk, entry)
     }
     
-    fn check_commandments(&self, statements: &[DivinePLStatement]) -> Result<(), String> {
-        // Example check: all functions must start with "bless" or "genesis"
+    /// The script to run for `path`: the path itself, or for a directory the one
+    /// script under it that declares the entry point, genesis unless another is
+    /// named. A directory with no such script, or several, is refused.
+    fn entry_script(&self, path: &Path, entry: Option<&str>) -> Result<PathBuf, DivineError> {
+        if !path.is_dir() {
+            return Ok(path.to_path_buf());
+        }
+        let dir = path;
+        let entry = entry.unwrap_or("genesis");
+        let (mut found, _) = walk::canonical_scripts(dir)?;
+        found.retain(|script| fs::read_to_string(script).is_ok_and(|content| {
+            self.scan_script(&content).0.iter().any(|stmt| Self::declared_function(stmt.code.trim()) == Some(entry))
+        }));
+        
+        match found.as_slice() {
+            [script] => Ok(script.clone()),
+            [] => Err(format!(
+                "RunError: no script in {} declares {}(); run a script directly instead of a directory", dir.display(), entry
+            ).into()),
+            scripts => Err(format!(
+                "RunError: {} scripts in {} declare {}(): {}; run one of them directly",
+                scripts.len(), dir.display(), entry,
+                scripts.iter().map(|script| script.display().to_string()).collect::<Vec<_>>().join(", ")
+            ).into()),
+        }
+    }
+    
+    /// Runs each DivinePL block embedded in a document as a script of its own.
+    /// Every block must be saved; errors are reported against the document's lines.
+    fn run_embedded(&self, path: &Path, format: Embedded, entry: Option<&str>) -> Result<(), DivineError> {
+        let document = fs::read_to_string(path)
+            .map_err(|e| DivineError::Io(format!("Failed to read the scripture: {}", e)))?;
+        let snippets = match format {
+            Embedded::Markdown => embedded::markdown_snippets(&document),
+        };
+        if snippets.is_empty() {
+            return Err(format!("No DivinePL blocks are embedded in {}", path.display()).into());
+        }
+        
+        let mut fallen = Vec::new();
+        for snippet in &snippets {
+            if !self.quiet() {
+                println!("\n{}", format!("🕊️ {}:{}: running embedded block...", path.display(), snippet.fence_line).green());
+            }
+            if let Err(e) = self.run_source(&snippet.source, path, &Scriptorium::Disk, entry) {
+                // Ctrl-C stops every block, not just the one it fell in
+                if let DivineError::Tribulation { .. } = e {
+                    return Err(e);
+                }
+                let message = snippet.map_message(&e.to_string());
+                let span = e.span(&snippet.source).map(|span| Span {
+                    line: snippet.document_line(span.line),
+                    column: snippet.document_column(span.line, span.column),
+                    ..span
+                });
+                match span.and_then(|span| Diagnostic::at(Level::Error, &message, &path.display().to_string(), &document, span)) {
+                    Some(diagnostic) => eprintln!("{}", diagnostic.render(environment::stderr_colored())),
+                    None => eprintln!("{}", environment::for_stderr(format!("{}:{}: {}", path.display(), snippet.fence_line, message).bright_red())),
+                }
+                fallen.push(snippet.fence_line.to_string());
+            }
+        }
+        
+        if !self.quiet() {
+            println!("\n{}", format!("{} of {} embedded blocks saved", snippets.len() - fallen.len(), snippets.len()).bright_blue());
+        }
+        if fallen.is_empty() {
+            Ok(())
+        } else {
+            Err(DivineError::Judgment {
+                message: format!(
+                    "{} embedded block{} in {} did not reach salvation (fenced at line{} {})",
+                    fallen.len(),
+                    if fallen.len() == 1 { "" } else { "s" },
+                    path.display(),
+                    if fallen.len() == 1 { "" } else { "s" },
+                    fallen.join(", ")
+                ),
+                span: None,
+            })
+        }
+    }
+    
+    /// Runs a script's source. `path` is where it came from in `scriptorium`, for
+    /// messages and to find the modules it imports.
+    fn run_source(&self, content: &str, path: &Path, scriptorium: &Scriptorium, entry: Option<&str>) -> Result<(), DivineError> {
+        // Each run is judged on its own time, however many came before it under --watch
+        let started = Instant::now();
+        if self.verbosity >= Verbosity::Statements {
+            println!("{}", format!("🎲 Seed {} (repeat this run with --seed {})", self.seed, self.seed).dimmed());
+        }
+        match &self.season {
+            _ if self.quiet() => {}
+            Season::Feast(_) => println!("{}", format!("🎉 Today is {}! Venial sins weigh nothing at judgment on a feast day.", self.season).bright_yellow()),
+            Season::Lent { .. } => println!("{}", format!("🕯️ It is {}. Judgment is sterner, and long functions are a sin.", self.season).dimmed()),
+            Season::Ordinary => {}
+        }
+        
+        let (statements, program, sins) = self.examine_source(content, path, scriptorium)?;
+        
+        // Imported scripture is proclaimed before execution begins
+        let verses = self.import_verses(&statements);
+        
+        // Simulate execution with divine timing
+        let testimony = self.execute_with_faith(content, &statements, &program, entry, &verses, &sins)?;
+        
+        // Perform judgment day validation
+        let verdict = self.judgment_day(content, &testimony, &sins, started);
+        
+        // Stewardship is reported whatever the verdict, since a slow run is as often a damned one
+        self.report_stewardship(&testimony);
+        verdict
+    }
+    
+    /// Parses a script and the modules it imports, and judges it by the
+    /// commandments and covenants, all without running it. Returns the sins
+    /// that remain against it, to be weighed at judgment day.
+    fn examine_source(&self, content: &str, path: &Path, scriptorium: &Scriptorium) -> Result<(Vec<DivinePLStatement>, Program, Vec<Sin>), DivineError> {
+        // Parse the script
+        let (statements, liturgical_error) = self.scan_script(content);
+        if let Some((line, message)) = liturgical_error {
+            return Err(DivineError::Parse { message, span: Span::find(content, line, None) });
+        }
+        let mut program = parser::parse_program(content)?;
+        modules::load(&mut program, path, scriptorium, self.verbosity >= Verbosity::Internals)
+            .map_err(|fault| DivineError::parse(fault, content))?;
+        
+        // Check for potential sins in the code
+        let standards = match scriptorium {
+            Scriptorium::Disk => self.standards_for(path)?,
+            Scriptorium::Ark(ark) => {
+                let commandments = ark.file(CONFIG_FILE_NAME).map(|config| Commandments::parse(Path::new(CONFIG_FILE_NAME), config)).transpose()?;
+                self.standards(commandments.as_ref())
+            }
+        };
+        self.check_commandments(content, &statements, &Forgiveness::read(content, self.today()), &standards)?;
+        
+        // Check for covenants (promises) in the code
+        self.check_covenants(&statements)?;
+        
+        let (sins, _) = self.confess_source(content, &standards)?;
+        Ok((statements, program, sins))
+    }
+    
+    /// Checks that a script, or the entry script of an ark, parses and keeps the
+    /// commandments, without running it or facing judgment.
+    fn check_script(&self, path: &Path) -> Result<(), DivineError> {
+        if Ark::is_ark(path) {
+            let ark = Ark::read(path)?;
+            self.examine_source(ark.entry_source(), Path::new(&ark.entry), &Scriptorium::Ark(&ark))?;
+        } else {
+            let content = read_scripture(path)
+                .map_err(|e| DivineError::Io(format!("Failed to read the scripture: {}", e)))?;
+            let shown = if is_stdio(path) { Path::new("<stdin>") } else { path };
+            self.examine_source(&content, shown, &Scriptorium::Disk)?;
+        }
+        println!("{}", "✓ The scripture is sound".green());
+        Ok(())
+    }
+    
+    /// Checks every script under a directory, reporting each that is unsound.
+    /// Returns the exit status of the first failure, or 0 if all are sound.
+    fn check_directory(&self, dir: &Path) -> Result<i32, DivineError> {
+        let (scripts, skipped) = walk::canonical_scripts(dir)?;
+        canon::report_skipped(skipped);
+        if scripts.is_empty() {
+            return Err(format!("No scripts (*.divine, *.dpl) were found in {}", dir.display()).into());
+        }
+        
+        let mut status = 0;
+        let mut unsound = 0;
+        for script in &scripts {
+            print!("{} ", format!("📜 {}:", script.display().to_string().trim_start_matches("./")).bright_blue());
+            if let Err(e) = self.check_script(script) {
+                println!();
+                report_pinned(&e, script);
+                unsound += 1;
+                if status == 0 {
+                    status = e.exit_code();
+                }
+            }
+        }
+        
+        if unsound > 0 {
+            println!("{}", format!("{} of {} scripts {} unsound", unsound, scripts.len(), if unsound == 1 { "is" } else { "are" }).bright_red());
+        } else {
+            println!("{}", format!("All {} script{} sound", scripts.len(), if scripts.len() == 1 { " is" } else { "s are" }).green());
+        }
+        Ok(status)
+    }
+    
+    /// Judges the statements of `source` by the commandments, failing at the
+    /// first sin that `forgiveness` doesn't forgive: an unblessed function,
+    /// unless `standards` turn the rule off, or another sin they judge mortal,
+    /// the project's custom commandments included. Sins they judge venial are
+    /// only warned about, except those against custom commandments, which are
+    /// left to confession.
+    fn check_commandments(&self, source: &str, statements: &[DivinePLStatement], forgiveness: &Forgiveness, standards: &Standards) -> Result<(), DivineError> {
+        // How a sin against the rule `id` on the statement's line is judged, if it is
+        let judged = |stmt: &DivinePLStatement, id| {
+            let forgiven = self.forgiving && forgiveness.forgives(stmt.line_num, id);
+            Rule::find(id).and_then(|rule| standards.severity(rule)).filter(|_| !forgiven)
+        };
+        // Example check: all functions must start with "bless" or "genesis"
         for stmt in statements {
-            if stmt.content.contains("function") && 
-               !(stmt.content.contains("bless") || stmt.content.contains("genesis") || stmt.content.contains("miracle")) {
-                return Err(format!("SinError: Function at line {} lacks divine blessing", stmt.line_num));
+            if stmt.code.contains("function") && judged(stmt, "DPL001").is_some() &&
+               !(stmt.code.contains("bless") || stmt.code.contains("genesis") || stmt.code.contains("miracle")) {
+                return Err(DivineError::Sin { line: stmt.line_num, kind: SinKind::UnblessedFunction });
             }
             
             // Check for forbidden kill commands on child processes
-            if stmt.content.contains("kill") && stmt.content.contains("Process") {
-                if self.dev_mode {
-                    println!("{}", "⚠️ Warning: Attempting to kill a child process is sinful, but permitted in dev mode.".yellow());
-                } else {
-                    return Err(format!("MoralError: Thou shalt not kill child processes at line {}", stmt.line_num));
+            if stmt.code.contains("kill") && stmt.code.contains("Process") {
+                match judged(stmt, "DPL010") {
+                    Some(SinSeverity::Mortal) if !self.dev_mode => {
+                        return Err(DivineError::Sin { line: stmt.line_num, kind: SinKind::KilledChild });
+                    }
+                    Some(SinSeverity::Mortal) if !self.quiet() => {
+                        println!("{}", "⚠️ Warning: Attempting to kill a child process is sinful, but permitted in dev mode.".yellow());
+                    }
+                    Some(SinSeverity::Venial) if !self.quiet() => {
+                        println!("{}", format!("⚠️ Warning: Killing a child process at line {} is sinful, but judged venial by these commandments.", stmt.line_num).yellow());
+                    }
+                    _ => {}
                 }
             }
             
             // Check blasphemy in variable naming
-            if stmt.content.contains("let devil") || stmt.content.contains("let satan") || stmt.content.contains("let demon") {
-                return Err(format!("BlasphemyError: Unholy variable names at line {}", stmt.line_num));
+            if stmt.code.contains("let devil") || stmt.code.contains("let satan") || stmt.code.contains("let demon") {
+                match judged(stmt, "DPL011") {
+                    Some(SinSeverity::Mortal) => return Err(DivineError::Blasphemy { line: stmt.line_num }),
+                    Some(SinSeverity::Venial) if !self.quiet() => {
+                        println!("{}", format!("⚠️ Warning: The name at line {} is blasphemous, but judged venial by these commandments.", stmt.line_num).yellow());
+                    }
+                    _ => {}
+                }
             }
             
             // Check for Trinity pattern compliance
-            if stmt.content.contains("trinity") && 
-               !(stmt.content.contains("father") && stmt.content.contains("son") && stmt.content.contains("holy")) {
+            if !self.quiet() && stmt.code.contains("trinity") && 
+               !(stmt.code.contains("father") && stmt.code.contains("son") && stmt.code.contains("holy")) {
                 println!("{}", format!("⚠️ Warning: Trinity pattern at line {} is incomplete. Father, Son, and Holy Ghost are required.", stmt.line_num).yellow());
             }
         }
         
+        for commandment in standards.custom.iter().filter(|commandment| commandment.severity == SinSeverity::Mortal) {
+            if let Some((line, _)) = commandment.broken(source).into_iter().next() {
+                return Err(DivineError::Sin { line, kind: SinKind::Custom { id: commandment.id.clone(), message: commandment.message.clone() } });
+            }
+        }
+        
         Ok(())
     }
     
-    fn check_covenants(&self, statements: &[DivinePLStatement]) -> Result<(), String> {
+    fn check_covenants(&self, statements: &[DivinePLStatement]) -> Result<(), DivineError> {
         let mut has_covenants = false;
         
         for stmt in statements {
@@ -330,815 +1587,1688 @@ impl DivinePLRuntime {
         }
         
         if has_covenants && self.revelation_mode {
-            println!("{}", "🤝 Divine covenants are binding. Ensure all promises resolve.".bright_green());
+            println!("{}", "🤝 Divine covenants are binding. Ensure every promise is kept with fulfill(\"...\").".bright_green());
         }
         
         Ok(())
     }
     
-    fn execute_with_faith(&self, statements: &[DivinePLStatement]) -> Result<(), String> {
+    /// Resolves a script's `import verse` statements against the known verses and
+    /// proclaims each verse once. Unknown topics are warned about, with the closest
+    /// known topic suggested. Returns the topics imported.
+    fn import_verses(&self, statements: &[DivinePLStatement]) -> Vec<&'static str> {
+        let mut imported = Vec::new();
+        for stmt in statements {
+            let Some(name) = &stmt.imported_verse else { continue };
+            match self.bible_verses.get_key_value(name.as_str()) {
+                Some((topic, _)) if imported.contains(&topic.as_str()) => {}
+                Some((topic, _)) if self.quiet() => imported.push(topic.as_str()),
+                None if self.quiet() => {}
+                Some((topic, verse)) => {
+                    if imported.is_empty() {
+                        println!("{}", "📖 Imported scripture:".underline().bright_blue());
+                    }
+                    println!("{}", format!("  {}", verse.text).green());
+                    imported.push(topic.as_str());
+                }
+                None => {
+                    let suggestion = self.bible_verses.keys()
+                        .min_by_key(|topic| edit_distance(topic, name))
+                        .filter(|topic| edit_distance(topic, name) <= topic.len() / 2)
+                        .map(|topic| format!("; did you mean \"{}\"?", topic))
+                        .unwrap_or_default();
+                    println!("{}", format!(
+                        "⚠️ No verse is known for \"{}\" (imported at line {}){}", name, stmt.line_num, suggestion
+                    ).yellow());
+                }
+            }
+        }
+        imported
+    }
+    
+    /// In revelation mode, recalls each imported verse whose topic a statement touches.
+    fn recall_verses(&self, topics: &[&'static str], content: &str) {
+        // The imports themselves were already proclaimed
+        if !self.revelation_mode || topics.is_empty() || content.starts_with("import ") {
+            return;
+        }
+        let words: Vec<String> = content.split(|c: char| !c.is_alphanumeric()).map(str::to_lowercase).collect();
+        for topic in topics {
+            let Some(verse) = self.bible_verses.get(*topic) else { continue };
+            if words.iter().any(|word| word == topic || verse.tags.contains(word)) {
+                println!("{}", format!("  📖 {}", verse.text).italic().green());
+            }
+        }
+    }
+    
+    fn execute_with_faith(&self, content: &str, statements: &[DivinePLStatement], program: &Program, entry: Option<&str>, verses: &[&'static str], sins: &[Sin]) -> Result<Testimony, DivineError> {
         let stages = ["Creation of light", "Separation of waters", "Land and vegetation", 
                      "Celestial bodies", "Sea creatures and birds", "Land animals and mankind", "Rest"];
         
-        // Simulate the 7 stages of creation
-        for (i, stage) in stages.iter().enumerate() {
+        // Simulate the 7 stages of creation, unless a watcher is waiting on every run or the run is quiet
+        for (i, stage) in stages.iter().enumerate().filter(|_| !self.watching && !self.quiet()) {
+            if tribulation::interrupted() {
+                return Err(self.tribulation(None));
+            }
             print!("{}... ", stage);
             std::io::Write::flush(&mut std::io::stdout()).unwrap();
             
             let sleep_duration = if i == 6 { 700 } else { 300 }; // Rest takes longer
-            std::thread::sleep(Duration::from_millis(sleep_duration));
+            self.linger(sleep_duration);
             
             println!("{}", "✓".green());
         }
         
         // Execute miracles first if any are present
         let has_miracles = statements.iter().any(|s| s.is_miracle);
-        if has_miracles {
+        if let Some(miracle) = statements.iter().find(|s| s.is_miracle).filter(|_| !self.miracles_allowed) {
+            return Err(DivineError::Sin { line: miracle.line_num, kind: SinKind::ForbiddenMiracle });
+        }
+        if has_miracles && !self.quiet() {
             println!("{}", "✨ Preparing to perform miracles...".bright_yellow());
-            std::thread::sleep(Duration::from_millis(500));
+            self.linger(500);
             
-            let mut rng = rand::thread_rng();
-            let miracle_index = rng.gen_range(0..self.miracles.len());
+            let miracle_index = self.fortune().gen_range(0..self.miracles.len());
             println!("{}", format!("🌟 MIRACLE PERFORMED: {} 🌟", self.miracles[miracle_index]).bright_yellow());
-            std::thread::sleep(Duration::from_millis(300));
-        }
-        
-        // If verbose or revelation mode, show more execution details
-        if self.verbose || self.revelation_mode {
-            for stmt in statements {
-                if !stmt.content.trim().is_empty() {
-                    // Different output formatting based on statement type
-                    if stmt.is_miracle {
-                        println!("Executing miracle: {}", stmt.content.bright_yellow());
-                    } else if stmt.has_revelation {
-                        println!("Revealing: {}", stmt.content.bright_magenta());
-                    } else if stmt.is_covenant {
-                        println!("Fulfilling covenant: {}", stmt.content.bright_cyan());
-                    } else {
-                        println!("Executing: {}", stmt.content.bright_cyan());
-                    }
-                    
-                    std::thread::sleep(Duration::from_millis(100));
-                    
-                    // Extra divine insights in revelation mode
-                    if self.revelation_mode && rand::thread_rng().gen_ratio(1, 3) {
-                        let categories = ["error_handling", "performance", "security"];
-                        let category = categories[rand::thread_rng().gen_range(0..categories.len())];
-                        
-                        if let Some(inspirations) = self.divine_inspirations.get(category) {
-                            let insight = inspirations[rand::thread_rng().gen_range(0..inspirations.len())];
-                            println!("{}", format!("  📖 Divine insight: {}", insight).italic().bright_blue());
-                            std::thread::sleep(Duration::from_millis(200));
-                        }
-                    }
-                    
-                    // Random chance of divine intervention
-                    let mut rng = rand::thread_rng();
-                    if rng.gen_ratio(1, 10) {
-                        println!("{}", "✨ Divine intervention occurred! ✨".yellow());
-                    }
+            self.linger(300);
+        }
+        
+        // Only the statements control flow reaches are executed
+        self.on_tall_stack(|| {
+            let mortal = sins.iter().filter(|sin| sin.severity == SinSeverity::Mortal).count();
+            let mut interpreter = Interpreter::new(self, program, verses).with_sins(sins.len() - mortal, mortal);
+            let outcome = interpreter.run(program, entry);
+            let halted = interpreter.halted();
+            let testimony = interpreter.into_testimony();
+            match (outcome, halted) {
+                (Ok(()), _) => Ok(testimony),
+                (Err(e), halted) => {
+                    // What was gathered before the run fell is still worth reporting
+                    self.report_stewardship(&testimony);
+                    Err(match halted {
+                        Some(Halt::Rapture(line)) => DivineError::Rapture { seconds: self.rapture_after, line },
+                        Some(Halt::Tribulation) => self.tribulation(Some(&testimony)),
+                        None => DivineError::judgment(e, content),
+                    })
                 }
             }
+        })
+    }
+    
+    /// Lists the covenants a run left unfulfilled, if it left any.
+    fn report_unfulfilled(&self, testimony: &Testimony) {
+        let unfulfilled: Vec<_> = testimony.ledger.unfulfilled().collect();
+        if !unfulfilled.is_empty() {
+            println!("{}", "💔 Unfulfilled covenants:".red());
+            for promise in &unfulfilled {
+                println!("  line {}: \"{}\"", promise.line, promise.text);
+            }
         }
-        
-        Ok(())
     }
     
-    fn judgment_day(&self) -> Result<(), String> {
-        let elapsed = self.start_time.elapsed();
-        
-        println!("{}", "\n🔔 JUDGMENT DAY 🔔".bright_yellow());
-        println!("Execution time: {:.2} seconds", elapsed.as_secs_f64());
+    /// Sums up a run interrupted by Ctrl-C, which left `testimony` behind if it
+    /// got past creation, and gives the error it ends with.
+    fn tribulation(&self, testimony: Option<&Testimony>) -> DivineError {
+        let executed = testimony.map_or(0, |testimony| testimony.executed);
+        // Ctrl-C is echoed wherever the line had got to
+        println!("\n{}", "⚡ TRIBULATION: execution interrupted by mortal hands".bright_red());
+        println!("Statements executed: {}", executed);
+        if let Some(testimony) = testimony {
+            self.report_unfulfilled(testimony);
+        }
+        DivineError::Tribulation { executed }
+    }
+    
+    /// Prints the Stewardship Report of a profiled run, as --format asks.
+    fn report_stewardship(&self, testimony: &Testimony) {
+        match (&testimony.stewardship, self.profile) {
+            (Some(stewardship), Some(OutputFormat::Json)) => println!("{}", stewardship.to_json()),
+            (Some(stewardship), Some(OutputFormat::Text)) => stewardship.report(),
+            _ => {}
+        }
+    }
+    
+    /// Runs `work` on a thread of its own. Each level of recursion costs native
+    /// stack, so the thread gets a stack sized to let the tower reach its limit
+    /// before TowerOfBabelError, never an overflow.
+    fn on_tall_stack<T: Send>(&self, work: impl FnOnce() -> Result<T, DivineError> + Send) -> Result<T, DivineError> {
+        let stack_size = self.recursion_limit.checked_mul(64 * 1024)
+            .and_then(|tower| tower.checked_add(8 * 1024 * 1024))
+            .ok_or_else(|| DivineError::Other(format!(
+                "TowerOfBabelError: a tower of {} levels is too tall to build (lower --recursion-limit)", self.recursion_limit
+            )))?;
+        std::thread::scope(|scope| {
+            std::thread::Builder::new()
+                .stack_size(stack_size)
+                .spawn_scoped(scope, work)
+                .map_err(|e| DivineError::Io(format!("Failed to prepare the interpreter: {}", e)))?
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+    
+    /// Announces a statement as it executes. Silent unless -v or revelation mode is on.
+    fn announce_statement(&self, content: &str) {
+        if !(self.verbosity >= Verbosity::Statements || self.revelation_mode) || content.trim().is_empty() {
+            return;
+        }
         
-        let mut rng = rand::thread_rng();
+        // Different output formatting based on statement type
+        if content.starts_with("miracle") {
+            println!("Executing miracle: {}", content.bright_yellow());
+        } else if content.contains("revelation") {
+            println!("Revealing: {}", content.bright_magenta());
+        } else if content.contains("fulfill") {
+            println!("Fulfilling covenant: {}", content.bright_cyan());
+        } else if content.contains("covenant") || content.contains("promise") {
+            println!("Making covenant: {}", content.bright_cyan());
+        } else {
+            println!("Executing: {}", content.bright_cyan());
+        }
         
-        // Higher chance of salvation in revelation mode
-        let saved_chance = if self.revelation_mode { 0.9 } else { 0.75 };
-        let saved = rng.gen_bool(saved_chance); // 75% or 90% chance of salvation
+        // A statement stepped through has already been paused on
+        if !self.stepping {
+            self.linger(100);
+        }
         
-        if saved {
-            println!("{}", "Your code has been found worthy and has ascended to PRODUCTION HEAVEN! 🙌".green());
+        // Extra divine insights in revelation mode, or at -vv
+        if (self.revelation_mode || self.verbosity >= Verbosity::Insights) && self.fortune().gen_ratio(if self.deep_revelation { 2 } else { 1 }, 3) {
+            let categories = ["error_handling", "performance", "security"];
+            let category = categories[self.fortune().gen_range(0..categories.len())];
             
-            // Extra blessing in revelation mode
-            if self.revelation_mode {
-                println!("{}", "✨ ADDITIONAL BLESSING: Optimized runtime performance granted! ✨".bright_green());
-            }
-        } else {
-            println!("{}", "Your code requires more faith. It has been sent to DEBUGGING PURGATORY. 🔥".red());
-            
-            if !self.dev_mode {
-                // Provide path to redemption
-                println!("{}", "Seek redemption through the 'confess' command to identify your sins.".yellow());
-                return Err("Your code requires purification before it can be saved.".to_string());
-            } else {
-                println!("{}", "But since you're in dev mode, execution continues by divine mercy.".yellow());
+            if let Some(inspirations) = self.divine_inspirations.get(category) {
+                let insight = inspirations[self.fortune().gen_range(0..inspirations.len())];
+                println!("{}", format!("  📖 Divine insight: {}", insight).italic().bright_blue());
+                self.linger(200);
             }
         }
         
-        Ok(())
+        // Random chance of divine intervention
+        if self.fortune().gen_ratio(1, 10) {
+            println!("{}", "✨ Divine intervention occurred! ✨".yellow());
+            if let Some(trace) = &self.trace {
+                trace.record(Event::Intervention);
+            }
+        }
     }
     
-    fn create_project(&self, name: &str, template: &str) -> Result<(), String> {
-        let project_dir = PathBuf::from(name);
-        
-        if project_dir.exists() {
-            return Err(format!("Project '{}' already exists. Creation is sacred, duplication is heresy.", name));
+    /// Weighs a run by the `sins` that remain against its script and the
+    /// covenants it left unfulfilled, and sends it to heaven or purgatory.
+    fn judgment_day(&self, content: &str, testimony: &Testimony, sins: &[Sin], started: Instant) -> Result<(), DivineError> {
+        let elapsed = started.elapsed();
+        // A quiet run is judged all the same, but only an unfavorable verdict is heard, as an error
+        let quiet = self.quiet();
+        
+        if !quiet {
+            println!("{}", "\n🔔 JUDGMENT DAY 🔔".bright_yellow());
+            // The clock is the one thing a seed can't repeat
+            if !self.seeded {
+                println!("Execution time: {:.2} seconds", elapsed.as_secs_f64());
+            }
+            if testimony.forgiven > 0 {
+                println!("Sins forgiven: {}", testimony.forgiven);
+            }
+            for affirmation in testimony.affirmations.iter().filter(|affirmation| !affirmation.passed) {
+                println!("{}", format!("⚖️ Affirmation at line {} failed: {}", affirmation.line, affirmation.message).yellow());
+            }
         }
         
-        // Create project directory
-        fs::create_dir(&project_dir).map_err(|e| format!("Failed to create project: {}", e))?;
-        
-        // Create appropriate template files based on template type
-        match template {
-            "miracle" => self.create_miracle_template(name, &project_dir)?,
-            "prophet" => self.create_prophet_template(name, &project_dir)?,
-            _ => self.create_default_template(name, &project_dir)?,
+        // Promises made during the run must all have been kept
+        let unfulfilled: Vec<_> = testimony.ledger.unfulfilled().collect();
+        if !quiet {
+            self.report_unfulfilled(testimony);
         }
-        
-        println!("{}", format!("🕊️ New DivinePL project '{}' has been blessed with creation!", name).green());
-        println!("Structure:");
-        println!("- {}/", name);
-        println!("  |- genesis.divine  (Main script)");
-        println!("  |- commandments.config  (Configuration)");
-        
-        if template != "default" {
-            println!("  |- holy_trinity/  (Module directory)");
-            println!("     |- father.divine");
-            println!("     |- son.divine");
-            println!("     |- holy_ghost.divine");
+        if !unfulfilled.is_empty() {
+            if !self.dev_mode {
+                let lines = unfulfilled.iter().map(|p| p.line.to_string()).collect::<Vec<_>>().join(", ");
+                return Err(DivineError::Judgment {
+                    message: format!(
+                        "CovenantBreachError: {} covenant{} remain{} unfulfilled (line{} {})",
+                        unfulfilled.len(),
+                        if unfulfilled.len() == 1 { "" } else { "s" },
+                        if unfulfilled.len() == 1 { "s" } else { "" },
+                        if unfulfilled.len() == 1 { "" } else { "s" },
+                        lines
+                    ),
+                    // A lone broken covenant is pinned to where it was made
+                    span: match unfulfilled.as_slice() {
+                        [promise] => Span::find(content, promise.line, None),
+                        _ => None,
+                    },
+                });
+            }
+            if !quiet {
+                println!("{}", "But since you're in dev mode, broken promises are forgiven by divine mercy.".yellow());
+            }
         }
         
-        Ok(())
-    }
-    
-    fn create_default_template(&self, name: &str, project_dir: &Path) -> Result<(), String> {
-        // Create main divine file
-        let main_file_path = project_dir.join("genesis.divine");
-        let main_content = r#"// DivinePL - The Holy Programming Experience
-bless Program {
-  genesis() {
-    🙏 Lord, guide this program to righteousness 🙏
-    
-    let light = createLight();
-    let world = new Creation();
-    
-    world.populate(light);
-    
-    let disciples = createChildProcesses(12);
-    disciples.forEach(disciple => {
-      disciple.spread_gospel();
-    });
-    
-    return salvation;
-  }
-}
-"#;
-        fs::write(main_file_path, main_content).map_err(|e| format!("Failed to write genesis file: {}", e))?;
-        
-        // Create commandments (config) file
-        let config_path = project_dir.join("commandments.config");
-        let config_content = r#"{
-  "trinity": {
-    "father": "main",
-    "son": "child_processes",
-    "holy_ghost": "background_services"
-  },
-  "sabbath_mode": true,
-  "resurrection_enabled": true,
-  "allow_confession": true
-}
-"#;
-        fs::write(config_path, config_content).map_err(|e| format!("Failed to write commandments: {}", e))?;
-        
-        Ok(())
-    }
-    
-    fn create_miracle_template(&self, name: &str, project_dir: &Path) -> Result<(), String> {
-        // Create main miracle file
-        let main_file_path = project_dir.join("genesis.divine");
-        let main_content = r#"// DivinePL - Divine Miracle Template
-import verse "creation";
-import verse "light";
-
-🙏 BEGIN PRAYER 🙏
-Lord, grant this code the power to transform and heal
-Guide my keystrokes with divine wisdom
-Let miracles flow through these functions
-🙏 END PRAYER 🙏
-
-miracle Program {
-  genesis() {
-    let light = createDivineLight();
-    
-    // This miracle transforms simple data into revelation
-    miracle transform(data) {
-      return data.map(item => {
-        item.blessed = true;
-        item.purified = removeImpurities(item);
-        return item;
-      });
-    }
-    
-    // Healing miracles for corrupted data
-    miracle heal(brokenSystem) {
-      covenant("This system shall be restored");
-      
-      brokenSystem.restoreFromBackup();
-      brokenSystem.cleanse();
-      
-      revelation("System has been restored through divine intervention");
-      return brokenSystem;
-    }
-    
-    return salvation;
-  }
-}
-"#;
-        fs::write(main_file_path, main_content).map_err(|e| format!("Failed to write genesis file: {}", e))?;
-        
-        // Create commandments (config) file
-        let config_path = project_dir.join("commandments.config");
-        let config_content = r#"{
-  "trinity": {
-    "father": "main",
-    "son": "child_processes",
-    "holy_ghost": "background_services"
-  },
-  "sabbath_mode": true,
-  "resurrection_enabled": true,
-  "allow_confession": true,
-  "miracles_enabled": true
-}
-"#;
-        fs::write(config_path, config_content).map_err(|e| format!("Failed to write commandments: {}", e))?;
-        
-        // Create Holy Trinity directory structure
-        let trinity_dir = project_dir.join("holy_trinity");
-        fs::create_dir(&trinity_dir).map_err(|e| format!("Failed to create holy trinity directory: {}", e))?;
-        
-        // Create Father module
-        let father_path = trinity_dir.join("father.divine");
-        let father_content = r#"// The Father - Source of all creation
-bless FatherModule {
-  createAll() {
-    return {
-      light: true,
-      earth: true,
-      heaven: true,
-      life: true
-    };
-  }
-  
-  miracle resurrection(deadCode) {
-    // Only the Father can resurrect dead code
-    return deadCode.restore();
-  }
-}
-"#;
-        fs::write(father_path, father_content).map_err(|e| format!("Failed to write father module: {}", e))?;
-        
-        // Create Son module
-        let son_path = trinity_dir.join("son.divine");
-        let son_content = r#"// The Son - Salvation for humanity
-bless SonModule {
-  saveBrokenCode(code) {
-    // Takes the sins of the code upon itself
-    let errors = code.findAllErrors();
-    return this.redeemErrors(errors, code);
-  }
-  
-  redeemErrors(errors, code) {
-    errors.forEach(error => {
-      confession(error);
-      forgive(error);
-    });
-    return code.purified();
-  }
-  
-  miracle healProcess(process) {
-    if (process.isDying) {
-      process.resurrect();
-      return true;
-    }
-    return false;
-  }
-}
-"#;
-        fs::write(son_path, son_content).map_err(|e| format!("Failed to write son module: {}", e))?;
-        
-        // Create Holy Ghost module
-        let holy_ghost_path = trinity_dir.join("holy_ghost.divine");
-        let holy_ghost_content = r#"// The Holy Ghost - Divine guidance and inspiration
-bless HolyGhostModule {
-  inspire(developer) {
-    // Fill the developer with divine inspiration
-    developer.productivity *= 3;
-    developer.errors /= 2;
-    developer.creativity += 10;
-  }
-  
-  guideCoding(codebase) {
-    // Analyze and provide divine guidance
-    revelation(codebase.analyze());
-    
-    return this.offerInsights(codebase);
-  }
-  
-  miracle tongues(code) {
-    // Translate code between programming languages
-    return code.translateTo("DivinePL");
-  }
-}
-"#;
-        fs::write(holy_ghost_path, holy_ghost_content).map_err(|e| format!("Failed to write holy ghost module: {}", e))?;
+        // The verdict is weighed from the sins that remain; only its phrasing is left to fortune
+        let mortal = sins.iter().filter(|sin| sin.severity == SinSeverity::Mortal).count();
+        let venial = sins.len() - mortal;
+        let weight = match (&self.season, self.revelation_mode) {
+            (Season::Feast(_), _) => 0,
+            (Season::Lent { .. }, _) => VENIAL_WEIGHT * 3 / 2,
+            (Season::Ordinary, true) => VENIAL_WEIGHT / 2,
+            (Season::Ordinary, false) => VENIAL_WEIGHT,
+        };
+        let grace = 100usize.saturating_sub(weight * venial);
+        
+        let mut factors = Vec::new();
+        if venial > 0 {
+            factors.push(format!("{} venial sin{} weighed against you, leaving {}% of grace",
+                venial, if venial == 1 { "" } else { "s" }, grace));
+            match &self.season {
+                Season::Feast(_) => factors.push(format!("{} lifted their weight", self.season)),
+                Season::Lent { .. } => factors.push("Lent made them weigh half again as much".to_string()),
+                Season::Ordinary if self.revelation_mode => factors.push("revelation mode halved their weight".to_string()),
+                Season::Ordinary => {}
+            }
+        }
+        if mortal > 0 {
+            factors.push(format!("{} mortal sin{} cannot be weighed away", mortal, if mortal == 1 { "" } else { "s" }));
+        }
+        if !unfulfilled.is_empty() {
+            factors.push(format!("{} covenant{} left unfulfilled", unfulfilled.len(), if unfulfilled.len() == 1 { "" } else { "s" }));
+        }
+        let saved = mortal == 0 && unfulfilled.is_empty() && grace >= GRACE_NEEDED;
+        if self.porcelain {
+            println!("verdict {}", if saved { "saved" } else { "purgatory" });
+        }
+        if let Some(trace) = &self.trace {
+            trace.record(Event::Judgment { saved, factors: &factors });
+        }
         
-        Ok(())
-    }
-    
-    fn create_prophet_template(&self, name: &str, project_dir: &Path) -> Result<(), String> {
-        // Create main prophet file
-        let main_file_path = project_dir.join("genesis.divine");
-        let main_content = r#"// DivinePL - Divine Prophet Template
-import verse "wisdom";
-import verse "promise";
-
-🙏 BEGIN PRAYER 🙏
-Grant me the vision to see beyond the present code
-Let future bugs be revealed before they manifest
-Guide this project through the fog of development
-🙏 END PRAYER 🙏
-
-bless Program {
-  genesis() {
-    let vision = seekVision();
-    let prophecies = analyze(vision);
-    
-    @prophesy("Future optimization required")
-    bless dataProcessor(data) {
-      covenant("This algorithm shall be optimized by version 2.0");
-      return data.process();
-    }
-    
-    // Predict future errors and provide guidance
-    revelation("Security vulnerabilities shall arise in v1.2");
-    covenant("Input validation shall be added before release");
-    
-    let roadmap = prophesy(3); // Look 3 versions ahead
-    return roadmap;
-  }
-  
-  prophesy(versions) {
-    // Determine future requirements
-    let roadmap = [];
-    
-    revelation("Adding user authentication in future version");
-    revelation("Database migration will be needed");
-    revelation("Mobile compatibility is coming");
-    
-    return roadmap;
-  }
-}
-"#;
-        fs::write(main_file_path, main_content).map_err(|e| format!("Failed to write genesis file: {}", e))?;
-        
-        // Create commandments (config) file
-        let config_path = project_dir.join("commandments.config");
-        let config_content = r#"{
-  "trinity": {
-    "father": "main",
-    "son": "child_processes",
-    "holy_ghost": "background_services"
-  },
-  "sabbath_mode": true,
-  "resurrection_enabled": true,
-  "allow_confession": true,
-  "prophecy_enabled": true,
-  "revelation_level": "deep"
-}
-"#;
-        fs::write(config_path, config_content).map_err(|e| format!("Failed to write commandments: {}", e))?;
-        
-        // Create Holy Trinity directory structure
-        let trinity_dir = project_dir.join("holy_trinity");
-        fs::create_dir(&trinity_dir).map_err(|e| format!("Failed to create holy trinity directory: {}", e))?;
-        
-        // Create Father module
-        let father_path = trinity_dir.join("father.divine");
-        let father_content = r#"// The Father - Eternal vision and wisdom
-bless FatherModule {
-  providePlan() {
-    return {
-      version1: "Foundation",
-      version2: "Growth",
-      version3: "Enlightenment"
-    };
-  }
-  
-  @prophesy("Will need to update dependencies")
-  revelation(message) {
-    // Record divine insights for future generations
-    log.divineInsight(message);
-  }
-}
-"#;
-        fs::write(father_path, father_content).map_err(|e| format!("Failed to write father module: {}", e))?;
+        if !quiet {
+            if factors.is_empty() {
+                println!("⚖️ No sin was found against you");
+            } else {
+                println!("⚖️ Weighed at judgment:");
+                for factor in &factors {
+                    println!("  {}", factor);
+                }
+            }
+        }
         
-        // Create Son module
-        let son_path = trinity_dir.join("son.divine");
-        let son_content = r#"// The Son - Implementation of the divine plan
-bless SonModule {
-  implementPlan(plan) {
-    covenant("This plan shall be fulfilled");
-    
-    @prophesy("Will require refactoring in version 2")
-    bless executePhase(phase) {
-      // Implementation details
-      return phase.complete();
-    }
-    
-    revelation("Testing will reveal hidden bugs");
-    return plan.fulfilled();
-  }
-}
-"#;
-        fs::write(son_path, son_content).map_err(|e| format!("Failed to write son module: {}", e))?;
-        
-        // Create Holy Ghost module
-        let holy_ghost_path = trinity_dir.join("holy_ghost.divine");
-        let holy_ghost_content = r#"// The Holy Ghost - Guidance and future insights
-bless HolyGhostModule {
-  revealFuture(project) {
-    // Prophetic insights into the future of the codebase
-    let prophecies = [];
-    
-    revelation("Technical debt will accumulate in module X");
-    revelation("New requirements will conflict with current architecture");
-    revelation("A more efficient algorithm will be discovered");
-    
-    @prophesy("Will need more comprehensive documentation")
-    return prophecies;
-  }
-  
-  guideDevelopment(team) {
-    // Provide spiritual guidance to the development team
-    team.forEach(developer => {
-      developer.inspireWithVision();
-      developer.grantWisdom();
-    });
-    
-    covenant("The team shall be guided to righteous development practices");
-  }
-}
-"#;
-        fs::write(holy_ghost_path, holy_ghost_content).map_err(|e| format!("Failed to write holy ghost module: {}", e))?;
+        if saved {
+            if !quiet {
+                let ascension = ASCENSIONS[self.fortune().gen_range(0..ASCENSIONS.len())];
+                println!("{}", format!("{} ascended to PRODUCTION HEAVEN! 🙌", ascension).green());
+                
+                // Extra blessing in revelation mode
+                if self.revelation_mode {
+                    println!("{}", "✨ ADDITIONAL BLESSING: Optimized runtime performance granted! ✨".bright_green());
+                }
+            }
+        } else {
+            if !quiet {
+                let descent = DESCENTS[self.fortune().gen_range(0..DESCENTS.len())];
+                println!("{}", format!("{} sent to DEBUGGING PURGATORY. 🔥", descent).red());
+                if !self.dev_mode {
+                    // Provide path to redemption
+                    println!("{}", "Seek redemption through the 'confess' command to identify your sins.".yellow());
+                } else {
+                    println!("{}", "But since you're in dev mode, execution continues by divine mercy.".yellow());
+                }
+            }
+            if !self.dev_mode {
+                return Err(DivineError::Judgment {
+                    message: format!("Your code requires purification before it can be saved: {}", factors.join("; ")),
+                    span: None,
+                });
+            }
+        }
         
         Ok(())
     }
     
-    fn confess_script(&self, path: &Path) -> Result<(), String> {
-        let content = fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read the script for confession: {}", e))?;
-        
-        let statements = self.parse_script(&content)?;
-        
-        println!("{}", "🙏 Beginning confession ritual... 🙏".bright_blue());
-        
-        let mut sins_found = 0;
-        let mut venial_sins = 0;
-        let mut mortal_sins = 0;
+    fn collect_sins(&self, content: &str) -> Result<Vec<Sin>, String> {
+        let (statements, liturgical_error) = self.scan_script(content);
+        let absolved_lines = Self::lines_within_absolution(content);
+        let mut sins = Vec::new();
+        
+        // A prayer left open swallows the rest of the script, which would otherwise look clean
+        if let Some((line_num, message)) = liturgical_error {
+            sins.push(Sin { line_num, severity: SinSeverity::Venial, rule: "open-prayer".into(), message, culprit: Some("BEGIN PRAYER".to_string()) });
+        }
         
         // Check for various sins
         for stmt in &statements {
-            let line = &stmt.content;
+            // Sins are judged on code alone; a forbidden word inside a string is no sin
+            let line = &stmt.code;
+            // `culprits` are the words the sin was found by; the first one present is pointed at
+            let mut sin = |severity, rule: &str, culprits: &[&str], message: &str| sins.push(Sin {
+                line_num: stmt.line_num,
+                severity,
+                rule: rule.into(),
+                message: message.to_string(),
+                culprit: culprits.iter().find(|culprit| line.contains(*culprit)).map(|culprit| culprit.to_string()),
+            });
             
             // Venial sins (less serious)
             if line.contains("var") && !line.contains("let") {
-                println!("{}: {} - Use 'let' instead of secular 'var'", 
-                         "Venial Sin".yellow(), stmt.line_num);
-                venial_sins += 1;
+                sin(SinSeverity::Venial, "secular-var", &["var"], "Use 'let' instead of secular 'var'");
             }
             
             if line.contains("while(true)") || line.contains("while (true)") {
-                println!("{}: {} - Infinite loops show lack of faith in termination", 
-                         "Venial Sin".yellow(), stmt.line_num);
-                venial_sins += 1;
+                sin(SinSeverity::Venial, "faithless-loop", &["while(true)", "while (true)"], "Infinite loops show lack of faith in termination");
             }
             
             // Check for missing blessings in function declarations
             if (line.contains("function") || line.contains("=>")) && 
                !(line.contains("bless") || line.contains("genesis") || line.contains("miracle")) {
-                println!("{}: {} - Function lacks divine blessing", 
-                         "Venial Sin".yellow(), stmt.line_num);
-                venial_sins += 1;
+                sin(SinSeverity::Venial, "unblessed-function", &["function", "=>"], "Function lacks divine blessing");
+            }
+            
+            // Scrolls should be sealed explicitly rather than left to seal themselves
+            if let Some((binding, value)) = line.strip_prefix("let ").and_then(|rest| rest.split_once('=')) {
+                if value.contains("scroll.open(") && !absolved_lines.contains(&stmt.line_num)
+                    && !content.contains(&format!("{}.seal()", binding.trim())) {
+                    sin(SinSeverity::Venial, "unsealed-scroll", &[binding.trim()], "Scroll opened but never sealed");
+                }
             }
             
             // Mortal sins (more serious)
             if line.contains("kill") || line.contains("terminate") {
-                println!("{}: {} - Thou shalt not kill processes", 
-                         "Mortal Sin".bright_red(), stmt.line_num);
-                mortal_sins += 1;
+                sin(SinSeverity::Mortal, "killing", &["kill", "terminate"], "Thou shalt not kill processes");
             }
             
             // Check for missing confessions around try/catch
             if line.contains("try") && !content.contains("confess") {
-                println!("{}: {} - Errors must be confessed, not caught", 
-                         "Mortal Sin".bright_red(), stmt.line_num);
-                mortal_sins += 1;
+                sin(SinSeverity::Mortal, "caught-error", &["try"], "Errors must be confessed, not caught");
+            }
+            
+            // A prophecy must be quoted to be understood
+            if line.starts_with("@prophesy") && stmt.prophecy.is_none() {
+                sin(SinSeverity::Venial, "malformed-prophecy", &["@prophesy"], "Malformed prophecy; quote the foretelling, as in @prophesy(\"...\")");
             }
             
             // Check for blasphemous variable names
             if line.contains("let devil") || line.contains("let satan") || line.contains("var devil") || line.contains("var satan") {
-                println!("{}: {} - Blasphemous variable name detected", 
-                         "Mortal Sin".bright_red(), stmt.line_num);
-                mortal_sins += 1;
+                sin(SinSeverity::Mortal, "blasphemous-name", &["devil", "satan"], "Blasphemous variable name detected");
             }
         }
         
-        sins_found = venial_sins + mortal_sins;
-        
-        // Final judgment
-        if sins_found == 0 {
-            println!("{}", "✝️ Your code is free from sin and ready for divine execution! ✝️".green());
-        } else {
-            println!("{}", format!("Found {} sins in your code ({} venial, {} mortal).", 
-                                sins_found, venial_sins, mortal_sins).yellow());
-            
-            if mortal_sins > 0 {
-                println!("{}", "Mortal sins require immediate repentance before execution.".bright_red());
-            } else {
-                println!("{}", "Venial sins can be forgiven with minor modifications.".yellow());
-            }
+        // Covenants are checked on the parsed script; one that doesn't parse is judged when it runs
+        if let Ok(program) = parser::parse_program(content) {
+            let mut sworn = HashMap::new();
+            Self::find_broken_covenants(&program.statements, &mut sworn, &mut sins);
+            Self::find_sinful_constants(&program.statements, &mut sins);
             
-            // Suggest penance
-            println!("\n{}", "Suggested Penance:".underline().bright_blue());
-            if venial_sins > 0 {
-                println!("- Replace 'var' with 'let' and add proper blessings to functions");
-                println!("- Avoid infinite loops by adding faithful termination conditions");
-            }
-            if mortal_sins > 0 {
-                println!("- Rename blasphemous variables to virtuous alternatives");
-                println!("- Replace 'try/catch' with 'confess' for proper error handling");
-                println!("- Remove all 'kill' statements and implement graceful process lifecycle");
+            // In Lent, functions fast
+            if let Season::Lent { .. } = self.season {
+                for function in program.functions.values() {
+                    let lines = Self::last_line(&function.body).max(function.line) - function.line + 1;
+                    if lines > LENTEN_FUNCTION_LINES {
+                        sins.push(Sin {
+                            line_num: function.line,
+                            severity: SinSeverity::Venial,
+                            rule: "lenten-excess".into(),
+                            message: format!("Function '{}' spans {} lines; in Lent, functions keep to {}", function.name, lines, LENTEN_FUNCTION_LINES),
+                            culprit: Some(function.name.clone()),
+                        });
+                    }
+                }
             }
         }
         
-        Ok(())
+        let forgiveness = Forgiveness::read(content, self.today());
+        for (line_num, message) in forgiveness.vague {
+            sins.push(Sin { line_num, severity: SinSeverity::Venial, rule: "vague-absolution".into(), message, culprit: Some("forgiven".to_string()) });
+        }
+        for (line_num, message) in forgiveness.expired {
+            sins.push(Sin { line_num, severity: SinSeverity::Venial, rule: "stale-absolution".into(), message, culprit: Some("until".to_string()) });
+        }
+        for (line_num, until) in forgiveness.undated {
+            warn_once(format!(
+                "⚠️ Warning: forgiveness at line {} lasts until '{}', which is no day written YYYY-MM-DD; it is taken as unexpired", line_num, until
+            ));
+        }
+        sins.sort_by_key(|sin| sin.line_num);
+        
+        Ok(sins)
     }
     
-    fn search_bible(&self, topic: &str) -> Result<(), String> {
-        println!("{}", "📖 Searching for divine guidance on...".bright_blue());
-        println!("{}", format!("Topic: \"{}\"", topic).underline().bright_blue());
-        println!();
-        
-        let mut found = false;
+    /// Sets aside the sins that `source`'s `// forgiven:` comments forgive,
+    /// unless forgiveness is ignored. Returns the sins that remain and how many
+    /// were forgiven.
+    fn forgive(&self, source: &str, sins: Vec<Sin>) -> (Vec<Sin>, usize) {
+        if !self.forgiving {
+            return (sins, 0);
+        }
+        let forgiveness = Forgiveness::read(source, self.today());
+        let found = sins.len();
+        let sins: Vec<Sin> = sins.into_iter().filter(|sin| !forgiveness.forgives(sin.line_num, sin.rule_id())).collect();
+        let forgiven = found - sins.len();
+        (sins, forgiven)
+    }
+    
+    /// Sets aside the sins of `script` the baseline excuses, when there is one,
+    /// adding a stale absolution, judged by the commandments, for each entry
+    /// that has expired. Returns the sins that remain and how many were excused.
+    fn excuse(&self, script: &Path, sins: Vec<Sin>) -> Result<(Vec<Sin>, usize), String> {
+        let Some(baseline) = &self.baseline else { return Ok((sins, 0)) };
+        let excused = baseline.lock().unwrap_or_else(PoisonError::into_inner).excuse(script, sins, self.today());
+        let mut sins = excused.sins;
+        if !excused.stale.is_empty() {
+            let standards = self.standards_for(script)?;
+            let severity = Rule::find("stale-absolution").and_then(|rule| standards.severity(rule));
+            sins.extend(excused.stale.into_iter().filter_map(|sin| Some(Sin { severity: severity?, ..sin })));
+            sins.sort_by_key(|sin| sin.line_num);
+        }
+        Ok((sins, excused.excused))
+    }
+    
+    /// Confesses `source` by `standards`, with the sins its comments forgive
+    /// set aside. Returns the sins that remain and how many were forgiven.
+    fn confess_source(&self, source: &str, standards: &Standards) -> Result<(Vec<Sin>, usize), String> {
+        Ok(self.forgive(source, standards.judge(source, self.collect_sins(source)?)))
+    }
+    
+    /// Reports every statement that rebinds a name sworn as a covenant earlier in the script.
+    fn find_broken_covenants(statements: &[ast::Stmt], sworn: &mut HashMap<String, usize>, sins: &mut Vec<Sin>) {
+        use ast::StmtKind;
         
-        // First try exact match
-        if let Some(verse) = self.bible_verses.get(topic.to_lowercase().as_str()) {
-            println!("{}", format!("📜 {}", verse).green());
-            found = true;
-        } else {
-            // Try keyword matching
-            let mut matches = Vec::new();
-            
-            for (key, verse) in &self.bible_verses {
-                if key.contains(topic.to_lowercase().as_str()) || 
-                   verse.to_lowercase().contains(topic.to_lowercase().as_str()) {
-                    matches.push((key, verse));
+        for stmt in statements {
+            let rebinds = match &stmt.kind {
+                StmtKind::Covenant { name, .. } => {
+                    if let Some(&line) = sworn.get(name) {
+                        Some((name, line))
+                    } else {
+                        sworn.insert(name.clone(), stmt.line);
+                        None
+                    }
                 }
+                StmtKind::Let { name, .. } | StmtKind::Assign { name, .. } => sworn.get(name).map(|&line| (name, line)),
+                _ => None,
+            };
+            if let Some((name, line)) = rebinds {
+                sins.push(Sin {
+                    line_num: stmt.line,
+                    severity: SinSeverity::Mortal,
+                    rule: "broken-covenant".into(),
+                    message: format!("Covenant '{}' sworn at line {} is broken", name, line),
+                    culprit: Some(name.clone()),
+                });
             }
             
-            if !matches.is_empty() {
-                for (key, verse) in matches {
-                    println!("{}", format!("📜 [{}] {}", key, verse).green());
+            match &stmt.kind {
+                StmtKind::Judge { then_branch, else_branch, .. } => {
+                    Self::find_broken_covenants(then_branch, sworn, sins);
+                    Self::find_broken_covenants(else_branch.as_deref().unwrap_or_default(), sworn, sins);
+                }
+                StmtKind::Bless(function) => Self::find_broken_covenants(&function.body, sworn, sins),
+                StmtKind::Confess { body, forgive, .. } => {
+                    Self::find_broken_covenants(body, sworn, sins);
+                    Self::find_broken_covenants(forgive, sworn, sins);
                 }
-                found = true;
+                StmtKind::Preach { body, .. }
+                | StmtKind::While { body, .. }
+                | StmtKind::Absolution { body }
+                | StmtKind::Block { body, .. } => Self::find_broken_covenants(body, sworn, sins),
+                _ => {}
             }
         }
+    }
+    
+    /// Finds the sins written in constants: fractions weighed for equality,
+    /// which may never balance, and secrets bound to a name that says so.
+    fn find_sinful_constants(statements: &[ast::Stmt], sins: &mut Vec<Sin>) {
+        use ast::StmtKind;
         
-        if !found {
-            println!("{}", "No direct verse found for this topic.".yellow());
-            println!("{}", "Consider broadening your search or consulting the Good Book directly.".yellow());
-        }
-        
-        // Programming connection
-        println!("\n{}", "Divine Programming Guidance:".underline().bright_blue());
-        
-        match topic.to_lowercase().as_str() {
-            "error" | "errors" | "bug" | "bugs" | "exception" => {
-                println!("In DivinePL, errors are treated as sins to be confessed, not exceptions to be caught.");
-                println!("Use 'confess {{ ... }}' instead of 'try {{ ... }} catch {{ ... }}'");
-                println!("Remember: To err is human, to forgive divine, to handle errors properly, divine programming.");
-            },
-            "loop" | "loops" | "iteration" => {
-                println!("Loops in DivinePL should be created with divine purpose and always include a path to termination.");
-                println!("For infinite is the kingdom of heaven, but finite should be thy loops.");
-                println!("Consider using 'blessing' loops that process each item with reverence.");
-            },
-            "function" | "functions" | "method" | "methods" => {
-                println!("Functions in DivinePL must be blessed to receive divine optimization.");
-                println!("Use 'bless functionName() {{ ... }}' for regular functions.");
-                println!("Use 'miracle functionName() {{ ... }}' for functions that perform extraordinary operations.");
-                println!("Use 'genesis() {{ ... }}' for program entry points.");
-            },
-            "variable" | "variables" | "let" | "const" => {
-                println!("Variables in DivinePL are vessels of divine data.");
-                println!("Use 'let' for mutable variables (as in 'Let there be light').");
-                println!("Use 'covenant' for constants that shall not be broken.");
-                println!("Avoid unholy variable names that invoke sin or blasphemy.");
-            },
-            _ => {
-                println!("The path of righteous code is illuminated through clarity and purpose.");
-                println!("Seek to write your code as a testament to divine order and comprehension.");
-                println!("Remember that all DivinePL code must rest on the Sabbath (unless overridden in dev mode).");
+        for stmt in statements {
+            let (named, expressions) = match &stmt.kind {
+                StmtKind::Let { name, value } | StmtKind::Covenant { name, value } | StmtKind::Assign { name, value } => (Some(name), vec![value]),
+                StmtKind::AssignField { object, field, value } => (Some(field), vec![object, value]),
+                StmtKind::Expr(value) | StmtKind::Ascend(Some(value)) => (None, vec![value]),
+                StmtKind::Judge { condition, .. } | StmtKind::While { condition, .. } => (None, vec![condition]),
+                StmtKind::Preach { iterable, .. } => (None, vec![iterable]),
+                _ => (None, Vec::new()),
+            };
+            if let (Some(name), Some(value)) = (named, expressions.last()) {
+                let word = name.to_lowercase().replace(['_', '-'], "");
+                let secret = SECRET_NAMES.iter().any(|secret| word.contains(secret))
+                    && matches!(interpreter::fold(value), Some(interpreter::Value::Str(text)) if !text.is_empty());
+                if secret {
+                    sins.push(Sin {
+                        line_num: stmt.line,
+                        severity: SinSeverity::Venial,
+                        rule: "secret-literal".into(),
+                        message: format!("Secret '{}' is written into the scripture", name),
+                        culprit: Some(printer::expr(value)),
+                    });
+                }
+            }
+            for expr in expressions {
+                Self::find_weighed_fractions(expr, stmt.line, sins);
+            }
+            
+            match &stmt.kind {
+                StmtKind::Judge { then_branch, else_branch, .. } => {
+                    Self::find_sinful_constants(then_branch, sins);
+                    Self::find_sinful_constants(else_branch.as_deref().unwrap_or_default(), sins);
+                }
+                StmtKind::Bless(function) => Self::find_sinful_constants(&function.body, sins),
+                StmtKind::Confess { body, forgive, .. } => {
+                    Self::find_sinful_constants(body, sins);
+                    Self::find_sinful_constants(forgive, sins);
+                }
+                StmtKind::Preach { body, .. }
+                | StmtKind::While { body, .. }
+                | StmtKind::Absolution { body }
+                | StmtKind::Block { body, .. } => Self::find_sinful_constants(body, sins),
+                _ => {}
             }
         }
-        
-        Ok(())
     }
     
-    fn transform_secular_code(&self, input_path: &Path, output_path: &Path) -> Result<(), String> {
-        // Read secular code
-        let content = fs::read_to_string(input_path)
-            .map_err(|e| format!("Failed to read secular code: {}", e))?;
-        
-        println!("{}", "🕊️ Beginning miraculous transformation of secular code...".bright_blue());
-        
-        // Start the transformation ritual
-        for i in 1..=7 {
-            print!("Phase {} of transformation... ", i);
-            std::io::Write::flush(&mut std::io::stdout()).unwrap();
-            std::thread::sleep(Duration::from_millis(300));
-            println!("{}", "✓".green());
+    /// Finds each `==` or `!=` in `expr` with a fraction on either side.
+    fn find_weighed_fractions(expr: &ast::Expr, line: usize, sins: &mut Vec<Sin>) {
+        use ast::Expr;
+        
+        // Whether an operand is a sum of fractions, whose rounding equality can't forgive
+        fn fractional(expr: &Expr) -> bool {
+            match expr {
+                Expr::Number(n) => n.fract() != 0.0,
+                Expr::Unary { op: "-", operand } => fractional(operand),
+                Expr::Binary { op: "+" | "-" | "*" | "/" | "%", left, right } => fractional(left) || fractional(right),
+                _ => false,
+            }
         }
         
-        // Apply divine transformation
-        let mut transformed = String::new();
-        
-        // Add divine header
-        transformed.push_str("// Transformed by the Divine Miracle of DivinePL\n");
-        transformed.push_str("// This code has been sanctified from its secular origins\n\n");
-        transformed.push_str("🙏 BEGIN PRAYER 🙏\n");
-        transformed.push_str("Lord, bless this transformed code\n");
-        transformed.push_str("Guide it to run with divine efficiency\n");
-        transformed.push_str("Protect it from bugs and runtime errors\n");
-        transformed.push_str("🙏 END PRAYER 🙏\n\n");
-        
-        // Transform function declarations
-        let content = content.replace("function ", "bless function ")
-                           .replace("class ", "covenant class ")
-                           .replace("async function", "miracle async function")
-                           .replace("throw new Error", "confess new Sin")
-                           .replace("try {", "attempt_salvation {")
-                           .replace("catch (", "forgive (")
-                           .replace("console.log", "revelation")
-                           .replace("for (", "preach (")
-                           .replace("return", "ascend with");
-        
-        transformed.push_str(&content);
-        
-        // Add divine footer
-        transformed.push_str("\n\n// End of sanctified code\n");
-        transformed.push_str("// \"In the beginning was the code, and the code was with God.\" - DivinePL 1:1\n");
-        
-        // Write the transformed code
-        fs::write(output_path, transformed)
-            .map_err(|e| format!("Failed to write divine transformation: {}", e))?;
-        
-        println!("{}", "\n✨ MIRACLE COMPLETE! ✨".bright_yellow());
-        println!("{}", format!("Secular code has been divinely transformed and saved to: {}", 
-                             output_path.display()).green());
-        
-        Ok(())
+        let inner: Vec<&Expr> = match expr {
+            Expr::Binary { op, left, right } => {
+                if matches!(*op, "==" | "!=") && (fractional(left) || fractional(right)) {
+                    sins.push(Sin {
+                        line_num: line,
+                        severity: SinSeverity::Venial,
+                        rule: "float-equality".into(),
+                        message: "Fractions are weighed for equality, which they may never balance".to_string(),
+                        culprit: Some(printer::expr(expr)),
+                    });
+                }
+                vec![left, right]
+            }
+            Expr::Array(items) | Expr::Call { args: items, .. } => items.iter().collect(),
+            Expr::Object(fields) => fields.iter().map(|(_, value)| value).collect(),
+            Expr::Member { object, .. } => vec![object],
+            Expr::Index { target, index } => vec![target, index],
+            Expr::Unary { operand, .. } => vec![operand],
+            Expr::MethodCall { receiver, args, .. } => std::iter::once(receiver.as_ref()).chain(args).collect(),
+            Expr::Number(_) | Expr::Str(_) | Expr::Bool(_) | Expr::Ident(_) => Vec::new(),
+        };
+        for expr in inner {
+            Self::find_weighed_fractions(expr, line, sins);
+        }
     }
     
-    fn prophesy_code(&self, path: &Path) -> Result<(), String> {
-        // Read the script
-        let content = fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read the script for prophecy: {}", e))?;
-        
-        let statements = self.parse_script(&content)?;
-        
-        println!("{}", "🔮 Entering prophetic vision... 🔮".bright_magenta());
-        std::thread::sleep(Duration::from_millis(1000));
-        
-        // Analyze for potential future issues
-        let mut prophesies = Vec::new();
-        
-        // Pattern matching for common issues
-        if content.contains("while") && !content.contains("break") {
-            prophesies.push("⏳ Infinite loop risk detected. Add a divine exit condition to prevent eternal execution.");
+    /// The last line any of `statements` begins on, however deeply nested.
+    fn last_line(statements: &[ast::Stmt]) -> usize {
+        use ast::StmtKind;
+        
+        statements.iter().map(|stmt| {
+            let nested = match &stmt.kind {
+                StmtKind::Judge { then_branch, else_branch, .. } => {
+                    Self::last_line(then_branch).max(Self::last_line(else_branch.as_deref().unwrap_or_default()))
+                }
+                StmtKind::Bless(function) => Self::last_line(&function.body),
+                StmtKind::Confess { body, forgive, .. } => Self::last_line(body).max(Self::last_line(forgive)),
+                StmtKind::Preach { body, .. }
+                | StmtKind::While { body, .. }
+                | StmtKind::Absolution { body }
+                | StmtKind::Block { body, .. } => Self::last_line(body),
+                _ => 0,
+            };
+            nested.max(stmt.line)
+        }).max().unwrap_or(0)
+    }
+    
+    /// A line with its `//` and `/* ... */` comments removed, leaving string
+    /// literals alone. `in_block_comment` carries an open block comment from one
+    /// line to the next.
+    fn without_comments(line: &str, in_block_comment: &mut bool) -> String {
+        let mut code = String::with_capacity(line.len());
+        let mut quote = None;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if *in_block_comment {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    *in_block_comment = false;
+                    code.push(' ');
+                }
+                continue;
+            }
+            match quote {
+                Some(open) => {
+                    code.push(c);
+                    if c == '\\' {
+                        code.extend(chars.next());
+                    } else if c == open {
+                        quote = None;
+                    }
+                }
+                None if c == '/' && chars.peek() == Some(&'/') => break,
+                None if c == '/' && chars.peek() == Some(&'*') => {
+                    chars.next();
+                    *in_block_comment = true;
+                }
+                None => {
+                    if matches!(c, '"' | '\'' | '`') {
+                        quote = Some(c);
+                    }
+                    code.push(c);
+                }
+            }
         }
-        
-        if content.contains("let ") && !content.contains("covenant") {
-            prophesies.push("📜 Future maintainers will appreciate constants declared as 'covenant' for important values.");
+        code
+    }
+    
+    /// The foretelling quoted in a `@prophesy("...")` annotation, or `None` if the
+    /// line isn't one or its foretelling is unquoted.
+    fn prophecy_annotation(line: &str) -> Option<String> {
+        let inner = line.strip_prefix("@prophesy(")?.trim_end_matches(';').trim_end().strip_suffix(')')?.trim();
+        let quote = inner.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let text = inner.strip_prefix(quote)?.strip_suffix(quote)?;
+        (!text.contains(quote)).then(|| text.to_string())
+    }
+    
+    /// The function a line declares, as in `bless name(...) {` or a `name(...) {`
+    /// method, if it declares one.
+    fn declared_function(line: &str) -> Option<&str> {
+        let header = ["bless ", "miracle ", "async function ", "function "].iter()
+            .find_map(|keyword| line.strip_prefix(keyword))
+            .unwrap_or(line);
+        let (name, _) = header.split_once('(')?;
+        let name = name.trim();
+        let is_name = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+        (is_name && line.ends_with('{') && !parser::RESERVED.contains(&name)).then_some(name)
+    }
+    
+    /// A line of code with the contents of its string literals removed, quotes kept.
+    fn without_strings(line: &str) -> String {
+        let mut code = String::with_capacity(line.len());
+        let mut quote = None;
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match quote {
+                Some(open) if c == open => {
+                    quote = None;
+                    code.push(c);
+                }
+                Some(_) => {
+                    if c == '\\' {
+                        chars.next();
+                    }
+                }
+                None => {
+                    if matches!(c, '"' | '\'' | '`') {
+                        quote = Some(c);
+                    }
+                    code.push(c);
+                }
+            }
         }
+        code
+    }
+    
+    /// Line numbers inside `absolution { ... }` blocks, where scrolls are sealed explicitly.
+    fn lines_within_absolution(content: &str) -> HashSet<usize> {
+        let mut lines = HashSet::new();
+        let mut depth = 0i32;
         
-        if content.lines().count() > 100 && !content.contains("module") {
-            prophesies.push("📚 As this code grows, consider divine modularization through the Holy Trinity pattern.");
+        for (index, line) in content.lines().enumerate() {
+            if depth == 0 && !line.trim_start().starts_with("absolution") {
+                continue;
+            }
+            lines.insert(index + 1);
+            depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+            depth = depth.max(0);
         }
         
-        if content.contains("data") && !content.contains("validate") {
-            prophesies.push("⚠️ Future security concerns: add divine validation to all data inputs to prevent unholy injections.");
+        lines
+    }
+    
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SinSeverity {
+    Venial,
+    Mortal,
+}
+
+impl SinSeverity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SinSeverity::Venial => "venial",
+            SinSeverity::Mortal => "mortal",
         }
-        
-        // Look for specific patterns that might indicate future technical debt
-        let complex_functions = statements.iter()
-            .filter(|s| s.content.contains("function") || s.content.contains("=>"))
-            .filter(|s| s.content.len() > 100)
-            .count();
-        
-        // if complex_functions > 0 {
-        //     let prophecy = format!("🔄 Prophecy reveals {} complex functions that will require refactoring in the future.", complex_functions);
-        //     prophesies.push(prophecy.clone());
-        // }
-        
-        // Random divine insights based on project type
-        let mut rng = rand::thread_rng();
-        let project_insights = [
-            "The path of deployment shall be fraught with environmental differences. Prepare with containerization.",
-            "A great refactoring shall be needed by the third version. Plan accordingly.",
-            "Security vulnerabilities shall manifest if input validation is neglected.",
-            "The user interface shall require redesign as requirements evolve.",
-            "Test coverage will prove insufficient in areas not yet considered.",
-            "Technical debt shall accumulate in the areas of error handling.",
-            "Documentation shall become outdated unless integrated with the development process.",
-            "Dependencies shall age and require updating, bringing both blessings and trials.",
-        ];
-        
-        for _ in 0..3 {
-            let insight = project_insights[rng.gen_range(0..project_insights.len())];
-            prophesies.push(insight);
+    }
+
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "venial" => Some(SinSeverity::Venial),
+            "mortal" => Some(SinSeverity::Mortal),
+            _ => None,
         }
-        
-        // Display the prophecies
-        println!("{}", "\n📜 DIVINE PROPHECIES FOR THIS CODE 📜".underline().bright_magenta());
-        for (i, prophecy) in prophesies.iter().enumerate() {
-            println!("{}. {}", i+1, prophecy.bright_cyan());
-            std::thread::sleep(Duration::from_millis(300));
+    }
+}
+
+/// A rule that a confession judges by.
+struct Rule {
+    /// The rule's stable identifier, such as `DPL001`. Venial rules were
+    /// numbered from 1 and mortal rules from 10; later rules take the next
+    /// number, and a number is never reused.
+    id: &'static str,
+    name: &'static str,
+    severity: SinSeverity,
+    description: &'static str,
+    /// Why breaking the rule is a sin, for `confess --explain`.
+    explanation: &'static str,
+    /// The penance that answers a sin against the rule.
+    suggestion: &'static str,
+}
+
+impl Rule {
+    /// The rule with the identifier or name `key`, ignoring case.
+    fn find(key: &str) -> Option<&'static Rule> {
+        RULES.iter().find(|rule| rule.id.eq_ignore_ascii_case(key) || rule.name.eq_ignore_ascii_case(key))
+    }
+    
+    fn explain(&self) {
+        let severity = match self.severity {
+            SinSeverity::Venial => "venial".yellow(),
+            SinSeverity::Mortal => "mortal".bright_red(),
+        };
+        println!("{} {} ({})", self.id.bold(), self.name.bright_blue(), severity);
+        println!("\n{}\n", self.description.italic());
+        println!("{}", self.explanation);
+        println!("\n{} {}", "Penance:".green(), self.suggestion);
+    }
+}
+
+/// How gravely a congregation judges each rule: the severity of the sins
+/// against it, or `None` when it is off. A rule not judged here keeps the
+/// severity [`RULES`] gives it, so without commandments nothing changes.
+#[derive(Default)]
+struct Standards {
+    judged: HashMap<&'static str, Option<SinSeverity>>,
+    /// The project's own commandments, judged alongside the rules.
+    custom: Vec<CustomCommandment>,
+}
+
+impl Standards {
+    /// Heeds the `sins` section of commandments `settings`, which maps rule
+    /// identifiers to "off", "venial" or "mortal".
+    fn heed(&mut self, settings: &Value) {
+        let Some(sins) = settings.get("sins").and_then(Value::as_object) else { return };
+        for (key, value) in sins {
+            // Loading the commandments has warned of unknown rules and judgments
+            let Some(rule) = Rule::find(key) else { continue };
+            match value.as_str() {
+                Some("off") => self.judged.insert(rule.id, None),
+                Some(severity) if SinSeverity::from_str(severity).is_some() => self.judged.insert(rule.id, SinSeverity::from_str(severity)),
+                _ => continue,
+            };
         }
-        
-        // Generate divine TODOs
-        println!("{}", "\n📋 DIVINE TODOs 📋".underline().bright_yellow());
-        println!("1. Add more comprehensive error confession throughout the codebase.");
-        println!("2. Implement divine logging for better visibility into runtime behavior.");
-        println!("3. Create a test suite with divine assertions to verify righteousness.");
-        println!("4. Consider implementing the Holy Trinity pattern for better code organization.");
-        println!("5. Add performance blessings to intensive operations.");
-        
-        // Final revelation
-        println!("{}", "\n⚡ FINAL REVELATION ⚡".bright_yellow());
-        if rng.gen_bool(0.7) {
-            println!("{}", "This codebase is destined for divine greatness, but must overcome trials of complexity and technical debt. Stay true to the righteous path of clean code and divine principles.".bright_green());
-        } else {
-            println!("{}", "Beware! This codebase walks a narrow path between salvation and damnation. Major restructuring will be required before reaching the promised land of production readiness.".yellow());
+    }
+
+    /// The severity of sins against `rule`, or `None` if it is off.
+    fn severity(&self, rule: &Rule) -> Option<SinSeverity> {
+        self.judged.get(rule.id).copied().unwrap_or(Some(rule.severity))
+    }
+
+    /// The sins found in `source` as these standards judge them: those against
+    /// rules that are off are dropped, the rest take the severity of their
+    /// rule, and those against the custom commandments join them.
+    fn judge(&self, source: &str, sins: Vec<Sin>) -> Vec<Sin> {
+        let mut sins: Vec<Sin> = sins.into_iter()
+            .filter_map(|sin| match Rule::find(&sin.rule) {
+                Some(rule) => self.severity(rule).map(|severity| Sin { severity, ..sin }),
+                None => Some(sin),
+            })
+            .collect();
+        for commandment in &self.custom {
+            sins.extend(commandment.broken(source).into_iter().map(|(line_num, culprit)| Sin {
+                line_num,
+                severity: commandment.severity,
+                rule: commandment.id.clone(),
+                message: commandment.message.to_string(),
+                culprit,
+            }));
         }
-        
-        Ok(())
+        sins.sort_by_key(|sin| sin.line_num);
+        sins
+    }
+}
+
+/// Prints a warning to stderr, unless it has been printed already.
+fn warn_once(warning: String) {
+    static WARNED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    let mut warned = WARNED.get_or_init(Default::default).lock().unwrap_or_else(PoisonError::into_inner);
+    if !warned.contains(&warning) {
+        eprintln!("{}", environment::for_stderr(warning.yellow()));
+        warned.insert(warning);
     }
 }
 
+/// Every rule a confession judges by, in the order of their identifiers.
+const RULES: &[Rule] = &[
+    Rule {
+        id: "DPL001", name: "unblessed-function", severity: SinSeverity::Venial,
+        description: "Functions must be declared with bless, genesis or miracle",
+        explanation: "A function declared with the secular 'function' keyword, or written as an arrow, has not been \
+            blessed. The interpreter refuses to call it, so the script falls when it is reached.",
+        suggestion: "Declare the function with bless, or miracle",
+    },
+    Rule {
+        id: "DPL002", name: "faithless-loop", severity: SinSeverity::Venial,
+        description: "Loops must have faith in their termination",
+        explanation: "A while(true) loop admits no hope of its own end. Such a loop only ends by the tribulation \
+            limit, and every script deserves a loop that knows when its work is done.",
+        suggestion: "Give the loop a faithful termination condition",
+    },
+    Rule {
+        id: "DPL003", name: "secular-var", severity: SinSeverity::Venial,
+        description: "Variables are declared with let, not var",
+        explanation: "'var' is a secular keyword carried over from other languages. DivinePL declares its \
+            variables with 'let'.",
+        suggestion: "Replace 'var' with 'let'; divine confess --absolve can do it for you",
+    },
+    Rule {
+        id: "DPL004", name: "open-prayer", severity: SinSeverity::Venial,
+        description: "Prayer blocks must be closed",
+        explanation: "A prayer that is begun but never ended swallows the rest of the script, which then looks \
+            clean and does nothing.",
+        suggestion: "Close the prayer with 🙏 END PRAYER 🙏, or END PRAYER",
+    },
+    Rule {
+        id: "DPL005", name: "unsealed-scroll", severity: SinSeverity::Venial,
+        description: "Opened scrolls must be sealed",
+        explanation: "A scroll left open is only sealed when the script ends. Sealing it once it has been read \
+            shows the script is done with it.",
+        suggestion: "Seal the scroll with .seal() once it has been read",
+    },
+    Rule {
+        id: "DPL006", name: "malformed-prophecy", severity: SinSeverity::Venial,
+        description: "Prophecies must quote their foretelling",
+        explanation: "A prophecy whose foretelling isn't quoted can't be understood, so it is never fulfilled.",
+        suggestion: "Quote the foretelling, as in @prophesy(\"...\")",
+    },
+    Rule {
+        id: "DPL007", name: "vague-absolution", severity: SinSeverity::Venial,
+        description: "Forgiveness must name the rules it forgives",
+        explanation: "A // forgiven: comment forgives sins against the rules it names, on its own line, and a \
+            // forgiven-file: comment at the top of a script forgives them in all of it. Forgiveness that names \
+            no rule, names one that doesn't exist or is out of place forgives nothing, and only hides that the \
+            sin was meant to be forgiven.",
+        suggestion: "Name the rule, as in // forgiven: DPL010 the cleanup must end its children",
+    },
+    Rule {
+        id: "DPL008", name: "lenten-excess", severity: SinSeverity::Venial,
+        description: "In Lent, functions must fast",
+        explanation: "Lent is a season of fasting, and functions keep it too. While it lasts, a function that spans \
+            more than 30 lines is a sin; out of Lent the rule is silent.",
+        suggestion: "Split the function into smaller blessings",
+    },
+    Rule {
+        id: "DPL009", name: "stale-absolution", severity: SinSeverity::Venial,
+        description: "Forgiveness must not outlive its day",
+        explanation: "A // forgiven: comment that gives until: and a day forgives nothing after that day, and a \
+            baseline entry older than the commandments' baseline_max_age_days excuses nothing. The sin it set aside \
+            is confessed again, and so is the absolution that has gone stale.",
+        suggestion: "Repent of the sin, or renew its forgiveness with a later day",
+    },
+    Rule {
+        id: "DPL010", name: "killing", severity: SinSeverity::Mortal,
+        description: "Thou shalt not kill processes",
+        explanation: "The fifth commandment forbids killing, and processes are no exception. A script that kills \
+            or terminates falls with a sin when it runs.",
+        suggestion: "Let processes end gracefully instead of killing them",
+    },
+    Rule {
+        id: "DPL011", name: "blasphemous-name", severity: SinSeverity::Mortal,
+        description: "Variables must not bear unholy names",
+        explanation: "Naming a variable devil or satan is blasphemy, and blasphemy is judged as gravely as any \
+            sin.",
+        suggestion: "Rename the variable to a virtuous alternative, such as adversary",
+    },
+    Rule {
+        id: "DPL012", name: "caught-error", severity: SinSeverity::Mortal,
+        description: "Errors must be confessed, not caught",
+        explanation: "Catching an error with try hides it away unconfessed. Errors are to be confessed, and \
+            forgiven if they may be.",
+        suggestion: "Confess errors with confess instead of catching them",
+    },
+    Rule {
+        id: "DPL013", name: "broken-covenant", severity: SinSeverity::Mortal,
+        description: "Covenants must not be rebound",
+        explanation: "A covenant is a promise that its name keeps its value. Rebinding the name breaks the \
+            promise, and the script falls when it does.",
+        suggestion: "Keep the covenant; bind the new value to a name of its own",
+    },
+    Rule {
+        id: "DPL014", name: "float-equality", severity: SinSeverity::Venial,
+        description: "Fractions must not be weighed for equality",
+        explanation: "Fractions are kept to the nearest number a machine can hold, so 0.1 + 0.2 is not quite 0.3. \
+            Comparing them with == or != judges the rounding rather than the numbers, and a comparison that looks \
+            true is false.",
+        suggestion: "Weigh the difference against a small tolerance instead, as in a - b < 0.000001",
+    },
+    Rule {
+        id: "DPL015", name: "secret-literal", severity: SinSeverity::Venial,
+        description: "Secrets must not be written into the scripture",
+        explanation: "A string bound to a name such as password, token or apiKey is a secret, and a secret written \
+            into a script is shared with everyone who reads it, and with the history of every copy.",
+        suggestion: "Read the secret from outside the script, where it can be kept and changed apart from it",
+    },
+];
+
+/// How much grace each venial sin costs at judgment day, and how much grace a
+/// run must keep to ascend: five venial sins are borne, six are not.
+/// Both are in percent.
+const VENIAL_WEIGHT: usize = 10;
+const GRACE_NEEDED: usize = 50;
+
+/// How judgment day tells of an ascension, and of a descent.
+const ASCENSIONS: &[&str] = &[
+    "Your code has been found worthy and has",
+    "The scales have tipped toward mercy; your code has",
+    "Well done, good and faithful servant. Your code has",
+];
+const DESCENTS: &[&str] = &[
+    "Your code requires more faith. It has been",
+    "Your code was weighed in the balances and found wanting. It has been",
+    "The gate is narrow, and your code did not pass. It has been",
+];
+
+/// How many lines a function may span in Lent.
+const LENTEN_FUNCTION_LINES: usize = 30;
+
+/// Words that give a name away as holding a secret, in lowercase without
+/// underscores or dashes, so `API_KEY` and `apiKey` are both found.
+const SECRET_NAMES: &[&str] = &["password", "passwd", "secret", "apikey", "token", "privatekey", "credential"];
+
+/// A single finding produced by the confession ritual.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Sin {
+    #[serde(rename = "line")]
+    line_num: usize,
+    severity: SinSeverity,
+    /// The rule broken, one of [`RULES`], or the identifier of the custom
+    /// commandment broken.
+    rule: Arc<str>,
+    message: String,
+    /// The text on the line that gave the sin away, for pointing at it.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    culprit: Option<String>,
+}
+
+impl Sin {
+    /// The identifier of the rule broken, such as `DPL001`, or of the custom
+    /// commandment.
+    fn rule_id(&self) -> &str {
+        Rule::find(&self.rule).map_or(&self.rule, |rule| rule.id)
+    }
+
+    /// Each rule broken by `sins`, as its identifier and name, with how many
+    /// times it was broken; the most broken first, ties by identifier.
+    fn most_frequent<'a>(sins: impl IntoIterator<Item = &'a Sin>) -> Vec<(&'a str, &'a str, usize)> {
+        let mut counts: HashMap<(&str, &str), usize> = HashMap::new();
+        for sin in sins {
+            *counts.entry((sin.rule_id(), &sin.rule)).or_default() += 1;
+        }
+        let mut frequent: Vec<_> = counts.into_iter().map(|((id, rule), count)| (id, rule, count)).collect();
+        frequent.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+        frequent
+    }
+
+    /// What to do about the sin.
+    fn suggestion(&self) -> &'static str {
+        Rule::find(&self.rule).map_or("", |rule| rule.suggestion)
+    }
+    
+    /// The sin pinned to `source`, the script it was found in, with the value
+    /// of the constant it points at, if it points at one.
+    fn diagnostic(&self, path: &str, source: &str) -> Option<Diagnostic> {
+        let level = match self.severity {
+            SinSeverity::Venial => Level::Venial,
+            SinSeverity::Mortal => Level::Mortal,
+        };
+        Diagnostic::new(level, &self.message, path, source, self.line_num, self.culprit.as_deref())
+            .map(|diagnostic| Diagnostic { code: Some(self.rule_id().to_string()), ..diagnostic }.with_folded_value())
+    }
+}
+
+/// A JSON account of a run, as `run --report` writes it.
+#[derive(Serialize)]
+struct RunReport<'a> {
+    script: String,
+    at: String,
+    entry: &'a str,
+    duration_ms: u128,
+    verdict: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Writes a JSON account of a run to `destination`, or into the project's
+/// artifacts when none is given. A report that can't be written never fails the run.
+fn write_run_report(script: &Path, destination: Option<&Path>, entry: Option<&str>, result: &Result<(), DivineError>, elapsed: Duration) {
+    let report = RunReport {
+        script: scripture_name(script),
+        at: Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+        entry: entry.unwrap_or("genesis"),
+        duration_ms: elapsed.as_millis(),
+        verdict: if result.is_ok() { "heaven" } else { "purgatory" },
+        error: result.as_ref().err().map(ToString::to_string),
+    };
+    let contents = format!("{}\n", serde_json::to_string(&report).expect("a run report is always serializable"));
+    
+    let written = match destination {
+        Some(path) => fs::write(path, &contents)
+            .map(|_| path.to_path_buf())
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e)),
+        None => artifacts::store(script, result.is_ok(), "report.json", &contents),
+    };
+    match written {
+        Ok(path) => println!("{}", format!("📄 Run report written to {}", path.display()).bright_blue()),
+        Err(e) => eprintln!("{}", environment::for_stderr(format!("⚠️ The run report could not be written: {}", e).yellow())),
+    }
+}
+
+/// Ends the trace of a run of `script`, if one is kept, recording the error the
+/// run ended in. A trace that can't be written never fails the run.
+fn close_trace(runtime: &DivinePLRuntime, script: &Path, error: Option<&DivineError>) {
+    let Some(trace) = &runtime.trace else {
+        return;
+    };
+    match trace.close(script, error.map(ToString::to_string).as_deref()) {
+        Ok(Some(path)) => println!("{}", format!("📜 Trace kept in {}", path.display()).bright_blue()),
+        Ok(None) => {}
+        Err(e) => eprintln!("{}", environment::for_stderr(format!("⚠️ {}", e).yellow())),
+    }
+}
+
+/// 64-bit FNV-1a; unlike std's hasher its output is stable across releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Reports a command's error on a script, pinned to the span of it at fault.
+fn report_pinned(e: &DivineError, path: &Path) {
+    let shown = scripture_name(path);
+    let source = read_scripture(path).ok();
+    diagnostic::report_error(&e.to_string(), source.as_deref().and_then(|source| Some((shown.as_str(), source, e.span(source)?))));
+}
+
+/// A path of `-` stands for standard input, or output.
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Reads a script, or standard input when the path is `-`. Standard input is
+/// read only once and remembered, so errors can still be shown against it.
+fn read_scripture(path: &Path) -> io::Result<String> {
+    static STDIN: OnceLock<Result<String, String>> = OnceLock::new();
+    if !is_stdio(path) {
+        return fs::read_to_string(path);
+    }
+    STDIN.get_or_init(|| io::read_to_string(io::stdin()).map_err(|e| e.to_string()))
+        .clone()
+        .map_err(io::Error::other)
+}
+
+/// Whether `path` has the extension of a blessed script.
+fn is_blessed(path: &Path) -> bool {
+    matches!(path.extension().and_then(|ext| ext.to_str()), Some("divine" | "dpl"))
+}
+
+/// Refuses a script `path` that names nothing, suggesting the blessed script
+/// beside it with the closest name, and one that names a file of another kind,
+/// unless it is `unorthodox`. Standard input, directories and arks pass.
+fn heed_scripture(path: &Path, unorthodox: bool) -> Result<(), DivineError> {
+    if is_stdio(path) || path.is_dir() || Ark::is_ark(path) {
+        return Ok(());
+    }
+    if !path.exists() {
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        // The same name with another extension is the likeliest meaning of all
+        let suggestion = fs::read_dir(dir).into_iter().flatten().filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|candidate| is_blessed(Path::new(candidate)))
+            .map(|candidate| if Path::new(&candidate).file_stem().is_some_and(|candidate| *candidate == *stem) {
+                (0, candidate)
+            } else {
+                (edit_distance(&candidate, &name), candidate)
+            })
+            .filter(|(distance, candidate)| *distance <= candidate.chars().count() / 3)
+            .min()
+            .map(|(_, candidate)| format!("; did you mean {}?", path.with_file_name(candidate).display()))
+            .unwrap_or_default();
+        return Err(DivineError::Io(format!("Failed to read the scripture: {} does not exist{}", path.display(), suggestion)));
+    }
+    if !unorthodox && !is_blessed(path) {
+        return Err(DivineError::Other(format!(
+            "UnorthodoxError: {} is not a .divine or .dpl scripture, and only those are blessed; pass --unorthodox to read it all the same",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// How a script is named in messages.
+fn scripture_name(path: &Path) -> String {
+    if is_stdio(path) { "<stdin>".to_string() } else { path.display().to_string() }
+}
+
+/// Suggests, for a command that doesn't exist, the commands it is close to
+/// by any of their names, each with its aliases: `chekc` suggests both
+/// confess and lint. Other errors are left as clap made them.
+fn suggest_commands(mut error: clap::Error) -> clap::Error {
+    use clap::error::{ContextKind, ContextValue, ErrorKind};
+    
+    if error.kind() != ErrorKind::InvalidSubcommand {
+        return error;
+    }
+    let Some(ContextValue::String(typed)) = error.get(ContextKind::InvalidSubcommand).cloned() else {
+        return error;
+    };
+    let cli = <Cli as clap::CommandFactory>::command();
+    let mut close: Vec<(usize, Vec<String>)> = cli.get_subcommands()
+        .filter_map(|command| {
+            let distance = std::iter::once(command.get_name()).chain(command.get_all_aliases())
+                .map(|name| edit_distance(name, &typed))
+                .filter(|distance| *distance <= typed.chars().count() / 2)
+                .min()?;
+            let names = std::iter::once(command.get_name()).chain(command.get_visible_aliases()).map(str::to_string).collect();
+            Some((distance, names))
+        })
+        .collect();
+    close.sort_by_key(|(distance, _)| *distance);
+    let suggestions: Vec<String> = close.into_iter().flat_map(|(_, names)| names).collect();
+    if !suggestions.is_empty() {
+        error.insert(ContextKind::SuggestedSubcommand, ContextValue::Strings(suggestions));
+    }
+    error
+}
+
+/// `text`, cut from the left with … to at most `width` characters.
+fn truncate_left(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        return text.to_string();
+    }
+    // Glyphs printed plain would turn … into the three dots it stands for
+    let ellipsis = if glyphs::plain() { "..." } else { "…" };
+    let kept: String = text.chars().skip(len + ellipsis.chars().count() - width.min(len)).collect();
+    format!("{}{}", ellipsis, kept)
+}
+
+/// Levenshtein distance, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 struct DivinePLStatement {
     line_num: usize,
     content: String,
-    has_revelation: bool,
+    /// `content` with the insides of its string literals removed, for checks
+    /// that must only see code.
+    code: String,
     is_miracle: bool,
     is_covenant: bool,
+    /// The topic named by an `import verse "topic"` statement.
+    imported_verse: Option<String>,
+    /// The foretelling of a well-formed `@prophesy("...")` annotation.
+    prophecy: Option<String>,
 }
 
 fn main() {
-    let cli = Cli::parse();
+    let cli = Cli::try_parse().unwrap_or_else(|e| suggest_commands(e).exit());
+    if cli.no_config {
+        config::forgo();
+    }
+    if cli.no_apocrypha {
+        canon::forgo();
+    }
     
-    // Create runtime with dev mode flag and revelation mode (if applicable)
+    // Create runtime with dev mode flag and revelation mode (if applicable); porcelain keeps to its form
     let revelation_mode = match &cli.command {
-        Commands::Run { revelation, .. } => *revelation,
+        Commands::Run { revelation, .. } => *revelation && !cli.porcelain,
         _ => false,
     };
     
-    let runtime = DivinePLRuntime::new(cli.dev, match &cli.command {
-        Commands::Run { verbose, .. } => *verbose,
-        _ => false,
-    }, revelation_mode);
+    // Flags win over DIVINEPL_* variables, which win over the commandments,
+    // which win over the defaults
+    let seed = match &cli.command {
+        Commands::Run { seed, .. } | Commands::Prophesy { seed, .. } => *seed,
+        _ => None,
+    };
+    let color = if cli.porcelain { Some(ColorChoice::Never) } else { cli.color };
+    let flags = Flags { dev: cli.dev, override_sabbath: cli.override_sabbath, seed, fast: cli.fast, color, no_color: cli.no_color, plain_glyphs: cli.plain_glyphs };
+    // Loaded without a word: whatever in them can't be heeded is warned of
+    // when the command itself loads them
+    let commandments = Commandments::discover(&cli.command.anchor()).and_then(|path| Commandments::read(&path).ok());
+    let environment = Environment::resolve(flags, commandments.as_ref()).unwrap_or_else(|e| {
+        diagnostic::report_error(&e, None);
+        process::exit(1);
+    });
+    let verbosity = Verbosity::new(cli.verbose, cli.quiet || cli.porcelain);
+    if verbosity >= Verbosity::Statements && matches!(cli.command, Commands::Run { .. }) {
+        environment.report();
+    }
     
-    // Check if today is Sunday before proceeding
-    if let Err(e) = runtime.check_sabbath(cli.override_sabbath) {
-        eprintln!("{}", e.bright_red());
-        eprintln!("{}", "The Lord commands rest on the seventh day. Try again tomorrow.".yellow());
+    let runtime = DivinePLRuntime::new(environment.dev.value, verbosity, revelation_mode);
+    // Examine reports what is wrong with the Sabbath and the calendar rather than falling over it
+    let runtime = match &cli.command {
+        Commands::Examine { .. } => Ok(runtime),
+        command => runtime
+            .with_sabbath(cli.sabbath_day.as_deref(), cli.sabbath_timezone.as_deref(), &command.anchor())
+            .and_then(|runtime| runtime.with_pretend_today(cli.pretend_today.as_deref()))
+            .and_then(|runtime| runtime.with_calendar(&command.anchor())),
+    }.unwrap_or_else(|e| {
+        diagnostic::report_error(&e, None);
         process::exit(1);
+    });
+    let runtime = runtime
+        .with_fast(environment.fast.value || cli.porcelain)
+        .with_porcelain(cli.porcelain)
+        .with_forgiveness(!matches!(&cli.command,
+            Commands::Run { no_forgiveness: true, .. } | Commands::Confess { no_forgiveness: true, .. }));
+    
+    let runtime = match &cli.command {
+        Commands::Run { path, tribulation_limit, recursion_limit, manna, watch, deny, allow, profile, format, trace, trace_format, smite_step, honor_selah, rapture_after, .. } => runtime
+            .with_watching(*watch)
+            .with_selah(*honor_selah)
+            .with_rapture_after(*rapture_after)
+            .with_profile(profile.then_some(*format))
+            .with_seed(environment.seed.value)
+            .with_manna(manna.clone())
+            .with_tribulation_limit(*tribulation_limit)
+            .and_then(|runtime| runtime.with_trace(trace.as_ref().map(Option::as_deref), *trace_format))
+            .and_then(|runtime| runtime.with_stepping(*smite_step))
+            .and_then(|runtime| runtime.with_recursion_limit(*recursion_limit))
+            .and_then(|runtime| runtime.with_decrees(deny, allow))
+            .and_then(|runtime| runtime.with_commandments(path)),
+        Commands::Confess { deny, allow, baseline, .. } => runtime.with_decrees(deny, allow)
+            .and_then(|runtime| runtime.with_baseline(baseline.as_deref())),
+        Commands::Prophesy { .. } => Ok(runtime.with_seed(environment.seed.value)),
+        _ => Ok(runtime),
+    }.unwrap_or_else(|e| {
+        diagnostic::report_error(&e, None);
+        process::exit(1);
+    });
+    
+    // On Sundays, labor waits; anoint reports on the Sabbath itself
+    let sabbath = match cli.command {
+        Commands::Anoint { .. } => Ok(()),
+        _ => runtime.check_sabbath(&cli.command, environment.override_sabbath.value),
+    };
+    if let Err(e) = sabbath {
+        eprintln!("{}", environment::for_stderr(e.to_string().bright_red()));
+        let advice = if runtime.sabbath.remaining(runtime.clock.now()) < chrono::Duration::minutes(60) {
+            "The Lord commands rest on the seventh day. It is nearly over; wait a little longer."
+        } else {
+            "The Lord commands rest on the seventh day. Wait until it ends, or pass --dev --override-sabbath."
+        };
+        eprintln!("{}", environment::for_stderr(advice.yellow()));
+        close_trace(&runtime, &cli.command.anchor(), Some(&e));
+        process::exit(e.exit_code());
+    }
+    
+    // A script named on the command line must be there, and be of a blessed kind
+    let named = match &cli.command {
+        Commands::Run { path, embedded: None, .. }
+        | Commands::Confess { path: Some(path), embedded: None, between: None, explain: None, .. }
+        | Commands::Prophesy { path, .. } => Some(path),
+        _ => None,
+    };
+    if let Some(Err(e)) = named.map(|path| heed_scripture(path, cli.unorthodox)) {
+        diagnostic::report_error(&e.to_string(), None);
+        close_trace(&runtime, &cli.command.anchor(), Some(&e));
+        process::exit(e.exit_code());
+    }
+    
+    // Ctrl-C stops a run between statements, so that it can still be summed up,
+    // and ends a watch in peace
+    if matches!(cli.command, Commands::Run { check: false, .. } | Commands::Commune | Commands::Confess { watch: true, .. }) {
+        if let Err(e) = tribulation::install() {
+            eprintln!("{}", environment::for_stderr(format!("⚠️ {}", e).yellow()));
+        }
     }
     
     // Process command
+    let started = Instant::now();
+    let mut sins_confessed = None;
+    // Commands that succeed can still report an unfavorable outcome through their exit status
+    let mut status = 0;
     let result = match &cli.command {
-        Commands::Run { path, .. } => runtime.run_script(path),
-        Commands::New { name, template } => runtime.create_project(name, template),
-        Commands::Confess { path } => runtime.confess_script(path),
-        Commands::Bible { topic } => runtime.search_bible(topic),
-        Commands::Miracle { input_path, output_path } => runtime.transform_secular_code(input_path, output_path),
-        Commands::Prophesy { path } => runtime.prophesy_code(path),
+        Commands::Confess { explain: Some(key), list, .. } => confession::explain_rules(key.as_deref(), *list),
+        Commands::Run { path, check: true, .. } if path.is_dir() => runtime.check_directory(path).map(|unsound| status = unsound),
+        Commands::Run { path, check: true, .. } => runtime.check_script(path),
+        Commands::Run { path, entry, embedded: Some(format), .. } => tribulation::endure(|| runtime.run_embedded(path, *format, entry.as_deref())),
+        Commands::Run { path, watch: true, .. } | Commands::Confess { path: Some(path), watch: true, .. } if is_stdio(path) => {
+            Err(DivineError::Other("WatchError: standard input cannot be watched; save the script to a file".to_string()))
+        }
+        Commands::Run { path, entry, watch: true, .. } => match runtime.entry_script(path, entry.as_deref()) {
+            // The first Ctrl-C stops the run in progress; the next ends the watch
+            Ok(script) => watch::watch(&script, || {
+                let result = tribulation::endure(|| runtime.run_script(&script, entry.as_deref()));
+                if let Err(e) = &result {
+                    report_pinned(e, &script);
+                }
+                // A watch ends with Ctrl-C, so each run's trace is written out as it ends
+                close_trace(&runtime, &script, result.as_ref().err());
+            }),
+            Err(e) => Err(e),
+        },
+        Commands::Run { path, entry, report, .. } => {
+            let result = tribulation::endure(|| runtime.run_script(path, entry.as_deref()));
+            if let Some(destination) = report {
+                write_run_report(path, destination.as_deref(), entry.as_deref(), &result, started.elapsed());
+            }
+            result
+        }
+        Commands::New { list_templates: true, template_dir, .. } => creation::list_templates(template_dir.as_deref()),
+        // clap guarantees a name unless templates are being listed
+        Commands::New { name, template, template_dir, .. } => {
+            creation::create_project(name.as_deref().unwrap_or_default(), template, template_dir.as_deref())
+        }
+        Commands::Baptize { old_path, new_name, dry_run } => creation::baptize_project(old_path, new_name, *dry_run),
+        Commands::Confess { path, between: Some(range), format, .. } => match format {
+            ConfessionFormat::Text => confession::confess_between(&runtime, range, path.as_deref(), OutputFormat::Text),
+            ConfessionFormat::Json => confession::confess_between(&runtime, range, path.as_deref(), OutputFormat::Json),
+            ConfessionFormat::Sarif => Err(DivineError::Other("SARIF reports a confession, not a comparison; use --format json with --between".to_string())),
+        },
+        Commands::Confess { path: Some(path), absolve: true, dry_run, .. } => {
+            absolve::absolve(&runtime, path, *dry_run).map(|sins| sins_confessed = Some(sins)).map_err(Into::into)
+        }
+        Commands::Confess { path: Some(path), interactive: true, .. } => {
+            absolve::interactive(&runtime, path).map(|sins| sins_confessed = Some(sins)).map_err(Into::into)
+        }
+        Commands::Confess { path: Some(path), watch: true, .. } if !path.is_dir() => watch::watch(path, || {
+            if let Err(e) = confession::confess_script(&runtime, path, false, ConfessionFormat::Text) {
+                report_pinned(&e, path);
+            }
+        }),
+        Commands::Confess { path, daemon_auto, embedded, ignore, penance_threshold, format, update_baseline, prune_baseline, .. } => {
+            // clap guarantees a path whenever --between is absent
+            let path = path.as_deref().unwrap_or(Path::new("."));
+            match embedded {
+                Some(embedded) => confession::confess_embedded(&runtime, path, *embedded, *format),
+                None if path.is_dir() => confession::confess_directory(&runtime, path, *daemon_auto, ignore, *format),
+                None => confession::confess_script(&runtime, path, *daemon_auto, *format),
+            }.and_then(|confessions| {
+                // The baseline is rewritten only once every script has been confessed
+                if let Some(baseline) = runtime.baseline.as_ref().filter(|_| *update_baseline || *prune_baseline) {
+                    let baseline = baseline.lock().unwrap_or_else(PoisonError::into_inner);
+                    let kept = if *update_baseline { baseline.update(runtime.today()) } else { baseline.prune() }?;
+                    eprintln!("{}", environment::for_stderr(format!("📒 The baseline holds {} sin{}", kept, if kept == 1 { "" } else { "s" }).dimmed()));
+                }
+                Ok(confessions)
+            }).map(|confessions| {
+                let (venial, mortal) = Confession::count(&confessions);
+                match format {
+                    ConfessionFormat::Json => println!("{}", serde_json::to_string(&Confession::report(&confessions))
+                        .expect("a confession is always serializable")),
+                    ConfessionFormat::Sarif => println!("{}", sarif::log(&confessions)),
+                    // Porcelain has a sin line for each sin and needs no tally
+                    ConfessionFormat::Text if runtime.porcelain => {}
+                    // A tally made for grep
+                    ConfessionFormat::Text => println!("sins: total={} venial={} mortal={} forgiven={}",
+                        venial + mortal, venial, mortal, Confession::forgiven(&confessions)),
+                }
+                sins_confessed = Some(venial + mortal);
+                status = confession::penance(venial, mortal, *penance_threshold);
+            })
+        }
+        Commands::Chronicles { action: Some(ChroniclesAction::Clear) } => chronicles::clear(&chronicles::chronicles_dir()).map_err(Into::into),
+        Commands::Chronicles { action: None } => chronicles::show(&chronicles::chronicles_dir()).map_err(Into::into),
+        Commands::Ark { action: ArkAction::Build { project, output, entry_script } } => {
+            ark::build(&runtime, project.as_deref().unwrap_or(Path::new(".")), output.as_deref(), entry_script)
+        }
+        Commands::Resurrect { file, relic, list, force } => sepulchre::resurrect(file, *relic, *list, *force).map_err(Into::into),
+        Commands::Artifacts { action: ArtifactsAction::List } => artifacts::list(Path::new(".")).map_err(Into::into),
+        Commands::Artifacts { action: ArtifactsAction::Clean } => artifacts::clean(Path::new(".")).map_err(Into::into),
+        Commands::Status { init: Some(shell), .. } => {
+            print!("{}", status::init_script(*shell));
+            Ok(())
+        }
+        Commands::Status { porcelain: Some(Porcelain::Prompt), .. } => {
+            status::prompt(Path::new("."));
+            Ok(())
+        }
+        Commands::Status { refresh: true, .. } => {
+            status::refresh_quietly(Path::new("."));
+            Ok(())
+        }
+        Commands::Status { .. } => {
+            status::show(Path::new("."));
+            Ok(())
+        }
+        Commands::Daemon { socket, idle_timeout } => {
+            let socket = socket.clone().unwrap_or_else(daemon::default_socket_path);
+            daemon::serve(&runtime, &socket, Duration::from_secs(*idle_timeout)).map_err(Into::into)
+        }
+        Commands::Commune => commune::commune(&runtime).map_err(Into::into),
+        Commands::Anoint { format, json } => {
+            let format = if *json { OutputFormat::Json } else { *format };
+            anoint::anoint(&runtime, &environment, format)
+        }
+        Commands::Examine { schema: true, .. } => {
+            println!("{}", examine::schema());
+            Ok(())
+        }
+        Commands::Examine { path, .. } => examine::examine(path.as_deref().unwrap_or(Path::new("."))),
+        Commands::Bible { list_sources: true, .. } => {
+            bible::list_verse_sources();
+            Ok(())
+        }
+        Commands::Bible { list: true, .. } => {
+            bible::list_verse_topics();
+            Ok(())
+        }
+        Commands::Bible { topics, .. } => {
+            let mut distinct: Vec<String> = Vec::new();
+            for topic in topics {
+                if !distinct.iter().any(|seen| seen.eq_ignore_ascii_case(topic)) {
+                    distinct.push(topic.clone());
+                }
+            }
+            match distinct.as_slice() {
+                [topic] => bible::search_bible(&runtime, topic),
+                _ => bible::search_bible_topics(&runtime, &distinct),
+            }.map(|found| {
+                if found == 0 {
+                    status = 1;
+                }
+            })
+        }
+        Commands::Miracle { input_path, output_path, in_place, retransform, language, preview, stat, extensions, liturgy, map } => {
+            liturgy.as_deref().map(miracle::Liturgy::load).transpose().map_err(DivineError::Other).and_then(|liturgy| {
+                let options = transfiguration::MiracleOptions {
+                    language: language.as_deref(),
+                    liturgy: liturgy.as_ref(),
+                    retransform: *retransform,
+                    rehearsal: match (preview, stat) {
+                        (true, _) => Some(transfiguration::Rehearsal::Diff),
+                        (_, true) => Some(transfiguration::Rehearsal::Stat),
+                        _ => None,
+                    },
+                    in_place: *in_place,
+                    map: map.as_deref(),
+                };
+                let output_path = output_path.as_deref().unwrap_or(input_path);
+                if input_path.is_dir() && map.is_some() {
+                    Err(DivineError::Other("--map writes the source map of a single file, not of a directory".to_string()))
+                } else if input_path.is_dir() {
+                    transfiguration::transform_secular_tree(input_path, output_path, extensions, &options)
+                } else if !extensions.is_empty() {
+                    Err(DivineError::Other("--extensions chooses among the files of a directory, not a single file".to_string()))
+                } else {
+                    transfiguration::transform_secular_code(&runtime, input_path, output_path, &options)
+                }
+            })
+        }
+        Commands::Secularize { input_path, output_path, report, language, liturgy, map } => {
+            let loaded = liturgy.as_deref().map(miracle::Liturgy::load).transpose()
+                .and_then(|liturgy| Ok((liturgy, map.as_deref().map(miracle::SourceMap::load).transpose()?)));
+            loaded.map_err(DivineError::Other).and_then(|(liturgy, map)| {
+                transfiguration::secularize(input_path, output_path, language.as_deref(), liturgy.as_ref(), map.as_ref(), *report)
+            })
+        }
+        Commands::MapLookup { map, generated_line } => miracle::SourceMap::load(map).map_err(DivineError::Other).and_then(|loaded| {
+            match loaded.origin(*generated_line) {
+                miracle::Origin::Line(original) => {
+                    println!("Line {} of the miracle was line {} of {}", generated_line, original, loaded.source);
+                    Ok(())
+                }
+                miracle::Origin::Header => {
+                    println!("Line {} of the miracle is in the header it wrote, and was no line of {}", generated_line, loaded.source);
+                    Ok(())
+                }
+                miracle::Origin::Footer => {
+                    println!("Line {} of the miracle is in the footer it wrote, and was no line of {}", generated_line, loaded.source);
+                    Ok(())
+                }
+                miracle::Origin::Unknown => Err(DivineError::Other(format!(
+                    "Line {} is beyond the {} lines {} maps", generated_line, loaded.footer.1, map.display()
+                ))),
+            }
+        }),
+        Commands::Sanctify { path, check, stdout } => transfiguration::sanctify(path, *check, *stdout),
+        Commands::Testament { path, filter } => testament::testament(&runtime, path, filter.as_deref()),
+        Commands::Tithe { path, format, json, ignore } => {
+            let format = if *json { OutputFormat::Json } else { *format };
+            tithe::tithe(&runtime, path, format, ignore).map_err(Into::into)
+        }
+        Commands::Prophesy { path, ledger, ledger_report: true, .. } => {
+            let ledger = ledger.clone().unwrap_or_else(|| foretold::Ledger::beside(path));
+            foretold::report(&ledger, runtime.today()).map_err(DivineError::from)
+        }
+        Commands::Prophesy { path, fail_on, output, todos_only, ledger, max_doom, .. } => {
+            let ledger = ledger.clone().unwrap_or_else(|| foretold::Ledger::beside(path));
+            prophesy::prophesy_code(&runtime, path, output.as_deref(), *todos_only, &ledger).map(|vision| {
+                for category in fail_on {
+                    let foretold = vision.prophecies.iter().filter(|p| p.category == *category).count();
+                    if foretold > 0 {
+                        eprintln!("{}", environment::for_stderr(format!("⚠️ {} {} {} foretold (--fail-on {})",
+                            foretold, category.as_str(), if foretold == 1 { "prophecy was" } else { "prophecies were" },
+                            category.as_str()).yellow()));
+                        status = 1;
+                    }
+                }
+                if let Some(max_doom) = max_doom.filter(|max_doom| vision.doom() > *max_doom) {
+                    eprintln!("{}", environment::for_stderr(format!("⚠️ The doom foretold is {}, past --max-doom {}",
+                        vision.doom(), max_doom).yellow()));
+                    status = 1;
+                }
+            })
+        }
     };
     
-    // Handle command result
+    // The trace is written out before anything can exit
+    close_trace(&runtime, &cli.command.anchor(), result.as_ref().err());
+    
+    // Prompts ask for a status every time they are drawn; that is not a habit worth chronicling
+    if !matches!(cli.command, Commands::Chronicles { .. } | Commands::Status { .. }) {
+        chronicles::record(&cli.command.anchor(), cli.command.name(), started.elapsed(), result.is_ok() && status == 0, sins_confessed);
+    }
+    
+    // Handle command result; errors from a single script are pinned to where they lie in it,
+    // and each kind of failure has its own exit status
     if let Err(e) = result {
-        eprintln!("{}", format!("Divine Error: {}", e).bright_red());
-        process::exit(1);
+        let scripture = match &cli.command {
+            Commands::Run { path, embedded: None, .. } if Ark::is_ark(path) => Ark::read(path).ok()
+                .map(|ark| (format!("{}:{}", path.display(), ark.entry), ark.entry_source().to_string())),
+            Commands::Run { path, embedded: None, .. }
+            | Commands::Confess { path: Some(path), between: None, embedded: None, .. } if !Ark::is_ark(path) => {
+                read_scripture(path).ok().map(|source| (scripture_name(path), source))
+            }
+            _ => None,
+        };
+        diagnostic::report_error(&e.to_string(), scripture.as_ref().and_then(|(path, source)| Some((path.as_str(), source.as_str(), e.span(source)?))));
+        process::exit(e.exit_code());
+    }
+    if status != 0 {
+        process::exit(status);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sin(line_num: usize, rule: &str, severity: SinSeverity) -> Sin {
+        Sin { line_num, severity, rule: rule.into(), message: format!("broke {}", rule), culprit: line_num.is_multiple_of(2).then(|| "console.log(\"🙏\")".to_string()) }
+    }
+
+    #[test]
+    fn a_sin_round_trips_between_the_daemon_and_its_pilgrims() {
+        // A custom commandment's sins are named by its identifier
+        let sins = vec![sin(3, RULES[2].name, SinSeverity::Mortal), sin(8, RULES[0].name, SinSeverity::Venial), sin(9, "no-todo", SinSeverity::Mortal)];
+        let json = serde_json::to_string(&sins).unwrap();
+        assert!(json.contains("\"line\":3") && json.contains("\"severity\":\"mortal\""), "{}", json);
+        assert_eq!(serde_json::from_str::<Vec<Sin>>(&json).unwrap(), sins);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn commandments_read_surrogate_pairs_and_reject_malformed_numbers() {
+        let path = Path::new("commandments.config");
+        let commandments = config::Commandments::parse(path, r#"{"custom_commandments": [{"id": "pray", "must_contain": "\ud83d\ude4f"}]}"#).unwrap();
+        assert!(matches!(&commandments.custom[0].test, config::Test::MustContain(text) if text == "🙏"));
+        for number in ["1-2", "1e+-.", "01", "1."] {
+            let content = format!(r#"{{"chronicles_max_bytes": {}}}"#, number);
+            assert!(config::Commandments::parse(path, &content).is_err(), "{} was taken for a number", number);
+        }
+    }
+
+    /// A runtime whose clock is stopped at `now`, keeping the Sabbath on `day` in UTC.
+    fn stopped_at(now: &str, day: &str, dev_mode: bool) -> DivinePLRuntime {
+        let mut runtime = DivinePLRuntime::new(dev_mode, Verbosity::Normal, false);
+        runtime.clock = Box::new(FixedClock(now.parse().unwrap()));
+        runtime.sabbath = Sabbath::resolve(None, Some(day), Some("utc")).unwrap();
+        runtime
+    }
+
+    fn command(args: &[&str]) -> Commands {
+        Cli::try_parse_from([&["divine"][..], args].concat()).unwrap().command
+    }
+
+    #[test]
+    fn labor_waits_on_a_sunday() {
+        let runtime = stopped_at("2024-03-10T20:48:00Z", "sun", false);
+        let error = runtime.check_sabbath(&command(&["run", "s.divine"]), false).unwrap_err();
+        assert!(matches!(&error, DivineError::Rest(message) if message.ends_with("The Sabbath ends in 3 hours and 12 minutes")), "{}", error);
+        assert!(runtime.check_sabbath(&command(&["confess", "s.divine"]), false).is_ok());
+        // Only development mode may override the Sabbath
+        assert!(runtime.check_sabbath(&command(&["run", "s.divine"]), true).is_err());
+    }
+
+    #[test]
+    fn development_mode_may_override_a_sunday() {
+        let runtime = stopped_at("2024-03-10T20:48:00Z", "sun", true);
+        assert!(runtime.check_sabbath(&command(&["run", "s.divine"]), false).is_err());
+        assert!(runtime.check_sabbath(&command(&["run", "s.divine"]), true).is_ok());
+    }
+
+    #[test]
+    fn a_saturday_sabbath_forbids_saturday_and_frees_sunday() {
+        let saturday = stopped_at("2024-03-09T12:00:00Z", "sat", false);
+        assert!(saturday.check_sabbath(&command(&["run", "s.divine"]), false).is_err());
+        let sunday = stopped_at("2024-03-10T12:00:00Z", "sat", false);
+        assert!(sunday.check_sabbath(&command(&["run", "s.divine"]), false).is_ok());
+    }
+}
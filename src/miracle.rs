@@ -0,0 +1,531 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A substitution a miracle makes on secular code, known by its id. The tokens
+/// of the secular text, matched with any whitespace between them, become the
+/// divine text, but only where the token after them is `before` when one is
+/// given. The tokens are matched whole, unless `whole_words` is set aside:
+/// then the first may end a longer word and the last begin one, and a single
+/// word is replaced wherever it appears within a word.
+#[derive(Clone, Copy)]
+pub struct Rule<'a> {
+    pub id: &'a str,
+    pub secular: &'a str,
+    pub divine: &'a str,
+    pub before: Option<&'a str>,
+    pub whole_words: bool,
+}
+
+impl<'a> Rule<'a> {
+    const fn new(id: &'a str, secular: &'a str, divine: &'a str) -> Self {
+        Self { id, secular, divine, before: None, whole_words: true }
+    }
+
+    /// The rule, applied only where `token` follows.
+    const fn before(self, token: &'a str) -> Self {
+        Self { before: Some(token), ..self }
+    }
+}
+
+/// The substitutions a miracle makes on JavaScript and TypeScript, tried in
+/// order at each token, so the longer of two that begin alike comes first.
+const JAVASCRIPT: &[Rule<'static>] = &[
+    Rule::new("async-function", "async function", "miracle async function"),
+    Rule::new("function", "function", "bless function"),
+    Rule::new("class", "class", "covenant class"),
+    Rule::new("throw", "throw new Error", "confess new Sin"),
+    Rule::new("try", "try", "attempt_salvation").before("{"),
+    Rule::new("catch", "catch", "forgive").before("("),
+    Rule::new("console-log", "console.log", "revelation"),
+    Rule::new("debugger", "debugger", "selah").before(";"),
+    Rule::new("for", "for", "preach").before("("),
+    Rule::new("return", "return", "ascend with"),
+];
+
+const PYTHON: &[Rule<'static>] = &[
+    Rule::new("async-def", "async def", "miracle async def"),
+    Rule::new("def", "def", "bless def"),
+    Rule::new("class", "class", "covenant class"),
+    Rule::new("raise", "raise", "confess"),
+    Rule::new("try", "try", "attempt_salvation").before(":"),
+    Rule::new("except", "except", "forgive"),
+    Rule::new("print", "print", "revelation").before("("),
+    Rule::new("return", "return", "ascend with"),
+];
+
+const RUST: &[Rule<'static>] = &[
+    Rule::new("async-fn", "async fn", "miracle async fn"),
+    Rule::new("fn", "fn", "bless fn"),
+    Rule::new("panic", "panic!", "confess!"),
+    Rule::new("println", "println!", "revelation!"),
+    Rule::new("return", "return", "ascend with"),
+];
+
+/// How a language writes what no miracle touches: its comments and strings.
+struct Syntax {
+    line_comment: &'static str,
+    block_comment: Option<(&'static str, &'static str)>,
+    /// The quotes that open a string, longest first, each with whether a
+    /// string it opens may span lines.
+    quotes: &'static [(&'static str, bool)],
+    /// Whether strings may be raw, as `r#"..."#`, and `'` opens a character
+    /// only when it closes right after, so lifetimes are left as code.
+    rust: bool,
+}
+
+const C_LIKE: Syntax = Syntax {
+    line_comment: "//",
+    block_comment: Some(("/*", "*/")),
+    quotes: &[("`", true), ("\"", false), ("'", false)],
+    rust: false,
+};
+
+/// A secular language a miracle can be performed on.
+pub struct Language {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    syntax: Syntax,
+    /// The substitutions made on it, which `secularize` undoes.
+    pub rules: &'static [Rule<'static>],
+}
+
+/// Every language a miracle knows. Another is taught with its syntax and rules.
+pub const LANGUAGES: &[Language] = &[
+    Language { name: "javascript", extensions: &["js", "mjs", "cjs", "jsx"], syntax: C_LIKE, rules: JAVASCRIPT },
+    Language { name: "typescript", extensions: &["ts", "mts", "cts", "tsx"], syntax: C_LIKE, rules: JAVASCRIPT },
+    Language {
+        name: "python",
+        extensions: &["py"],
+        syntax: Syntax {
+            line_comment: "#",
+            block_comment: None,
+            quotes: &[("\"\"\"", true), ("'''", true), ("\"", false), ("'", false)],
+            rust: false,
+        },
+        rules: PYTHON,
+    },
+    Language {
+        name: "rust",
+        extensions: &["rs"],
+        syntax: Syntax { line_comment: "//", block_comment: Some(("/*", "*/")), quotes: &[("\"", true)], rust: true },
+        rules: RUST,
+    },
+];
+
+impl Language {
+    /// What begins a comment that runs to the end of the line.
+    pub fn comment(&self) -> &'static str {
+        self.syntax.line_comment
+    }
+}
+
+/// The languages a miracle knows, with their extensions, for errors.
+pub fn known() -> String {
+    LANGUAGES.iter()
+        .map(|language| {
+            let extensions: Vec<String> = language.extensions.iter().map(|ext| format!(".{}", ext)).collect();
+            format!("{} ({})", language.name, extensions.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The language called `name`, as given with --language.
+pub fn named(name: &str) -> Result<&'static Language, String> {
+    LANGUAGES.iter()
+        .find(|language| language.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("A miracle doesn't know the language '{}'; it knows {}", name, known()))
+}
+
+/// The language of the code at `path`, told from its extension. Standard
+/// input, `-`, has no extension to tell by, and is taken for JavaScript unless
+/// --language names another.
+pub fn of(path: &Path) -> Result<&'static Language, String> {
+    if path.as_os_str() == "-" {
+        return named("javascript");
+    }
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    LANGUAGES.iter()
+        .find(|language| language.extensions.iter().any(|known| known.eq_ignore_ascii_case(extension)))
+        .ok_or_else(|| format!(
+            "Can't tell the language of {} from its extension; a miracle knows {}. Name it with --language",
+            path.display(), known()
+        ))
+}
+
+/// Rules a team adds to a miracle's own, read from a liturgy file: a JSON
+/// object, or a TOML table when the file ends in .toml, whose `rules` are
+/// applied in order after the built-in ones, and whose `disable` lists the
+/// ids of built-in rules to set aside.
+pub struct Liturgy {
+    rules: Vec<Written>,
+    disabled: Vec<String>,
+}
+
+/// A rule of a liturgy.
+struct Written {
+    id: String,
+    secular: String,
+    divine: String,
+    whole_words: bool,
+    /// The languages it is for, or every language when empty.
+    languages: Vec<&'static str>,
+}
+
+impl Liturgy {
+    /// The liturgy at `path`. A rule that disables or repeats what isn't
+    /// there to disable or repeat, or two rules that match the same words in
+    /// one language, are errors here rather than surprises in the miracle.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read the liturgy {}: {}", path.display(), e))?;
+        let json: Value = if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("toml")) {
+            toml::from_str::<toml::Value>(&text).map_err(|e| e.message().trim_end().to_string())
+                .and_then(|toml| serde_json::to_value(toml).map_err(|e| e.to_string()))
+                .map_err(|e| format!("The liturgy {} is not valid TOML: {}", path.display(), e))?
+        } else {
+            serde_json::from_str(&text).map_err(|e| format!("The liturgy {} is not valid JSON: {}", path.display(), e))?
+        };
+        let wrong = |what: String| format!("The liturgy {} {}", path.display(), what);
+        let strings = |json: &Value| json.as_array()?.iter().map(|item| item.as_str().map(str::to_string)).collect::<Option<Vec<_>>>();
+
+        let disabled = match json.get("disable") {
+            Some(ids) => strings(ids).ok_or_else(|| wrong("has a `disable` that isn't a list of rule ids".to_string()))?,
+            None => Vec::new(),
+        };
+        let built_in = |id: &str| LANGUAGES.iter().any(|language| language.rules.iter().any(|rule| rule.id == id));
+        if let Some(id) = disabled.iter().find(|id| !built_in(id)) {
+            return Err(wrong(format!("disables '{}', which is no built-in rule", id)));
+        }
+
+        let mut rules: Vec<Written> = Vec::new();
+        let listed = match json.get("rules") {
+            Some(listed) => listed.as_array().ok_or_else(|| wrong("has `rules` that aren't a list".to_string()))?.as_slice(),
+            None => &[],
+        };
+        for (i, rule) in listed.iter().enumerate() {
+            let field = |key: &str| rule.get(key).and_then(Value::as_str).filter(|value| !value.trim().is_empty());
+            let (Some(secular), Some(divine)) = (field("match"), field("replacement")) else {
+                return Err(wrong(format!("has a rule, number {} of its list, without a `match` and a `replacement`", i + 1)));
+            };
+            let id = field("id").unwrap_or(secular).to_string();
+            if built_in(&id) || rules.iter().any(|rule| rule.id == id) {
+                return Err(wrong(format!("has two rules known as '{}'; give one an `id` of its own", id)));
+            }
+            let names = match rule.get("language") {
+                None => Vec::new(),
+                Some(Value::String(name)) => vec![name.clone()],
+                Some(names) => strings(names).ok_or_else(|| wrong(format!("has a rule '{}' whose `language` isn't a name or a list of them", id)))?,
+            };
+            let languages = names.iter().map(|name| named(name).map(|language| language.name)).collect::<Result<Vec<_>, _>>()
+                .map_err(|e| wrong(format!("has a rule '{}' for an unknown language: {}", id, e)))?;
+            let whole_words = rule.get("word_boundary").and_then(Value::as_bool).unwrap_or(true);
+            rules.push(Written { id, secular: secular.to_string(), divine: divine.to_string(), whole_words, languages });
+        }
+
+        let liturgy = Self { rules, disabled };
+        for language in LANGUAGES {
+            let rules = self::rules(language, Some(&liturgy));
+            for (i, rule) in rules.iter().enumerate() {
+                let matched = words(rule.secular, &language.syntax);
+                if let Some(other) = rules[..i].iter().find(|other| words(other.secular, &language.syntax) == matched) {
+                    return Err(wrong(format!(
+                        "has rules '{}' and '{}' that both match '{}' in {}", other.id, rule.id, rule.secular, language.name
+                    )));
+                }
+            }
+        }
+        Ok(liturgy)
+    }
+}
+
+/// The rules a miracle on `language` applies: its own, but for those the
+/// liturgy disables, then the liturgy's rules for it.
+pub fn rules<'a>(language: &'static Language, liturgy: Option<&'a Liturgy>) -> Vec<Rule<'a>> {
+    let mut rules: Vec<Rule<'a>> = language.rules.iter()
+        .filter(|rule| liturgy.is_none_or(|liturgy| !liturgy.disabled.iter().any(|id| id == rule.id)))
+        .copied()
+        .collect();
+    let written = liturgy.iter().flat_map(|liturgy| &liturgy.rules)
+        .filter(|rule| rule.languages.is_empty() || rule.languages.contains(&language.name));
+    rules.extend(written.map(|rule| Rule {
+        id: &rule.id,
+        secular: &rule.secular,
+        divine: &rule.divine,
+        before: None,
+        whole_words: rule.whole_words,
+    }));
+    rules
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Word,
+    Punct,
+    Space,
+    /// A string or comment, which no miracle touches.
+    Verbatim,
+}
+
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+}
+
+/// Splits secular code into words, punctuation, whitespace, and the strings
+/// and comments that are left as written. A string or comment left open runs
+/// to the end of the code.
+fn tokenize<'a>(code: &'a str, syntax: &Syntax) -> Vec<Token<'a>> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut tokens = Vec::new();
+    let mut rest = code;
+    while let Some(c) = rest.chars().next() {
+        let quote = syntax.quotes.iter().find(|(quote, _)| rest.starts_with(quote));
+        let (kind, len) = if rest.starts_with(syntax.line_comment) {
+            (Kind::Verbatim, rest.find('\n').unwrap_or(rest.len()))
+        } else if let Some((open, close)) = syntax.block_comment.filter(|(open, _)| rest.starts_with(open)) {
+            (Kind::Verbatim, rest[open.len()..].find(close).map_or(rest.len(), |end| open.len() + end + close.len()))
+        } else if let Some(len) = raw_string(rest).filter(|_| syntax.rust) {
+            (Kind::Verbatim, len)
+        } else if c == '\'' && syntax.rust {
+            // A character, as 'a' or '\n', or else a lifetime
+            let len = quoted(rest, "'", false);
+            let closed = len > 2 && rest[..len].ends_with('\'');
+            if closed && (rest[1..].starts_with('\\') || rest[1..len - 1].chars().count() == 1) {
+                (Kind::Verbatim, len)
+            } else {
+                (Kind::Punct, 1)
+            }
+        } else if let Some(&(quote, multiline)) = quote {
+            (Kind::Verbatim, quoted(rest, quote, multiline))
+        } else if is_word(c) {
+            (Kind::Word, rest.find(|c: char| !is_word(c)).unwrap_or(rest.len()))
+        } else if c.is_whitespace() {
+            (Kind::Space, rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len()))
+        } else {
+            (Kind::Punct, c.len_utf8())
+        };
+        tokens.push(Token { kind, text: &rest[..len] });
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+/// The length of the string `rest` opens with `quote`, escapes included.
+fn quoted(rest: &str, quote: &str, multiline: bool) -> usize {
+    let mut escaped = false;
+    for (i, c) in rest.char_indices().skip_while(|&(i, _)| i < quote.len()) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\n' if !multiline => return i,
+            _ if rest[i..].starts_with(quote) => return i + quote.len(),
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+/// The length of the raw string, as `r"..."` or `br#"..."#`, that `rest`
+/// begins with, if it begins with one.
+fn raw_string(rest: &str) -> Option<usize> {
+    let body = rest.strip_prefix('r').or_else(|| rest.strip_prefix("br"))?;
+    let hashes = body.len() - body.trim_start_matches('#').len();
+    let body = body[hashes..].strip_prefix('"')?;
+    let opened = rest.len() - body.len();
+    let close = format!("\"{}", "#".repeat(hashes));
+    Some(body.find(&close).map_or(rest.len(), |end| opened + end + close.len()))
+}
+
+/// Code a miracle wrote, with where each of its lines came from.
+pub struct Performed {
+    pub code: String,
+    /// The line of the code it was made from that each line begins on, from 0.
+    pub lines: Vec<usize>,
+    /// How many times each rule was applied.
+    pub counts: Vec<usize>,
+    /// The line of the code it was made from that writing has reached.
+    line: usize,
+}
+
+impl Performed {
+    /// Writes `text` as it stood.
+    fn copy(&mut self, text: &str) {
+        for _ in text.matches('\n') {
+            self.line += 1;
+            self.lines.push(self.line);
+        }
+        self.code.push_str(text);
+    }
+
+    /// Writes `replacement` for `replaced`, whose lines it stands in for.
+    fn replace(&mut self, replacement: &str, replaced: &str) {
+        self.lines.extend(replacement.matches('\n').map(|_| self.line));
+        self.line += replaced.matches('\n').count();
+        self.code.push_str(replacement);
+    }
+}
+
+/// Applies `rules` to the code in `language` outside its strings and comments,
+/// or with `reverse` undoes them. A word after a `.` is a property, such as a
+/// promise's `.catch(`, and is left alone, and so is text a rule of whole
+/// words already made, such as the `function` of `bless function`, so
+/// performing a miracle twice changes nothing more.
+pub fn perform(code: &str, language: &Language, rules: &[Rule], reverse: bool) -> Performed {
+    let tokens = tokenize(code, &language.syntax);
+    // The tokens that aren't whitespace, by their index in `tokens`
+    let significant: Vec<usize> = (0..tokens.len()).filter(|&i| tokens[i].kind != Kind::Space).collect();
+    let patterns: Vec<(Vec<&str>, Vec<&str>, &str)> = rules.iter()
+        .map(|rule| if reverse { (rule.divine, rule.secular) } else { (rule.secular, rule.divine) })
+        .map(|(from, to)| (words(from, &language.syntax), words(to, &language.syntax), to))
+        .collect();
+
+    let text = |k: usize| significant.get(k).map(|&i| &tokens[i]).filter(|token| token.kind != Kind::Verbatim).map(|token| token.text);
+    let reads = |k: usize, words: &[&str]| words.iter().enumerate().all(|(at, word)| text(k + at) == Some(*word));
+    // Where `words` are found at `k`, what is left of the first token before
+    // them and of the last after them
+    let fits = |k: usize, words: &[&str], whole: bool| -> Option<(&str, &str)> {
+        let last = words.len() - 1;
+        if whole || last == 0 {
+            let found = if whole { reads(k, words) } else { text(k).is_some_and(|text| text.contains(words[0])) };
+            return found.then_some(("", ""));
+        }
+        let head = text(k)?.strip_suffix(words[0])?;
+        let tail = text(k + last)?.strip_prefix(words[last])?;
+        reads(k + 1, &words[1..last]).then_some((head, tail))
+    };
+    let mut performed = Performed { code: String::with_capacity(code.len()), lines: vec![0], counts: vec![0; rules.len()], line: 0 };
+    let mut written = 0;
+    let mut k = 0;
+    'tokens: while k < significant.len() {
+        if k > 0 && text(k - 1) == Some(".") {
+            k += 1;
+            continue;
+        }
+        for (r, ((from, to, replacement), rule)) in patterns.iter().zip(rules).enumerate() {
+            let Some((head, tail)) = fits(k, from, rule.whole_words) else { continue };
+            if rule.before.is_some_and(|before| text(k + from.len()) != Some(before)) {
+                continue;
+            }
+            // Where the words stand inside what the rule makes of them, they
+            // were made by it already and are passed over whole
+            let made = (0..=to.len().saturating_sub(from.len()))
+                .filter(|&at| rule.whole_words && at <= k && to[at..].starts_with(from))
+                .find(|&at| reads(k - at, to));
+            if let Some(at) = made {
+                k = k - at + to.len();
+                continue 'tokens;
+            }
+            tokens[written..significant[k]].iter().for_each(|token| performed.copy(token.text));
+            let end = significant[k + from.len() - 1] + 1;
+            let replaced: String = tokens[significant[k]..end].iter().map(|token| token.text).collect();
+            if from.len() == 1 && !rule.whole_words {
+                performed.counts[r] += replaced.matches(from[0]).count();
+                performed.replace(&replaced.replace(from[0], replacement), &replaced);
+            } else {
+                performed.counts[r] += 1;
+                performed.replace(&format!("{}{}{}", head, replacement, tail), &replaced);
+            }
+            written = end;
+            k += from.len();
+            continue 'tokens;
+        }
+        k += 1;
+    }
+    tokens[written..].iter().for_each(|token| performed.copy(token.text));
+    performed
+}
+
+/// The tokens of a rule's words, without the whitespace between them.
+fn words<'a>(text: &'a str, syntax: &Syntax) -> Vec<&'a str> {
+    tokenize(text, syntax).into_iter().filter(|token| token.kind != Kind::Space).map(|token| token.text).collect()
+}
+
+/// Where each line a miracle wrote came from, so stack traces and reviews of
+/// the transformed code can be read against the secular code. Lines are
+/// counted from 1.
+pub struct SourceMap {
+    /// The secular code, as it was named to the miracle.
+    pub source: String,
+    /// The first and last lines of the header the miracle wrote.
+    pub header: (usize, usize),
+    /// The first and last lines of the footer the miracle wrote.
+    pub footer: (usize, usize),
+    /// Each line of transformed code, as (generated, original).
+    pub lines: Vec<(usize, usize)>,
+}
+
+/// What a line of transformed code was before the miracle.
+pub enum Origin {
+    Header,
+    Footer,
+    Line(usize),
+    /// The line is beyond what the map knows.
+    Unknown,
+}
+
+impl SourceMap {
+    /// Where the transformed line `generated` came from.
+    pub fn origin(&self, generated: usize) -> Origin {
+        let within = |(first, last): (usize, usize)| first <= generated && generated <= last;
+        if within(self.header) {
+            return Origin::Header;
+        }
+        if within(self.footer) {
+            return Origin::Footer;
+        }
+        self.lines.iter()
+            .find(|(line, _)| *line == generated)
+            .map_or(Origin::Unknown, |(_, original)| Origin::Line(*original))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let extent = |(first_line, last_line)| Extent { first_line, last_line };
+        let saved = SavedMap {
+            source: self.source.clone(),
+            header: extent(self.header),
+            footer: extent(self.footer),
+            lines: self.lines.iter()
+                .map(|&(generated_line, original_line)| MappedLine { generated_line, original_line })
+                .collect(),
+        };
+        let json = serde_json::to_string(&saved).map_err(|e| format!("Failed to write the source map {}: {}", path.display(), e))?;
+        fs::write(path, format!("{}\n", json)).map_err(|e| format!("Failed to write the source map {}: {}", path.display(), e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read the source map {}: {}", path.display(), e))?;
+        let saved: SavedMap = serde_json::from_str(&text)
+            .map_err(|_| format!("{} is not a source map a miracle wrote", path.display()))?;
+        let extent = |extent: Extent| (extent.first_line, extent.last_line);
+        Ok(Self {
+            source: saved.source,
+            header: extent(saved.header),
+            footer: extent(saved.footer),
+            lines: saved.lines.into_iter().map(|line| (line.generated_line, line.original_line)).collect(),
+        })
+    }
+}
+
+/// A source map as it is written beside the transformed code.
+#[derive(Serialize, Deserialize)]
+struct SavedMap {
+    source: String,
+    header: Extent,
+    footer: Extent,
+    lines: Vec<MappedLine>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Extent {
+    first_line: usize,
+    last_line: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MappedLine {
+    generated_line: usize,
+    original_line: usize,
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+
+use crate::ark::Ark;
+use crate::ast::{Program, Stmt, StmtKind};
+use crate::config::{Commandments, CONFIG_FILE_NAME};
+use crate::error::Fault;
+use serde_json::Value;
+use crate::parser;
+
+/// Where modules are kept, relative to the script being run.
+const MODULE_DIR: &str = "holy_trinity";
+
+/// Where a script and the modules it imports are read from.
+pub enum Scriptorium<'a> {
+    Disk,
+    /// Paths are relative to the root of the ark.
+    Ark(&'a Ark),
+}
+
+impl Scriptorium<'_> {
+    fn read(&self, path: &Path) -> Option<String> {
+        match self {
+            Scriptorium::Disk => fs::read_to_string(path).ok(),
+            Scriptorium::Ark(ark) => ark.file(&path.to_string_lossy().replace('\\', "/")).map(str::to_string),
+        }
+    }
+
+    /// Where the commandments governing `script` were found, and what they say.
+    fn commandments(&self, script: &Path) -> Option<(PathBuf, String)> {
+        match self {
+            Scriptorium::Disk => {
+                let path = Commandments::discover(script)?;
+                let content = fs::read_to_string(&path).ok()?;
+                Some((path, content))
+            }
+            Scriptorium::Ark(ark) => ark.file(CONFIG_FILE_NAME).map(|config| (PathBuf::from(CONFIG_FILE_NAME), config.to_string())),
+        }
+    }
+}
+
+/// The commandments' `trinity` mapping, which names the module each role of
+/// the trinity is kept in: a bare name, looked for in `holy_trinity/` like any
+/// other module, or a path relative to the commandments.
+struct Trinity {
+    /// The commandments the mapping was found in.
+    origin: PathBuf,
+    roles: Vec<Role>,
+}
+
+struct Role {
+    name: String,
+    module: String,
+    /// The line of the commandments mapping the role, and what it says.
+    line: usize,
+    text: String,
+}
+
+impl Trinity {
+    fn read(script: &Path, scriptorium: &Scriptorium) -> Option<Self> {
+        let (origin, content) = scriptorium.commandments(script)?;
+        let settings: Value = serde_json::from_str(&content).ok()?;
+        let lines: Vec<&str> = content.lines().collect();
+        // Roles are found by their key, after the line that opens the mapping
+        let opened = lines.iter().position(|line| line.contains("\"trinity\"")).unwrap_or(0);
+        let roles = settings.get("trinity")?.as_object()?.iter()
+            .filter_map(|(name, module)| {
+                let key = format!("\"{}\"", name);
+                let (line, text) = lines.iter().enumerate().skip(opened)
+                    .find(|(_, line)| line.contains(&key))
+                    .map(|(index, line)| (index + 1, line.trim().trim_end_matches(',').to_string()))
+                    .unwrap_or_default();
+                Some(Role { name: name.clone(), module: module.as_str()?.to_string(), line, text })
+            })
+            .collect();
+        Some(Self { origin, roles })
+    }
+
+    fn role(&self, name: &str) -> Option<&Role> {
+        self.roles.iter().find(|role| role.name == name)
+    }
+
+    /// The role whose module is named `alias`.
+    fn aliased(&self, alias: &str) -> Option<&Role> {
+        self.roles.iter().find(|role| role.module == alias)
+    }
+
+    /// The file the module of `role` is kept in, looking for bare names in the
+    /// `holy_trinity` directory under `base`. A bare name that isn't there
+    /// falls back to the module named after the role itself.
+    fn module(&self, role: &Role, base: &Path, scriptorium: &Scriptorium) -> Option<PathBuf> {
+        if role.module.contains(['/', '\\']) {
+            let path = self.origin.parent().unwrap_or(Path::new("")).join(&role.module);
+            return scriptorium.read(&path).is_some().then_some(path);
+        }
+        find(&role.module, base, scriptorium).or_else(|| find(&role.name, base, scriptorium))
+    }
+
+    /// Why the module of `role` can't be found, pointing at the line of the
+    /// commandments that maps it.
+    fn missing(&self, role: &Role) -> String {
+        format!("the trinity maps it to \"{}\", which does not exist ({}:{}: {})",
+            role.module, display(&self.origin), role.line, role.text)
+    }
+}
+
+/// The file the module of each role of the trinity is kept in, for the
+/// scripts in `dir`, when the commandments governing it map the trinity.
+/// Roles whose modules can't be found are left out.
+pub fn trinity_modules(dir: &Path) -> Option<Vec<(String, PathBuf)>> {
+    let trinity = Trinity::read(dir, &Scriptorium::Disk)?;
+    Some(trinity.roles.iter()
+        .filter_map(|role| Some((role.name.clone(), trinity.module(role, dir, &Scriptorium::Disk)?)))
+        .collect())
+}
+
+/// The module `name` in the `holy_trinity` directory under `base`, as a
+/// `.divine` or `.dpl` file, or as named when it has its extension already.
+fn find(name: &str, base: &Path, scriptorium: &Scriptorium) -> Option<PathBuf> {
+    let dir = base.join(MODULE_DIR);
+    if name.ends_with(".divine") || name.ends_with(".dpl") {
+        return scriptorium.read(&dir.join(name)).is_some().then(|| dir.join(name));
+    }
+    ["divine", "dpl"].iter()
+        .map(|extension| dir.join(format!("{}.{}", name, extension)))
+        .find(|path| scriptorium.read(path).is_some())
+}
+
+/// Loads every module `program` imports, directly or through other modules, and
+/// makes their blessed functions callable from it.
+///
+/// `import module "name"` resolves to `holy_trinity/name.divine` (or `.dpl`) beside
+/// `script`, whichever script the import appears in. A role of the trinity,
+/// such as `"father"`, resolves to the module the commandments' `trinity`
+/// mapping names for it, and that module's name to the same file. Only the
+/// modules' functions are taken; their top-level statements never run.
+pub fn load(program: &mut Program, script: &Path, scriptorium: &Scriptorium, verbose: bool) -> Result<(), Fault> {
+    if verbose {
+        println!("{}", format!("📂 Loaded {} ({} blessed functions)", display(script), program.functions.len()).italic().bright_blue());
+    }
+    let imports = imports(&program.statements);
+    Loader::new(program, script, scriptorium, verbose).load_all(program, &imports, &display(script))
+}
+
+/// The module files a script on disk imports, directly or through other
+/// modules, as far as its imports can be followed.
+pub fn imported_files(script: &Path) -> Vec<PathBuf> {
+    let Some(mut program) = fs::read_to_string(script).ok().and_then(|source| parser::parse_program(&source).ok()) else {
+        return Vec::new();
+    };
+    let imports = imports(&program.statements);
+    let mut loader = Loader::new(&program, script, &Scriptorium::Disk, false);
+    // A module that can't be loaded still leaves those before it worth watching
+    let _ = loader.load_all(&mut program, &imports, &display(script));
+    loader.loaded
+}
+
+/// What each module a script on disk imports directly resolves to: its file,
+/// or the name it was imported by when no file could be found. The imports of
+/// a module in `holy_trinity` are resolved from beside that directory, as they
+/// are for the scripts that import it.
+pub fn direct_imports(program: &Program, script: &Path) -> Vec<Result<PathBuf, String>> {
+    // The loader resolves from the directory holding the path it is given
+    let anchor = match script.parent() {
+        Some(dir) if is_module(script) => dir,
+        _ => script,
+    };
+    let loader = Loader::new(program, anchor, &Scriptorium::Disk, false);
+    imports(&program.statements).into_iter()
+        .map(|(name, line)| loader.resolve(&name, &format!("line {}", line))
+            .map(|path| fs::canonicalize(&path).unwrap_or(path))
+            .map_err(|_| name))
+        .collect()
+}
+
+/// Whether `script` is a module, kept in a `holy_trinity` directory.
+pub fn is_module(script: &Path) -> bool {
+    script.parent().and_then(Path::file_name).is_some_and(|name| name == MODULE_DIR)
+}
+
+struct Loader<'a, 'b> {
+    base: PathBuf,
+    trinity: Option<Trinity>,
+    scriptorium: &'a Scriptorium<'b>,
+    verbose: bool,
+    /// Modules already loaded, so one imported twice is loaded once.
+    loaded: Vec<PathBuf>,
+    /// The scripts whose imports are being followed, outermost first.
+    chain: Vec<String>,
+    /// The script each callable function was blessed in.
+    origins: HashMap<String, String>,
+}
+
+impl<'a, 'b> Loader<'a, 'b> {
+    fn new(program: &Program, script: &Path, scriptorium: &'a Scriptorium<'b>, verbose: bool) -> Self {
+        let base = script.parent().map(Path::to_path_buf).unwrap_or_default();
+        let trinity = Trinity::read(script, scriptorium);
+        let origins = program.functions.keys().map(|name| (name.clone(), display(script))).collect();
+        Self { base, trinity, scriptorium, verbose, loaded: Vec::new(), chain: vec![display(script)], origins }
+    }
+
+    fn load_all(&mut self, program: &mut Program, wanted: &[(String, usize)], importer: &str) -> Result<(), Fault> {
+        for (name, line) in wanted {
+            // Only the script being run is named by line alone
+            let place = if self.chain.len() == 1 { format!("line {}", line) } else { format!("line {} of {}", line, importer) };
+            // Only a module missing from the script being run is pinned to a line of it
+            let path = self.resolve(name, &place)
+                .map_err(|e| if self.chain.len() == 1 { Fault::blaming(name, e).on(*line) } else { Fault::from(e) })?;
+            let shown = display(&path);
+
+            if self.chain.contains(&shown) {
+                return Err(Fault::from(format!("OuroborosError: circular import {} → {}", self.chain.join(" → "), shown)));
+            }
+            // A module may be reached both by name and by a path in the trinity mapping
+            let identity = match self.scriptorium {
+                Scriptorium::Disk => fs::canonicalize(&path).unwrap_or_else(|_| path.clone()),
+                Scriptorium::Ark(_) => path.clone(),
+            };
+            if self.loaded.contains(&identity) {
+                continue;
+            }
+            self.loaded.push(identity);
+
+            let source = self.scriptorium.read(&path).unwrap_or_default();
+            let module = parser::parse_program(&source).map_err(|e| Fault::from(format!("{} (in {})", e, shown)))?;
+            if self.verbose {
+                println!("{}", format!("📂 Loaded {} ({} blessed functions)", shown, module.functions.len()).italic().bright_blue());
+            }
+
+            for (function_name, function) in &module.functions {
+                if let Some(existing) = program.functions.get(function_name) {
+                    return Err(Fault::from(format!(
+                        "SchismError: '{}' is blessed both at line {} of {} and at line {} of {}",
+                        function_name, existing.line, self.origins[function_name], function.line, shown
+                    )));
+                }
+                program.functions.insert(function_name.clone(), function.clone());
+                self.origins.insert(function_name.clone(), shown.clone());
+            }
+
+            self.chain.push(shown.clone());
+            self.load_all(program, &imports(&module.statements), &shown)?;
+            self.chain.pop();
+        }
+        Ok(())
+    }
+
+    /// The file of the module `name`, imported at `place`.
+    fn resolve(&self, name: &str, place: &str) -> Result<PathBuf, String> {
+        if let Some(trinity) = &self.trinity {
+            if let Some(role) = trinity.role(name) {
+                return trinity.module(role, &self.base, self.scriptorium)
+                    .ok_or_else(|| format!("HeresyError: module '{}' was not found ({}); {}", name, place, trinity.missing(role)));
+            }
+        }
+        find(name, &self.base, self.scriptorium)
+            .or_else(|| {
+                let trinity = self.trinity.as_ref()?;
+                trinity.module(trinity.aliased(name)?, &self.base, self.scriptorium)
+            })
+            .ok_or_else(|| format!("HeresyError: module '{}' was not found in {} ({})", name, display(&self.base.join(MODULE_DIR)), place))
+    }
+}
+
+/// The modules a script imports, with the lines importing them.
+fn imports(statements: &[Stmt]) -> Vec<(String, usize)> {
+    statements.iter()
+        .filter_map(|stmt| match &stmt.kind {
+            StmtKind::Import { module } => Some((module.clone(), stmt.line)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn display(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+}
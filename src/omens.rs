@@ -0,0 +1,239 @@
+use std::collections::BTreeSet;
+
+use crate::ast::{Expr, Program, Stmt, StmtKind};
+use crate::config::Commandments;
+use serde_json::Value;
+
+/// The limits a function may reach before a prophecy is made about it, unless
+/// the commandments' `prophecy` section sets others.
+pub const DEFAULT_MAX_STATEMENTS: usize = 30;
+pub const DEFAULT_MAX_NESTING: usize = 4;
+pub const DEFAULT_MAX_PARAMS: usize = 5;
+pub const DEFAULT_MAX_CALLEES: usize = 8;
+
+/// How many days a declared prophecy may stand in the ledger before it adds to
+/// the doom, unless the commandments' `prophecy` section says otherwise.
+pub const DEFAULT_STALE_DAYS: usize = 30;
+
+/// The doom each omen spells, unless the commandments' `prophecy.doom` table
+/// says otherwise: the omen, the commandment it is scored by, and its score.
+/// Functions are named in their omens after the measure, as `statements:name`.
+pub const DOOM: &[(&str, &str, usize)] = &[
+    ("unvalidated-data", "unvalidated_data", 3),
+    ("endless-loop", "endless_loop", 2),
+    ("statements", "statements", 2),
+    ("nesting", "nesting", 2),
+    ("params", "params", 1),
+    ("callees", "callees", 1),
+    ("uncovenanted-let", "uncovenanted_let", 1),
+    ("unmodularized", "unmodularized", 1),
+    (STALE, "stale_declaration", 1),
+];
+
+/// The omen of a declared prophecy or TODO that has stood past its days.
+const STALE: &str = "stale";
+
+/// How far a function may go before its measure is an omen.
+pub struct Thresholds {
+    pub statements: usize,
+    pub nesting: usize,
+    pub params: usize,
+    pub callees: usize,
+}
+
+impl Thresholds {
+    /// The thresholds in the `prophecy` section of `commandments`, each
+    /// defaulting when not given.
+    pub fn from(commandments: Option<&Commandments>) -> Self {
+        let prophecy = commandments.and_then(|commandments| commandments.settings.get("prophecy"));
+        let limit = |key: &str, default: usize| prophecy.and_then(|prophecy| prophecy.get(key))
+            .and_then(Value::as_f64)
+            .map_or(default, |n| n as usize);
+        Self {
+            statements: limit("max_statements", DEFAULT_MAX_STATEMENTS),
+            nesting: limit("max_nesting", DEFAULT_MAX_NESTING),
+            params: limit("max_params", DEFAULT_MAX_PARAMS),
+            callees: limit("max_callees", DEFAULT_MAX_CALLEES),
+        }
+    }
+}
+
+/// How much doom each omen spells.
+pub struct Doom {
+    scores: Vec<(&'static str, usize)>,
+    /// How many days a declared prophecy may stand before it is stale.
+    pub stale_days: usize,
+}
+
+impl Doom {
+    /// The scores in the `prophecy` section of `commandments`, each defaulting
+    /// when not given.
+    pub fn from(commandments: Option<&Commandments>) -> Self {
+        let prophecy = commandments.and_then(|commandments| commandments.settings.get("prophecy"));
+        let doom = prophecy.and_then(|prophecy| prophecy.get("doom"));
+        let score = |key: &str, default: usize| doom.and_then(|doom| doom.get(key))
+            .and_then(Value::as_f64)
+            .map_or(default, |n| n as usize);
+        Self {
+            scores: DOOM.iter().map(|&(omen, key, default)| (omen, score(key, default))).collect(),
+            stale_days: prophecy.and_then(|prophecy| prophecy.get("stale_days"))
+                .and_then(Value::as_f64)
+                .map_or(DEFAULT_STALE_DAYS, |n| n as usize),
+        }
+    }
+
+    /// The doom `omen` spells, or none for an omen that isn't scored.
+    pub fn of(&self, omen: &str) -> usize {
+        let kind = omen.split_once(':').map_or(omen, |(kind, _)| kind);
+        self.scores.iter().find(|(scored, _)| *scored == kind).map_or(0, |(_, score)| *score)
+    }
+
+    /// The doom of a declared prophecy that has stood `age` days: none until
+    /// it is stale.
+    pub fn of_declared(&self, age: Option<i64>) -> usize {
+        match age {
+            Some(age) if age > self.stale_days as i64 => self.of(STALE),
+            _ => 0,
+        }
+    }
+}
+
+/// What a blessed function was measured to be.
+pub struct Measure {
+    pub name: String,
+    pub line: usize,
+    /// Every statement in its body, however deeply nested, but not those of
+    /// the functions blessed inside it.
+    pub statements: usize,
+    /// How many blocks deep its deepest statement lies.
+    pub nesting: usize,
+    pub params: usize,
+    /// How many different functions it calls.
+    pub callees: usize,
+}
+
+/// Measures every function blessed in `program`, in the order they were written.
+pub fn measure(program: &Program) -> Vec<Measure> {
+    let mut measures: Vec<Measure> = program.functions.values().map(|function| {
+        let mut callees = BTreeSet::new();
+        let (statements, nesting) = walk(&function.body, 0, &mut callees);
+        Measure {
+            name: function.name.clone(),
+            line: function.line,
+            statements,
+            nesting,
+            params: function.params.len(),
+            callees: callees.len(),
+        }
+    }).collect();
+    measures.sort_by_key(|measure| measure.line);
+    measures
+}
+
+/// Counts the statements in `body`, found `depth` blocks deep, returning the
+/// count and the depth of the deepest, and gathers the functions they call.
+fn walk(body: &[Stmt], depth: usize, callees: &mut BTreeSet<String>) -> (usize, usize) {
+    let mut count = 0;
+    let mut deepest = depth;
+    for stmt in body {
+        let (blocks, exprs): (Vec<&[Stmt]>, Vec<&Expr>) = match &stmt.kind {
+            StmtKind::Dangling => continue,
+            // A function blessed inside is measured on its own
+            StmtKind::Bless(_) => (Vec::new(), Vec::new()),
+            StmtKind::Let { value, .. } | StmtKind::Covenant { value, .. } | StmtKind::Assign { value, .. } => (Vec::new(), vec![value]),
+            StmtKind::AssignField { object, value, .. } => (Vec::new(), vec![object, value]),
+            StmtKind::Expr(expr) | StmtKind::Ascend(Some(expr)) => (Vec::new(), vec![expr]),
+            StmtKind::Judge { condition, then_branch, else_branch } => {
+                (std::iter::once(then_branch.as_slice()).chain(else_branch.as_deref()).collect(), vec![condition])
+            }
+            StmtKind::Preach { iterable, body, .. } => (vec![body], vec![iterable]),
+            StmtKind::While { condition, body } => (vec![body], vec![condition]),
+            StmtKind::Confess { body, forgive, .. } => (vec![body, forgive], Vec::new()),
+            StmtKind::Absolution { body } | StmtKind::Block { body, .. } => (vec![body], Vec::new()),
+            _ => (Vec::new(), Vec::new()),
+        };
+        count += 1;
+        for expr in exprs {
+            gather_calls(expr, callees);
+        }
+        for block in blocks {
+            let (inner, depth) = walk(block, depth + 1, callees);
+            count += inner;
+            deepest = deepest.max(depth);
+        }
+    }
+    (count, deepest)
+}
+
+/// The first `let` in `program` that binds a literal to a name nothing ever
+/// assigns again: a constant in all but name, which a covenant would keep so.
+/// Returns its name and line.
+pub fn uncovenanted(program: &Program) -> Option<(String, usize)> {
+    let mut lets = Vec::new();
+    let mut assigned = BTreeSet::new();
+    gather_bindings(&program.statements, &mut lets, &mut assigned);
+    lets.into_iter().find(|(name, _)| !assigned.contains(name))
+}
+
+/// Gathers the names bound to literals by `let` in `body`, with their lines,
+/// and the names assigned anywhere in it.
+fn gather_bindings(body: &[Stmt], lets: &mut Vec<(String, usize)>, assigned: &mut BTreeSet<String>) {
+    for stmt in body {
+        let blocks: Vec<&[Stmt]> = match &stmt.kind {
+            StmtKind::Let { name, value } => {
+                if is_literal(value) {
+                    lets.push((name.clone(), stmt.line));
+                }
+                Vec::new()
+            }
+            StmtKind::Assign { name, .. } => {
+                assigned.insert(name.clone());
+                Vec::new()
+            }
+            StmtKind::Bless(function) => vec![&function.body],
+            StmtKind::Judge { then_branch, else_branch, .. } => std::iter::once(then_branch.as_slice()).chain(else_branch.as_deref()).collect(),
+            StmtKind::Preach { body, .. } | StmtKind::While { body, .. } | StmtKind::Absolution { body } | StmtKind::Block { body, .. } => vec![body],
+            StmtKind::Confess { body, forgive, .. } => vec![body, forgive],
+            _ => Vec::new(),
+        };
+        for block in blocks {
+            gather_bindings(block, lets, assigned);
+        }
+    }
+}
+
+/// Whether `expr` is a number, string or truth written out, as `-1` or `"amen"`.
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Number(_) | Expr::Str(_) | Expr::Bool(_) => true,
+        Expr::Unary { op: "-", operand } => matches!(**operand, Expr::Number(_)),
+        _ => false,
+    }
+}
+
+/// Gathers the names of the functions `expr` calls.
+fn gather_calls(expr: &Expr, callees: &mut BTreeSet<String>) {
+    match expr {
+        Expr::Call { callee, args } => {
+            callees.insert(callee.clone());
+            args.iter().for_each(|arg| gather_calls(arg, callees));
+        }
+        Expr::MethodCall { receiver, args, .. } => {
+            gather_calls(receiver, callees);
+            args.iter().for_each(|arg| gather_calls(arg, callees));
+        }
+        Expr::Array(items) => items.iter().for_each(|item| gather_calls(item, callees)),
+        Expr::Object(fields) => fields.iter().for_each(|(_, value)| gather_calls(value, callees)),
+        Expr::Member { object, .. } => gather_calls(object, callees),
+        Expr::Index { target, index } => {
+            gather_calls(target, callees);
+            gather_calls(index, callees);
+        }
+        Expr::Unary { operand, .. } => gather_calls(operand, callees),
+        Expr::Binary { left, right, .. } => {
+            gather_calls(left, callees);
+            gather_calls(right, callees);
+        }
+        Expr::Number(_) | Expr::Str(_) | Expr::Bool(_) | Expr::Ident(_) => {}
+    }
+}
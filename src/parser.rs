@@ -0,0 +1,866 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::ast::{Expr, Function, Program, Stmt, StmtKind, Trivia};
+use crate::error::{DivineError, Span};
+use crate::lexer::{tokenize, without_bom, Token, TokenKind};
+
+/// Words that never name a variable, so statements starting with them are not
+/// mistaken for expressions.
+pub const RESERVED: &[&str] = &[
+    "let", "const", "var", "judge", "else", "return", "bless", "miracle", "new", "function",
+    "class", "if", "for", "while", "preach", "import", "ascend", "this", "confess", "forgive", "in", "absolution",
+    "selah",
+];
+
+/// How deeply blocks, brackets and operators may nest before the parser gives
+/// up, well before the walks over the tree would run out of stack.
+pub const MAX_NESTING: usize = 128;
+
+/// Parses a script into a program. Constructs the runtime understands become
+/// structured statements; code in another tongue is kept as an opaque statement
+/// so that secular-looking code still flows through execution as it always has.
+/// A statement written in DivinePL that can't be parsed whole is an error.
+///
+/// Comments, prayers and blank lines between statements are attached to the
+/// statement that follows them (or share its line), so that printing the
+/// program with [`crate::printer::pretty`] loses nothing. Comments inside a
+/// structured statement's expressions are not kept.
+pub fn parse_program(source: &str) -> Result<Program, DivineError> {
+    let source = without_bom(source);
+    let mut parser = Parser { source, tokens: tokenize(source), pos: 0, depth: 0, functions: HashMap::new() };
+    let statements = parser.statements(None)?;
+    Ok(Program { statements, functions: parser.functions })
+}
+
+/// Parses `source` as a single expression, or `None` if it is anything else.
+pub fn parse_expression(source: &str) -> Option<Expr> {
+    let mut parser = Parser { source, tokens: tokenize(source), pos: 0, depth: 0, functions: HashMap::new() };
+    let expr = parser.expression().ok()?;
+    parser.skip_newlines();
+    matches!(parser.peek(), TokenKind::Eof).then_some(expr)
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    /// How many blocks and expressions are open around the current token.
+    depth: usize,
+    /// Blessed functions registered so far.
+    functions: HashMap<String, Arc<Function>>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &TokenKind {
+        &self.tokens[self.pos].kind
+    }
+
+    fn peek_at(&self, offset: usize) -> &TokenKind {
+        let index = (self.pos + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].kind
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.pos].line
+    }
+
+    /// The current token's column, counted in characters from 1.
+    fn column(&self) -> usize {
+        let start = self.tokens[self.pos].start;
+        let line_start = self.source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+        self.source[line_start..start].chars().count() + 1
+    }
+
+    /// "line N, column C" for the current token, for errors that stop on it.
+    fn position(&self) -> String {
+        format!("line {}, column {}", self.line(), self.column())
+    }
+
+    /// A parse error stopping on the current token.
+    fn stop(&self, message: String) -> DivineError {
+        let token = &self.tokens[self.pos];
+        let len = self.source[token.start..token.end].chars().count().max(1);
+        DivineError::Parse { message, span: Some(Span { line: self.line(), column: self.column(), len }) }
+    }
+
+    /// A parse error about the statement on `line`, blaming `culprit` in it.
+    fn stop_at(&self, line: usize, culprit: Option<&str>, message: String) -> DivineError {
+        DivineError::Parse { message, span: Span::find(self.source, line, culprit) }
+    }
+
+    /// Parses one level deeper with `parse`, unless that would nest too deep.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, DivineError>) -> Result<T, DivineError> {
+        if self.depth == MAX_NESTING {
+            return Err(self.stop(format!("ParseError: nested more than {} levels deep at {}", MAX_NESTING, self.position())));
+        }
+        self.depth += 1;
+        let parsed = parse(self);
+        self.depth -= 1;
+        parsed
+    }
+
+    fn advance(&mut self) {
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), TokenKind::Symbol(s) if *s == symbol)
+    }
+
+    fn is_ident(&self, word: &str) -> bool {
+        matches!(self.peek(), TokenKind::Ident(s) if s == word)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if self.is_symbol(symbol) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str, context: &str) -> Result<(), DivineError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.stop(format!("ParseError: expected '{}' {} at {}", symbol, context, self.position())))
+        }
+    }
+
+    /// Skips line breaks (and any comments between them) within a statement.
+    fn skip_newlines(&mut self) {
+        while matches!(self.peek(), TokenKind::Newline | TokenKind::Comment(_)) {
+            self.advance();
+        }
+    }
+
+    /// Collects the trivia between statements, skipping stray semicolons. A run of
+    /// empty lines becomes a single blank line, except at the start of a block.
+    fn trivia(&mut self, at_block_start: bool) -> Vec<Trivia> {
+        let mut trivia = Vec::new();
+        let mut after_newline = false;
+        loop {
+            let item = match self.peek() {
+                TokenKind::Newline => {
+                    let blank = after_newline && !(at_block_start && trivia.is_empty())
+                        && trivia.last() != Some(&Trivia::BlankLine);
+                    after_newline = true;
+                    self.advance();
+                    if blank {
+                        trivia.push(Trivia::BlankLine);
+                    }
+                    continue;
+                }
+                TokenKind::Comment(text) => Trivia::Comment(text.clone()),
+                TokenKind::Prayer(text) => Trivia::Prayer(text.clone()),
+                TokenKind::PrayerBlock(lines) => Trivia::PrayerBlock(lines.clone()),
+                TokenKind::Symbol(";") => {
+                    self.advance();
+                    continue;
+                }
+                _ => return trivia,
+            };
+            trivia.push(item);
+            after_newline = false;
+            self.advance();
+        }
+    }
+
+    /// Takes a comment that shares a line with the end of the previous statement.
+    fn trailing_comment(&mut self) -> Option<String> {
+        let TokenKind::Comment(text) = self.peek() else { return None };
+        if self.pos == 0 || self.tokens[self.pos - 1].line != self.line() {
+            return None;
+        }
+        let text = text.clone();
+        self.advance();
+        Some(text)
+    }
+
+    fn at_terminator(&self) -> bool {
+        matches!(self.peek(), TokenKind::Newline | TokenKind::Comment(_) | TokenKind::Eof)
+            || self.is_symbol(";")
+            || self.is_symbol("}")
+    }
+
+    /// The source text covered by tokens `start..end`.
+    fn text(&self, start: usize, end: usize) -> String {
+        if end <= start {
+            return String::new();
+        }
+        self.source[self.tokens[start].start..self.tokens[end - 1].end].trim().to_string()
+    }
+
+    /// Parses statements up to the `}` closing a block opened at `opened_at`, or to
+    /// the end of the script at the top level.
+    fn statements(&mut self, opened_at: Option<usize>) -> Result<Vec<Stmt>, DivineError> {
+        let mut statements: Vec<Stmt> = Vec::new();
+        loop {
+            let mut leading = self.trivia(statements.is_empty());
+            match (self.peek(), opened_at) {
+                (TokenKind::Eof, Some(line)) => {
+                    return Err(self.stop_at(line, None, format!("ParseError: block opened at line {} was never closed", line)));
+                }
+                (TokenKind::Symbol("}"), None) => {
+                    return Err(self.stop(format!("ParseError: unexpected '}}' at {} with no block to close", self.position())));
+                }
+                (TokenKind::Eof, None) | (TokenKind::Symbol("}"), Some(_)) => {
+                    if leading.last() == Some(&Trivia::BlankLine) {
+                        leading.pop();
+                    }
+                    if !leading.is_empty() {
+                        statements.push(Stmt { line: self.line(), kind: StmtKind::Dangling, leading, trailing: None });
+                    }
+                    self.advance();
+                    return Ok(statements);
+                }
+                _ => {
+                    let mut stmt = self.statement()?;
+                    stmt.leading = leading;
+                    stmt.trailing = self.trailing_comment();
+                    statements.push(stmt);
+                }
+            }
+        }
+    }
+
+    /// Parses the statements of a block opened at `opened_at`, up to its `}`.
+    fn block(&mut self, opened_at: usize) -> Result<Vec<Stmt>, DivineError> {
+        self.nested(|parser| parser.statements(Some(opened_at)))
+    }
+
+    fn stmt(line: usize, kind: StmtKind) -> Stmt {
+        Stmt { line, kind, leading: Vec::new(), trailing: None }
+    }
+
+    fn statement(&mut self) -> Result<Stmt, DivineError> {
+        if self.is_ident("judge") && matches!(self.peek_at(1), TokenKind::Symbol("(")) {
+            return self.judge();
+        }
+        if self.is_ident("absolution") && matches!(self.peek_at(1), TokenKind::Symbol("{")) {
+            let line = self.line();
+            self.pos += 2;
+            let body = self.block(line)?;
+            return Ok(Self::stmt(line, StmtKind::Absolution { body }));
+        }
+        if self.is_ident("confess") && matches!(self.peek_at(1), TokenKind::Symbol("{")) {
+            return self.confess();
+        }
+        if self.is_ident("preach") && matches!(self.peek_at(1), TokenKind::Symbol("(")) {
+            return self.preach();
+        }
+        if self.is_ident("while") && matches!(self.peek_at(1), TokenKind::Symbol("(")) {
+            return self.while_loop();
+        }
+        if self.is_ident("ascend") {
+            return self.ascend();
+        }
+        if self.is_ident("import") && matches!(self.peek_at(1), TokenKind::Ident(word) if word == "module") {
+            return self.import_module();
+        }
+        if self.is_ident("selah") {
+            return self.selah();
+        }
+
+        let start = self.pos;
+        if self.is_ident("bless") && matches!(self.peek_at(2), TokenKind::Symbol("(")) {
+            // Blessings whose parameters can't be discerned are left as opaque blocks
+            if let Some(stmt) = self.bless(Vec::new())? {
+                return Ok(stmt);
+            }
+        } else if self.is_symbol("@") {
+            // Annotations on anything but a blessing are left as they were written
+            if let Some(stmt) = self.annotated()? {
+                return Ok(stmt);
+            }
+        } else if !self.is_symbol("{") {
+            // A statement that opens with a brace is a block, never an object
+            match self.simple_statement() {
+                Ok(stmt) => return Ok(stmt),
+                // Code in another tongue runs on faith; DivinePL written wrongly does not
+                Err(e) if !self.is_foreign(start) => return Err(e),
+                Err(_) => {}
+            }
+        }
+        self.pos = start;
+        self.opaque()
+    }
+
+    /// Whether the statement from token `start` is written in another tongue:
+    /// it opens a braced block, or holds a word, literal or symbol that no
+    /// DivinePL expression has. Such a statement is kept opaque when it doesn't
+    /// parse, where any other is a parse error.
+    fn is_foreign(&self, start: usize) -> bool {
+        const FOREIGN_SYMBOLS: &[&str] = &["=>", "++", "--", "+=", "-=", "*=", "/=", "?", "@"];
+
+        let mut depth = 0usize;
+        for (index, token) in self.tokens.iter().enumerate().skip(start) {
+            let after_equals = index > start && self.tokens[index - 1].kind == TokenKind::Symbol("=");
+            match &token.kind {
+                TokenKind::Eof => return false,
+                TokenKind::Newline | TokenKind::Comment(_) if depth == 0 => return false,
+                TokenKind::Symbol(";") | TokenKind::Symbol("}") if depth == 0 => return false,
+                // A brace straight after `=` opens an object; anywhere else, a block
+                TokenKind::Symbol("{") if depth == 0 && !after_equals => return true,
+                TokenKind::Symbol("(") | TokenKind::Symbol("[") | TokenKind::Symbol("{") => depth += 1,
+                TokenKind::Symbol(")") | TokenKind::Symbol("]") | TokenKind::Symbol("}") => {
+                    depth = depth.saturating_sub(1);
+                }
+                TokenKind::Symbol(symbol) if FOREIGN_SYMBOLS.contains(symbol) => return true,
+                // Fields and items outside any brackets belong to an object read as a block
+                TokenKind::Symbol(":") | TokenKind::Symbol(",") if depth == 0 => return true,
+                TokenKind::Ident(word) if RESERVED.contains(&word.as_str()) && !(index == start && word == "let") => {
+                    return true;
+                }
+                TokenKind::Template(_) | TokenKind::Unknown(_) => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// The error for a statement that goes on where it should have ended.
+    fn unexpected(&self) -> DivineError {
+        let token = &self.tokens[self.pos];
+        self.stop(format!("ParseError: unexpected '{}' at {}", &self.source[token.start..token.end], self.position()))
+    }
+
+    /// Ends a statement, which must have nothing left on its line but a
+    /// semicolon or a comment.
+    fn end_statement(&mut self) -> Result<(), DivineError> {
+        if !self.at_terminator() {
+            return Err(self.unexpected());
+        }
+        self.eat_symbol(";");
+        Ok(())
+    }
+
+    /// Parses a `let` or `covenant` binding, assignment or expression statement.
+    fn simple_statement(&mut self) -> Result<Stmt, DivineError> {
+        let line = self.line();
+
+        // `covenant(...)` on its own is a promise, not a binding
+        let binding = self.is_ident("let") || (self.is_ident("covenant") && matches!(self.peek_at(1), TokenKind::Ident(_)));
+        let kind = if binding {
+            let covenant = self.is_ident("covenant");
+            self.advance();
+            let TokenKind::Ident(name) = self.peek().clone() else {
+                return Err(self.stop(format!("ParseError: expected a name to bind at {}", self.position())));
+            };
+            if RESERVED.contains(&name.as_str()) {
+                return Err(self.stop(format!("ParseError: '{}' can't be bound at {}", name, self.position())));
+            }
+            self.advance();
+            self.expect_symbol("=", "after the name")?;
+            let value = self.expression()?;
+            if covenant {
+                StmtKind::Covenant { name, value }
+            } else {
+                StmtKind::Let { name, value }
+            }
+        } else if let (TokenKind::Ident(name), TokenKind::Symbol("=")) = (self.peek().clone(), self.peek_at(1)) {
+            if RESERVED.contains(&name.as_str()) {
+                return Err(self.stop(format!("ParseError: '{}' can't be assigned at {}", name, self.position())));
+            }
+            self.pos += 2;
+            StmtKind::Assign { name, value: self.expression()? }
+        } else {
+            match self.expression()? {
+                Expr::Member { object, field } if self.eat_symbol("=") => {
+                    StmtKind::AssignField { object: *object, field, value: self.expression()? }
+                }
+                expr => StmtKind::Expr(expr),
+            }
+        };
+
+        self.end_statement()?;
+        Ok(Self::stmt(line, kind))
+    }
+
+    /// Parses `@prophesy("...")` annotations, each on a line of its own, and the
+    /// blessing they stand above, or returns `None` (with the position
+    /// unspecified) when they decorate anything else.
+    fn annotated(&mut self) -> Result<Option<Stmt>, DivineError> {
+        let mut prophecies = Vec::new();
+        while self.is_symbol("@") {
+            let (TokenKind::Ident(word), TokenKind::Symbol("("), TokenKind::Str(text), TokenKind::Symbol(")")) =
+                (self.peek_at(1), self.peek_at(2), self.peek_at(3), self.peek_at(4)) else { return Ok(None) };
+            if word != "prophesy" {
+                return Ok(None);
+            }
+            prophecies.push(text.clone());
+            self.pos += 5;
+            if !matches!(self.peek(), TokenKind::Newline) {
+                return Ok(None);
+            }
+            self.advance();
+        }
+        if !(self.is_ident("bless") && matches!(self.peek_at(2), TokenKind::Symbol("("))) {
+            return Ok(None);
+        }
+        self.bless(prophecies)
+    }
+
+    /// Parses `bless name(params) { ... }` and registers the function, or returns
+    /// `None` (with the position unspecified) when the header is not one.
+    fn bless(&mut self, prophecies: Vec<String>) -> Result<Option<Stmt>, DivineError> {
+        let line = self.line();
+        let TokenKind::Ident(name) = self.peek_at(1).clone() else { return Ok(None) };
+        if RESERVED.contains(&name.as_str()) {
+            return Ok(None);
+        }
+
+        self.pos += 3;
+        let mut params = Vec::new();
+        self.skip_newlines();
+        while let TokenKind::Ident(param) = self.peek().clone() {
+            if RESERVED.contains(&param.as_str()) {
+                return Ok(None);
+            }
+            params.push(param);
+            self.advance();
+            self.skip_newlines();
+            if !self.eat_symbol(",") {
+                break;
+            }
+            self.skip_newlines();
+        }
+        if !self.eat_symbol(")") {
+            return Ok(None);
+        }
+        self.skip_newlines();
+        if !self.eat_symbol("{") {
+            return Ok(None);
+        }
+
+        let body = self.block(line)?;
+        if let Some(existing) = self.functions.get(&name) {
+            return Err(self.stop_at(line, Some(&name), format!(
+                "ParseError: function '{}' at line {} was already blessed at line {}", name, line, existing.line
+            )));
+        }
+        let function = Arc::new(Function { name: name.clone(), params, body, line, prophecies });
+        self.functions.insert(name, function.clone());
+        Ok(Some(Self::stmt(line, StmtKind::Bless(function))))
+    }
+
+    /// Parses `ascend with value` or a bare `ascend`.
+    fn ascend(&mut self) -> Result<Stmt, DivineError> {
+        let line = self.line();
+        self.advance();
+        let value = if self.is_ident("with") {
+            self.advance();
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.end_statement()?;
+        Ok(Self::stmt(line, StmtKind::Ascend(value)))
+    }
+
+    /// Parses a bare `selah`.
+    fn selah(&mut self) -> Result<Stmt, DivineError> {
+        let line = self.line();
+        self.advance();
+        self.end_statement()?;
+        Ok(Self::stmt(line, StmtKind::Selah))
+    }
+
+    fn import_module(&mut self) -> Result<Stmt, DivineError> {
+        let line = self.line();
+        self.pos += 2;
+        let TokenKind::Str(module) = self.peek().clone() else {
+            return Err(self.stop_at(line, None, format!("ParseError: import module at line {} needs the module's name in quotes", line)));
+        };
+        self.advance();
+        self.end_statement()?;
+        Ok(Self::stmt(line, StmtKind::Import { module }))
+    }
+
+    fn judge(&mut self) -> Result<Stmt, DivineError> {
+        let line = self.line();
+
+        self.advance();
+        self.expect_symbol("(", "after judge")?;
+        let condition = self.expression()
+            .map_err(|_| self.stop_at(line, None, format!("ParseError: judge at line {} needs a condition it can discern", line)))?;
+        self.expect_symbol(")", "to close the judge condition")?;
+
+        self.skip_newlines();
+        self.expect_symbol("{", "to open the judge block")?;
+        let then_branch = self.block(line)?;
+
+        let before_else = self.pos;
+        self.skip_newlines();
+        let else_branch = if self.is_ident("else") {
+            self.advance();
+            self.skip_newlines();
+            if self.is_ident("judge") {
+                Some(vec![self.judge()?])
+            } else {
+                let else_line = self.line();
+                self.expect_symbol("{", "to open the else block")?;
+                Some(self.block(else_line)?)
+            }
+        } else {
+            self.pos = before_else;
+            None
+        };
+
+        Ok(Self::stmt(line, StmtKind::Judge { condition, then_branch, else_branch }))
+    }
+
+    fn confess(&mut self) -> Result<Stmt, DivineError> {
+        let line = self.line();
+
+        self.pos += 2;
+        let body = self.block(line)?;
+
+        self.skip_newlines();
+        let forgive_line = self.line();
+        let sin = match (self.peek(), self.peek_at(1), self.peek_at(2), self.peek_at(3)) {
+            (TokenKind::Ident(forgive), TokenKind::Symbol("("), TokenKind::Ident(sin), TokenKind::Symbol(")"))
+                if forgive == "forgive" && !RESERVED.contains(&sin.as_str()) => sin.clone(),
+            _ => {
+                return Err(self.stop_at(line, None, format!(
+                    "ParseError: confess at line {} must be followed by forgive (sin) {{ ... }}", line
+                )));
+            }
+        };
+        self.pos += 4;
+        self.skip_newlines();
+        self.expect_symbol("{", "to open the forgive block")?;
+        let forgive = self.block(forgive_line)?;
+
+        Ok(Self::stmt(line, StmtKind::Confess { body, sin, forgive }))
+    }
+
+    fn while_loop(&mut self) -> Result<Stmt, DivineError> {
+        let line = self.line();
+
+        self.pos += 2;
+        self.skip_newlines();
+        let condition = self.expression()
+            .map_err(|e| match e {
+                DivineError::Parse { message, span } => DivineError::Parse {
+                    message: format!("{} (while at line {} needs a condition it can discern)", message, line),
+                    span,
+                },
+                other => other,
+            })?;
+        self.skip_newlines();
+        self.expect_symbol(")", "to close the while condition")?;
+
+        self.skip_newlines();
+        self.expect_symbol("{", "to open the while block")?;
+        let body = self.block(line)?;
+
+        Ok(Self::stmt(line, StmtKind::While { condition, body }))
+    }
+
+    fn preach(&mut self) -> Result<Stmt, DivineError> {
+        let line = self.line();
+
+        self.pos += 2;
+        let (TokenKind::Ident(variable), TokenKind::Ident(keyword)) = (self.peek().clone(), self.peek_at(1)) else {
+            return Err(self.stop_at(line, None, format!("ParseError: preach at line {} must take the form preach (name in expr)", line)));
+        };
+        if keyword != "in" || RESERVED.contains(&variable.as_str()) {
+            return Err(self.stop_at(line, None, format!("ParseError: preach at line {} must take the form preach (name in expr)", line)));
+        }
+        self.pos += 2;
+        let iterable = self.expression()
+            .map_err(|_| self.stop_at(line, None, format!("ParseError: preach at line {} needs a flock to preach to", line)))?;
+        self.expect_symbol(")", "to close the preach header")?;
+
+        self.skip_newlines();
+        self.expect_symbol("{", "to open the preach block")?;
+        let body = self.block(line)?;
+
+        Ok(Self::stmt(line, StmtKind::Preach { variable, iterable, body }))
+    }
+
+    /// Consumes a statement the runtime can't interpret, keeping any braced body
+    /// as a block of statements in its own right.
+    fn opaque(&mut self) -> Result<Stmt, DivineError> {
+        let start = self.pos;
+        let line = self.line();
+        let mut depth = 0usize;
+
+        loop {
+            match self.peek() {
+                TokenKind::Eof => break,
+                TokenKind::Newline | TokenKind::Comment(_) if depth == 0 => break,
+                TokenKind::Symbol(";") | TokenKind::Symbol("}") if depth == 0 => break,
+                TokenKind::Symbol("{") if depth == 0 => {
+                    let header = self.text(start, self.pos);
+                    self.advance();
+                    let body = self.block(line)?;
+                    return Ok(Self::stmt(line, StmtKind::Block { header, body }));
+                }
+                TokenKind::Symbol("(") | TokenKind::Symbol("[") | TokenKind::Symbol("{") => depth += 1,
+                TokenKind::Symbol(")") | TokenKind::Symbol("]") | TokenKind::Symbol("}") => {
+                    depth = depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+            self.advance();
+        }
+
+        let binds = match (&self.tokens[start].kind, &self.tokens[(start + 1).min(self.tokens.len() - 1)].kind) {
+            (TokenKind::Ident(keyword), TokenKind::Ident(name))
+                if matches!(keyword.as_str(), "let" | "const" | "var" | "covenant")
+                    && !RESERVED.contains(&name.as_str()) => Some(name.clone()),
+            _ => None,
+        };
+
+        let text = self.text(start, self.pos);
+        let terminated = self.eat_symbol(";");
+        Ok(Self::stmt(line, StmtKind::Opaque { text, binds, terminated }))
+    }
+
+    fn expression(&mut self) -> Result<Expr, DivineError> {
+        self.nested(|parser| parser.binary(0))
+    }
+
+    /// Precedence climbing over the binary operators, loosest first.
+    fn binary(&mut self, level: usize) -> Result<Expr, DivineError> {
+        const LEVELS: &[&[&str]] = &[
+            &["||"],
+            &["&&"],
+            &["==", "!=", "===", "!=="],
+            &["<", ">", "<=", ">="],
+            &["+", "-"],
+            &["*", "/", "%"],
+        ];
+
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+
+        let mut left = self.binary(level + 1)?;
+        while let TokenKind::Symbol(op) = *self.peek() {
+            if !LEVELS[level].contains(&op) {
+                break;
+            }
+            self.advance();
+            let right = self.binary(level + 1)?;
+            let op = match op {
+                "===" => "==",
+                "!==" => "!=",
+                other => other,
+            };
+            left = Expr::Binary { op, left: Box::new(left), right: Box::new(right) };
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, DivineError> {
+        for op in ["!", "-"] {
+            if self.eat_symbol(op) {
+                return Ok(Expr::Unary { op, operand: Box::new(self.nested(Self::unary)?) });
+            }
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, DivineError> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat_symbol("[") {
+                self.skip_newlines();
+                let index = self.expression()?;
+                self.skip_newlines();
+                self.expect_symbol("]", "to close the index")?;
+                expr = Expr::Index { target: Box::new(expr), index: Box::new(index) };
+                continue;
+            }
+            if !self.is_symbol(".") {
+                return Ok(expr);
+            }
+            let TokenKind::Ident(name) = self.peek_at(1).clone() else {
+                return Err(self.stop(format!("ParseError: expected a field or method name at {}", self.position())));
+            };
+            self.pos += 2;
+            expr = if self.eat_symbol("(") {
+                let args = self.list(")")?;
+                Expr::MethodCall { receiver: Box::new(expr), method: name, args }
+            } else {
+                Expr::Member { object: Box::new(expr), field: name }
+            };
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, DivineError> {
+        let expr = match self.peek().clone() {
+            TokenKind::Number(n) => Expr::Number(n),
+            TokenKind::Str(s) => Expr::Str(s),
+            TokenKind::Ident(word) if word == "true" => Expr::Bool(true),
+            TokenKind::Ident(word) if word == "false" => Expr::Bool(false),
+            TokenKind::Ident(name) if !RESERVED.contains(&name.as_str()) => {
+                self.advance();
+                if self.eat_symbol("(") {
+                    let args = self.list(")")?;
+                    return Ok(Expr::Call { callee: name, args });
+                }
+                return Ok(Expr::Ident(name));
+            }
+            TokenKind::Symbol("[") => {
+                self.advance();
+                return Ok(Expr::Array(self.list("]")?));
+            }
+            TokenKind::Symbol("{") => {
+                self.advance();
+                return self.object();
+            }
+            TokenKind::Symbol("(") => {
+                self.advance();
+                self.skip_newlines();
+                let inner = self.expression()?;
+                self.skip_newlines();
+                self.expect_symbol(")", "to close the parenthesis")?;
+                return Ok(inner);
+            }
+            _ => return Err(self.stop(format!("ParseError: expected an expression at {}", self.position()))),
+        };
+        self.advance();
+        Ok(expr)
+    }
+
+    /// Parses the `key: value` fields of an object literal after its opening brace.
+    /// Keys are names or strings.
+    fn object(&mut self) -> Result<Expr, DivineError> {
+        let mut fields = Vec::new();
+        loop {
+            self.skip_newlines();
+            if self.eat_symbol("}") {
+                return Ok(Expr::Object(fields));
+            }
+            let key = match self.peek().clone() {
+                TokenKind::Ident(key) | TokenKind::Str(key) => key,
+                _ => return Err(self.stop(format!("ParseError: expected a field name at {}", self.position()))),
+            };
+            self.advance();
+            self.expect_symbol(":", "after the field name")?;
+            self.skip_newlines();
+            fields.push((key, self.expression()?));
+            self.skip_newlines();
+            // Allow a trailing comma before the closing brace
+            if !self.eat_symbol(",") {
+                self.skip_newlines();
+                self.expect_symbol("}", "to close the object")?;
+                return Ok(Expr::Object(fields));
+            }
+        }
+    }
+
+    /// Parses comma-separated expressions after an opening bracket, up to `close`.
+    fn list(&mut self, close: &str) -> Result<Vec<Expr>, DivineError> {
+        let mut items = Vec::new();
+        self.skip_newlines();
+        if self.eat_symbol(close) {
+            return Ok(items);
+        }
+        loop {
+            self.skip_newlines();
+            items.push(self.expression()?);
+            self.skip_newlines();
+            if self.eat_symbol(close) {
+                return Ok(items);
+            }
+            self.expect_symbol(",", "between items")?;
+            // Allow a trailing comma before the closing bracket
+            self.skip_newlines();
+            if self.eat_symbol(close) {
+                return Ok(items);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_program, MAX_NESTING};
+    use crate::ast::{Expr, StmtKind};
+    use crate::error::{DivineError, Span};
+
+    fn error(source: &str) -> String {
+        parse_program(source).err().unwrap_or_else(|| panic!("{:?} parsed", source)).to_string()
+    }
+
+    #[test]
+    fn malformed_statements_are_parse_errors() {
+        assert_eq!(error("let x = ;"), "ParseError: expected an expression at line 1, column 9");
+        assert_eq!(error("print(\"a\" +);"), "ParseError: expected an expression at line 1, column 12");
+        assert_eq!(error("print(1 2);"), "ParseError: expected ',' between items at line 1, column 9");
+        assert_eq!(error("let y = 1 +* 2;"), "ParseError: expected an expression at line 1, column 12");
+        assert_eq!(error("let x = 1\nx = 2 3"), "ParseError: unexpected '3' at line 2, column 7");
+        assert_eq!(error("print(1e);"), "ParseError: expected ',' between items at line 1, column 8");
+    }
+
+    #[test]
+    fn parse_errors_span_the_token_they_stop_on() {
+        let Err(DivineError::Parse { span, .. }) = parse_program("let x = 1\nx = 2 \"three\"") else { panic!("parsed") };
+        assert_eq!(span, Some(Span { line: 2, column: 7, len: 7 }));
+        let Err(DivineError::Parse { span, .. }) = parse_program("genesis() {\n  print(1)\n") else { panic!("parsed") };
+        assert_eq!(span, Some(Span { line: 1, column: 1, len: 11 }));
+    }
+
+    #[test]
+    fn numbers_take_exponents() {
+        let program = parse_program("print(1e5, 2.5E-3, 4e+2)").unwrap();
+        let StmtKind::Expr(Expr::Call { args, .. }) = &program.statements[0].kind else { panic!("{:?}", program) };
+        assert_eq!(args, &[Expr::Number(1e5), Expr::Number(2.5e-3), Expr::Number(4e2)]);
+    }
+
+    #[test]
+    fn a_while_without_a_condition_it_can_discern_is_a_parse_error() {
+        assert_eq!(
+            error("let i = 0\nwhile (i <) { print(\"loop body\"); }"),
+            "ParseError: expected an expression at line 2, column 11 (while at line 2 needs a condition it can discern)"
+        );
+        assert_eq!(error("while (true) print(1)"), "ParseError: expected '{' to open the while block at line 1, column 14");
+        assert_eq!(error("while (true { }"), "ParseError: expected ')' to close the while condition at line 1, column 13");
+    }
+
+    #[test]
+    fn a_preach_header_not_of_the_form_name_in_expr_is_a_parse_error() {
+        for source in ["preach (x of [1, 2]) { print(x) }", "preach ([1, 2]) { }", "preach (in in [1]) { }"] {
+            assert_eq!(error(source), "ParseError: preach at line 1 must take the form preach (name in expr)", "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn preach_nests() {
+        let program = parse_program("preach (row in rows) {\n  preach (cell in row) {\n    print(cell)\n  }\n}").unwrap();
+        let StmtKind::Preach { variable, body, .. } = &program.statements[0].kind else { panic!("{:?}", program) };
+        assert_eq!(variable, "row");
+        assert!(matches!(&body[0].kind, StmtKind::Preach { variable, .. } if variable == "cell"), "{:?}", body);
+    }
+
+    #[test]
+    fn code_in_another_tongue_is_kept_opaque() {
+        for source in ["const x = 5;", "i++;", "blessing.power *= 3;", "print(`light`)", "items.map(x => x * 2)"] {
+            let program = parse_program(source).unwrap_or_else(|e| panic!("{:?}: {}", source, e));
+            assert!(matches!(program.statements[0].kind, StmtKind::Opaque { .. }), "{:?}", source);
+        }
+        let program = parse_program("genesis() {\n  print(1)\n}").unwrap();
+        assert!(matches!(&program.statements[0].kind, StmtKind::Block { header, .. } if header == "genesis()"));
+    }
+
+    #[test]
+    fn nesting_too_deep_is_a_parse_error() {
+        let nested = |depth: usize, open: &str, close: &str| format!("let x = {}1{}", open.repeat(depth), close.repeat(depth));
+        parse_program(&nested(MAX_NESTING - 1, "[", "]")).unwrap();
+        for (open, close) in [("[", "]"), ("(", ")"), ("{a: ", "}"), ("!", "")] {
+            let source = nested(600, open, close);
+            assert!(error(&source).starts_with("ParseError: nested more than 128 levels deep at line 1"), "{:?}", open);
+        }
+        let blocks = format!("{}print(1){}", "while (true) {\n".repeat(600), "}\n".repeat(600));
+        assert!(error(&blocks).starts_with("ParseError: nested more than 128 levels deep at line 129"), "{}", error(&blocks));
+    }
+}
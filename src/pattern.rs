@@ -0,0 +1,146 @@
+use std::fmt;
+
+use regex::{Regex, RegexBuilder};
+
+/// A regular expression, as the custom commandments are written in, with the
+/// syntax of the `regex` crate. Repetitions are greedy, and the first
+/// alternative that matches is preferred, as with backtracking; but a line is
+/// read only once however the pattern is written.
+#[derive(Clone)]
+pub struct Pattern(Regex);
+
+/// The most a pattern may compile to, so that `{n,m}` can't ask for more work
+/// than any line is worth.
+const SIZE_LIMIT: usize = 1 << 20;
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Self, String> {
+        RegexBuilder::new(source).size_limit(SIZE_LIMIT).build().map(Self).map_err(|e| match e {
+            regex::Error::CompiledTooBig(_) => "the pattern repeats too much to be read".to_string(),
+            e => told(&e.to_string()),
+        })
+    }
+
+    /// The byte range of the first match in `text`, if there is one.
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        self.0.find(text).map(|found| (found.start(), found.end()))
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+/// A syntax error as one line. `regex` tells it last, beneath the pattern,
+/// indented, and a caret under where it went wrong.
+fn told(error: &str) -> String {
+    let lines: Vec<&str> = error.lines().collect();
+    let Some(what) = lines.iter().rev().find_map(|line| line.strip_prefix("error: ")) else {
+        return error.to_string();
+    };
+    let caret = lines.iter().find(|line| line.trim_start().starts_with('^')).and_then(|line| line.find('^'));
+    match caret {
+        Some(column) => format!("{} at position {}", what, column.saturating_sub(4) + 1),
+        None => what.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+
+    fn find<'a>(pattern: &str, text: &'a str) -> Option<&'a str> {
+        Pattern::new(pattern).unwrap().find(text).map(|(start, end)| &text[start..end])
+    }
+
+    #[test]
+    fn literals_and_any() {
+        assert_eq!(find("kill", "we kill the process"), Some("kill"));
+        assert_eq!(find("k.ll", "we kill the process"), Some("kill"));
+        assert_eq!(find("kill", "we spare the process"), None);
+    }
+
+    #[test]
+    fn classes_and_escapes() {
+        assert_eq!(find("[a-c]+", "xxabcabd"), Some("abcab"));
+        assert_eq!(find("[^\"]+", "\"quoted\""), Some("quoted"));
+        assert_eq!(find("\\d{3}", "call 5551234"), Some("555"));
+        assert_eq!(find("\\w+\\s\\W", "hello world!"), None);
+        assert_eq!(find("\\w+\\s\\w", "hello world!"), Some("hello w"));
+        assert_eq!(find("a\\.b", "axb a.b"), Some("a.b"));
+    }
+
+    #[test]
+    fn anchors_and_boundaries() {
+        assert_eq!(find("^let", "let x"), Some("let"));
+        assert_eq!(find("^let", " let x"), None);
+        assert_eq!(find("x$", "let x"), Some("x"));
+        assert_eq!(find("\\bsin\\b", "sinful sin"), Some("sin"));
+        assert_eq!(Pattern::new("\\bsin\\b").unwrap().find("sinful sin"), Some((7, 10)));
+        assert_eq!(find("\\Bin", "in sin"), Some("in"));
+        assert_eq!(Pattern::new("\\Bin").unwrap().find("in sin"), Some((4, 6)));
+    }
+
+    #[test]
+    fn repetitions_are_greedy() {
+        assert_eq!(find("a.*z", "a-z-z-"), Some("a-z-z"));
+        assert_eq!(find("ab?c", "ac abc"), Some("ac"));
+        assert_eq!(find("a{2,3}", "aaaa"), Some("aaa"));
+        assert_eq!(find("a{2}", "aaaa"), Some("aa"));
+        assert_eq!(find("a{2,}", "aaaa"), Some("aaaa"));
+        assert_eq!(find("(ab)+", "ababa"), Some("abab"));
+    }
+
+    #[test]
+    fn the_leftmost_match_wins_then_the_first_alternative() {
+        assert_eq!(find("b|ab", "xab"), Some("ab"));
+        assert_eq!(find("a|ab", "ab"), Some("a"));
+        assert_eq!(find("(?:ab|a)c?", "abc"), Some("abc"));
+        assert_eq!(find("(a|ab)(c|bcd)", "abcd"), Some("abcd"));
+        assert_eq!(find("(ab|a)(c|bcd)", "abcd"), Some("abc"));
+    }
+
+    #[test]
+    fn empty_repetitions_end() {
+        assert_eq!(find("(a*)*b", "aab"), Some("aab"));
+        assert_eq!(find("(a?)*$", "aa"), Some("aa"));
+        assert_eq!(find("x*", "yyy"), Some(""));
+    }
+
+    #[test]
+    fn dots_match_whole_characters_and_ranges_are_in_bytes() {
+        assert_eq!(find("é.", "café au lait"), Some("é "));
+        assert_eq!(Pattern::new("au").unwrap().find("café au lait"), Some((6, 8)));
+    }
+
+    #[test]
+    fn long_lines_do_not_overflow_the_stack() {
+        let line = format!("a{}", "b".repeat(20_000));
+        assert_eq!(find("a.*z", &line), None);
+        let line = format!("a{}z", "b".repeat(20_000));
+        assert_eq!(find("a.*z", &line).map(str::len), Some(20_002));
+    }
+
+    #[test]
+    fn ambiguous_patterns_take_linear_time() {
+        let line = "a".repeat(30);
+        let started = std::time::Instant::now();
+        assert_eq!(find("(a|a)*b", &line), None);
+        assert_eq!(find("(a*)*b", &line), None);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn malformed_patterns_are_refused() {
+        let error = |pattern: &str| Pattern::new(pattern).err().unwrap();
+        assert_eq!(error("(ab"), "unclosed group at position 1");
+        assert_eq!(error("ab)"), "unopened group at position 3");
+        assert_eq!(error("[ab"), "unclosed character class at position 1");
+        assert_eq!(error("*a"), "repetition operator missing expression at position 1");
+        assert_eq!(error("a{3,1}"), "invalid repetition count range, the start must be <= the end at position 2");
+        assert_eq!(error("\\q"), "unrecognized escape sequence at position 1");
+        assert!(error("(a{1000}){1000}").starts_with("the pattern repeats too much"));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::ast::{Expr, Program, Stmt, StmtKind, Trivia};
+
+const INDENT: &str = "  ";
+
+/// Regenerates canonical source for a program.
+///
+/// Printing is lossless up to formatting: for any script `x` that parses,
+/// `parse(pretty(parse(x)))` equals `parse(x)` apart from line numbers (see
+/// [`Program::without_lines`]). Opaque statements and block headers are
+/// reproduced verbatim since the runtime doesn't know their grammar.
+pub fn pretty(program: &Program) -> String {
+    let mut out = String::new();
+    block(&mut out, &program.statements, 0);
+    out
+}
+
+/// Source as `sanctify` writes it: [`pretty`], with a blank line setting each
+/// top-level `bless` or `miracle` declaration apart from its neighbours.
+pub fn sanctified(program: &Program) -> String {
+    let mut program = program.clone();
+    let mut previous_declared = false;
+    for (index, stmt) in program.statements.iter_mut().enumerate() {
+        let declared = is_declaration(stmt);
+        if index > 0 && (declared || previous_declared) && stmt.leading.first() != Some(&Trivia::BlankLine)
+            && !matches!(stmt.kind, StmtKind::Dangling) {
+            stmt.leading.insert(0, Trivia::BlankLine);
+        }
+        previous_declared = declared;
+    }
+    pretty(&program)
+}
+
+fn is_declaration(stmt: &Stmt) -> bool {
+    match &stmt.kind {
+        StmtKind::Bless(_) => true,
+        StmtKind::Block { header, .. } => header.starts_with("bless ") || header.starts_with("miracle "),
+        _ => false,
+    }
+}
+
+/// The first line of a statement as it would be printed, without its body or
+/// trivia. Used when announcing statements as they execute.
+pub fn headline(stmt: &Stmt) -> String {
+    match &stmt.kind {
+        StmtKind::Let { name, value } => format!("let {} = {}", name, expr(value)),
+        StmtKind::Covenant { name, value } => format!("covenant {} = {}", name, expr(value)),
+        StmtKind::Assign { name, value } => format!("{} = {}", name, expr(value)),
+        StmtKind::AssignField { object, field, value } => {
+            format!("{}.{} = {}", operand(object, UNARY_PRECEDENCE + 1), field, expr(value))
+        }
+        StmtKind::Expr(value) => expr(value),
+        StmtKind::Judge { condition, .. } => format!("judge ({})", expr(condition)),
+        StmtKind::Preach { variable, iterable, .. } => format!("preach ({} in {})", variable, expr(iterable)),
+        StmtKind::While { condition, .. } => format!("while ({})", expr(condition)),
+        StmtKind::Bless(function) => format!("bless {}({})", function.name, function.params.join(", ")),
+        StmtKind::Ascend(Some(value)) => format!("ascend with {}", expr(value)),
+        StmtKind::Ascend(None) => "ascend".to_string(),
+        StmtKind::Absolution { .. } => "absolution".to_string(),
+        StmtKind::Confess { .. } => "confess".to_string(),
+        StmtKind::Import { module } => format!("import module {}", string(module)),
+        StmtKind::Selah => "selah".to_string(),
+        StmtKind::Block { header, .. } => header.clone(),
+        StmtKind::Opaque { text, .. } => text.clone(),
+        StmtKind::Dangling => String::new(),
+    }
+}
+
+fn block(out: &mut String, statements: &[Stmt], depth: usize) {
+    for stmt in statements {
+        statement(out, stmt, depth);
+    }
+}
+
+fn line(out: &mut String, depth: usize, text: &str) {
+    let text = text.trim_end();
+    if !text.is_empty() {
+        out.push_str(&INDENT.repeat(depth));
+        out.push_str(text);
+    }
+    out.push('\n');
+}
+
+fn trivia(out: &mut String, trivia: &Trivia, depth: usize) {
+    match trivia {
+        Trivia::Comment(text) | Trivia::Prayer(text) => line(out, depth, text),
+        // Prayer blocks are the faithful's own words and are never reindented
+        Trivia::PrayerBlock(lines) => {
+            for text in lines {
+                out.push_str(text);
+                out.push('\n');
+            }
+        }
+        Trivia::BlankLine => line(out, depth, ""),
+    }
+}
+
+fn statement(out: &mut String, stmt: &Stmt, depth: usize) {
+    for item in &stmt.leading {
+        trivia(out, item, depth);
+    }
+
+    if let StmtKind::Bless(function) = &stmt.kind {
+        for prophecy in &function.prophecies {
+            line(out, depth, &format!("@prophesy({})", string(prophecy)));
+        }
+    }
+
+    let head = headline(stmt);
+    let (first, body): (String, Option<&[Stmt]>) = match &stmt.kind {
+        StmtKind::Dangling => return,
+        StmtKind::Let { .. }
+        | StmtKind::Covenant { .. }
+        | StmtKind::Assign { .. }
+        | StmtKind::AssignField { .. }
+        | StmtKind::Expr(_)
+        | StmtKind::Ascend(_)
+        | StmtKind::Import { .. }
+        | StmtKind::Selah => {
+            (format!("{};", head), None)
+        }
+        StmtKind::Opaque { terminated, .. } => (if *terminated { format!("{};", head) } else { head }, None),
+        StmtKind::Block { header, body } if header.is_empty() => ("{".to_string(), Some(body)),
+        StmtKind::Bless(function) => (format!("{} {{", head), Some(&function.body)),
+        StmtKind::Judge { then_branch: body, .. }
+        | StmtKind::Preach { body, .. }
+        | StmtKind::While { body, .. }
+        | StmtKind::Absolution { body }
+        | StmtKind::Confess { body, .. }
+        | StmtKind::Block { body, .. } => (format!("{} {{", head), Some(body)),
+    };
+
+    let Some(body) = body else {
+        line(out, depth, &with_trailing(first, &stmt.trailing));
+        return;
+    };
+    line(out, depth, &first);
+    block(out, body, depth + 1);
+
+    if let StmtKind::Confess { sin, forgive, .. } = &stmt.kind {
+        line(out, depth, &format!("}} forgive ({}) {{", sin));
+        block(out, forgive, depth + 1);
+    }
+
+    let mut else_branch = match &stmt.kind {
+        StmtKind::Judge { else_branch, .. } => else_branch.as_deref(),
+        _ => None,
+    };
+    while let Some(branch) = else_branch {
+        match branch {
+            // `else judge` chains print flat rather than nesting a block per link
+            [chained @ Stmt { kind: StmtKind::Judge { condition, then_branch, else_branch: next }, .. }]
+                if chained.leading.is_empty() && chained.trailing.is_none() =>
+            {
+                line(out, depth, &format!("}} else judge ({}) {{", expr(condition)));
+                block(out, then_branch, depth + 1);
+                else_branch = next.as_deref();
+            }
+            _ => {
+                line(out, depth, "} else {");
+                block(out, branch, depth + 1);
+                else_branch = None;
+            }
+        }
+    }
+    line(out, depth, &with_trailing("}".to_string(), &stmt.trailing));
+}
+
+fn with_trailing(text: String, trailing: &Option<String>) -> String {
+    match trailing {
+        Some(comment) => format!("{} {}", text, comment),
+        None => text,
+    }
+}
+
+/// Binding strength of an operator; higher binds tighter.
+fn precedence(op: &str) -> u8 {
+    match op {
+        "||" => 1,
+        "&&" => 2,
+        "==" | "!=" => 3,
+        "<" | ">" | "<=" | ">=" => 4,
+        "+" | "-" => 5,
+        _ => 6,
+    }
+}
+
+const UNARY_PRECEDENCE: u8 = 7;
+
+fn expr_precedence(value: &Expr) -> u8 {
+    match value {
+        Expr::Binary { op, .. } => precedence(op),
+        Expr::Unary { .. } => UNARY_PRECEDENCE,
+        _ => UNARY_PRECEDENCE + 1,
+    }
+}
+
+/// Prints `value`, parenthesized when it binds looser than `min` requires.
+fn operand(value: &Expr, min: u8) -> String {
+    if expr_precedence(value) < min {
+        format!("({})", expr(value))
+    } else {
+        expr(value)
+    }
+}
+
+pub fn expr(value: &Expr) -> String {
+    match value {
+        Expr::Number(n) => n.to_string(),
+        Expr::Str(s) => string(s),
+        Expr::Bool(b) => b.to_string(),
+        Expr::Ident(name) => name.clone(),
+        Expr::Array(items) => format!("[{}]", list(items)),
+        Expr::Object(fields) if fields.is_empty() => "{}".to_string(),
+        Expr::Object(fields) => {
+            let fields = fields.iter().map(|(key, value)| format!("{}: {}", field_name(key), expr(value))).collect::<Vec<_>>();
+            format!("{{ {} }}", fields.join(", "))
+        }
+        Expr::Member { object, field } => format!("{}.{}", operand(object, UNARY_PRECEDENCE + 1), field),
+        Expr::Index { target, index } => format!("{}[{}]", operand(target, UNARY_PRECEDENCE + 1), expr(index)),
+        // Nested unary operators are parenthesized so `- -x` never prints as `--x`
+        Expr::Unary { op, operand: inner } => format!("{}{}", op, operand(inner, UNARY_PRECEDENCE + 1)),
+        Expr::Binary { op, left, right } => {
+            // Operators associate to the left, so an equal-strength right operand needs parentheses
+            let strength = precedence(op);
+            format!("{} {} {}", operand(left, strength), op, operand(right, strength + 1))
+        }
+        Expr::Call { callee, args } => format!("{}({})", callee, list(args)),
+        Expr::MethodCall { receiver, method, args } => {
+            format!("{}.{}({})", operand(receiver, UNARY_PRECEDENCE + 1), method, list(args))
+        }
+    }
+}
+
+fn list(items: &[Expr]) -> String {
+    items.iter().map(expr).collect::<Vec<_>>().join(", ")
+}
+
+/// Object keys print bare when they read as a name, and quoted otherwise.
+fn field_name(key: &str) -> String {
+    let mut chars = key.chars();
+    let is_name = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if is_name {
+        key.to_string()
+    } else {
+        string(key)
+    }
+}
+
+fn string(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            other => out.push(other),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::parser::parse_program;
+    use crate::creation::TEMPLATES;
+
+    /// Parsing what `pretty` printed gives back what was parsed, apart from lines.
+    fn assert_round_trips(name: &str, source: &str) {
+        let program = parse_program(source).unwrap_or_else(|e| panic!("{} does not parse: {}", name, e));
+        let printed = super::pretty(&program);
+        let reparsed = parse_program(&printed).unwrap_or_else(|e| panic!("{} does not parse once printed: {}", name, e));
+        assert_eq!(reparsed.without_lines(), program.without_lines(), "{} does not round-trip", name);
+        assert_eq!(super::pretty(&reparsed), printed, "printing {} again changes it", name);
+    }
+
+    fn scripts(dir: &Path, found: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap().map(Result::unwrap) {
+            let path = entry.path();
+            if path.is_dir() {
+                scripts(&path, found);
+            } else if path.extension().is_some_and(|extension| extension == "divine") {
+                found.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn the_sample_script_round_trips() {
+        assert_round_trips("test.divine", include_str!("../test.divine"));
+    }
+
+    #[test]
+    fn comments_and_prayers_round_trip() {
+        assert_round_trips("trivia.divine", include_str!("../tests/fixtures/trivia.divine"));
+        assert_round_trips("ascii_prayers.divine", include_str!("../tests/fixtures/ascii_prayers.divine"));
+    }
+
+    #[test]
+    fn every_template_round_trips() {
+        for template in TEMPLATES {
+            let dir = std::env::temp_dir().join(format!("divinepl-printer-{}-{}", template.name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            (template.generate)("corpus", &dir).unwrap_or_else(|e| panic!("the {} template: {}", template.name, e));
+
+            let mut found = Vec::new();
+            scripts(&dir, &mut found);
+            assert!(!found.is_empty(), "the {} template wrote no scripts", template.name);
+            for script in found {
+                let name = format!("{} ({} template)", script.strip_prefix(&dir).unwrap().display(), template.name);
+                assert_round_trips(&name, &fs::read_to_string(&script).unwrap());
+            }
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
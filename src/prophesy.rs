@@ -0,0 +1,688 @@
+//! `divine prophesy`: what the faithful declared in a script, the risks
+//! foretold of its code, and the doom they spell, gathered into a vision.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use colored::*;
+use rand::Rng;
+
+use crate::config::Commandments;
+use crate::error::DivineError;
+use crate::{ast, canon, environment, foretold, lexer, modules, omens, parser, vision, walk};
+use crate::{scripture_name, DivinePLRuntime};
+
+/// The area of concern a prophecy speaks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProphecyCategory {
+    Reliability,
+    Maintainability,
+    Security,
+    Deployment,
+    Design,
+    Testing,
+    Documentation,
+    Dependencies,
+}
+
+impl ProphecyCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProphecyCategory::Reliability => "reliability",
+            ProphecyCategory::Maintainability => "maintainability",
+            ProphecyCategory::Security => "security",
+            ProphecyCategory::Deployment => "deployment",
+            ProphecyCategory::Design => "design",
+            ProphecyCategory::Testing => "testing",
+            ProphecyCategory::Documentation => "documentation",
+            ProphecyCategory::Dependencies => "dependencies",
+        }
+    }
+}
+
+/// A single prophecy foretold about a script.
+pub struct Prophecy {
+    /// The script it was foretold about, as it is named in reports.
+    pub file: String,
+    pub category: ProphecyCategory,
+    pub text: String,
+    /// The line that gave the risk away, for the risks seen in the code.
+    pub line: Option<usize>,
+    /// What in the code the prophecy was grounded in, as `unvalidated-data` or
+    /// `statements:name`, which holds while the code still shows it. Insights
+    /// drawn by lot are grounded in nothing.
+    pub omen: Option<String>,
+    /// How many days the prophecy has stood in the ledger, once it was recorded there.
+    pub age: Option<i64>,
+    /// The doom it spells, as the commandments score its omen.
+    pub doom: usize,
+}
+
+impl Prophecy {
+    /// The glyph a prophecy is shown with on a terminal, by what it was
+    /// grounded in. Its text is kept plain for the reports written to files.
+    pub fn glyph(&self) -> Option<&'static str> {
+        let omen = self.omen.as_deref()?;
+        let kind = omen.split_once(':').map_or(omen, |(kind, _)| kind);
+        Some(match kind {
+            "endless-loop" => "⏳",
+            "uncovenanted-let" => "📜",
+            "unmodularized" => "📚",
+            "unvalidated-data" => "⚠️",
+            "statements" => "🔄",
+            "nesting" => "🌀",
+            "params" => "⚖️",
+            "callees" => "🕸️",
+            _ => return None,
+        })
+    }
+}
+
+/// The marks by which the faithful declare what is to come in a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    Prophesy,
+    Todo,
+    Fixme,
+    Hack,
+}
+
+impl Marker {
+    /// Every marker, in the order what it marks is listed.
+    pub const ALL: [Marker; 4] = [Marker::Prophesy, Marker::Todo, Marker::Fixme, Marker::Hack];
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Marker::Prophesy => "@prophesy",
+            Marker::Todo => "TODO",
+            Marker::Fixme => "FIXME",
+            Marker::Hack => "HACK",
+        }
+    }
+    
+    /// The marker `comment` begins with, as in `// TODO: ...` or
+    /// `// FIXME(owner): ...`, and what it says, with the owner kept.
+    fn in_comment(comment: &str) -> Option<(Marker, String)> {
+        let comment = comment.trim_start_matches(['/', '*', ' ', '\t']).trim_end_matches("*/").trim_end();
+        Self::ALL[1..].iter().find_map(|&marker| {
+            let rest = comment.strip_prefix(marker.as_str())?;
+            if !(rest.is_empty() || rest.starts_with([':', '(']) || rest.starts_with(char::is_whitespace)) {
+                return None;
+            }
+            let said = |text: &str| text.trim_start_matches(':').trim().to_string();
+            Some((marker, match rest.strip_prefix('(').and_then(|rest| rest.split_once(')')) {
+                Some((owner, text)) => format!("({}) {}", owner, said(text)),
+                None => said(rest),
+            }))
+        })
+    }
+}
+
+/// What the faithful declared in a script: a prophecy written with
+/// `@prophesy("...")`, or a TODO, FIXME or HACK comment.
+pub struct Declaration {
+    pub file: String,
+    pub line: usize,
+    pub marker: Marker,
+    /// For a prophecy, the function it decorates, as `name()`, or "no declaration".
+    pub decorated: Option<String>,
+    pub text: String,
+    /// How many days it has stood in the ledger, once it was recorded there.
+    pub age: Option<i64>,
+    /// The doom it spells: none until it has stood too long.
+    pub doom: usize,
+}
+
+/// How many scripts a project must have for `prophesy` to show which it is divining.
+const PROPHESY_PROGRESS_AFTER: usize = 20;
+
+/// The TODOs every vision gives.
+pub const DIVINE_TODOS: &[&str] = &[
+    "Add more comprehensive error confession throughout the codebase.",
+    "Implement divine logging for better visibility into runtime behavior.",
+    "Create a test suite with divine assertions to verify righteousness.",
+    "Consider implementing the Holy Trinity pattern for better code organization.",
+    "Add performance blessings to intensive operations.",
+];
+
+/// Everything prophesied about a script, to be printed or written out.
+pub struct Vision {
+    /// The script, or the project's directory, as it is named in reports.
+    pub script: String,
+    /// Whether a whole directory was prophesied about, rather than one script.
+    pub project: bool,
+    /// Every script prophesied about, as named in reports.
+    pub scripts: Vec<String>,
+    /// What was seen across the scripts of a project, such as a module that
+    /// nothing imports.
+    pub observations: Vec<String>,
+    pub declared: Vec<Declaration>,
+    pub prophecies: Vec<Prophecy>,
+    /// Whether the final revelation foresees greatness rather than a narrow
+    /// path, or None when only the declared were harvested, with --todos-only.
+    pub hopeful: Option<bool>,
+    /// The prophecies of the ledger found fulfilled by this vision.
+    pub fulfilled: Vec<foretold::Entry>,
+}
+
+impl Vision {
+    /// Each script with how many prophecies were foretold about it and the
+    /// doom of all that was prophesied about it, in order.
+    pub fn by_script(&self) -> Vec<(&str, usize, usize)> {
+        self.scripts.iter().map(|script| {
+            let prophecies: Vec<&Prophecy> = self.prophecies.iter().filter(|prophecy| prophecy.file == *script).collect();
+            let doom = prophecies.iter().map(|prophecy| prophecy.doom).sum::<usize>()
+                + self.declared.iter().filter(|declaration| declaration.file == *script).map(|declaration| declaration.doom).sum::<usize>();
+            (script.as_str(), prophecies.len(), doom)
+        }).collect()
+    }
+    
+    /// The three scripts of the most doom, most first, leaving out those of none.
+    pub fn most_doomed(&self) -> Vec<(&str, usize)> {
+        let mut doomed: Vec<(&str, usize)> = self.by_script().into_iter()
+            .filter(|(_, _, doom)| *doom > 0)
+            .map(|(script, _, doom)| (script, doom))
+            .collect();
+        doomed.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        doomed.truncate(3);
+        doomed
+    }
+    
+    /// The doom of everything prophesied.
+    pub fn doom(&self) -> usize {
+        self.declared.iter().map(|declaration| declaration.doom).sum::<usize>()
+            + self.prophecies.iter().map(|prophecy| prophecy.doom).sum::<usize>()
+    }
+    
+    /// The final revelation, unless nothing was divined.
+    pub fn revelation(&self) -> Option<&'static str> {
+        Some(if self.hopeful? {
+            "This codebase is destined for divine greatness, but must overcome trials of complexity and technical debt. Stay true to the righteous path of clean code and divine principles."
+        } else {
+            "Beware! This codebase walks a narrow path between salvation and damnation. Major restructuring will be required before reaching the promised land of production readiness."
+        })
+    }
+}
+
+/// Everything declared in the script at `path`, in the order it was
+/// written: its `@prophesy` annotations and its TODO, FIXME and HACK comments.
+fn harvest(runtime: &DivinePLRuntime, path: &Path) -> Result<Vec<Declaration>, DivineError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| DivineError::Io(format!("Failed to read {} for prophecy: {}", path.display(), e)))?;
+    let file = scripture_name(path);
+    // A prayer left open doesn't hide what was declared before it
+    let (statements, _) = runtime.scan_script(&content);
+    let mut declared: Vec<Declaration> = statements.iter().enumerate()
+        .filter_map(|(i, stmt)| {
+            let decorated = statements[i + 1..].iter()
+                .find(|next| next.prophecy.is_none())
+                .and_then(|next| DivinePLRuntime::declared_function(&next.content))
+                .map_or_else(|| "no declaration".to_string(), |name| format!("{}()", name));
+            Some(Declaration {
+                file: file.clone(),
+                line: stmt.line_num,
+                marker: Marker::Prophesy,
+                decorated: Some(decorated),
+                text: stmt.prophecy.clone()?,
+                age: None,
+                doom: 0,
+            })
+        })
+        .collect();
+    
+    // Markers are looked for in comments alone, so one in a string is not taken
+    for token in lexer::tokenize(lexer::without_bom(&content)) {
+        let lexer::TokenKind::Comment(comment) = &token.kind else { continue };
+        for (offset, line) in comment.lines().enumerate() {
+            if let Some((marker, text)) = Marker::in_comment(line) {
+                declared.push(Declaration { file: file.clone(), line: token.line + offset, marker, decorated: None, text, age: None, doom: 0 });
+            }
+        }
+    }
+    declared.sort_by_key(|declaration| declaration.line);
+    Ok(declared)
+}
+
+/// Prophesies about the script at `path`, or every script under it when
+/// it is a directory, printing the vision and writing it to `output` too
+/// when given, as Markdown or JSON by its extension. With --quiet, a vision
+/// written to a file is not printed. With `todos_only`, nothing is divined:
+/// only what was declared is harvested. The vision is weighed against the
+/// prophecies kept in `ledger`, and recorded there.
+pub fn prophesy_code(runtime: &DivinePLRuntime, path: &Path, output: Option<&Path>, todos_only: bool, ledger: &Path) -> Result<Vision, DivineError> {
+    let format = output.map(vision::Format::for_path).transpose()?;
+    let project = path.is_dir();
+    let scripts = if project {
+        let (scripts, skipped) = walk::canonical_scripts(path)?;
+        canon::report_skipped(skipped);
+        if scripts.is_empty() {
+            return Err(format!("No scripts (*.divine, *.dpl) were found in {}", path.display()).into());
+        }
+        scripts
+    } else {
+        vec![path.to_path_buf()]
+    };
+    let mut declared = Vec::new();
+    for script in &scripts {
+        declared.extend(harvest(runtime, script)?);
+    }
+    let printed = !(runtime.quiet() && output.is_some());
+    
+    let mut prophecies = Vec::new();
+    let mut observations = Vec::new();
+    let hopeful = if todos_only {
+        None
+    } else {
+        prophecies = if project { divine_project(runtime, &scripts) } else { divine(runtime, path)? };
+        if project {
+            observations = observe(&scripts);
+        }
+        draw_insights(runtime, &mut prophecies, &scripture_name(path));
+        if !runtime.quiet() {
+            println!("{}", "🔮 Entering prophetic vision... 🔮".bright_magenta());
+            runtime.linger(1000);
+        }
+        Some(runtime.fortune().gen_bool(0.7))
+    };
+    let mut vision = Vision {
+        script: scripture_name(path),
+        project,
+        scripts: scripts.iter().map(|script| scripture_name(script)).collect(),
+        observations,
+        declared,
+        prophecies,
+        hopeful,
+        fulfilled: Vec::new(),
+    };
+    reckon(runtime, &mut vision, &scripts, ledger)?;
+    let doom = omens::Doom::from(Commandments::for_script(path)?.as_ref());
+    for declaration in &mut vision.declared {
+        declaration.doom = doom.of_declared(declaration.age);
+    }
+    for prophecy in &mut vision.prophecies {
+        prophecy.doom = prophecy.omen.as_deref().map_or(0, |omen| doom.of(omen));
+    }
+    
+    if let (Some(output), Some(format)) = (output, format) {
+        let report = match format {
+            vision::Format::Markdown => vision::markdown(&vision),
+            vision::Format::Json => format!("{}\n", serde_json::to_string(&vision::json(&vision)).expect("a vision is always serializable")),
+        };
+        fs::write(output, report)
+            .map_err(|e| DivineError::Io(format!("Failed to write the prophecies to {}: {}", output.display(), e)))?;
+    }
+    
+    if runtime.porcelain {
+        for declaration in &vision.declared {
+            println!("declared {} {}:{} {}", declaration.marker.as_str(), declaration.file, declaration.line, declaration.text);
+        }
+        for (i, prophecy) in vision.prophecies.iter().enumerate() {
+            println!("prophecy {} {}", i + 1, prophecy.text.trim_start_matches(|c: char| !c.is_alphanumeric()));
+        }
+        if vision.project {
+            for (script, prophecies, doom) in vision.by_script() {
+                println!("script {} {} {}", script, prophecies, doom);
+            }
+            for observation in &vision.observations {
+                println!("observation {}", observation);
+            }
+        }
+        println!("doom {}", vision.doom());
+        return Ok(vision);
+    }
+    if !printed {
+        return Ok(vision);
+    }
+    
+    if !vision.fulfilled.is_empty() {
+        println!("{}", "\n🕊️ Prophecies fulfilled since last vision 🕊️".underline().bright_green());
+        for entry in &vision.fulfilled {
+            println!("{} {} {}", entry.script, entry.text.bright_green(), format!("(foretold {})", entry.foretold.format("%Y-%m-%d")).dimmed());
+        }
+    }
+    
+    if !vision.declared.is_empty() {
+        println!("{}", "\n📜 Declared by the faithful 📜".underline().bright_magenta());
+        for declared in vision.declared.chunk_by(|a, b| a.file == b.file) {
+            let file = &declared[0].file;
+            println!("{}", format!("📄 {}", file).bold());
+            for marker in Marker::ALL {
+                let marked: Vec<&Declaration> = declared.iter().filter(|declaration| declaration.marker == marker).collect();
+                if marked.is_empty() {
+                    continue;
+                }
+                println!("  {} ({})", marker.as_str().bright_yellow(), marked.len());
+                for declaration in marked {
+                    let decorated = declaration.decorated.as_ref().map_or(String::new(), |decorated| format!("[{}] ", decorated));
+                    let age = match (declaration.age, declaration.doom) {
+                        (Some(age), 0) => format!(" ({})", foretold::days(age)),
+                        (Some(age), doom) => format!(" ({}, doom {})", foretold::days(age), doom),
+                        (None, _) => String::new(),
+                    };
+                    println!("    {}:{} {}{}{}", file, declaration.line, decorated.dimmed(), declaration.text.bright_cyan(), age.dimmed());
+                }
+            }
+        }
+    } else if todos_only {
+        println!("{}", "🕊️ Nothing was declared: no @prophesy, TODO, FIXME or HACK was found.".bright_green());
+    }
+    let Some(revelation) = vision.revelation() else {
+        proclaim_doom(vision.doom());
+        return Ok(vision);
+    };
+    
+    // Display the prophecies
+    let foretell = |i: usize, prophecy: &Prophecy, indent: &str| {
+        let age = prophecy.age.map_or_else(String::new, |age| format!(" ({})", foretold::days(age)));
+        let doom = if prophecy.doom > 0 { format!(", doom {}", prophecy.doom) } else { String::new() };
+        let glyph = prophecy.glyph().map_or_else(String::new, |glyph| format!("{} ", glyph));
+        println!("{}{}. {}{} {}", indent, i+1, glyph, prophecy.text.bright_cyan(), format!("[{}{}]{}", prophecy.category.as_str(), doom, age).dimmed());
+        runtime.linger(300);
+    };
+    if !vision.project {
+        println!("{}", "\n📜 DIVINE PROPHECIES FOR THIS CODE 📜".underline().bright_magenta());
+        for (i, prophecy) in vision.prophecies.iter().enumerate() {
+            foretell(i, prophecy, "");
+        }
+    } else {
+        println!("{}", "\n📜 DIVINE PROPHECIES FOR THIS PROJECT 📜".underline().bright_magenta());
+        let mut i = 0;
+        for prophecies in vision.prophecies.chunk_by(|a, b| a.file == b.file) {
+            // Insights drawn by lot are foretold about the project as a whole
+            if prophecies[0].file == vision.script {
+                println!("{}", "✨ For the whole project".bold());
+            } else {
+                println!("{}", format!("📄 {}", prophecies[0].file).bold());
+            }
+            for prophecy in prophecies {
+                foretell(i, prophecy, "  ");
+                i += 1;
+            }
+        }
+        
+        println!("{}", "\n📊 PROPHECIES BY SCRIPT 📊".underline().bright_blue());
+        let by_script = vision.by_script();
+        let width = by_script.iter().map(|(script, _, _)| script.chars().count()).max().unwrap_or(0).max("Script".len());
+        println!("{}", format!("  {:<width$}  {:>10}  {:>4}", "Script", "Prophecies", "Doom", width = width).bold());
+        for (script, prophecies, doom) in by_script {
+            println!("  {:<width$}  {:>10}  {:>4}", script, prophecies, doom, width = width);
+        }
+        
+        let most_doomed = vision.most_doomed();
+        if !most_doomed.is_empty() {
+            println!("{}", "\n☠️ MOST DOOMED ☠️".underline().bright_red());
+            for (i, (script, doom)) in most_doomed.iter().enumerate() {
+                println!("{}. {} {}", i + 1, script, format!("(doom {})", doom).dimmed());
+            }
+        }
+        
+        if !vision.observations.is_empty() {
+            println!("{}", "\n🕸️ ACROSS THE SCRIPTS 🕸️".underline().bright_magenta());
+            for observation in &vision.observations {
+                println!("- {}", observation.bright_cyan());
+            }
+        }
+    }
+    
+    // Generate divine TODOs
+    println!("{}", "\n📋 DIVINE TODOs 📋".underline().bright_yellow());
+    for (i, todo) in DIVINE_TODOS.iter().enumerate() {
+        println!("{}. {}", i + 1, todo);
+    }
+    
+    // Final revelation
+    println!("{}", "\n⚡ FINAL REVELATION ⚡".bright_yellow());
+    if vision.hopeful == Some(true) {
+        println!("{}", revelation.bright_green());
+    } else {
+        println!("{}", revelation.yellow());
+    }
+    proclaim_doom(vision.doom());
+    
+    Ok(vision)
+}
+
+/// Prints the doom a vision spells, where it can't be missed.
+fn proclaim_doom(doom: usize) {
+    let proclamation = format!("\n☠️ DOOM: {} ☠️", doom).bold();
+    if doom == 0 {
+        println!("{}", proclamation.bright_green());
+    } else {
+        println!("{}", proclamation.bright_red());
+    }
+}
+
+/// Weighs `vision`, made of `scripts`, against the prophecies kept in the
+/// ledger at `path`: what it no longer foretells is fulfilled, and what it
+/// foretells is given its age, or recorded when it is new.
+fn reckon(runtime: &DivinePLRuntime, vision: &mut Vision, scripts: &[PathBuf], path: &Path) -> Result<(), DivineError> {
+    let mut ledger = foretold::Ledger::load(path)?;
+    let names: HashMap<String, String> = scripts.iter()
+        .map(|script| (scripture_name(script), foretold::Ledger::name(path, script)))
+        .collect();
+    let name = |file: &str| names.get(file).cloned().unwrap_or_else(|| file.to_string());
+    
+    let declared = vision.declared.iter().map(|declaration| foretold::Sign {
+        script: name(&declaration.file),
+        kind: foretold::Kind::Declared,
+        omen: format!("{}:{}", declaration.marker.as_str(), declaration.text),
+        text: declaration.text.clone(),
+    });
+    // Insights drawn by lot are grounded in nothing that can come true
+    let divined = vision.prophecies.iter().filter_map(|prophecy| Some(foretold::Sign {
+        script: name(&prophecy.file),
+        kind: foretold::Kind::Divined,
+        omen: prophecy.omen.clone()?,
+        text: prophecy.text.clone(),
+    }));
+    let signs: Vec<foretold::Sign> = declared.chain(divined).collect();
+    let names: Vec<String> = scripts.iter().map(|script| foretold::Ledger::name(path, script)).collect();
+    let (ages, fulfilled) = ledger.reckon(&names, vision.hopeful.is_some(), &signs, runtime.today());
+    ledger.save(path)?;
+    
+    let mut ages = ages.into_iter();
+    for declaration in &mut vision.declared {
+        declaration.age = ages.next();
+    }
+    for prophecy in vision.prophecies.iter_mut().filter(|prophecy| prophecy.omen.is_some()) {
+        prophecy.age = ages.next();
+    }
+    vision.fulfilled = fulfilled;
+    Ok(())
+}
+
+/// Foretells the future of every script of a project. A script that can't
+/// be divined is passed over with a warning rather than ending the vision.
+/// When there are many, which is being divined is shown as it is.
+fn divine_project(runtime: &DivinePLRuntime, scripts: &[PathBuf]) -> Vec<Prophecy> {
+    let progress = scripts.len() > PROPHESY_PROGRESS_AFTER && !runtime.quiet() && io::stderr().is_terminal();
+    let mut prophecies = Vec::new();
+    for (i, script) in scripts.iter().enumerate() {
+        if progress {
+            eprint!("\r\x1b[K{}", environment::for_stderr(format!("🔮 Divining {}/{}: {}", i + 1, scripts.len(), script.display()).dimmed()));
+        }
+        match divine(runtime, script) {
+            Ok(foretold) => prophecies.extend(foretold),
+            Err(e) => {
+                if progress {
+                    eprint!("\r\x1b[K");
+                }
+                eprintln!("{}", environment::for_stderr(format!("⚠️ {} could not be divined: {}", script.display(), e).yellow()));
+            }
+        }
+    }
+    if progress {
+        eprint!("\r\x1b[K");
+    }
+    prophecies
+}
+
+/// What is seen across the scripts of a project: modules that nothing
+/// imports, modules imported that can't be found, and functions blessed in
+/// a module and again elsewhere. The imports of each script are resolved
+/// once, into a graph every observation is read from.
+fn observe(scripts: &[PathBuf]) -> Vec<String> {
+    let programs: Vec<(&PathBuf, ast::Program)> = scripts.iter()
+        .filter_map(|script| Some((script, parser::parse_program(&fs::read_to_string(script).ok()?).ok()?)))
+        .collect();
+    let graph: Vec<(&PathBuf, Vec<Result<PathBuf, String>>)> = programs.iter()
+        .map(|(script, program)| (*script, modules::direct_imports(program, script)))
+        .collect();
+    let imported: HashSet<&PathBuf> = graph.iter().flat_map(|(_, imports)| imports.iter().flatten()).collect();
+    let mut observations = Vec::new();
+    
+    for (script, program) in &programs {
+        let canonical = fs::canonicalize(script).unwrap_or_else(|_| script.to_path_buf());
+        if modules::is_module(script) && !program.functions.is_empty() && !imported.contains(&canonical) {
+            observations.push(format!("{} defines functions never imported anywhere", scripture_name(script)));
+        }
+    }
+    for (script, imports) in &graph {
+        for name in imports.iter().filter_map(|import| import.as_ref().err()) {
+            observations.push(format!("{} imports module \"{}\", which is nowhere to be found", scripture_name(script), name));
+        }
+    }
+    
+    // A module's function blessed again elsewhere is a schism waiting for the script that imports both
+    let mut blessed: HashMap<&str, Vec<&PathBuf>> = HashMap::new();
+    for (script, program) in &programs {
+        for name in program.functions.keys() {
+            blessed.entry(name.as_str()).or_default().push(script);
+        }
+    }
+    let mut schisms: Vec<(&str, Vec<&PathBuf>)> = blessed.into_iter()
+        .filter(|(_, scripts)| scripts.len() > 1 && scripts.iter().any(|script| modules::is_module(script)))
+        .collect();
+    schisms.sort();
+    for (name, scripts) in schisms {
+        let scripts: Vec<String> = scripts.iter().map(|script| scripture_name(script)).collect();
+        observations.push(format!("{}() is blessed in {}; a script importing both shall fall into schism", name, scripts.join(" and ")));
+    }
+    observations
+}
+
+/// Foretells the future of the script at `path`: the risks seen in its
+/// code and in the measures of its functions.
+fn divine(runtime: &DivinePLRuntime, path: &Path) -> Result<Vec<Prophecy>, DivineError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| DivineError::Io(format!("Failed to read the script for prophecy: {}", e)))?;
+    let file = scripture_name(path);
+    
+    let statements = runtime.parse_script(&content)?;
+    let program = parser::parse_program(&content).ok();
+    // Prophecies are read in the code alone, not in comments, prayers or strings
+    let code = statements.iter().map(|s| s.code.as_str()).collect::<Vec<_>>().join("\n");
+    // The line a risk was seen on: the first whose code shows it
+    let seen = |sign: &str| statements.iter().find(|s| s.code.contains(sign)).map(|s| s.line_num);
+    
+    // Analyze for potential future issues
+    let mut prophesies = Vec::new();
+    
+    // Pattern matching for common issues
+    if code.contains("while") && !code.contains("break") {
+        prophesies.push(Prophecy {
+            file: file.clone(),
+            category: ProphecyCategory::Reliability,
+            text: "Infinite loop risk detected. Add a divine exit condition to prevent eternal execution.".to_string(),
+            line: seen("while"),
+            omen: Some("endless-loop".to_string()),
+            age: None,
+            doom: 0,
+        });
+    }
+    
+    if let Some((name, line)) = program.as_ref().and_then(omens::uncovenanted) {
+        prophesies.push(Prophecy {
+            file: file.clone(),
+            category: ProphecyCategory::Maintainability,
+            text: format!("Future maintainers will appreciate constants such as '{}' declared as 'covenant', since it never changes.", name),
+            line: Some(line),
+            omen: Some("uncovenanted-let".to_string()),
+            age: None,
+            doom: 0,
+        });
+    }
+    
+    if content.lines().count() > 100 && !code.contains("module") {
+        prophesies.push(Prophecy {
+            file: file.clone(),
+            category: ProphecyCategory::Maintainability,
+            text: "As this code grows, consider divine modularization through the Holy Trinity pattern.".to_string(),
+            line: None,
+            omen: Some("unmodularized".to_string()),
+            age: None,
+            doom: 0,
+        });
+    }
+    
+    if code.contains("data") && !code.contains("validate") {
+        prophesies.push(Prophecy {
+            file: file.clone(),
+            category: ProphecyCategory::Security,
+            text: "Future security concerns: add divine validation to all data inputs to prevent unholy injections.".to_string(),
+            line: seen("data"),
+            omen: Some("unvalidated-data".to_string()),
+            age: None,
+            doom: 0,
+        });
+    }
+    
+    // Functions are measured whole, and prophesied about when they outgrow the thresholds
+    if let Some(program) = &program {
+        let thresholds = omens::Thresholds::from(Commandments::for_script(path)?.as_ref());
+        for measure in omens::measure(program) {
+            let mut foretell = |category, measured: &str, text: String| prophesies.push(Prophecy {
+                file: file.clone(),
+                category,
+                text,
+                line: Some(measure.line),
+                omen: Some(format!("{}:{}", measured, measure.name)),
+                age: None,
+                doom: 0,
+            });
+            let function = format!("Function {} (line {})", measure.name, measure.line);
+            if measure.statements > thresholds.statements {
+                foretell(ProphecyCategory::Maintainability, "statements", format!(
+                    "{} spans {} statements; a great refactoring is foretold.", function, measure.statements));
+            }
+            if measure.nesting > thresholds.nesting {
+                foretell(ProphecyCategory::Maintainability, "nesting", format!(
+                    "{} nests {} blocks deep; those who come after shall lose their way in it.", function, measure.nesting));
+            }
+            if measure.params > thresholds.params {
+                foretell(ProphecyCategory::Design, "params", format!(
+                    "{} takes {} parameters; its callers shall confuse their order.", function, measure.params));
+            }
+            if measure.callees > thresholds.callees {
+                foretell(ProphecyCategory::Reliability, "callees", format!(
+                    "{} calls {} different functions; a change to any of them shall shake it.", function, measure.callees));
+            }
+        }
+    }
+    
+    Ok(prophesies)
+}
+
+/// Makes `prophecies`, foretold about `file`, up to three with insights
+/// drawn by lot, for code that gives little to prophesy about.
+fn draw_insights(runtime: &DivinePLRuntime, prophecies: &mut Vec<Prophecy>, file: &str) {
+    let project_insights = [
+        (ProphecyCategory::Deployment, "The path of deployment shall be fraught with environmental differences. Prepare with containerization."),
+        (ProphecyCategory::Maintainability, "A great refactoring shall be needed by the third version. Plan accordingly."),
+        (ProphecyCategory::Security, "Security vulnerabilities shall manifest if input validation is neglected."),
+        (ProphecyCategory::Design, "The user interface shall require redesign as requirements evolve."),
+        (ProphecyCategory::Testing, "Test coverage will prove insufficient in areas not yet considered."),
+        (ProphecyCategory::Maintainability, "Technical debt shall accumulate in the areas of error handling."),
+        (ProphecyCategory::Documentation, "Documentation shall become outdated unless integrated with the development process."),
+        (ProphecyCategory::Dependencies, "Dependencies shall age and require updating, bringing both blessings and trials."),
+    ];
+    
+    // Insights are drawn by lot only to make up three prophecies when the code gives fewer
+    for _ in prophecies.len()..3 {
+        let (category, text) = project_insights[runtime.fortune().gen_range(0..project_insights.len())];
+        prophecies.push(Prophecy { file: file.to_string(), category, text: text.to_string(), line: None, omen: None, age: None, doom: 0 });
+    }
+}
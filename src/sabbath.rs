@@ -0,0 +1,229 @@
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::config::Commandments;
+use serde_json::Value;
+
+/// The days `sabbath_day` may name, Monday first.
+pub const DAYS: &[&str] = &["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Which day is the Sabbath, and in which timezone that day is reckoned.
+pub struct Sabbath {
+    pub day: Weekday,
+    pub timezone: Timezone,
+}
+
+pub enum Timezone {
+    Local,
+    Utc,
+    /// A zone of the IANA database, such as `America/New_York`.
+    Named(Tz),
+}
+
+impl Default for Sabbath {
+    fn default() -> Self {
+        Self { day: Weekday::Sun, timezone: Timezone::Local }
+    }
+}
+
+impl Sabbath {
+    /// The Sabbath as --sabbath-day and --sabbath-timezone give it, else as the
+    /// commandments' `sabbath_day` and `sabbath_timezone` do, else Sunday in
+    /// local time.
+    pub fn resolve(commandments: Option<&Commandments>, day: Option<&str>, timezone: Option<&str>) -> Result<Self, String> {
+        let setting = |key: &str| commandments.and_then(|commandments| commandments.settings.get(key)).and_then(Value::as_str);
+        let day = match (day, setting("sabbath_day")) {
+            (Some(day), _) => parse_day(day, "--sabbath-day")?,
+            (None, Some(day)) => parse_day(day, "sabbath_day")?,
+            (None, None) => Weekday::Sun,
+        };
+        let timezone = match (timezone, setting("sabbath_timezone")) {
+            (Some(timezone), _) => Timezone::parse(timezone, "--sabbath-timezone")?,
+            (None, Some(timezone)) => Timezone::parse(timezone, "sabbath_timezone")?,
+            (None, None) => Timezone::Local,
+        };
+        Ok(Self { day, timezone })
+    }
+
+    /// Whether `now` falls on the Sabbath.
+    pub fn is_on(&self, now: DateTime<Utc>) -> bool {
+        self.timezone.weekday_at(now) == self.day
+    }
+
+    /// How long from `now` until the day `now` falls on ends.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Duration {
+        self.timezone.midnight_after(now) - now
+    }
+}
+
+impl fmt::Display for Sabbath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} in {}", day_name(self.day), self.timezone)
+    }
+}
+
+impl Timezone {
+    /// The timezone `text` names, as given by `origin`.
+    fn parse(text: &str, origin: &str) -> Result<Self, String> {
+        match text.to_ascii_lowercase().as_str() {
+            "local" => return Ok(Timezone::Local),
+            "utc" => return Ok(Timezone::Utc),
+            _ => {}
+        }
+        text.parse().map(Timezone::Named).map_err(|_| format!(
+            "{} must be \"local\", \"utc\" or an IANA timezone such as \"America/New_York\", but is '{}'", origin, text
+        ))
+    }
+
+    /// The weekday `instant` falls on in this timezone.
+    pub fn weekday_at(&self, instant: DateTime<Utc>) -> Weekday {
+        self.date_at(instant).weekday()
+    }
+
+    /// The date `instant` falls on in this timezone.
+    pub fn date_at(&self, instant: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Timezone::Local => instant.with_timezone(&Local).date_naive(),
+            Timezone::Utc => instant.date_naive(),
+            Timezone::Named(zone) => instant.with_timezone(zone).date_naive(),
+        }
+    }
+
+    /// The instant the clocks in this timezone show `time`.
+    pub fn at(&self, time: NaiveDateTime) -> DateTime<Utc> {
+        match self {
+            Timezone::Local => at(&Local, time),
+            Timezone::Utc => at(&Utc, time),
+            Timezone::Named(zone) => at(zone, time),
+        }
+    }
+
+    /// The first midnight in this timezone after `instant`: when the day it
+    /// falls on ends.
+    pub fn midnight_after(&self, instant: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Timezone::Local => midnight_after(&Local, instant),
+            Timezone::Utc => midnight_after(&Utc, instant),
+            Timezone::Named(zone) => midnight_after(zone, instant),
+        }
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Timezone::Local => f.write_str("local time"),
+            Timezone::Utc => f.write_str("UTC"),
+            Timezone::Named(zone) => f.write_str(zone.name()),
+        }
+    }
+}
+
+/// The instant the clocks in `zone` show `time`; a time a change of clocks
+/// skips is taken as the same time in UTC.
+fn at<Z: TimeZone>(zone: &Z, time: NaiveDateTime) -> DateTime<Utc> {
+    zone.from_local_datetime(&time).earliest()
+        .map_or_else(|| time.and_utc(), |time| time.with_timezone(&Utc))
+}
+
+/// The first midnight in `zone` after `instant`.
+fn midnight_after<Z: TimeZone>(zone: &Z, instant: DateTime<Utc>) -> DateTime<Utc> {
+    let day = instant.with_timezone(zone).date_naive();
+    let midnight = day.succ_opt().unwrap_or(day).and_hms_opt(0, 0, 0).unwrap_or_default();
+    // Where a change of clocks skips midnight, the day ends when the clocks resume
+    (0..=3).find_map(|hour| zone.from_local_datetime(&(midnight + Duration::hours(hour))).earliest())
+        .map_or_else(|| midnight.and_utc(), |midnight| midnight.with_timezone(&Utc))
+}
+
+/// A day given as `mon` to `sun`, or in full, in any case, by `origin`.
+fn parse_day(text: &str, origin: &str) -> Result<Weekday, String> {
+    text.trim().parse().map_err(|_| format!("{} must be one of {}, but is '{}'", origin, DAYS.join(", "), text))
+}
+
+/// A span of time as a person would say it: "3 hours and 12 minutes". Any
+/// part of a minute counts as one, so it is never said to be no time at all.
+pub fn spoken(duration: Duration) -> String {
+    let minutes = (duration.num_seconds() + 59) / 60;
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    let unit = |count: i64, unit: &str| format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" });
+    match (hours, minutes) {
+        (0, _) => unit(minutes.max(1), "minute"),
+        (_, 0) => unit(hours, "hour"),
+        _ => format!("{} and {}", unit(hours, "hour"), unit(minutes, "minute")),
+    }
+}
+
+pub fn day_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant(text: &str) -> DateTime<Utc> {
+        text.parse().unwrap()
+    }
+
+    fn zone(name: &str) -> Timezone {
+        Timezone::parse(name, "sabbath_timezone").unwrap()
+    }
+
+    #[test]
+    fn the_day_is_reckoned_in_the_sabbaths_own_zone() {
+        // Late on Saturday in New York is already Sunday in UTC, and Sunday afternoon in Auckland
+        let now = instant("2024-03-10T03:30:00Z");
+        assert_eq!(zone("utc").weekday_at(now), Weekday::Sun);
+        assert_eq!(zone("America/New_York").weekday_at(now), Weekday::Sat);
+        assert_eq!(zone("Pacific/Auckland").weekday_at(now), Weekday::Sun);
+
+        let saturday_in_new_york = Sabbath { day: Weekday::Sat, timezone: zone("America/New_York") };
+        assert!(saturday_in_new_york.is_on(now));
+        assert!(!Sabbath { day: Weekday::Sat, timezone: zone("utc") }.is_on(now));
+        assert_eq!(saturday_in_new_york.remaining(now), Duration::minutes(90));
+    }
+
+    #[test]
+    fn a_day_whose_midnight_the_clocks_skip_ends_when_they_resume() {
+        // Havana springs forward at midnight, from 00:00 CST to 01:00 CDT
+        let havana = zone("America/Havana");
+        assert_eq!(havana.midnight_after(instant("2024-03-09T17:00:00Z")), instant("2024-03-10T05:00:00Z"));
+        assert_eq!(havana.at("2024-03-09T12:00:00".parse().unwrap()), instant("2024-03-09T17:00:00Z"));
+    }
+
+    #[test]
+    fn the_countdown_is_spoken_to_the_minute() {
+        let sunday = Sabbath { day: Weekday::Sun, timezone: zone("utc") };
+        let remaining = |now: &str| spoken(sunday.remaining(instant(now)));
+        assert_eq!(remaining("2024-03-10T20:48:00Z"), "3 hours and 12 minutes");
+        assert_eq!(remaining("2024-03-10T23:00:00Z"), "1 hour");
+        assert_eq!(remaining("2024-03-10T23:50:00Z"), "10 minutes");
+        assert_eq!(remaining("2024-03-10T23:59:01Z"), "1 minute");
+        assert_eq!(remaining("2024-03-10T23:59:59Z"), "1 minute");
+        // At the stroke of midnight the whole day lies ahead
+        assert!(sunday.is_on(instant("2024-03-10T00:00:00Z")));
+        assert_eq!(remaining("2024-03-10T00:00:00Z"), "24 hours");
+        assert!(!sunday.is_on(instant("2024-03-11T00:00:00Z")));
+    }
+
+    #[test]
+    fn zones_are_the_iana_names_local_or_utc() {
+        assert_eq!(zone("Europe/London").to_string(), "Europe/London");
+        assert!(matches!(zone("UTC"), Timezone::Utc));
+        assert!(matches!(zone("Local"), Timezone::Local));
+        for name in ["Mars/Olympus_Mons", "../etc/passwd", ""] {
+            let error = Timezone::parse(name, "--sabbath-timezone").err().unwrap();
+            assert!(error.starts_with("--sabbath-timezone must be \"local\", \"utc\" or an IANA timezone"), "{}", error);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use serde_json::{json, Value};
+use crate::confession::Confession;
+use crate::{Sin, SinSeverity, RULES};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The report of `confess --format sarif`: a SARIF 2.1.0 log with one run, a
+/// rule for each rule or custom commandment that was broken and a result for
+/// every sin.
+pub fn log(confessions: &[Confession]) -> Value {
+    let sins = || confessions.iter().flat_map(|confession| confession.sins.iter().map(move |sin| (confession, sin)));
+    // The rules come first, in their order, then the custom commandments
+    let mut broken: Vec<&Sin> = sins().map(|(_, sin)| sin).collect();
+    broken.sort_by_key(|sin| (RULES.iter().position(|rule| rule.name == &*sin.rule).unwrap_or(RULES.len()), sin.rule.clone()));
+    broken.dedup_by(|a, b| a.rule == b.rule);
+
+    let rules = broken.iter()
+        .map(|sin| match RULES.iter().find(|rule| rule.name == &*sin.rule) {
+            Some(rule) => json!({
+                "id": rule_id(sin),
+                "name": rule.name,
+                "shortDescription": { "text": rule.description },
+                "help": { "text": rule.suggestion },
+            }),
+            None => json!({
+                "id": rule_id(sin),
+                "shortDescription": { "text": sin.message },
+            }),
+        })
+        .collect::<Vec<_>>();
+
+    let results = sins()
+        .map(|(confession, sin)| json!({
+            "ruleId": rule_id(sin),
+            "ruleIndex": broken.iter().position(|broken| broken.rule == sin.rule).unwrap_or(0),
+            "level": match sin.severity {
+                SinSeverity::Venial => "warning",
+                SinSeverity::Mortal => "error",
+            },
+            "message": { "text": sin.message },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": uri(&confession.file) },
+                    "region": { "startLine": sin.line_num },
+                },
+            }],
+        }))
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "divinepl-confess",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// The rule a sin broke, as SARIF names it: `DPL001-unblessed-function`, or
+/// the identifier of a custom commandment.
+fn rule_id(sin: &Sin) -> String {
+    match RULES.iter().find(|rule| rule.name == &*sin.rule) {
+        Some(rule) => format!("{}-{}", rule.id, rule.name),
+        None => sin.rule.to_string(),
+    }
+}
+
+/// A script's name as a relative URI.
+fn uri(file: &str) -> String {
+    file.replace('\\', "/").trim_start_matches("./").to_string()
+}
@@ -0,0 +1,54 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use colored::*;
+
+/// A file opened by a script through `scroll.open(path)`.
+///
+/// Scrolls are sealed deterministically: explicitly via `seal()`, by an enclosing
+/// `absolution` block, or implicitly when the last binding holding them goes out
+/// of scope — including when a sin unwinds past it.
+#[derive(Debug)]
+pub struct Scroll {
+    pub path: String,
+    writer: Option<BufWriter<File>>,
+    /// Whether to announce scrolls that had to seal themselves.
+    announce: bool,
+}
+
+impl Scroll {
+    pub fn open(path: &str, announce: bool) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to unroll the scroll {}: {}", path, e))?;
+        Ok(Self { path: path.to_string(), writer: Some(BufWriter::new(file)), announce })
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.writer.is_none()
+    }
+
+    pub fn inscribe(&mut self, text: &str) -> Result<(), String> {
+        let writer = self.writer.as_mut()
+            .ok_or_else(|| format!("SealedScrollError: the scroll {} has already been sealed", self.path))?;
+        writeln!(writer, "{}", text).map_err(|e| format!("Failed to inscribe the scroll {}: {}", self.path, e))
+    }
+
+    /// Flushes and closes the scroll. Sealing twice is harmless.
+    pub fn seal(&mut self) -> Result<(), String> {
+        match self.writer.take() {
+            Some(mut writer) => writer.flush().map_err(|e| format!("Failed to seal the scroll {}: {}", self.path, e)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Scroll {
+    fn drop(&mut self) {
+        if self.is_sealed() {
+            return;
+        }
+        let _ = self.seal();
+        if self.announce {
+            println!("{}", format!("📜 The scroll {} sealed itself", self.path).italic().bright_blue());
+        }
+    }
+}
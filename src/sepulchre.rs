@@ -0,0 +1,170 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+
+use crate::artifacts;
+use crate::config::Commandments;
+use crate::environment;
+use crate::git;
+use serde_json::Value;
+
+/// Where a project keeps relics, relative to its root.
+const SEPULCHRE_DIR: &str = ".sepulchre";
+
+/// How many relics of each file are kept unless the config says otherwise.
+pub const DEFAULT_KEEP: usize = 10;
+
+/// The sepulchre of the project `file` belongs to.
+fn sepulchre(file: &Path) -> PathBuf {
+    artifacts::project_root(file).join(SEPULCHRE_DIR)
+}
+
+/// `file` with its directory resolved, even once the file itself is gone,
+/// so that its relics are found by the same fingerprint they were kept under.
+fn canonical(file: &Path) -> PathBuf {
+    fs::canonicalize(file).unwrap_or_else(|_| {
+        let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        match (fs::canonicalize(dir), file.file_name()) {
+            (Ok(dir), Some(name)) => dir.join(name),
+            _ => file.to_path_buf(),
+        }
+    })
+}
+
+/// What a relic of `file` is named after: the file's own extension.
+fn kind(file: &Path) -> String {
+    file.extension().map(|ext| ext.to_string_lossy().into_owned()).unwrap_or_else(|| "relic".to_string())
+}
+
+/// Keeps a copy of `file` in its project's sepulchre before a command rewrites
+/// it, when the project's `resurrection_enabled` allows. Older relics of the
+/// file are pruned beyond `resurrection_keep`. Files outside a project, and
+/// files that don't exist yet, are not entombed. Returns where the relic was kept.
+pub fn entomb(file: &Path) -> Result<Option<PathBuf>, String> {
+    if !file.is_file() {
+        return Ok(None);
+    }
+    let Some(commandments) = Commandments::for_script(file)? else { return Ok(None) };
+    if !commandments.allows_resurrection() {
+        return Ok(None);
+    }
+    let keep = commandments.settings.get("resurrection_keep").and_then(Value::as_f64)
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_KEEP);
+
+    let contents = fs::read_to_string(file).map_err(|e| format!("Failed to entomb {}: {}", file.display(), e))?;
+    let file = canonical(file);
+    let dir = sepulchre(&file);
+    let relic = artifacts::write_new(&dir, &artifacts::stem(&file), &kind(&file), &contents)?;
+    artifacts::prune(&dir, &artifacts::fingerprint(&file), &kind(&file), keep)?;
+    eprintln!("{}", environment::for_stderr(format!("⚰️ A relic of {} rests in {}", file.display(), relic.display()).dimmed()));
+    Ok(Some(relic))
+}
+
+/// Where the seal of `file` is kept: beside its relics, but named so that it
+/// is never taken for one.
+fn seal_of(file: &Path) -> PathBuf {
+    sepulchre(file).join(format!("{}.sealed", artifacts::fingerprint(file)))
+}
+
+fn hash(contents: &str) -> String {
+    format!("{:016x}", crate::fnv1a(contents.as_bytes()))
+}
+
+/// Records what a command has just written to `file`, so that `resurrect`
+/// knows the file holds nothing it would lose, though no relic or commit keeps
+/// that state. Only the last write is remembered; files `entomb` would pass
+/// over are not sealed.
+pub fn seal(file: &Path) -> Result<(), String> {
+    let Some(commandments) = Commandments::for_script(file)? else { return Ok(()) };
+    if !commandments.allows_resurrection() {
+        return Ok(());
+    }
+    let contents = fs::read_to_string(file).map_err(|e| format!("Failed to seal {}: {}", file.display(), e))?;
+    let seal = seal_of(&canonical(file));
+    if let Some(dir) = seal.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(&seal, hash(&contents)).map_err(|e| format!("Failed to seal {}: {}", file.display(), e))
+}
+
+/// Whether `contents` is what a command last wrote to `file`.
+fn sealed(file: &Path, contents: &str) -> bool {
+    fs::read_to_string(seal_of(&canonical(file))).is_ok_and(|seal| seal.trim() == hash(contents))
+}
+
+/// The relics kept of `file`, newest first.
+fn relics(file: &Path) -> Vec<PathBuf> {
+    let file = canonical(file);
+    let dir = sepulchre(&file);
+    let marker = format!("-{}", artifacts::fingerprint(&file));
+    let suffix = format!(".{}", kind(&file));
+    let Ok(entries) = fs::read_dir(&dir) else { return Vec::new() };
+    let mut relics: Vec<PathBuf> = entries.filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().map(|name| name.to_string_lossy()).is_some_and(|name| name.contains(&marker) && name.ends_with(&suffix)))
+        .collect();
+    relics.sort();
+    relics.reverse();
+    relics
+}
+
+/// When a relic was entombed, read back from the timestamp its name begins with.
+fn entombed_at(relic: &Path) -> String {
+    let name = relic.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    name.get(..15)
+        .and_then(|stamp| chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%dT%H%M%S").ok())
+        .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "unknown time".to_string())
+}
+
+/// Lists the relics of `file`, newest first, with `list_only`, and otherwise
+/// restores the `chosen`th of them. Changes to the file that neither a relic,
+/// the last commit nor a command's own last write holds are not overwritten
+/// without `force`, and even then are entombed first.
+pub fn resurrect(file: &Path, chosen: usize, list_only: bool, force: bool) -> Result<(), String> {
+    let relics = relics(file);
+    if relics.is_empty() {
+        return Err(format!("ResurrectionError: no relics of {} rest in {}", file.display(), sepulchre(&canonical(file)).display()));
+    }
+
+    if list_only {
+        println!("{}", format!("⚰️ RELICS OF {} ⚰️", file.display()).underline().bright_blue());
+        for (index, relic) in relics.iter().enumerate() {
+            let size = fs::metadata(relic).map(|m| m.len()).unwrap_or(0);
+            println!("  {:>2}  {}  {:>8} B  {}", index + 1, entombed_at(relic), size, relic.display());
+        }
+        return Ok(());
+    }
+    if chosen == 0 || chosen > relics.len() {
+        return Err(format!(
+            "ResurrectionError: there is no relic {} of {}; choose from 1 (the newest) to {}", chosen, file.display(), relics.len()
+        ));
+    }
+
+    let relic = &relics[chosen - 1];
+    let restored = fs::read_to_string(relic).map_err(|e| format!("Failed to read {}: {}", relic.display(), e))?;
+    if let Ok(current) = fs::read_to_string(file) {
+        if current == restored {
+            println!("{}", format!("✨ {} is already as relic {} keeps it", file.display(), chosen).green());
+            return Ok(());
+        }
+        let preserved = relics.iter().any(|relic| fs::read_to_string(relic).is_ok_and(|kept| kept == current))
+            || git::committed(file).is_ok_and(|committed| committed == current)
+            || sealed(file, &current);
+        if !preserved {
+            if !force {
+                return Err(format!(
+                    "ResurrectionError: {} has changes that are neither committed nor entombed; resurrect --force to overwrite them",
+                    file.display()
+                ));
+            }
+            entomb(file)?;
+        }
+    }
+
+    fs::write(file, &restored).map_err(|e| format!("Failed to resurrect {}: {}", file.display(), e))?;
+    println!("{}", format!("🌅 {} has risen again from {}", file.display(), entombed_at(relic)).green());
+    Ok(())
+}
@@ -0,0 +1,310 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use colored::*;
+
+use crate::artifacts;
+use crate::config::Commandments;
+use crate::walk;
+use serde::{Deserialize, Serialize};
+use crate::{DivinePLRuntime, Shell, SinSeverity, Verbosity};
+
+/// Findings are cached next to the project's artifacts.
+const CACHE_NAME: &str = "status.cache";
+
+/// A prompt must never make the shell wait longer than this; past it, the last
+/// known counts are shown and refreshed in the background instead.
+const PROMPT_BUDGET: Duration = Duration::from_millis(150);
+
+/// Sin counts for a script or a whole project.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Tally {
+    pub venial: usize,
+    pub mortal: usize,
+}
+
+impl Tally {
+    fn add(&mut self, other: Tally) {
+        self.venial += other.venial;
+        self.mortal += other.mortal;
+    }
+
+    /// The compact prompt token: `✝3|1` for 3 venial and 1 mortal sin, `✝✓` when clean.
+    fn token(self) -> String {
+        if self.venial == 0 && self.mortal == 0 {
+            "✝✓".to_string()
+        } else {
+            format!("✝{}|{}", self.venial, self.mortal)
+        }
+    }
+}
+
+/// Per-script findings keyed by a hash of each script's contents, so that only
+/// scripts that changed since the last look are confessed again.
+#[derive(Default)]
+struct Cache {
+    files: BTreeMap<String, (u64, Tally)>,
+    total: Tally,
+}
+
+/// The cache as it is written to disk.
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    total: Tally,
+    files: Vec<SavedFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedFile {
+    #[serde(flatten)]
+    tally: Tally,
+    path: String,
+    hash: String,
+}
+
+impl Cache {
+    fn path(root: &Path) -> PathBuf {
+        artifacts::artifacts_root(root).join(CACHE_NAME)
+    }
+
+    fn load(root: &Path) -> Option<Self> {
+        let saved: Saved = serde_json::from_str(&fs::read_to_string(Self::path(root)).ok()?).ok()?;
+        let mut files = BTreeMap::new();
+        for entry in saved.files {
+            let hash = u64::from_str_radix(&entry.hash, 16).ok()?;
+            files.insert(entry.path, (hash, entry.tally));
+        }
+        Some(Self { files, total: saved.total })
+    }
+
+    /// Best-effort: a cache that can't be written only makes the next prompt slower.
+    fn save(&self, root: &Path) {
+        let files = self.files.iter().map(|(path, (hash, tally))| {
+            SavedFile { tally: *tally, path: path.clone(), hash: format!("{:016x}", hash) }
+        }).collect();
+        let saved = Saved { total: self.total, files };
+
+        let path = Self::path(root);
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        // Written aside and renamed into place, so a prompt never reads half a cache
+        let written = path.with_extension(format!("{}.tmp", std::process::id()));
+        let Ok(json) = serde_json::to_string(&saved) else { return };
+        if fs::write(&written, json).is_err() || fs::rename(&written, &path).is_err() {
+            let _ = fs::remove_file(&written);
+        }
+    }
+}
+
+/// The project a status is taken for: the directory of the nearest
+/// commandments.config above `anchor`, or `anchor` itself.
+fn project_root(anchor: &Path) -> PathBuf {
+    artifacts::artifacts_root(anchor).parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Counts the sins of a script, given its contents, by the runtime's lights.
+type Confessor = fn(&DivinePLRuntime, &Path, &str) -> Tally;
+
+/// Counts a script's sins as `confess` would: judged by its commandments'
+/// standards, less those its `// forgiven:` comments set aside.
+fn confess(runtime: &DivinePLRuntime, script: &Path, content: &str) -> Tally {
+    let sins = runtime.standards_for(script)
+        .and_then(|standards| runtime.confess_source(content, &standards))
+        .map(|(sins, _)| sins)
+        .unwrap_or_default();
+    let mortal = sins.iter().filter(|sin| sin.severity == SinSeverity::Mortal).count();
+    Tally { venial: sins.len() - mortal, mortal }
+}
+
+/// Recounts the project's sins with `confess`, confessing again only scripts
+/// whose contents, or the commandments governing them, changed since they
+/// were cached. The recount is not saved; that is left to the caller.
+fn recount(root: &Path, confess: Confessor) -> Cache {
+    let runtime = DivinePLRuntime::new(false, Verbosity::Quiet, false);
+    let previous = Cache::load(root).unwrap_or_default();
+    let mut cache = Cache::default();
+    let mut commandments: HashMap<Option<PathBuf>, Vec<u8>> = HashMap::new();
+
+    let (found, _) = walk::scripts_heeding(root, "confess_ignore", &[]).unwrap_or_default();
+    for script in found {
+        let Ok(content) = fs::read_to_string(&script) else { continue };
+        let config = commandments.entry(Commandments::discover(&script))
+            .or_insert_with_key(|path| path.as_ref().and_then(|path| fs::read(path).ok()).unwrap_or_default());
+        let hash = crate::fnv1a(&[content.as_bytes(), b"\0", config.as_slice()].concat());
+        let key = script.to_string_lossy().into_owned();
+
+        let tally = match previous.files.get(&key) {
+            Some((cached, tally)) if *cached == hash => *tally,
+            _ => confess(&runtime, &script, &content),
+        };
+        cache.total.add(tally);
+        cache.files.insert(key, (hash, tally));
+    }
+    cache
+}
+
+/// Prints the prompt token for the project around `anchor` within the prompt's
+/// time budget. When counting takes longer, the last cached token is printed
+/// marked with `~` and a background process finishes refreshing the cache.
+pub fn prompt(anchor: &Path) {
+    let root = project_root(anchor);
+    let (token, settled) = prompt_token(&root, PROMPT_BUDGET, confess);
+    println!("{}", token);
+    if !settled {
+        refresh_in_background(&root);
+    }
+}
+
+/// The prompt token for the project at `root`, and whether it was counted
+/// within `budget`. Only a count that finished in time is saved here; one that
+/// didn't is left for the background refresh to save, so the two never race.
+fn prompt_token(root: &Path, budget: Duration, confess: Confessor) -> (String, bool) {
+    let cached = Cache::load(root).map(|cache| cache.total);
+
+    let (sender, receiver) = mpsc::channel();
+    let worker_root = root.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = sender.send(recount(&worker_root, confess));
+    });
+
+    match receiver.recv_timeout(budget) {
+        Ok(cache) => {
+            cache.save(root);
+            (cache.total.token(), true)
+        }
+        Err(_) => match cached {
+            Some(tally) => (format!("~{}", tally.token()), false),
+            None => ("~✝?".to_string(), false),
+        },
+    }
+}
+
+/// Hands the refresh to a detached process, since this one is about to exit.
+fn refresh_in_background(root: &Path) {
+    use std::process::{Command, Stdio};
+
+    if let Ok(exe) = std::env::current_exe() {
+        let _ = Command::new(exe)
+            .arg("status")
+            .arg("--refresh")
+            .current_dir(if root.as_os_str().is_empty() { Path::new(".") } else { root })
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+    }
+}
+
+/// Recounts and caches without printing; used by the background refresh.
+pub fn refresh_quietly(anchor: &Path) {
+    let root = project_root(anchor);
+    recount(&root, confess).save(&root);
+}
+
+/// Prints a human-readable account of the project's sins.
+pub fn show(anchor: &Path) {
+    let root = project_root(anchor);
+    let cache = recount(&root, confess);
+    cache.save(&root);
+    let (tally, scripts) = (cache.total, cache.files.len());
+
+    let summary = format!("{} venial, {} mortal sins across {} scripts", tally.venial, tally.mortal, scripts);
+    match tally {
+        Tally { venial: 0, mortal: 0 } => println!("{}", format!("✝️ {}", summary).green()),
+        Tally { mortal: 0, .. } => println!("{}", format!("✝️ {}", summary).yellow()),
+        _ => println!("{}", format!("✝️ {}", summary).bright_red()),
+    }
+}
+
+/// Shell code that puts the prompt token in front of the prompt.
+pub fn init_script(shell: Shell) -> String {
+    let exe = std::env::current_exe()
+        .map(|exe| exe.display().to_string())
+        .unwrap_or_else(|_| "divine-pl".to_string());
+
+    match shell {
+        Shell::Zsh => format!(
+            "# DivinePL confession status. Add to ~/.zshrc: eval \"$('{exe}' status --init zsh)\"\n\
+             divinepl_prompt() {{ '{exe}' status --porcelain=prompt 2>/dev/null }}\n\
+             setopt PROMPT_SUBST\n\
+             PROMPT='$(divinepl_prompt) '\"$PROMPT\"\n",
+            exe = exe
+        ),
+        Shell::Bash => format!(
+            "# DivinePL confession status. Add to ~/.bashrc: eval \"$('{exe}' status --init bash)\"\n\
+             divinepl_prompt() {{ '{exe}' status --porcelain=prompt 2>/dev/null; }}\n\
+             PS1='$(divinepl_prompt) '\"$PS1\"\n",
+            exe = exe
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A project directory of its own for one test, removed when it is done.
+    struct Project(PathBuf);
+
+    impl Project {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("divinepl-status-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, file: &str, content: &str) {
+            fs::write(self.0.join(file), content).unwrap();
+        }
+    }
+
+    impl Drop for Project {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Confesses as `confess` does, but only after a long prayer.
+    fn slowly(runtime: &DivinePLRuntime, script: &Path, content: &str) -> Tally {
+        std::thread::sleep(Duration::from_millis(400));
+        confess(runtime, script, content)
+    }
+
+    #[test]
+    fn a_slow_count_keeps_the_budget_and_leaves_saving_to_the_background() {
+        let project = Project::new("slow");
+        project.write("sinner.divine", "var x = 1;\n");
+
+        assert_eq!(prompt_token(&project.0, Duration::from_millis(20), slowly), ("~✝?".to_string(), false));
+        // The count that overran its budget is never saved by the prompt
+        std::thread::sleep(Duration::from_millis(800));
+        assert!(Cache::load(&project.0).is_none());
+
+        assert_eq!(prompt_token(&project.0, Duration::from_secs(30), slowly), ("✝1|0".to_string(), true));
+        assert_eq!(Cache::load(&project.0).map(|cache| cache.total), Some(Tally { venial: 1, mortal: 0 }));
+
+        project.write("sinner.divine", "var x = 1;\nvar y = 2;\n");
+        assert_eq!(prompt_token(&project.0, Duration::from_millis(20), slowly), ("~✝1|0".to_string(), false));
+    }
+
+    #[test]
+    fn sins_are_counted_as_confess_counts_them() {
+        let project = Project::new("standards");
+        project.write("sinner.divine", "var x = 1;\nvar y = 2; // forgiven: DPL003 kept for the old ways\n");
+        assert_eq!(recount(&project.0, confess).total, Tally { venial: 1, mortal: 0 });
+
+        project.write(crate::config::CONFIG_FILE_NAME, r#"{"sins": {"secular-var": "mortal"}}"#);
+        let cache = recount(&project.0, confess);
+        assert_eq!(cache.total, Tally { venial: 0, mortal: 1 });
+        cache.save(&project.0);
+
+        // The script is unchanged, but the commandments judging it are not
+        project.write(crate::config::CONFIG_FILE_NAME, r#"{"sins": {"secular-var": "off"}}"#);
+        assert_eq!(recount(&project.0, confess).total, Tally::default());
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use colored::*;
+
+use serde_json::{json, Value};
+
+/// How many of the slowest lines the report names.
+const SLOWEST_LINES: usize = 10;
+
+/// An account of where a run spent its time, kept under --profile. Times are
+/// wall time with the ritual pauses taken out, which are counted apart.
+pub struct Stewardship {
+    lines: BTreeMap<usize, Cost>,
+    functions: BTreeMap<String, Cost>,
+    /// How many times the loop on each line went round.
+    iterations: BTreeMap<usize, usize>,
+    /// How many executions of each line or call to each function are in
+    /// progress, so time spent recursing is only counted once.
+    active: HashMap<Entry, usize>,
+    /// How long the runtime had lingered when the account was opened, until it
+    /// is closed; then how long the run lingered.
+    lingered: Duration,
+}
+
+/// The time spent on a line or in a function, over every time it ran.
+#[derive(Default)]
+struct Cost {
+    /// The line as the source reads, or empty for a function.
+    text: String,
+    time: Duration,
+    count: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Entry {
+    Line(usize),
+    Function(String),
+}
+
+impl Stewardship {
+    /// Opens an account for a run, with the runtime having lingered for `lingered` so far.
+    pub fn new(lingered: Duration) -> Self {
+        Self {
+            lines: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            iterations: BTreeMap::new(),
+            active: HashMap::new(),
+            lingered,
+        }
+    }
+
+    /// Closes the account once the run is over, with the runtime having lingered for `lingered`.
+    pub fn close(&mut self, lingered: Duration) {
+        self.lingered = lingered.saturating_sub(self.lingered);
+    }
+
+    /// Marks the start of an execution of the statement on `line`.
+    pub fn begin_line(&mut self, line: usize, text: &str) {
+        let cost = self.lines.entry(line).or_default();
+        if cost.text.is_empty() {
+            cost.text = text.to_string();
+        }
+        cost.count += 1;
+        *self.active.entry(Entry::Line(line)).or_default() += 1;
+    }
+
+    /// Marks the end of an execution of the statement on `line`, which took `time`.
+    pub fn end_line(&mut self, line: usize, time: Duration) {
+        if self.settle(Entry::Line(line)) {
+            self.lines.entry(line).or_default().time += time;
+        }
+    }
+
+    /// Marks the start of a call to the blessed function `name`.
+    pub fn begin_call(&mut self, name: &str) {
+        self.functions.entry(name.to_string()).or_default().count += 1;
+        *self.active.entry(Entry::Function(name.to_string())).or_default() += 1;
+    }
+
+    /// Marks the end of a call to the blessed function `name`, which took `time`.
+    pub fn end_call(&mut self, name: &str, time: Duration) {
+        if self.settle(Entry::Function(name.to_string())) {
+            self.functions.entry(name.to_string()).or_default().time += time;
+        }
+    }
+
+    /// Counts one more time round the loop on `line`.
+    pub fn iterated(&mut self, line: usize) {
+        *self.iterations.entry(line).or_default() += 1;
+    }
+
+    /// Ends one execution of `entry`, and says whether it was the outermost.
+    fn settle(&mut self, entry: Entry) -> bool {
+        match self.active.get_mut(&entry) {
+            Some(depth) if *depth > 1 => {
+                *depth -= 1;
+                false
+            }
+            _ => {
+                self.active.remove(&entry);
+                true
+            }
+        }
+    }
+
+    /// The lines that took longest, slowest first.
+    fn slowest(&self) -> Vec<(&usize, &Cost)> {
+        let mut lines: Vec<_> = self.lines.iter().collect();
+        lines.sort_by(|(a_line, a), (b_line, b)| b.time.cmp(&a.time).then(a_line.cmp(b_line)));
+        lines.truncate(SLOWEST_LINES);
+        lines
+    }
+
+    /// Prints the Stewardship Report.
+    pub fn report(&self) {
+        println!("{}", "\n📜 STEWARDSHIP REPORT 📜".bright_blue());
+
+        println!("Slowest lines:");
+        for (line, cost) in self.slowest() {
+            println!("  line {:>4}  {:>10}  {:>6}×  {}", line, spent(cost.time), cost.count, cost.text);
+        }
+
+        if !self.functions.is_empty() {
+            println!("Blessed functions:");
+            for (name, cost) in &self.functions {
+                println!("  {:<20} {:>10}  {} call{}", name, spent(cost.time), cost.count, if cost.count == 1 { "" } else { "s" });
+            }
+        }
+
+        if !self.iterations.is_empty() {
+            println!("Loops:");
+            for (line, count) in &self.iterations {
+                println!("  line {:>4}  {} iteration{}", line, count, if *count == 1 { "" } else { "s" });
+            }
+        }
+
+        if !self.lingered.is_zero() {
+            println!("{}", format!("Ritual pauses, not counted above: {}", spent(self.lingered)).dimmed());
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let lines = self.slowest().into_iter()
+            .map(|(line, cost)| json!({
+                "line": line,
+                "text": cost.text,
+                "ms": as_millis(cost.time),
+                "executions": cost.count,
+            }))
+            .collect::<Vec<_>>();
+        let functions = self.functions.iter()
+            .map(|(name, cost)| json!({ "name": name, "ms": as_millis(cost.time), "calls": cost.count }))
+            .collect::<Vec<_>>();
+        let loops = self.iterations.iter()
+            .map(|(line, count)| json!({ "line": line, "iterations": count }))
+            .collect::<Vec<_>>();
+        json!({
+            "slowest_lines": lines,
+            "functions": functions,
+            "loops": loops,
+            "lingered_ms": as_millis(self.lingered),
+        })
+    }
+}
+
+fn as_millis(time: Duration) -> f64 {
+    (time.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+fn spent(time: Duration) -> String {
+    match time.as_secs() {
+        0 => format!("{:.3} ms", as_millis(time)),
+        _ => format!("{:.2} s", time.as_secs_f64()),
+    }
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::Path;
+
+use colored::*;
+
+use crate::error::DivineError;
+use crate::interpreter::{Affirmation, Interpreter};
+use crate::modules::{self, Scriptorium};
+use crate::{canon, parser, walk, DivinePLRuntime, Verbosity};
+
+/// Scripts whose names end this way hold testaments.
+const TESTAMENT_SUFFIX: &str = "_testament.divine";
+
+/// Blessed functions whose names start this way are testaments.
+const TEST_PREFIX: &str = "test_";
+
+/// How one testament fared.
+struct Verdict {
+    /// The testament script, as it is shown.
+    script: String,
+    name: String,
+    affirmations: Vec<Affirmation>,
+    /// The sin that ended the testament early, if any.
+    fallen: Option<String>,
+}
+
+impl Verdict {
+    fn passed(&self) -> bool {
+        self.fallen.is_none() && self.affirmations.iter().all(|affirmation| affirmation.passed)
+    }
+}
+
+/// Runs every `bless test_*` function in the testament scripts at `path`, a
+/// script or a directory searched for `*_testament.divine`, each in a fresh
+/// interpreter, and reports what their affirmations found. Only testaments
+/// whose names contain `filter` are run. Fails if any testament does.
+pub fn testament(runtime: &DivinePLRuntime, path: &Path, filter: Option<&str>) -> Result<(), DivineError> {
+    let scripts = if path.is_dir() {
+        let (mut found, skipped) = walk::canonical_scripts(path)?;
+        canon::report_skipped(skipped);
+        found.retain(|script| script.file_name().is_some_and(|name| name.to_string_lossy().ends_with(TESTAMENT_SUFFIX)));
+        found
+    } else {
+        vec![path.to_path_buf()]
+    };
+    if scripts.is_empty() {
+        return Err(DivineError::Other(format!("No testaments (*{}) were found in {}", TESTAMENT_SUFFIX, path.display())));
+    }
+
+    let mut verdicts = Vec::new();
+    for script in &scripts {
+        let content = fs::read_to_string(script)
+            .map_err(|e| DivineError::Io(format!("Failed to read testament {}: {}", script.display(), e)))?;
+        let mut program = parser::parse_program(&content)
+            .map_err(|e| DivineError::Parse { message: format!("{} (in {})", e, script.display()), span: None })?;
+        modules::load(&mut program, script, &Scriptorium::Disk, runtime.verbosity >= Verbosity::Internals)
+            .map_err(|e| DivineError::Parse { message: format!("{} (in {})", e, script.display()), span: None })?;
+
+        let mut tests: Vec<_> = program.functions.values()
+            .filter(|function| function.name.starts_with(TEST_PREFIX) && function.params.is_empty())
+            .filter(|function| filter.is_none_or(|filter| function.name.contains(filter)))
+            .map(|function| (function.line, function.name.clone()))
+            .collect();
+        tests.sort();
+
+        let shown = script.display().to_string().trim_start_matches("./").to_string();
+        for (_, name) in tests {
+            println!("{}", format!("📜 {} → {}", shown, name).bright_blue());
+            let (fallen, affirmations) = runtime.on_tall_stack(|| {
+                let mut interpreter = Interpreter::new(runtime, &program, &[]);
+                let fallen = interpreter.run(&program, Some(&name)).err().map(|fault| fault.message);
+                Ok((fallen, interpreter.into_testimony().affirmations))
+            })?;
+            verdicts.push(Verdict { script: shown.clone(), name, affirmations, fallen });
+        }
+    }
+
+    if verdicts.is_empty() {
+        return Err(DivineError::Other(match filter {
+            Some(filter) => format!("No testament names contain '{}'", filter),
+            None => format!("No bless {}* functions were found in {}", TEST_PREFIX, path.display()),
+        }));
+    }
+    report(&verdicts)
+}
+
+/// Prints a table of the verdicts, with the failing affirmations of each
+/// testament that fell beneath it.
+fn report(verdicts: &[Verdict]) -> Result<(), DivineError> {
+    let script_width = verdicts.iter().map(|verdict| verdict.script.chars().count()).max().unwrap_or(0);
+    let name_width = verdicts.iter().map(|verdict| verdict.name.len()).max().unwrap_or(0);
+
+    println!("\n{}", "⚖️ THE TESTAMENTS ⚖️".bright_yellow());
+    for verdict in verdicts {
+        let affirmed = verdict.affirmations.iter().filter(|affirmation| affirmation.passed).count();
+        let row = format!("{:<script_width$}  {:<name_width$}  {}/{} affirmed",
+            verdict.script, verdict.name, affirmed, verdict.affirmations.len());
+        if verdict.passed() {
+            println!("  {}  {}", "✓ PASSED".green(), row);
+            continue;
+        }
+        println!("  {}  {}", "✗ FAILED".bright_red(), row);
+        for affirmation in verdict.affirmations.iter().filter(|affirmation| !affirmation.passed) {
+            println!("{}", format!("      line {}: {}", affirmation.line, affirmation.message).red());
+        }
+        if let Some(fallen) = &verdict.fallen {
+            println!("{}", format!("      {}", fallen).red());
+        }
+    }
+
+    let failed = verdicts.iter().filter(|verdict| !verdict.passed()).count();
+    let summary = format!("{} passed, {} failed", verdicts.len() - failed, failed);
+    if failed > 0 {
+        println!("\n{}", summary.bright_red());
+        return Err(DivineError::Judgment {
+            message: format!("TestamentError: {} of {} testament{} failed", failed, verdicts.len(), if verdicts.len() == 1 { "" } else { "s" }),
+            span: None,
+        });
+    }
+    println!("\n{}", summary.green());
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+
+use serde_json::{json, Map, Value};
+use crate::lexer;
+use crate::{canon, modules, walk, DivinePLRuntime, OutputFormat};
+
+/// What a script, or a whole project, gives.
+#[derive(Default, Clone, Copy)]
+struct Offering {
+    /// Every line, blank or not.
+    lines: usize,
+    /// Lines with code on them.
+    code: usize,
+    /// Lines given over to comments and prayers.
+    devotion: usize,
+    blessings: usize,
+    miracles: usize,
+    genesis: usize,
+    prayer_blocks: usize,
+    covenants: usize,
+    revelations: usize,
+}
+
+impl Offering {
+    fn add(&mut self, other: Offering) {
+        self.lines += other.lines;
+        self.code += other.code;
+        self.devotion += other.devotion;
+        self.blessings += other.blessings;
+        self.miracles += other.miracles;
+        self.genesis += other.genesis;
+        self.prayer_blocks += other.prayer_blocks;
+        self.covenants += other.covenants;
+        self.revelations += other.revelations;
+    }
+
+    /// Comment and prayer lines for every line of code.
+    fn devotion_ratio(&self) -> f64 {
+        if self.code == 0 { 0.0 } else { self.devotion as f64 / self.code as f64 }
+    }
+
+    fn to_json(self) -> Value {
+        json!({
+            "lines": self.lines,
+            "code_lines": self.code,
+            "devotional_lines": self.devotion,
+            "blessings": self.blessings,
+            "miracles": self.miracles,
+            "genesis": self.genesis,
+            "prayer_blocks": self.prayer_blocks,
+            "covenants": self.covenants,
+            "revelations": self.revelations,
+            "devotion_ratio": (self.devotion_ratio() * 100.0).round() / 100.0,
+        })
+    }
+}
+
+/// Reports what each script at `path`, a script or a directory of them, gives,
+/// and what they give together. When the commandments map the trinity, what
+/// each role's module gives is reported too, beside what the other scripts
+/// give. Paths under `path` that start with one of `ignore`, or with an entry
+/// of the commandments' `tithe_ignore` list, are passed over.
+pub fn tithe(runtime: &DivinePLRuntime, path: &Path, format: OutputFormat, ignore: &[String]) -> Result<(), String> {
+    let (scripts, skipped) = if path.is_dir() {
+        walk::scripts_heeding(path, "tithe_ignore", ignore)?
+    } else {
+        (vec![path.to_path_buf()], 0)
+    };
+
+    let mut total = Offering::default();
+    let mut offerings = Vec::new();
+    for script in &scripts {
+        let content = fs::read_to_string(script).map_err(|e| format!("Failed to read {}: {}", script.display(), e))?;
+        let offering = offering(runtime, &content);
+        total.add(offering);
+        offerings.push((script.display().to_string().trim_start_matches("./").to_string(), offering));
+    }
+
+    let dir = if path.is_dir() { path } else { path.parent().unwrap_or(Path::new("")) };
+    let roles = modules::trinity_modules(dir).map(|modules| by_role(&modules, &scripts, &offerings));
+
+    match format {
+        OutputFormat::Json => {
+            let files = offerings.iter()
+                .map(|(script, offering)| {
+                    let mut json = offering.to_json();
+                    json["path"] = Value::from(script.as_str());
+                    json
+                })
+                .collect::<Vec<_>>();
+            let mut report = json!({ "files": files, "total": total.to_json(), "apocryphal": skipped });
+            if let Some(roles) = &roles {
+                report["trinity"] = Value::Object(roles.iter().map(|(role, offering)| (role.clone(), offering.to_json())).collect::<Map<_, _>>());
+            }
+            println!("{}", report);
+        }
+        OutputFormat::Text => {
+            println!("{}", "💰 The tithe of the scriptures 💰".bright_yellow());
+            print_table("Script", &offerings, total);
+            if let Some(roles) = roles {
+                println!();
+                print_table("Role", &roles, total);
+            }
+            println!("{}", format!(
+                "Devotion ratio: {:.2} lines of comments and prayers for every line of code", total.devotion_ratio()
+            ).italic().bright_cyan());
+            canon::report_skipped(skipped);
+        }
+    }
+    Ok(())
+}
+
+/// What the module of each role of the trinity gives, among the `offerings`
+/// of `scripts`, in the order of the mapping, and what the rest give as
+/// `unassigned`.
+fn by_role(modules: &[(String, PathBuf)], scripts: &[PathBuf], offerings: &[(String, Offering)]) -> Vec<(String, Offering)> {
+    let identity = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let modules: Vec<(&String, PathBuf)> = modules.iter().map(|(role, module)| (role, identity(module))).collect();
+    let mut roles: Vec<(String, Offering)> = modules.iter().map(|(role, _)| (role.to_string(), Offering::default())).collect();
+    let mut unassigned = Offering::default();
+    for (script, (_, offering)) in scripts.iter().zip(offerings) {
+        let script = identity(script);
+        match modules.iter().position(|(_, module)| *module == script) {
+            Some(index) => roles[index].1.add(*offering),
+            None => unassigned.add(*offering),
+        }
+    }
+    roles.push(("unassigned".to_string(), unassigned));
+    roles
+}
+
+fn offering(runtime: &DivinePLRuntime, content: &str) -> Offering {
+    let (statements, _) = runtime.scan_script(content);
+    let mut offering = Offering {
+        lines: content.lines().count(),
+        code: statements.len(),
+        prayer_blocks: content.lines().filter(|line| lexer::is_begin_prayer(line)).count(),
+        ..Offering::default()
+    };
+    // Whatever isn't blank and isn't code was written in devotion
+    offering.devotion = content.lines().filter(|line| !line.trim().is_empty()).count().saturating_sub(offering.code);
+
+    for stmt in &statements {
+        let code = stmt.code.trim();
+        match DivinePLRuntime::declared_function(code) {
+            Some("genesis") => offering.genesis += 1,
+            Some(_) if stmt.is_miracle => offering.miracles += 1,
+            Some(_) if code.starts_with("bless ") => offering.blessings += 1,
+            _ => {}
+        }
+        if stmt.is_covenant {
+            offering.covenants += 1;
+        }
+        offering.revelations += code.matches("revelation(").count();
+    }
+    offering
+}
+
+/// Prints a row of `offerings` for each of what `heading` names, and the total.
+fn print_table(heading: &str, offerings: &[(String, Offering)], total: Offering) {
+    const HEADINGS: [&str; 8] = ["Lines", "Blessings", "Miracles", "Genesis", "Prayers", "Covenants", "Revelations", "Devotion"];
+    let width = offerings.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max("Total".len());
+    let row = |name: &str, offering: &Offering| {
+        let cells = [
+            offering.lines.to_string(), offering.blessings.to_string(), offering.miracles.to_string(),
+            offering.genesis.to_string(), offering.prayer_blocks.to_string(), offering.covenants.to_string(),
+            offering.revelations.to_string(), format!("{:.2}", offering.devotion_ratio()),
+        ];
+        let cells = cells.iter().zip(HEADINGS).map(|(cell, heading)| format!("{:>w$}", cell, w = heading.len())).collect::<Vec<_>>();
+        format!("{:<width$}  {}", name, cells.join("  "))
+    };
+
+    println!("{}", format!("{:<width$}  {}", heading, HEADINGS.join("  ")).underline().bright_blue());
+    for (name, offering) in offerings {
+        println!("{}", row(name, offering));
+    }
+    println!("{}", row("Total", &total).bold().green());
+}
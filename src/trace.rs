@@ -0,0 +1,124 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use chrono::{SecondsFormat, Utc};
+
+use serde_json::{json, Value};
+use crate::{artifacts, OutputFormat};
+
+/// A log of everything a run does, one line per event, appended to the file
+/// given by --trace, or kept as an artifact of the project when no file is.
+pub struct Trace {
+    out: Mutex<Sink>,
+    format: OutputFormat,
+}
+
+/// Where the lines of a trace go.
+enum Sink {
+    File(BufWriter<File>),
+    /// Held until the run ends, when its verdict tells heaven from purgatory.
+    Artifact(Vec<u8>),
+}
+
+/// Something that happened during a run, worth a line of the trace.
+pub enum Event<'a> {
+    /// A statement was executed. `value` is what a `let` bound.
+    Statement { line: usize, kind: &'a str, text: &'a str, value: Option<String> },
+    Intervention,
+    /// A sin raised inside `confess` was forgiven.
+    Forgiven { line: usize, sin: &'a str },
+    Judgment { saved: bool, factors: &'a [String] },
+    /// The run ended in an error, which is what a failed judgment ends in too.
+    Error { message: &'a str },
+}
+
+impl Trace {
+    /// Opens `path` to append to, creating it if it is missing.
+    pub fn open(path: &Path, format: OutputFormat) -> Result<Self, String> {
+        let file = OpenOptions::new().create(true).append(true).open(path)
+            .map_err(|e| format!("Failed to open trace file {}: {}", path.display(), e))?;
+        Ok(Self { out: Mutex::new(Sink::File(BufWriter::new(file))), format })
+    }
+
+    /// A trace kept as an artifact of the project once the run ends.
+    pub fn kept(format: OutputFormat) -> Self {
+        Self { out: Mutex::new(Sink::Artifact(Vec::new())), format }
+    }
+
+    pub fn record(&self, event: Event) {
+        let time = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        let line = match self.format {
+            OutputFormat::Json => event.to_json(time).to_string(),
+            OutputFormat::Text => format!("{} {}", time, event),
+        };
+        // A trace that can't be written is not worth ending the run over
+        let _ = match &mut *self.out.lock().unwrap_or_else(PoisonError::into_inner) {
+            Sink::File(out) => writeln!(out, "{}", line),
+            Sink::Artifact(out) => writeln!(out, "{}", line),
+        };
+    }
+
+    /// Ends the trace of a run of `script`, recording the `error` it ended in,
+    /// if any, and writes out whatever is still buffered. A trace kept as an
+    /// artifact is stored then, and where it was stored is returned; the next
+    /// run under --watch begins a trace of its own.
+    pub fn close(&self, script: &Path, error: Option<&str>) -> Result<Option<PathBuf>, String> {
+        if let Some(message) = error {
+            self.record(Event::Error { message });
+        }
+        match &mut *self.out.lock().unwrap_or_else(PoisonError::into_inner) {
+            Sink::File(out) => out.flush().map(|_| None).map_err(|e| format!("Failed to write the trace: {}", e)),
+            Sink::Artifact(lines) if lines.is_empty() => Ok(None),
+            Sink::Artifact(lines) => {
+                let contents = String::from_utf8_lossy(&mem::take(lines)).into_owned();
+                let kind = match self.format {
+                    OutputFormat::Json => "trace.jsonl",
+                    OutputFormat::Text => "trace.txt",
+                };
+                artifacts::store(script, error.is_none(), kind, &contents).map(Some)
+                    .map_err(|e| format!("Failed to keep the trace: {}", e))
+            }
+        }
+    }
+}
+
+impl Event<'_> {
+    fn to_json(&self, time: String) -> Value {
+        match self {
+            Event::Statement { line, kind, text, value } => {
+                let mut event = json!({ "time": time, "event": "statement", "line": line, "kind": kind, "text": text });
+                if let Some(value) = value {
+                    event["value"] = Value::from(value.as_str());
+                }
+                event
+            }
+            Event::Intervention => json!({ "time": time, "event": "intervention" }),
+            Event::Forgiven { line, sin } => json!({ "time": time, "event": "forgiven", "line": line, "sin": sin }),
+            Event::Judgment { saved, factors } => json!({ "time": time, "event": "judgment", "saved": saved, "factors": factors }),
+            Event::Error { message } => json!({ "time": time, "event": "error", "message": message }),
+        }
+    }
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Statement { line, kind, text, value: Some(value) } => write!(f, "line {} {} {} => {}", line, kind, text, value),
+            Event::Statement { line, kind, text, value: None } => write!(f, "line {} {} {}", line, kind, text),
+            Event::Intervention => f.write_str("divine intervention occurred"),
+            Event::Forgiven { line, sin } => write!(f, "line {} forgiven {}", line, sin),
+            Event::Judgment { saved, factors } => {
+                f.write_str(if *saved { "judgment ascended to production heaven" } else { "judgment sent to debugging purgatory" })?;
+                if !factors.is_empty() {
+                    write!(f, ": {}", factors.join("; "))?;
+                }
+                Ok(())
+            }
+            Event::Error { message } => write!(f, "error {}", message),
+        }
+    }
+}
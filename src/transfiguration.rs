@@ -0,0 +1,492 @@
+//! The rewritings of code on disk: the miracle that turns secular code into
+//! DivinePL, secularizing that turns it back, and sanctifying that puts a
+//! script in its canonical form.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use colored::*;
+
+use crate::error::DivineError;
+use crate::{canon, diff, environment, glyphs, lexer, miracle, parser, printer, sepulchre, walk};
+use crate::{is_stdio, read_scripture, DivinePLRuntime};
+
+/// Written before the code a miracle transforms, each line in a comment of
+/// its language, with ASCII prayer delimiters under --plain-glyphs.
+const MIRACLE_HEADER: &str = "Transformed by the Divine Miracle of DivinePL
+This code has been sanctified from its secular origins
+
+🙏 BEGIN PRAYER 🙏
+Lord, bless this transformed code
+Guide it to run with divine efficiency
+Protect it from bugs and runtime errors
+🙏 END PRAYER 🙏
+
+";
+
+/// Written after the code a miracle transforms, in comments as the header is.
+const MIRACLE_FOOTER: &str = "
+
+End of sanctified code
+\"In the beginning was the code, and the code was with God.\" - DivinePL 1:1
+";
+
+/// `text` with each of its lines that isn't blank put in a comment of `language`.
+fn commented(text: &str, language: &miracle::Language) -> String {
+    text.split_inclusive('\n')
+        .map(|line| if line.trim().is_empty() { line.to_string() } else { format!("{} {}", language.comment(), line) })
+        .collect()
+}
+
+/// The header a miracle on `language` writes, in the delimiter style `plain` picks.
+fn miracle_header(language: &miracle::Language, plain: bool) -> String {
+    commented(&glyphs_of_prayer(MIRACLE_HEADER, plain), language)
+}
+
+/// The prayer delimiters of `text` in the style `plain` picks.
+fn glyphs_of_prayer(text: &str, plain: bool) -> String {
+    if plain {
+        text.replace(lexer::BEGIN_PRAYER, lexer::PLAIN_BEGIN_PRAYER).replace(lexer::END_PRAYER, lexer::PLAIN_END_PRAYER)
+    } else {
+        text.to_string()
+    }
+}
+
+/// The headers a miracle on `language` may have written before: in either
+/// delimiter style, and for JavaScript as miracles once wrote it, with the
+/// prayer outside any comment.
+fn miracle_headers(language: &miracle::Language) -> Vec<String> {
+    let mut headers: Vec<String> = [false, true].iter().map(|plain| miracle_header(language, *plain)).collect();
+    if language.comment() == "//" {
+        let (marks, prayer) = MIRACLE_HEADER.split_at(MIRACLE_HEADER.find(lexer::BEGIN_PRAYER).unwrap_or_default());
+        headers.extend([false, true].iter().map(|plain| format!("{}{}", commented(marks, language), glyphs_of_prayer(prayer, *plain))));
+    }
+    headers
+}
+
+/// The footer a miracle on `language` writes.
+fn miracle_footer(language: &miracle::Language) -> String {
+    commented(MIRACLE_FOOTER, language)
+}
+
+/// The line a miracle's header on `language` begins with, by which code it
+/// already transformed is known.
+fn miracle_mark(language: &miracle::Language) -> String {
+    format!("{} {}", language.comment(), MIRACLE_HEADER.lines().next().unwrap_or_default())
+}
+
+/// `content` without the header and footer a miracle on `language` wrapped it
+/// in, in either delimiter style.
+fn without_miracle_frame<'a>(content: &'a str, language: &miracle::Language) -> &'a str {
+    let content = without_miracle_header(content, language);
+    content.strip_suffix(miracle_footer(language).as_str()).unwrap_or(content)
+}
+
+/// `content` without the header a miracle on `language` wrote before it.
+fn without_miracle_header<'a>(content: &'a str, language: &miracle::Language) -> &'a str {
+    miracle_headers(language).iter()
+        .find_map(|header| content.strip_prefix(header.as_str()))
+        .unwrap_or(content)
+}
+
+/// How a miracle is shown instead of performed, with nothing written.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Rehearsal {
+    /// A diff of what it would change, then its substitutions.
+    Diff,
+    /// Only how often it would make each substitution.
+    Stat,
+}
+
+/// How `miracle` was asked to perform.
+pub struct MiracleOptions<'a> {
+    /// The language named with --language, rather than told from the extension.
+    pub language: Option<&'a str>,
+    /// The rules --liturgy adds to the miracle's own, or disables of them.
+    pub liturgy: Option<&'a miracle::Liturgy>,
+    pub retransform: bool,
+    pub rehearsal: Option<Rehearsal>,
+    /// Whether the code is rewritten where it lies, after a backup is kept.
+    pub in_place: bool,
+    /// Where to write the source map of the miracle.
+    pub map: Option<&'a Path>,
+}
+
+/// Keeps the secular code at `path` before a miracle rewrites it in place: in
+/// the sepulchre where the project allows resurrection, and otherwise beside
+/// it as `<name>.secular.bak`. Returns where it was kept.
+fn keep_secular(path: &Path) -> Result<PathBuf, DivineError> {
+    if let Some(relic) = sepulchre::entomb(path).map_err(DivineError::Io)? {
+        return Ok(relic);
+    }
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".secular.bak");
+    let backup = PathBuf::from(backup);
+    // Copying keeps the original's permissions on the backup too
+    fs::copy(path, &backup)
+        .map_err(|e| DivineError::Io(format!("Failed to back up {} to {}: {}", path.display(), backup.display(), e)))?;
+    Ok(backup)
+}
+
+/// A miracle performed in memory, before anything is written.
+struct Transformation {
+    /// The secular code, with plain newlines.
+    secular: String,
+    /// What the miracle makes of it, with plain newlines.
+    divine: String,
+    /// How many times each of the rules was applied.
+    counts: Vec<usize>,
+    /// Whether the secular code's lines end in CRLF, as the divine code's will.
+    crlf: bool,
+    /// How many lines the header the miracle wrote takes.
+    header_lines: usize,
+    /// Each line of the transformed code after the header, as the line of the
+    /// secular code it came from, counted from 1.
+    lines: Vec<usize>,
+}
+
+impl Transformation {
+    fn new(content: &str, language: &miracle::Language, rules: &[miracle::Rule]) -> Self {
+        // Transform with plain newlines, then restore the source's own line endings
+        let crlf = content.contains("\r\n");
+        let secular = lexer::without_bom(content).replace("\r\n", "\n");
+        // The lines of a header an earlier miracle wrote are passed over
+        let unheaded = without_miracle_header(&secular, language);
+        let passed_over = secular[..secular.len() - unheaded.len()].matches('\n').count();
+        let footer = miracle_footer(language);
+        let performed = miracle::perform(unheaded.strip_suffix(footer.as_str()).unwrap_or(unheaded), language, rules, false);
+        
+        let mut divine = miracle_header(language, glyphs::plain());
+        let header_lines = divine.matches('\n').count();
+        // The last line begun holds nothing when the code ends with a newline
+        let written = if performed.code.is_empty() || performed.code.ends_with('\n') { performed.lines.len() - 1 } else { performed.lines.len() };
+        let lines = performed.lines[..written].iter().map(|line| passed_over + line + 1).collect();
+        divine.push_str(&performed.code);
+        divine.push_str(&footer);
+        Self { secular, divine, counts: performed.counts, crlf, header_lines, lines }
+    }
+    
+    /// Where each line of the transformed code came from in `source`.
+    fn source_map(&self, source: &str) -> miracle::SourceMap {
+        miracle::SourceMap {
+            source: source.to_string(),
+            header: (1, self.header_lines),
+            footer: (self.header_lines + self.lines.len() + 1, self.divine.lines().count()),
+            lines: self.lines.iter().enumerate().map(|(at, original)| (self.header_lines + at + 1, *original)).collect(),
+        }
+    }
+    
+    /// The divine code as it is written, with the secular code's line endings.
+    fn written(&self) -> String {
+        if self.crlf { self.divine.replace('\n', "\r\n") } else { self.divine.clone() }
+    }
+    
+    fn substitutions(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+/// Whether `content` bears the mark of a miracle on `language` already.
+fn bears_miracle_mark(content: &str, language: &miracle::Language) -> bool {
+    let mark = miracle_mark(language);
+    content.lines().any(|line| line.trim() == mark)
+}
+
+/// Prints what a miracle on `source` would do: with `Rehearsal::Diff` the diff
+/// to `destination`, then how often each of the `rules` it was performed with
+/// would be applied, by id.
+fn rehearse(rehearsal: Rehearsal, source: &str, destination: &str, language: &miracle::Language, rules: &[miracle::Rule],
+            transformation: &Transformation) {
+    if rehearsal == Rehearsal::Diff {
+        print!("{}", diff::paint(&diff::unified(source, destination, &transformation.secular, &transformation.divine)));
+    }
+    let made: Vec<(String, usize)> = rules.iter().zip(transformation.counts.iter().copied())
+        .filter(|(_, count)| *count > 0)
+        .map(|(rule, count)| (format!("{}: {} → {}", rule.id, rule.secular, rule.divine), count))
+        .collect();
+    let width = made.iter().map(|(made, _)| made.chars().count()).max().unwrap_or(0);
+    for (made, count) in &made {
+        println!(" {:<width$} | {}", made, count.to_string().green(), width = width);
+    }
+    let total = transformation.substitutions();
+    println!(" {} substitution{} in {} ({}); nothing was written", total, if total == 1 { "" } else { "s" }, source, language.name);
+}
+
+/// Writes a miracle on `input_path` to `output_path`, over a file there that is
+/// entombed first, or in place after the original is kept. Writing over a file
+/// keeps its permissions. Returns where an original kept in place rests.
+fn write_miracle(input_path: &Path, output_path: &Path, in_place: bool, transformation: &Transformation) -> Result<Option<PathBuf>, DivineError> {
+    let backup = if in_place {
+        Some(keep_secular(input_path)?)
+    } else {
+        sepulchre::entomb(output_path).map_err(DivineError::Io)?;
+        None
+    };
+    fs::write(output_path, transformation.written())
+        .map_err(|e| DivineError::Io(format!("Failed to write divine transformation to {}: {}", output_path.display(), e)))?;
+    sepulchre::seal(output_path).map_err(DivineError::Io)?;
+    Ok(backup)
+}
+
+/// Rewrites a script in its sanctified form. With `check`, only reports whether
+/// that would change anything; with `to_stdout`, prints it instead.
+pub fn sanctify(path: &Path, check: bool, to_stdout: bool) -> Result<(), DivineError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| DivineError::Io(format!("Failed to read script: {}", e)))?;
+    let program = parser::parse_program(&content)?;
+    let sanctified = printer::sanctified(&program);
+    
+    if to_stdout {
+        print!("{}", sanctified);
+    } else if sanctified == content {
+        println!("{}", format!("✨ {} is already sanctified", path.display()).green());
+    } else if check {
+        return Err(DivineError::Other(format!(
+            "SanctifyError: {} is not sanctified; run 'divine sanctify {}'", path.display(), path.display()
+        )));
+    } else {
+        sepulchre::entomb(path).map_err(DivineError::Io)?;
+        fs::write(path, &sanctified)
+            .map_err(|e| DivineError::Io(format!("Failed to write sanctified script: {}", e)))?;
+        sepulchre::seal(path).map_err(DivineError::Io)?;
+        println!("{}", format!("✨ {} has been sanctified", path.display()).green());
+    }
+    
+    Ok(())
+}
+
+/// Transforms secular code into DivinePL. Either path may be `-` for standard
+/// input or output; when the miracle goes to standard output, the ritual is
+/// recited on standard error so the transformed code stays clean. Code that
+/// bears the mark of a miracle is refused unless `retransform` is given, and
+/// then its header and footer are replaced rather than added to. The
+/// language is told from the input's extension unless one is named. A
+/// rehearsal prints what the miracle would do and writes nothing. In place,
+/// `output_path` is the input, which is kept before it is rewritten.
+pub fn transform_secular_code(runtime: &DivinePLRuntime, input_path: &Path, output_path: &Path, options: &MiracleOptions) -> Result<(), DivineError> {
+    if options.in_place && is_stdio(input_path) {
+        return Err(DivineError::Other("--in-place needs a file to rewrite, not standard input".to_string()));
+    }
+    let language = options.language.map_or_else(|| miracle::of(input_path), miracle::named).map_err(DivineError::Other)?;
+    // Read secular code
+    let content = read_scripture(input_path)
+        .map_err(|e| DivineError::Io(format!("Failed to read secular code: {}", e)))?;
+    let source = if is_stdio(input_path) { "<stdin>".to_string() } else { input_path.display().to_string() };
+    let destination = if is_stdio(output_path) { "<stdout>".to_string() } else { output_path.display().to_string() };
+    if !options.retransform && bears_miracle_mark(&content, language) {
+        return Err(DivineError::Other(format!(
+            "{} already bears the mark of a miracle (\"{}\"); pass --retransform to perform it again",
+            source, miracle_mark(language)
+        )));
+    }
+    
+    // Apply divine transformation
+    let rules = miracle::rules(language, options.liturgy);
+    let transformation = Transformation::new(&content, language, &rules);
+    if let Some(rehearsal) = options.rehearsal {
+        rehearse(rehearsal, &source, &destination, language, &rules, &transformation);
+        return Ok(());
+    }
+    
+    let mut ritual: Box<dyn Write> = if is_stdio(output_path) { Box::new(io::stderr()) } else { Box::new(io::stdout()) };
+    let recite = |ritual: &mut Box<dyn Write>, words: String| {
+        write!(ritual, "{}", glyphs::render(&words)).and_then(|_| ritual.flush())
+            .map_err(|e| DivineError::Io(format!("Failed to recite the ritual: {}", e)))
+    };
+    
+    recite(&mut ritual, format!("{}\n", "🕊️ Beginning miraculous transformation of secular code...".bright_blue()))?;
+    
+    // Start the transformation ritual
+    for i in 1..=7 {
+        recite(&mut ritual, format!("Phase {} of transformation... ", i))?;
+        runtime.linger(300);
+        recite(&mut ritual, format!("{}\n", "✓".green()))?;
+    }
+    
+    // Write the transformed code
+    let backup = if is_stdio(output_path) {
+        io::stdout().write_all(transformation.written().as_bytes()).and_then(|_| io::stdout().flush())
+            .map_err(|e| DivineError::Io(format!("Failed to write divine transformation: {}", e)))?;
+        None
+    } else {
+        write_miracle(input_path, output_path, options.in_place, &transformation)?
+    };
+    if let Some(map) = options.map {
+        transformation.source_map(&source).save(map).map_err(DivineError::Io)?;
+    }
+    
+    recite(&mut ritual, format!("{}\n", "\n✨ MIRACLE COMPLETE! ✨".bright_yellow()))?;
+    let saved = match backup {
+        Some(backup) => format!("Secular code has been divinely transformed in place in {}; the original rests in {}", destination, backup.display()),
+        None => format!("Secular code has been divinely transformed and saved to: {}", destination),
+    };
+    recite(&mut ritual, format!("{}\n", saved.green()))?;
+    
+    Ok(())
+}
+
+/// Transforms every file of secular code under `dir`, as --extensions
+/// chooses and the apocrypha allow, into the same place under `output_dir`,
+/// or where it lies in place. Files that bear the mark of a miracle, or whose
+/// language isn't known, are passed over; a file that can't be read or
+/// written is told of and passed over too, and fails the batch at its end.
+pub fn transform_secular_tree(dir: &Path, output_dir: &Path, extensions: &[String], options: &MiracleOptions) -> Result<(), DivineError> {
+    let extensions: Vec<String> = extensions.iter().map(|ext| ext.trim_start_matches('.').to_lowercase()).collect();
+    if options.language.is_none() {
+        for ext in &extensions {
+            miracle::of(Path::new(&format!("secular.{}", ext))).map_err(|_| DivineError::Other(format!(
+                "--extensions names .{}, of no language a miracle knows; it knows {}", ext, miracle::known()
+            )))?;
+        }
+    }
+    // What is written under the directory isn't transformed again
+    let written_to = (!options.in_place).then(|| fs::canonicalize(output_dir).ok()).flatten();
+    let mut found = Vec::new();
+    walk::files(dir, &|path| {
+        let ext = path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        (extensions.is_empty() || extensions.contains(&ext))
+            && written_to.as_ref().is_none_or(|written_to| !fs::canonicalize(path).is_ok_and(|path| path.starts_with(written_to)))
+    }, &mut found);
+    found.sort();
+    let (found, apocryphal) = canon::Canon::for_dir(dir).map_err(DivineError::Other)?.sift(found);
+    canon::report_skipped(apocryphal);
+    let files: Vec<(PathBuf, &miracle::Language)> = found.iter()
+        .filter_map(|file| Some((file.clone(), options.language.map_or_else(|| miracle::of(file), miracle::named).ok()?)))
+        .collect();
+    let unknown = found.len() - files.len();
+    
+    if options.rehearsal.is_none() {
+        println!("{}", format!("🕊️ Beginning miraculous transformation of {} file{} of secular code...",
+            files.len(), if files.len() == 1 { "" } else { "s" }).bright_blue());
+    }
+    let (mut transformed, mut marked, mut failed, mut substitutions) = (0, 0, 0, 0);
+    for (i, (file, language)) in files.iter().enumerate() {
+        let relative = file.strip_prefix(dir).unwrap_or(file);
+        let destination = if options.in_place { file.clone() } else { output_dir.join(relative) };
+        let progress = format!("[{}/{}]", i + 1, files.len());
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("{}", environment::for_stderr(format!("⚠️ {} Failed to read {}: {}", progress, file.display(), e).yellow()));
+                failed += 1;
+                continue;
+            }
+        };
+        if !options.retransform && bears_miracle_mark(&content, language) {
+            println!("{}", format!("{} ⏭️ {} already bears the mark of a miracle", progress, relative.display()).dimmed());
+            marked += 1;
+            continue;
+        }
+        
+        let rules = miracle::rules(language, options.liturgy);
+        let transformation = Transformation::new(&content, language, &rules);
+        substitutions += transformation.substitutions();
+        if let Some(rehearsal) = options.rehearsal {
+            rehearse(rehearsal, &file.display().to_string(), &destination.display().to_string(), language, &rules, &transformation);
+            transformed += 1;
+            continue;
+        }
+        let written = destination.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| DivineError::Io(format!("Failed to create the directory of {}: {}", destination.display(), e)))
+            .and_then(|_| write_miracle(file, &destination, options.in_place, &transformation));
+        match written {
+            Ok(backup) => {
+                let to = match backup {
+                    Some(backup) => format!("the original rests in {}", backup.display()),
+                    None => format!("→ {}", destination.display()),
+                };
+                println!("{} ✨ {} {} ({} substitution{})", progress, relative.display(), to,
+                    transformation.substitutions(), if transformation.substitutions() == 1 { "" } else { "s" });
+                transformed += 1;
+            }
+            Err(e) => {
+                eprintln!("{}", environment::for_stderr(format!("⚠️ {} {}", progress, e).yellow()));
+                failed += 1;
+            }
+        }
+    }
+    
+    let verb = if options.rehearsal.is_some() { "would be transformed" } else { "transformed" };
+    println!("{}", format!("\n✨ {} file{} {}, {} skipped ({} already transformed, {} of a language a miracle doesn't know), {} failed; {} substitution{} in all",
+        transformed, if transformed == 1 { "" } else { "s" }, verb, marked + unknown, marked, unknown, failed,
+        substitutions, if substitutions == 1 { "" } else { "s" }).bright_yellow());
+    if failed > 0 {
+        return Err(DivineError::Other(format!("{} file{} could not be transformed", failed, if failed == 1 { "" } else { "s" })));
+    }
+    Ok(())
+}
+
+/// Undoes a miracle: strips the header and footer it added and reverses each
+/// of its substitutions outside strings and comments. Anything else is left
+/// as it is. The language is told from the output's extension unless
+/// `language` names it, and the rules of a `liturgy` are undone too. With
+/// the source `map` of the miracle, the header and footer are those it
+/// records, and each line goes back to the line it came from.
+pub fn secularize(input_path: &Path, output_path: &Path, language: Option<&str>, liturgy: Option<&miracle::Liturgy>,
+              map: Option<&miracle::SourceMap>, report: bool) -> Result<(), DivineError> {
+    let language = language.map_or_else(|| miracle::of(output_path), miracle::named).map_err(DivineError::Other)?;
+    let rules = miracle::rules(language, liturgy);
+    let content = fs::read_to_string(input_path)
+        .map_err(|e| DivineError::Io(format!("Failed to read sanctified code: {}", e)))?;
+    
+    println!("{}", "🍂 Returning sanctified code to the secular world...".bright_blue());
+    
+    let crlf = content.contains("\r\n");
+    let content = lexer::without_bom(&content).replace("\r\n", "\n");
+    let body = match map {
+        Some(map) => {
+            let lines: Vec<&str> = content.lines().collect();
+            if map.footer.1 != lines.len() || map.header.1 >= map.footer.0 {
+                return Err(DivineError::Other(format!(
+                    "The source map of {} doesn't fit {}: it maps {} lines, and {} has {}",
+                    map.source, input_path.display(), map.footer.1, input_path.display(), lines.len()
+                )));
+            }
+            lines[map.header.1..map.footer.0 - 1].iter().map(|line| format!("{}\n", line)).collect()
+        }
+        None => without_miracle_frame(&content, language).to_string(),
+    };
+    
+    let performed = miracle::perform(&body, language, &rules, true);
+    let mut secular = match map {
+        Some(map) => {
+            // Blank lines stand in for those the miracle joined, or the
+            // header an earlier miracle wrote
+            let mut placed = String::with_capacity(performed.code.len());
+            let mut next = 1;
+            for (at, line) in performed.code.split_inclusive('\n').enumerate() {
+                if let miracle::Origin::Line(original) = map.origin(map.header.1 + performed.lines[at] + 1) {
+                    while next < original {
+                        placed.push('\n');
+                        next += 1;
+                    }
+                }
+                placed.push_str(line);
+                next += 1;
+            }
+            placed
+        }
+        None => performed.code,
+    };
+    let applied = performed.counts;
+    if crlf {
+        secular = secular.replace('\n', "\r\n");
+    }
+    
+    sepulchre::entomb(output_path).map_err(DivineError::Io)?;
+    fs::write(output_path, secular)
+        .map_err(|e| DivineError::Io(format!("Failed to write secular code: {}", e)))?;
+    sepulchre::seal(output_path).map_err(DivineError::Io)?;
+    
+    if report {
+        println!("{}", format!("Reverse substitutions ({}):", language.name).underline().bright_blue());
+        for (rule, count) in rules.iter().zip(&applied) {
+            println!("  {:>4}  {} → {}  {}", count, rule.divine, rule.secular, format!("({})", rule.id).dimmed());
+        }
+    }
+    println!("{}", format!("Sanctified code has been returned to the world and saved to: {}", output_path.display()).green());
+    
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use colored::*;
+
+/// The exit status of a run interrupted by Ctrl-C, as a shell gives a process
+/// killed by SIGINT.
+pub const EXIT_CODE: i32 = 130;
+
+/// Whether a run that Ctrl-C can stop is in progress.
+static ENDURING: AtomicBool = AtomicBool::new(false);
+
+/// Whether Ctrl-C has been pressed during the run in progress.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether a watch is being kept, which Ctrl-C ends in peace.
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+/// Takes over Ctrl-C. While a run endures, the first press asks it to stop
+/// between statements so it can be summed up; a second press, or any press
+/// while no run is in progress, ends the process at once, or ends a watch
+/// with a blessing.
+pub fn install() -> Result<(), String> {
+    ctrlc::set_handler(|| {
+        if ENDURING.load(Ordering::SeqCst) && !INTERRUPTED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        if WATCHING.load(Ordering::SeqCst) {
+            println!("\n{}", "🕊️ The watch is ended. Go in peace.".bright_cyan());
+            process::exit(0);
+        }
+        process::exit(EXIT_CODE);
+    }).map_err(|e| format!("Failed to take over Ctrl-C: {}", e))
+}
+
+/// Makes the Ctrl-C that would end the process end it as a watch that was
+/// kept, successfully.
+pub fn keep_watch() {
+    WATCHING.store(true, Ordering::SeqCst);
+}
+
+/// Calls `run` as a run Ctrl-C can interrupt. Once it returns, the next press
+/// ends the process, and the next run starts uninterrupted.
+pub fn endure<T>(run: impl FnOnce() -> T) -> T {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    ENDURING.store(true, Ordering::SeqCst);
+    let outcome = run();
+    ENDURING.store(false, Ordering::SeqCst);
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    outcome
+}
+
+/// Whether Ctrl-C has been pressed during the run in progress.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,403 @@
+//! The verses scripture is searched and imported from: those the runtime was
+//! born knowing, with any verse packs merged over them.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use colored::*;
+
+use crate::config::Commandments;
+use crate::environment;
+use serde::Deserialize;
+
+/// The verse pack read for every project, in ~/.divinepl.
+const HOME_PACK: &str = "verses.toml";
+
+/// The verses known without any pack: the topic, the verse, and the words
+/// besides the topic that call it to mind.
+const BUILT_IN: &[(&str, &str, &[&str])] = &[
+    ("creation", "In the beginning God created the heaven and the earth. (Genesis 1:1)", &["create", "new", "let", "genesis"]),
+    ("light", "And God said, Let there be light: and there was light. (Genesis 1:3)", &["illuminate", "shine"]),
+    ("error", "For all have sinned, and come short of the glory of God. (Romans 3:23)", &["confess", "forgive", "transgress", "sin"]),
+    ("wisdom", "The fear of the LORD is the beginning of wisdom. (Proverbs 9:10)", &["judge", "else"]),
+    ("debug", "Prove all things; hold fast that which is good. (1 Thessalonians 5:21)", &["print", "revelation"]),
+    ("loop", "And let us not be weary in well doing: for in due season we shall reap, if we faint not. (Galatians 6:9)", &["preach", "while", "for"]),
+    ("concurrency", "For where two or three are gathered together in my name, there am I in the midst of them. (Matthew 18:20)", &["thread", "spawn", "process", "processes"]),
+    ("promise", "For I know the thoughts that I think toward you, saith the LORD, thoughts of peace, and not of evil, to give you an expected future. (Jeremiah 29:11)", &["covenant", "fulfill"]),
+];
+
+pub struct Verse {
+    pub text: String,
+    /// Words besides the topic that call the verse to mind.
+    pub tags: Vec<String>,
+}
+
+/// Where verses came from: the built-in ones, or a pack.
+pub struct Source {
+    pub name: String,
+    pub verses: usize,
+    /// How many of its verses replaced one given before it.
+    pub overriding: usize,
+    /// Why the pack gave no verses, when it was malformed or unreadable.
+    pub trouble: Option<String>,
+}
+
+pub struct Scripture {
+    pub verses: HashMap<String, Verse>,
+    pub sources: Vec<Source>,
+}
+
+/// The built-in verses with every pack merged over them, gathered once.
+pub fn scripture() -> &'static Scripture {
+    static SCRIPTURE: OnceLock<Scripture> = OnceLock::new();
+    SCRIPTURE.get_or_init(|| Scripture::gather(&packs()))
+}
+
+/// The packs to read, in order: ~/.divinepl/verses.toml where there is one,
+/// then the `verse_packs` of the commandments over the current directory.
+fn packs() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".divinepl").join(HOME_PACK))
+        .filter(|pack| pack.is_file());
+    let listed = Commandments::discover(Path::new("."))
+        .and_then(|path| Commandments::load(&path).ok())
+        .map(|commandments| commandments.verse_packs())
+        .unwrap_or_default();
+    home.into_iter().chain(listed).collect()
+}
+
+impl Scripture {
+    /// Merges each of `packs` over the built-in verses, a later one replacing
+    /// any verse an earlier one gave. A pack that can't be read is warned
+    /// about and gives nothing.
+    fn gather(packs: &[PathBuf]) -> Self {
+        let mut verses: HashMap<_, _> = BUILT_IN.iter()
+            .map(|(topic, text, tags)| (topic.to_string(), Verse {
+                text: text.to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            }))
+            .collect();
+        let mut sources = vec![Source { name: "built-in".to_string(), verses: verses.len(), overriding: 0, trouble: None }];
+
+        for pack in packs {
+            let mut source = Source { name: pack.display().to_string(), verses: 0, overriding: 0, trouble: None };
+            match read(pack) {
+                Ok(given) => {
+                    source.verses = given.len();
+                    for (topic, verse) in given {
+                        if verses.insert(topic, verse).is_some() {
+                            source.overriding += 1;
+                        }
+                    }
+                }
+                Err(trouble) => {
+                    eprintln!("{}", environment::for_stderr(format!("⚠️ {}; its verses were passed over", trouble).yellow()));
+                    source.trouble = Some(trouble);
+                }
+            }
+            sources.push(source);
+        }
+
+        Self { verses, sources }
+    }
+}
+
+/// A verse pack as it is written: `{"verses": [{"key": ..., "text": ...,
+/// "tags": [...]}]}` in JSON, or a `[[verses]]` table for each verse in TOML:
+///
+/// ```toml
+/// [[verses]]
+/// key = "patience"
+/// text = "But let patience have her perfect work. (James 1:4)"
+/// tags = ["wait", "sleep"]
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Pack {
+    verses: Vec<Entry>,
+}
+
+/// A verse as a pack gives it, before it is known to be whole.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    key: Option<String>,
+    text: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl Entry {
+    /// The verse's key and the verse, or why it isn't whole; `at` says where
+    /// in the pack it is given.
+    fn finish(self, at: &str) -> Result<(String, Verse), String> {
+        let key = self.key.map(|key| key.trim().to_lowercase()).filter(|key| !key.is_empty())
+            .ok_or_else(|| format!("{}: the verse has no \"key\"", at))?;
+        let text = self.text.filter(|text| !text.trim().is_empty())
+            .ok_or_else(|| format!("{}: the verse \"{}\" has no \"text\"", at, key))?;
+        Ok((key, Verse { text, tags: self.tags.iter().map(|tag| tag.to_lowercase()).collect() }))
+    }
+}
+
+/// The verses of the pack at `path`: JSON when it ends in .json, and TOML
+/// otherwise. Nothing is taken from a pack unless all of it is well formed.
+fn read(path: &Path) -> Result<Vec<(String, Verse)>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read the verse pack {}: {}", path.display(), e))?;
+    let pack = if path.extension().is_some_and(|extension| extension == "json") {
+        serde_json::from_str::<Pack>(&content).map_err(|e| e.to_string())
+    } else {
+        toml::from_str::<Pack>(&content).map_err(|e| toml_error(&content, &e))
+    };
+    pack.and_then(|pack| pack.verses.into_iter().enumerate()
+            .map(|(index, entry)| entry.finish(&format!("verse {}", index + 1)))
+            .collect())
+        .map_err(|e| format!("Malformed verse pack {}: {}", path.display(), e))
+}
+
+/// A TOML error on one line, with the line of `content` it was found on.
+fn toml_error(content: &str, error: &toml::de::Error) -> String {
+    let message = error.message().trim_end();
+    match error.span() {
+        Some(span) => format!("line {}: {}", content[..span.start].matches('\n').count() + 1, message),
+        None => message.to_string(),
+    }
+}
+
+/// How alike two words must be for one to be taken as a misspelling of the other.
+const FUZZY_THRESHOLD: f64 = 0.7;
+
+/// How alike a word must be to a topic to be suggested in its place.
+const SUGGESTION_THRESHOLD: f64 = 0.5;
+
+/// Why a verse was found for a topic, best first.
+pub enum Reason {
+    /// The topic is the verse's key.
+    Exact,
+    /// The topic is one of the verse's tags.
+    Tagged,
+    /// The topic is part of the verse's key or text.
+    Contains,
+    /// The topic is a near miss for the key or a tag.
+    Similar { word: &'static str, similarity: f64 },
+}
+
+pub struct Found {
+    pub key: &'static str,
+    pub verse: &'static Verse,
+    pub reason: Reason,
+}
+
+impl Found {
+    /// What to say about why the verse was found, if anything.
+    pub fn explanation(&self, topic: &str) -> Option<String> {
+        match self.reason {
+            Reason::Exact => None,
+            Reason::Tagged => Some(format!("tagged '{}'", topic)),
+            Reason::Contains => Some(format!("mentions '{}'", topic)),
+            Reason::Similar { word, similarity } => Some(format!("matched '{}', similarity {:.2}", word, similarity)),
+        }
+    }
+}
+
+/// How alike two words are, from 0 for nothing in common to 1 for the same word.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - crate::edit_distance(a, b) as f64 / longest as f64
+}
+
+/// The key of `verse` and its tags.
+fn words(key: &'static str, verse: &'static Verse) -> impl Iterator<Item = &'static str> {
+    std::iter::once(key).chain(verse.tags.iter().map(String::as_str))
+}
+
+/// The verses `topic` finds, best first: the verse it is the key of, those
+/// tagged with it, those that mention it, and then those whose key or a tag
+/// it comes near to spelling. Verses found equally well are in order of key.
+pub fn search(verses: &'static HashMap<String, Verse>, topic: &str) -> Vec<Found> {
+    let topic = topic.to_lowercase();
+    let mut found: Vec<Found> = Vec::new();
+    for (key, verse) in verses {
+        let reason = if *key == topic {
+            Reason::Exact
+        } else if verse.tags.contains(&topic) {
+            Reason::Tagged
+        } else if key.contains(topic.as_str()) || verse.text.to_lowercase().contains(topic.as_str()) {
+            Reason::Contains
+        } else {
+            let nearest = words(key, verse)
+                .map(|word| (word, similarity(word, &topic)))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            match nearest {
+                Some((word, similarity)) if similarity >= FUZZY_THRESHOLD => Reason::Similar { word, similarity },
+                _ => continue,
+            }
+        };
+        found.push(Found { key, verse, reason });
+    }
+    found.sort_by(|a, b| rank(&a.reason).total_cmp(&rank(&b.reason)).then(a.key.cmp(b.key)));
+    found
+}
+
+/// Orders reasons best first: the lower, the better.
+fn rank(reason: &Reason) -> f64 {
+    match reason {
+        Reason::Exact => 0.0,
+        Reason::Tagged => 1.0,
+        Reason::Contains => 2.0,
+        Reason::Similar { similarity, .. } => 4.0 - similarity,
+    }
+}
+
+/// Up to three keys or tags that `topic` might have meant, most alike first.
+pub fn suggestions(verses: &'static HashMap<String, Verse>, topic: &str) -> Vec<&'static str> {
+    let topic = topic.to_lowercase();
+    let mut near: Vec<(&'static str, f64)> = verses.iter()
+        .flat_map(|(key, verse)| words(key, verse))
+        .map(|word| (word, similarity(word, &topic)))
+        .filter(|(_, similarity)| *similarity >= SUGGESTION_THRESHOLD)
+        .collect();
+    near.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+    near.dedup_by_key(|(word, _)| *word);
+    near.into_iter().take(3).map(|(word, _)| word).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The verses given, as search takes them.
+    fn verses(given: &[(&str, &[&str])]) -> &'static HashMap<String, Verse> {
+        Box::leak(Box::new(given.iter().map(|(key, tags)| {
+            let verse = Verse { text: format!("A verse of {}.", key), tags: tags.iter().map(|tag| tag.to_string()).collect() };
+            (key.to_string(), verse)
+        }).collect()))
+    }
+
+    /// A directory of packs for one test, removed when it is done.
+    struct Packs(PathBuf);
+
+    impl Packs {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("divinepl-verses-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, name: &str, content: &str) -> PathBuf {
+            let path = self.0.join(name);
+            fs::write(&path, content).unwrap();
+            path
+        }
+    }
+
+    impl Drop for Packs {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn a_pack_is_toml_unless_it_ends_in_json() {
+        let packs = Packs::new("formats");
+        let toml = packs.write("p.toml", "[[verses]]\nkey = \"Patience\"\ntext = \"Let patience have her perfect work.\"\ntags = [\"Wait\"]\n");
+        let json = packs.write("p.json", r#"{"verses": [{"key": "humility", "text": "Pride goeth before destruction."}]}"#);
+
+        let given = read(&toml).unwrap();
+        assert_eq!(given.len(), 1);
+        // Keys and tags are found whatever their case
+        assert_eq!(given[0].0, "patience");
+        assert_eq!(given[0].1.tags, ["wait"]);
+        let given = read(&json).unwrap();
+        assert_eq!((given[0].0.as_str(), given[0].1.text.as_str()), ("humility", "Pride goeth before destruction."));
+        assert!(given[0].1.tags.is_empty());
+    }
+
+    #[test]
+    fn a_malformed_pack_is_refused_whole_saying_where() {
+        let packs = Packs::new("malformed");
+        let broken = packs.write("broken.toml", "[[verses]]\nkey = \"patience\"\ntext = \"unending\n");
+        let error = read(&broken).err().unwrap();
+        assert!(error.starts_with(&format!("Malformed verse pack {}: line 3:", broken.display())), "{}", error);
+
+        let textless = packs.write("textless.json", r#"{"verses": [{"key": "a", "text": "A."}, {"key": "b"}]}"#);
+        assert!(read(&textless).err().unwrap().ends_with(r#"verse 2: the verse "b" has no "text""#));
+        let keyless = packs.write("keyless.toml", "[[verses]]\nkey = \" \"\ntext = \"A.\"\n");
+        assert!(read(&keyless).err().unwrap().ends_with(r#"verse 1: the verse has no "key""#));
+        let unknown = packs.write("unknown.json", r#"{"verses": [{"key": "a", "text": "A.", "chapter": 3}]}"#);
+        assert!(read(&unknown).err().unwrap().contains("unknown field `chapter`"));
+    }
+
+    #[test]
+    fn packs_are_merged_over_the_built_in_verses_in_order() {
+        let packs = Packs::new("merged");
+        let first = packs.write("first.toml", "[[verses]]\nkey = \"light\"\ntext = \"First light.\"\n[[verses]]\nkey = \"patience\"\ntext = \"Wait.\"\n");
+        let broken = packs.write("broken.json", "{");
+        let second = packs.write("second.json", r#"{"verses": [{"key": "patience", "text": "Wait on.", "tags": ["sleep"]}]}"#);
+
+        let scripture = Scripture::gather(&[first.clone(), broken.clone(), second.clone()]);
+        assert_eq!(scripture.verses.len(), BUILT_IN.len() + 1);
+        assert_eq!(scripture.verses["light"].text, "First light.");
+        assert_eq!(scripture.verses["patience"].text, "Wait on.");
+        assert_eq!(scripture.verses["patience"].tags, ["sleep"]);
+
+        let sources: Vec<(&str, usize, usize, bool)> = scripture.sources.iter()
+            .map(|source| (source.name.as_str(), source.verses, source.overriding, source.trouble.is_some()))
+            .collect();
+        let (first, broken, second) = (first.display().to_string(), broken.display().to_string(), second.display().to_string());
+        assert_eq!(sources, [
+            ("built-in", BUILT_IN.len(), 0, false),
+            (first.as_str(), 2, 1, false),
+            (broken.as_str(), 0, 0, true),
+            (second.as_str(), 1, 1, false),
+        ]);
+    }
+
+    #[test]
+    fn similarity_runs_from_nothing_in_common_to_the_same_word() {
+        assert_eq!(similarity("light", "light"), 1.0);
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("light", ""), 0.0);
+        assert_eq!(similarity("abc", "xyz"), 0.0);
+    }
+
+    #[test]
+    fn similarity_is_the_share_of_the_longer_word_left_unedited() {
+        assert_eq!(similarity("creaton", "creation"), 0.875);
+        assert_eq!(similarity("creation", "creaton"), 0.875);
+        assert_eq!(similarity("debgu", "debug"), 0.6);
+        // Letters are counted, not bytes
+        assert_eq!(similarity("grâce", "grace"), 0.8);
+    }
+
+    #[test]
+    fn search_finds_verses_best_first() {
+        let verses = verses(&[("light", &["shine"]), ("lamp", &["light"]), ("night", &[])]);
+        let found = search(verses, "Light");
+        let keys: Vec<&str> = found.iter().map(|found| found.key).collect();
+        assert_eq!(keys, ["light", "lamp", "night"]);
+        assert_eq!(found[0].explanation("light"), None);
+        assert_eq!(found[1].explanation("light").as_deref(), Some("tagged 'light'"));
+        assert_eq!(found[2].explanation("light").as_deref(), Some("matched 'night', similarity 0.80"));
+    }
+
+    #[test]
+    fn search_passes_over_words_too_unlike_the_topic() {
+        let verses = verses(&[("debug", &[]), ("loop", &[])]);
+        assert!(search(verses, "dbg").is_empty());
+    }
+
+    #[test]
+    fn suggestions_are_the_three_nearest_words_above_the_threshold() {
+        let verses = verses(&[("creation", &["create", "new"]), ("covenant", &["crease", "creed"]), ("crate", &[])]);
+        // crate, at 0.6, is the fourth nearest; creed, at 0.4, is too far
+        assert_eq!(suggestions(verses, "creat"), ["create", "crease", "creation"]);
+        assert!(suggestions(verses, "zzz").is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+use std::path::Path;
+
+use serde::Serialize;
+use crate::prophesy::{Marker, Vision, DIVINE_TODOS};
+
+/// The forms `prophesy --output` writes a vision in.
+#[derive(Clone, Copy)]
+pub enum Format {
+    Markdown,
+    Json,
+}
+
+impl Format {
+    /// The form a report at `path` is written in, by its extension.
+    pub fn for_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|extension| extension.to_str()).map(str::to_lowercase).as_deref() {
+            Some("md" | "markdown") => Ok(Format::Markdown),
+            Some("json") => Ok(Format::Json),
+            _ => Err(format!("Prophecies are written as Markdown (.md) or JSON (.json), so {} can't hold them", path.display())),
+        }
+    }
+}
+
+/// A vision as Markdown, for planning documents: the prophecies of the ledger
+/// it fulfilled, what was declared in the scripts, grouped by script and
+/// marker, then, unless nothing was divined, the risks foretold with the lines
+/// that gave them away, for a project each script's prophecies and doom, the
+/// most doomed and what was seen across the scripts, the divine TODOs and the
+/// final revelation, and last the doom.
+pub fn markdown(vision: &Vision) -> String {
+    let mut report = format!("# Prophecies for {}\n\n", vision.script);
+    if !vision.fulfilled.is_empty() {
+        report.push_str("## Fulfilled since last vision\n\n");
+        for entry in &vision.fulfilled {
+            report.push_str(&format!("- {}: {} (foretold {})\n", entry.script, entry.text, entry.foretold.format("%Y-%m-%d")));
+        }
+        report.push('\n');
+    }
+    report.push_str("## Declared\n\n");
+    if vision.declared.is_empty() {
+        report.push_str("None were declared.\n");
+    }
+    for declared in vision.declared.chunk_by(|a, b| a.file == b.file) {
+        report.push_str(&format!("### {}\n", declared[0].file));
+        for marker in Marker::ALL {
+            let mut marked = declared.iter().filter(|declaration| declaration.marker == marker).peekable();
+            if marked.peek().is_none() {
+                continue;
+            }
+            report.push_str(&format!("\n#### {}\n\n", marker.as_str()));
+            for declaration in marked {
+                match &declaration.decorated {
+                    Some(decorated) => report.push_str(&format!("- Line {} (`{}`): {}\n", declaration.line, decorated, declaration.text)),
+                    None => report.push_str(&format!("- Line {}: {}\n", declaration.line, declaration.text)),
+                }
+            }
+        }
+        report.push('\n');
+    }
+    let Some(revelation) = vision.revelation() else {
+        report.push_str(&format!("\n## Doom\n\n{}\n", vision.doom()));
+        return report;
+    };
+
+    if !vision.project {
+        report.push_str("\n## Detected risks\n\n| # | Category | Line | Doom | Prophecy |\n|---|----------|------|------|----------|\n");
+        for (i, prophecy) in vision.prophecies.iter().enumerate() {
+            let line = prophecy.line.map_or("-".to_string(), |line| line.to_string());
+            report.push_str(&format!("| {} | {} | {} | {} | {} |\n",
+                i + 1, prophecy.category.as_str(), line, prophecy.doom, prophecy.text.replace('|', "\\|")));
+        }
+    } else {
+        report.push_str("\n## Detected risks\n\n| # | Script | Category | Line | Doom | Prophecy |\n|---|--------|----------|------|------|----------|\n");
+        for (i, prophecy) in vision.prophecies.iter().enumerate() {
+            let line = prophecy.line.map_or("-".to_string(), |line| line.to_string());
+            report.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+                i + 1, prophecy.file, prophecy.category.as_str(), line, prophecy.doom, prophecy.text.replace('|', "\\|")));
+        }
+
+        report.push_str("\n## Prophecies by script\n\n| Script | Prophecies | Doom |\n|--------|------------|------|\n");
+        for (script, prophecies, doom) in vision.by_script() {
+            report.push_str(&format!("| {} | {} | {} |\n", script, prophecies, doom));
+        }
+
+        let most_doomed = vision.most_doomed();
+        if !most_doomed.is_empty() {
+            report.push_str("\n## Most doomed\n\n");
+            for (i, (script, doom)) in most_doomed.iter().enumerate() {
+                report.push_str(&format!("{}. {} (doom {})\n", i + 1, script, doom));
+            }
+        }
+
+        if !vision.observations.is_empty() {
+            report.push_str("\n## Across the scripts\n\n");
+            for observation in &vision.observations {
+                report.push_str(&format!("- {}\n", observation));
+            }
+        }
+    }
+
+    report.push_str("\n## Divine TODOs\n\n");
+    for todo in DIVINE_TODOS {
+        report.push_str(&format!("- [ ] {}\n", todo));
+    }
+
+    report.push_str(&format!("\n## Final revelation\n\n{}\n", revelation));
+    report.push_str(&format!("\n## Doom\n\n{}\n", vision.doom()));
+    report
+}
+
+/// A vision as JSON: an array of prophecies, each with its `kind` (fulfilled,
+/// declared, divined, todo, revelation or doom), `message` and `severity`, and
+/// the `line`, `category`, `age_days` in the ledger and `doom` where it has
+/// them. What was declared has its `marker` and `file`, and what was fulfilled
+/// its `file` and the day it was `foretold`. For a project, each divined
+/// prophecy has its `file` too, and after them come a `script` for each
+/// script, with its `prophecies` and `doom`, and each `observation`. The doom
+/// comes last, with its `score`.
+pub fn json(vision: &Vision) -> Vec<Prophecy<'_>> {
+    let prophecy = |kind: &'static str, message: &str, severity: &'static str| Prophecy {
+        kind,
+        message: message.to_string(),
+        severity,
+        ..Prophecy::default()
+    };
+    let declared = vision.declared.iter().map(|declaration| Prophecy {
+        marker: Some(declaration.marker.as_str()),
+        file: Some(&declaration.file),
+        line: Some(declaration.line),
+        decorates: declaration.decorated.as_deref(),
+        age_days: aged(declaration.age),
+        doom: doomed(declaration.doom),
+        // What is marked as broken or makeshift is warned of; the rest is only told
+        ..prophecy("declared", &declaration.text, if matches!(declaration.marker, Marker::Fixme | Marker::Hack) { "warning" } else { "info" })
+    });
+    let divined = vision.prophecies.iter().map(|foretold| Prophecy {
+        category: Some(foretold.category.as_str()),
+        line: foretold.line,
+        file: vision.project.then_some(foretold.file.as_str()),
+        age_days: aged(foretold.age),
+        doom: doomed(foretold.doom),
+        // Risks seen in the code are warned of; those foretold by chance are only told
+        ..prophecy("divined", &foretold.text, if foretold.line.is_some() { "warning" } else { "info" })
+    });
+    let fulfilled = vision.fulfilled.iter().map(|entry| Prophecy {
+        file: Some(&entry.script),
+        foretold: Some(entry.foretold.format("%Y-%m-%d").to_string()),
+        ..prophecy("fulfilled", &entry.text, "info")
+    });
+    let mut prophecies: Vec<Prophecy> = fulfilled.chain(declared).chain(divined).collect();
+    if vision.project && vision.revelation().is_some() {
+        prophecies.extend(vision.by_script().into_iter().map(|(script, foretold, doom)| Prophecy {
+            file: Some(script),
+            prophecies: Some(foretold),
+            doom: Some(doom),
+            ..prophecy("script", &format!("{}: {} {}, doom {}", script, foretold, if foretold == 1 { "prophecy" } else { "prophecies" }, doom), if doom > 0 { "warning" } else { "info" })
+        }));
+        prophecies.extend(vision.observations.iter().map(|observation| prophecy("observation", observation, "warning")));
+    }
+    if let Some(revelation) = vision.revelation() {
+        prophecies.extend(DIVINE_TODOS.iter().map(|todo| prophecy("todo", todo, "info")));
+        prophecies.push(prophecy("revelation", revelation, if vision.hopeful == Some(true) { "info" } else { "warning" }));
+    }
+    let doom = vision.doom();
+    prophecies.push(Prophecy {
+        score: Some(doom),
+        ..prophecy("doom", &format!("The doom foretold is {}", doom), if doom > 0 { "warning" } else { "info" })
+    });
+    prophecies
+}
+
+/// A prophecy of a vision as JSON, with only the fields it has.
+#[derive(Default, Serialize)]
+pub struct Prophecy<'a> {
+    kind: &'static str,
+    message: String,
+    severity: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    marker: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decorates: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    foretold: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prophecies: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    age_days: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    doom: Option<usize>,
+}
+
+/// The days a prophecy has stood in the ledger, once recorded there.
+fn aged(age: Option<i64>) -> Option<usize> {
+    age.map(|age| age.max(0) as usize)
+}
+
+/// The doom a prophecy spells, when it spells any.
+fn doomed(doom: usize) -> Option<usize> {
+    (doom > 0).then_some(doom)
+}
@@ -0,0 +1,66 @@
+//! How directory operations find their scripts: walking a directory for
+//! them, then leaving out the apocrypha and whatever else they are told to.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::canon::Canon;
+use crate::config::Commandments;
+
+/// Every script in the project, skipping hidden directories such as .git and .divinepl.
+/// A directory reached again through a symbolic link is walked only once.
+pub fn scripts(dir: &Path, found: &mut Vec<PathBuf>) {
+    files(dir, &|path| matches!(path.extension().and_then(|ext| ext.to_str()), Some("divine" | "dpl")), found);
+}
+
+/// Every file under `dir` that `keep` keeps, walked as `scripts` walks.
+pub fn files(dir: &Path, keep: &dyn Fn(&Path) -> bool, found: &mut Vec<PathBuf>) {
+    walk(dir, keep, found, &mut HashSet::new());
+}
+
+fn walk(dir: &Path, keep: &dyn Fn(&Path) -> bool, found: &mut Vec<PathBuf>, visited: &mut HashSet<PathBuf>) {
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    // A link back up the tree would otherwise be followed forever
+    if fs::canonicalize(dir).is_ok_and(|canonical| !visited.insert(canonical)) {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if path.is_dir() && !hidden {
+            walk(&path, keep, found, visited);
+        } else if path.is_file() && keep(&path) {
+            found.push(path);
+        }
+    }
+}
+
+/// Every canonical script under `dir`, in order, and how many apocryphal ones
+/// were passed over.
+pub fn canonical_scripts(dir: &Path) -> Result<(Vec<PathBuf>, usize), String> {
+    let mut found = Vec::new();
+    scripts(dir, &mut found);
+    found.sort();
+    Ok(Canon::for_dir(dir)?.sift(found))
+}
+
+/// Every canonical script under `dir`, in order, except those whose paths
+/// relative to it start with one of `ignore` or with an entry of the
+/// commandments' `key` list, and how many apocryphal ones were passed over.
+pub fn scripts_heeding(dir: &Path, key: &str, ignore: &[String]) -> Result<(Vec<PathBuf>, usize), String> {
+    let mut ignored: Vec<String> = ignore.to_vec();
+    if let Some(commandments) = Commandments::for_script(dir)? {
+        let listed = commandments.settings.get(key).and_then(Value::as_array).into_iter().flatten();
+        ignored.extend(listed.filter_map(Value::as_str).map(str::to_string));
+    }
+    let (mut found, skipped) = canonical_scripts(dir)?;
+    found.retain(|script| {
+        let relative = script.strip_prefix(dir).unwrap_or(script);
+        !ignored.iter().any(|entry| relative.starts_with(entry.trim_end_matches('/')))
+    });
+    Ok((found, skipped))
+}
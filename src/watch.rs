@@ -0,0 +1,58 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::Local;
+use colored::*;
+
+use crate::{modules, tribulation};
+
+/// How often the watched files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long the files must stay unchanged after a save before the script is
+/// run again, so an editor's burst of writes causes only one run.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Calls `interpret` now and again every time `script`, or a module it imports,
+/// changes. `interpret` reports its own errors, so a fallen run never ends the
+/// watch; only Ctrl-C does, with a blessing.
+pub fn watch(script: &Path, mut interpret: impl FnMut()) -> ! {
+    tribulation::keep_watch();
+    loop {
+        interpret();
+        println!("{}", format!("👀 Watching {} for changes to the scripture...", script.display()).bright_blue());
+        let _ = io::stdout().flush();
+
+        let mut seen = snapshot(script);
+        // Wait for a change, then for the saving to settle
+        let mut changed_at = None;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let now = snapshot(script);
+            if now != seen {
+                seen = now;
+                changed_at = Some(Instant::now());
+            } else if changed_at.is_some_and(|at| at.elapsed() >= DEBOUNCE) {
+                break;
+            }
+        }
+
+        print!("\x1b[2J\x1b[H");
+        println!("{}", format!("🔔 [{}] The scripture has changed, re-interpreting...", Local::now().format("%H:%M:%S")).bright_yellow());
+    }
+}
+
+/// The files to watch and when each was last modified; a file that is
+/// missing has no time, so its return is noticed too.
+fn snapshot(script: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    std::iter::once(script.to_path_buf())
+        .chain(modules::imported_files(script))
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
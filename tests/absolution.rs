@@ -0,0 +1,94 @@
+mod common;
+
+use common::Sandbox;
+
+/// The porcelain sin lines of confessing with `args` as if it were `day`, and
+/// what was warned.
+fn sins_on(sandbox: &Sandbox, day: &str, args: &[&str]) -> (Vec<String>, String) {
+    let mut command = vec!["--porcelain", "--dev", "--override-sabbath", "--pretend-today", day, "confess"];
+    command.extend(args);
+    let outcome = sandbox.run(&command);
+    let sins = outcome.stdout.lines().filter(|line| line.starts_with("sin ")).map(str::to_string).collect();
+    (sins, outcome.stderr)
+}
+
+#[test]
+fn forgiveness_lasts_through_its_day_and_no_longer() {
+    let sandbox = Sandbox::new("forgiveness-until");
+    sandbox.write("s.divine", "var x = 1; // forgiven: DPL003 until: 2025-06-01 the old scrolls need it\n");
+
+    let (sins, _) = sins_on(&sandbox, "2025-06-01", &["s.divine"]);
+    assert!(sins.is_empty(), "{:?}", sins);
+
+    let (sins, _) = sins_on(&sandbox, "2025-06-02", &["s.divine"]);
+    assert_eq!(sins, ["sin venial DPL003 s.divine:1", "sin venial DPL009 s.divine:1"]);
+}
+
+#[test]
+fn whole_script_forgiveness_expires_too() {
+    let sandbox = Sandbox::new("forgiveness-file-until");
+    sandbox.write("s.divine", "// forgiven-file: DPL003 until:2025-06-01\nvar x = 1;\n");
+    let (sins, _) = sins_on(&sandbox, "2025-06-02", &["s.divine"]);
+    assert_eq!(sins, ["sin venial DPL009 s.divine:1", "sin venial DPL003 s.divine:2"]);
+}
+
+#[test]
+fn forgiveness_until_no_day_is_warned_of_and_still_forgives() {
+    let sandbox = Sandbox::new("forgiveness-undated");
+    sandbox.write("s.divine", "var x = 1; // forgiven: DPL003 until: 2025-13-01\n");
+    let (sins, stderr) = sins_on(&sandbox, "2030-01-01", &["s.divine"]);
+    assert!(sins.is_empty(), "{:?}", sins);
+    assert!(stderr.contains("lasts until '2025-13-01', which is no day written YYYY-MM-DD"), "{}", stderr);
+}
+
+#[test]
+fn a_baseline_excuses_the_sins_it_records_until_they_are_too_old() {
+    let sandbox = Sandbox::new("baseline-age");
+    sandbox.write("commandments.config", r#"{ "baseline_max_age_days": 30 }"#);
+    sandbox.write("s.divine", "var x = 1;\n");
+    sins_on(&sandbox, "2025-06-01", &["s.divine", "--baseline", "baseline.json", "--update-baseline"]);
+    assert!(sandbox.read("baseline.json").contains(r#""created": "2025-06-01""#));
+
+    let (sins, _) = sins_on(&sandbox, "2025-07-01", &["s.divine", "--baseline", "baseline.json"]);
+    assert!(sins.is_empty(), "{:?}", sins);
+
+    let (sins, _) = sins_on(&sandbox, "2025-07-02", &["s.divine", "--baseline", "baseline.json"]);
+    assert_eq!(sins, ["sin venial DPL003 s.divine:1", "sin venial DPL009 s.divine:1"]);
+}
+
+#[test]
+fn a_baseline_only_excuses_as_many_sins_as_it_records() {
+    let sandbox = Sandbox::new("baseline-new-sins");
+    sandbox.write("s.divine", "var x = 1;\n");
+    sins_on(&sandbox, "2025-06-01", &["s.divine", "--baseline", "baseline.json", "--update-baseline"]);
+    sandbox.write("s.divine", "var x = 1;\nvar y = 2;\n");
+    let (sins, _) = sins_on(&sandbox, "2025-06-02", &["s.divine", "--baseline", "baseline.json"]);
+    assert_eq!(sins.len(), 1, "{:?}", sins);
+}
+
+#[test]
+fn updating_a_baseline_keeps_the_day_each_sin_was_first_recorded() {
+    let sandbox = Sandbox::new("baseline-update");
+    sandbox.write("s.divine", "var x = 1;\n");
+    sins_on(&sandbox, "2025-06-01", &["s.divine", "--baseline", "baseline.json", "--update-baseline"]);
+    sandbox.write("s.divine", "var x = 1;\nvar y = 2;\n");
+    sins_on(&sandbox, "2025-09-01", &["s.divine", "--baseline", "baseline.json", "--update-baseline"]);
+    let baseline = sandbox.read("baseline.json");
+    assert!(baseline.contains(r#""created": "2025-06-01""#) && baseline.contains(r#""created": "2025-09-01""#), "{}", baseline);
+}
+
+#[test]
+fn pruning_drops_what_was_repented_of_and_keeps_what_expired() {
+    let sandbox = Sandbox::new("baseline-prune");
+    sandbox.write("commandments.config", r#"{ "baseline_max_age_days": 30 }"#);
+    sandbox.write("a.divine", "var x = 1;\n");
+    sandbox.write("b.divine", "var y = 2;\n");
+    sins_on(&sandbox, "2025-06-01", &[".", "--baseline", "baseline.json", "--update-baseline"]);
+    sandbox.write("b.divine", "let y = 2;\n");
+
+    let (sins, _) = sins_on(&sandbox, "2025-08-01", &[".", "--baseline", "baseline.json", "--prune-baseline"]);
+    assert_eq!(sins, ["sin venial DPL003 ./a.divine:1", "sin venial DPL009 ./a.divine:1"]);
+    let baseline = sandbox.read("baseline.json");
+    assert!(baseline.contains("a.divine") && !baseline.contains("b.divine"), "{}", baseline);
+    assert!(baseline.contains(r#""created": "2025-06-01""#), "{}", baseline);
+}
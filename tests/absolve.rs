@@ -0,0 +1,50 @@
+mod common;
+
+use common::Sandbox;
+
+const SINFUL: &str = "var x = 1\r\nfunction speak() {\r\n  let devil = 2\r\n  print(devil, x)\r\n}\r\nbless genesis() {\r\n  speak()\r\n}\r\n";
+
+#[test]
+fn absolve_fixes_venial_sins_and_keeps_line_endings() {
+    let sandbox = Sandbox::new("absolve-fix");
+    sandbox.write("s.divine", SINFUL);
+    let outcome = sandbox.run(&["confess", "s.divine", "--absolve"]).success();
+    assert!(outcome.stdout.contains("s.divine: 4 absolutions granted"), "{}", outcome.stdout);
+    for absolution in ["line 1: var → let", "line 2: blessed speak", "line 3: devil → adversary", "line 4: devil → adversary"] {
+        assert!(outcome.stdout.contains(absolution), "{}", outcome.stdout);
+    }
+    assert_eq!(
+        sandbox.read("s.divine"),
+        "let x = 1\r\nbless speak() {\r\n  let adversary = 2\r\n  print(adversary, x)\r\n}\r\nbless genesis() {\r\n  speak()\r\n}\r\n"
+    );
+}
+
+#[test]
+fn a_dry_run_shows_a_diff_and_writes_nothing() {
+    let sandbox = Sandbox::new("absolve-dry-run");
+    sandbox.write("s.divine", SINFUL);
+    let outcome = sandbox.run(&["confess", "s.divine", "--absolve", "--dry-run"]).success();
+    assert!(outcome.stdout.contains("--- a/s.divine\n+++ b/s.divine\n"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("-var x = 1\n+let x = 1\n"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("4 absolutions would be granted"), "{}", outcome.stdout);
+    assert_eq!(sandbox.read("s.divine"), SINFUL);
+}
+
+#[test]
+fn a_clean_script_is_not_rewritten() {
+    let sandbox = Sandbox::new("absolve-clean");
+    sandbox.write("s.divine", "bless genesis() {\n  print(1)\n}\n");
+    let before = std::fs::metadata(sandbox.path().join("s.divine")).unwrap().modified().unwrap();
+    let outcome = sandbox.run(&["confess", "s.divine", "--absolve"]).success();
+    assert!(outcome.stdout.contains("Nothing needed absolving"), "{}", outcome.stdout);
+    assert_eq!(std::fs::metadata(sandbox.path().join("s.divine")).unwrap().modified().unwrap(), before);
+}
+
+#[test]
+fn mortal_sins_are_left_for_the_sinner() {
+    let sandbox = Sandbox::new("absolve-mortal");
+    sandbox.write("s.divine", "var x = 1\nbless genesis() {\n  kill(x)\n}\n");
+    let outcome = sandbox.run(&["confess", "s.divine", "--absolve"]).success();
+    assert!(outcome.stdout.contains("s.divine:3: Thou shalt not kill processes cannot be absolved mechanically"), "{}", outcome.stdout);
+    assert_eq!(sandbox.read("s.divine"), "let x = 1\nbless genesis() {\n  kill(x)\n}\n");
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::Sandbox;
+
+const SCRIPT: &str = "genesis() {\n  let x = 1\n    print(x)\n}\n";
+
+/// Each command's visible alias, the arguments to give both, and the file
+/// whose contents they should leave alike.
+const ALIASES: &[(&str, &str, &[&str], &str)] = &[
+    ("confess", "lint", &["s.divine"], "s.divine"),
+    ("run", "exec", &["--seed", "7", "s.divine"], "s.divine"),
+    ("new", "init", &["flock"], "flock/genesis.divine"),
+    ("sanctify", "fmt", &["s.divine"], "s.divine"),
+    ("prophesy", "todo", &["--seed", "7", "s.divine"], "s.divine"),
+];
+
+#[test]
+fn every_visible_alias_does_what_its_command_does() {
+    for (command, alias, args, file) in ALIASES {
+        let run = |name: &str| {
+            let sandbox = Sandbox::new(&format!("alias-{}", name));
+            sandbox.write("s.divine", SCRIPT);
+            let outcome = sandbox.run(&[&[name][..], args].concat());
+            (outcome, sandbox.read(file))
+        };
+        let (by_command, left_by_command) = run(command);
+        let (by_alias, left_by_alias) = run(alias);
+        assert_eq!(by_alias.status, by_command.status, "{} as {}: {}", command, alias, by_alias.stderr);
+        assert_eq!(by_alias.stdout, by_command.stdout, "{} as {}", command, alias);
+        assert_eq!(by_alias.stderr, by_command.stderr, "{} as {}", command, alias);
+        assert_eq!(left_by_alias, left_by_command, "{} as {}", command, alias);
+    }
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::Sandbox;
+
+/// The lines a run proclaimed, between the ritual and judgment day.
+fn preached(stdout: &str) -> Vec<&str> {
+    stdout.lines().skip_while(|line| !line.starts_with("Rest...")).skip(1).take_while(|line| !line.is_empty()).collect()
+}
+
+fn project(sandbox: &Sandbox) {
+    sandbox.write("project/genesis.divine", "import module \"psalms\"\n\nbless genesis() {\n  psalm();\n}\n");
+    sandbox.write("project/holy_trinity/psalms.divine", "bless psalm() {\n  print(\"The Lord is my shepherd\");\n}\n");
+    sandbox.write("project/commandments.config", r#"{"sabbath_mode": false}"#);
+}
+
+#[test]
+fn a_sealed_ark_runs_as_the_project_it_was_sealed_from() {
+    let sandbox = Sandbox::new("ark-round-trip");
+    project(&sandbox);
+    let unsealed = sandbox.run(&["run", "project/genesis.divine"]).success();
+
+    let sealed = sandbox.run(&["ark", "build", "project", "-o", "project.ark"]).success();
+    assert!(sealed.stdout.contains("Sealed 3 files"), "{}", sealed.stdout);
+    let opened = sandbox.run(&["run", "project.ark"]).success();
+
+    assert!(preached(&unsealed.stdout).contains(&"The Lord is my shepherd"), "{}", unsealed.stdout);
+    assert_eq!(preached(&opened.stdout), preached(&unsealed.stdout));
+}
+
+#[cfg(unix)]
+#[test]
+fn a_link_back_up_the_project_is_sealed_only_once() {
+    let sandbox = Sandbox::new("ark-cycle");
+    project(&sandbox);
+    std::os::unix::fs::symlink("..", sandbox.path().join("project/holy_trinity/loop")).unwrap();
+
+    sandbox.run(&["ark", "build", "project", "-o", "project.ark"]).success();
+    let ark = sandbox.read("project.ark");
+    let files: Vec<&str> = ark.lines().filter(|line| line.starts_with("file ")).collect();
+    assert_eq!(files.len(), 3, "{:?}", files);
+    sandbox.run(&["run", "project.ark"]).success();
+}
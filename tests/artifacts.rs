@@ -0,0 +1,110 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::Sandbox;
+use serde_json::Value;
+
+const BLESSED: &str = "print(\"amen\");\n";
+const FALLEN: &str = "bless genesis() {\n  covenant(\"p\")\n}\n";
+
+/// The names of the files in `dir`, in order.
+fn names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir).map(|entries| entries
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect()).unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Where `run --report` said it wrote its report, taken from the sandbox.
+fn written(sandbox: &Sandbox, stdout: &str) -> PathBuf {
+    let line = stdout.lines().find_map(|line| line.split_once("Run report written to ")).unwrap_or_else(|| panic!("no report in\n{}", stdout));
+    sandbox.path().join(line.1.trim())
+}
+
+#[test]
+fn a_report_is_kept_in_heaven_or_purgatory_by_its_verdict() {
+    let sandbox = Sandbox::new("artifacts-verdict");
+    sandbox.write("amen.divine", BLESSED);
+    sandbox.write("fallen.divine", FALLEN);
+
+    let blessed = written(&sandbox, &sandbox.run(&["run", "amen.divine", "--report"]).success().stdout);
+    assert_eq!(blessed.parent().unwrap(), sandbox.path().join(".divinepl").join("heaven"));
+    let name = blessed.file_name().unwrap().to_string_lossy().into_owned();
+    assert!(name.contains("-amen-") && name.ends_with(".report.json"), "{}", name);
+    let report: Value = serde_json::from_str(&fs::read_to_string(&blessed).unwrap()).unwrap();
+    assert_eq!(report["verdict"], "heaven");
+
+    let fallen = written(&sandbox, &sandbox.run(&["run", "fallen.divine", "--report"]).expect(6).stdout);
+    assert_eq!(fallen.parent().unwrap(), sandbox.path().join(".divinepl").join("purgatory"));
+}
+
+#[test]
+fn only_the_newest_reports_are_kept_for_a_script() {
+    let sandbox = Sandbox::new("artifacts-keep");
+    sandbox.write("commandments.config", r#"{"artifacts_keep": 2}"#);
+    sandbox.write("amen.divine", BLESSED);
+    sandbox.write("other.divine", BLESSED);
+
+    let reports: Vec<PathBuf> = (0..4)
+        .map(|_| written(&sandbox, &sandbox.run(&["run", "amen.divine", "--report"]).success().stdout))
+        .collect();
+    sandbox.run(&["run", "other.divine", "--report"]).success();
+
+    let heaven = names(&sandbox.path().join(".divinepl").join("heaven"));
+    let amen: Vec<&String> = heaven.iter().filter(|name| name.contains("-amen-")).collect();
+    assert_eq!(amen.len(), 2, "{:?}", heaven);
+    // The two kept are the last two written, whatever second they fell in
+    for report in &reports[2..] {
+        assert!(report.exists(), "{} was pruned from {:?}", report.display(), heaven);
+    }
+    assert_eq!(heaven.iter().filter(|name| name.contains("-other-")).count(), 1);
+}
+
+#[test]
+fn artifacts_are_listed_and_cleansed() {
+    let sandbox = Sandbox::new("artifacts-list");
+    sandbox.write("amen.divine", BLESSED);
+    sandbox.write("fallen.divine", FALLEN);
+
+    let empty = sandbox.run(&["artifacts", "list"]).success();
+    assert!(empty.stdout.contains("No artifacts are kept in"), "{}", empty.stdout);
+
+    let blessed = written(&sandbox, &sandbox.run(&["run", "amen.divine", "--report"]).success().stdout);
+    let fallen = written(&sandbox, &sandbox.run(&["run", "fallen.divine", "--report"]).expect(6).stdout);
+    let listed = sandbox.run(&["artifacts", "list"]).success().stdout;
+    let shown = |path: &Path| path.strip_prefix(sandbox.path()).unwrap().to_string_lossy().into_owned();
+    let lines: Vec<&str> = listed.lines().skip(1).collect();
+    assert_eq!(lines.len(), 2, "{}", listed);
+    assert!(lines[0].trim_start().starts_with("heaven") && lines[0].ends_with(&shown(&blessed)), "{}", listed);
+    assert!(lines[1].trim_start().starts_with("purgatory") && lines[1].ends_with(&shown(&fallen)), "{}", listed);
+
+    let cleansed = sandbox.run(&["artifacts", "clean"]).success();
+    assert!(cleansed.stdout.contains("Cleansed 2 artifacts"), "{}", cleansed.stdout);
+    assert!(!blessed.exists() && !fallen.exists());
+    assert!(sandbox.run(&["artifacts", "list"]).success().stdout.contains("No artifacts are kept in"));
+}
+
+#[test]
+fn artifacts_that_cannot_be_kept_in_the_project_go_to_the_temp_dir_and_are_still_found() {
+    let sandbox = Sandbox::new("artifacts-fallback");
+    sandbox.write("amen.divine", BLESSED);
+    // A file where the artifacts directory should be, so nothing can be written beneath it
+    sandbox.write(".divinepl", "");
+
+    let outcome = sandbox.run(&["run", "amen.divine", "--report"]).success();
+    assert!(outcome.stderr.contains("Cannot keep artifacts in"), "{}", outcome.stderr);
+    let report = written(&sandbox, &outcome.stdout);
+    assert!(report.starts_with(std::env::temp_dir()) && report.exists(), "{}", report.display());
+    let fallback = report.parent().unwrap().parent().unwrap().to_path_buf();
+
+    let listed = sandbox.run(&["artifacts", "list"]).success().stdout;
+    assert!(listed.contains(&*report.to_string_lossy()), "{}", listed);
+
+    let cleansed = sandbox.run(&["artifacts", "clean"]).success();
+    assert!(cleansed.stdout.contains("Cleansed 1 artifacts"), "{}", cleansed.stdout);
+    assert!(!report.exists());
+    let _ = fs::remove_dir_all(fallback);
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::Sandbox;
+
+/// A project called zion, which names itself in its genesis header and its trinity.
+fn zion(sandbox: &Sandbox) {
+    sandbox.write("zion/genesis.divine", "// zion: a holy project\nbless genesis() {\n  print(\"zion\")\n}\n");
+    sandbox.write("zion/commandments.config", r#"{"trinity": {"father": "zion", "son": "child", "holy_ghost": "ghost"}}"#);
+}
+
+#[test]
+fn baptism_renames_the_project_and_the_mentions_of_its_name() {
+    let sandbox = Sandbox::new("baptize");
+    zion(&sandbox);
+    let outcome = sandbox.run(&["baptize", "zion", "eden"]).success();
+    assert!(outcome.stdout.contains("rewrite genesis.divine (1 mention of 'zion')"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("rewrite commandments.config (1 mention of 'zion')"), "{}", outcome.stdout);
+    assert!(!sandbox.path().join("zion").exists());
+    // Only the header is rewritten, never the code
+    assert_eq!(sandbox.read("eden/genesis.divine"), "// eden: a holy project\nbless genesis() {\n  print(\"zion\")\n}\n");
+    assert_eq!(sandbox.read("eden/commandments.config"), r#"{"trinity": {"father": "eden", "son": "child", "holy_ghost": "ghost"}}"#);
+}
+
+#[test]
+fn a_dry_run_lists_the_changes_and_makes_none() {
+    let sandbox = Sandbox::new("baptize-dry-run");
+    zion(&sandbox);
+    let outcome = sandbox.run(&["baptize", "zion", "eden", "--dry-run"]).success();
+    assert!(outcome.stdout.contains("Would baptize 'zion' as 'eden'"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("rewrite genesis.divine (1 mention of 'zion')"), "{}", outcome.stdout);
+    assert!(!sandbox.path().join("eden").exists());
+    assert!(sandbox.read("zion/genesis.divine").starts_with("// zion:"));
+}
+
+#[test]
+fn baptism_refuses_to_overwrite_or_to_rename_what_is_no_project() {
+    let sandbox = Sandbox::new("baptize-refused");
+    zion(&sandbox);
+    sandbox.write("eden/genesis.divine", "bless genesis() {\n}\n");
+    let outcome = sandbox.run(&["baptize", "zion", "eden"]).expect(1);
+    assert!(outcome.stderr.contains("Project 'eden' already exists. Creation is sacred, duplication is heresy."), "{}", outcome.stderr);
+    assert!(sandbox.path().join("zion").exists());
+
+    sandbox.write("other/notes.txt", "");
+    let outcome = sandbox.run(&["baptize", "other", "babel"]).expect(1);
+    assert!(outcome.stderr.contains("other is not a DivinePL project; it has neither genesis.divine nor commandments.config"), "{}", outcome.stderr);
+    assert!(sandbox.path().join("other").exists());
+}
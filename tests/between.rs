@@ -0,0 +1,83 @@
+//! Confession between two commits of a git repository made in the sandbox.
+
+mod common;
+
+use std::process::Command;
+
+use common::Sandbox;
+use serde_json::Value;
+
+fn git(sandbox: &Sandbox, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(sandbox.path())
+        .env("HOME", sandbox.path().join("home"))
+        .args(["-c", "user.name=Scribe", "-c", "user.email=scribe@example.com", "-c", "commit.gpgsign=false"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?}", args);
+}
+
+/// A repository of two commits. The second fixes the var of a.divine but
+/// kills in it, shifts the lasting sin of b.divine down a line, deletes
+/// gone.divine and adds new.divine, each with a sin of its own.
+fn history(name: &str) -> Sandbox {
+    let sandbox = Sandbox::new(name);
+    git(&sandbox, &["init", "-q"]);
+    sandbox.write("a.divine", "var x = 1\nbless genesis() {\n  print(x)\n}\n");
+    sandbox.write("b.divine", "let devil = 1\n");
+    sandbox.write("gone.divine", "var gone = 1\n");
+    git(&sandbox, &["add", "-A"]);
+    git(&sandbox, &["commit", "-q", "-m", "first"]);
+    git(&sandbox, &["tag", "first"]);
+
+    sandbox.write("a.divine", "let x = 1\nbless genesis() {\n  kill(x)\n}\n");
+    sandbox.write("b.divine", "// the adversary abides\nlet devil = 1\n");
+    std::fs::remove_file(sandbox.path().join("gone.divine")).unwrap();
+    sandbox.write("new.divine", "var fresh = 1\n");
+    git(&sandbox, &["add", "-A"]);
+    git(&sandbox, &["commit", "-q", "-m", "second"]);
+    sandbox
+}
+
+#[test]
+fn sins_are_weighed_between_two_revisions() {
+    let sandbox = history("between-text");
+    // The working tree is never read
+    sandbox.write("new.divine", "bless genesis() {\n}\n");
+    let outcome = sandbox.run(&["confess", "--between", "first..HEAD"]).success();
+    assert!(outcome.stdout.contains("Introduced sins (2):"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("+ a.divine:3 Mortal Sin[DPL010]"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("+ new.divine:1 Venial Sin[DPL003]"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("Resolved sins (2):"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("- a.divine:1 Venial Sin[DPL003]"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("- gone.divine:1 Venial Sin[DPL003]"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("= b.divine:2 Mortal Sin"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("introduced=2 resolved=2 persisting=1"), "{}", outcome.stdout);
+}
+
+#[test]
+fn the_weighing_is_reported_as_json() {
+    let sandbox = history("between-json");
+    let outcome = sandbox.run(&["confess", "--between", "first..", "--format", "json"]).success();
+    let report: Value = serde_json::from_str(&outcome.stdout).unwrap();
+    assert_eq!(report["from"], "first");
+    assert_eq!(report["to"], "HEAD");
+    assert_eq!(report["summary"], serde_json::json!({ "introduced": 2, "resolved": 2, "persisting": 1 }));
+    assert_eq!(report["persisting"][0]["file"], "b.divine");
+    assert_eq!(report["persisting"][0]["line"], 2);
+    assert_eq!(report["introduced"][0]["severity"], "mortal");
+}
+
+#[test]
+fn a_missing_repository_or_revision_is_a_clear_error() {
+    let sandbox = Sandbox::new("between-no-repo");
+    let outcome = sandbox.run(&["confess", "--between", "HEAD~1..HEAD"]).expect(1);
+    assert!(outcome.stderr.contains("Not inside a git repository; there is no history to judge"), "{}", outcome.stderr);
+
+    let sandbox = history("between-no-revision");
+    let outcome = sandbox.run(&["confess", "--between", "first..nowhere"]).expect(1);
+    assert!(outcome.stderr.contains("Revision 'nowhere' does not exist"), "{}", outcome.stderr);
+    let outcome = sandbox.run(&["confess", "--between", "first"]).expect(1);
+    assert!(outcome.stderr.contains("'first' is not a revision range; use REV1..REV2"), "{}", outcome.stderr);
+}
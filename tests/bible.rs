@@ -0,0 +1,73 @@
+mod common;
+
+use common::Sandbox;
+
+/// A sandbox with a pack in its home and another listed in its commandments,
+/// which gives patience again.
+fn with_packs(name: &str) -> Sandbox {
+    let sandbox = Sandbox::new(name);
+    sandbox.write("home/.divinepl/verses.toml", "[[verses]]\nkey = \"patience\"\ntext = \"But let patience have her perfect work. (James 1:4)\"\ntags = [\"wait\"]\n");
+    sandbox.write("commandments.config", r#"{"verse_packs": ["packs/more.json"]}"#);
+    sandbox.write("packs/more.json", r#"{"verses": [
+        {"key": "patience", "text": "Wait on the LORD. (Psalm 27:14)"},
+        {"key": "humility", "text": "Pride goeth before destruction. (Proverbs 16:18)", "tags": ["refactor"]}
+    ]}"#);
+    sandbox
+}
+
+#[test]
+fn verse_packs_are_merged_over_the_built_in_verses() {
+    let sandbox = with_packs("bible-packs");
+    let outcome = sandbox.run(&["bible", "--list-sources"]).success();
+    let home = sandbox.path().join("home/.divinepl/verses.toml").display().to_string();
+    let listed = sandbox.path().join("packs/more.json").display().to_string();
+    let sources: Vec<String> = outcome.stdout.lines().skip(1).map(|line| line.split_whitespace().collect::<Vec<_>>().join(" ")).collect();
+    assert_eq!(sources, [
+        "built-in 8 verses".to_string(),
+        format!("{} 1 verse", home),
+        format!("{} 2 verses (1 replacing earlier ones)", listed),
+        "10 verses are known".to_string(),
+    ]);
+
+    // The later pack's patience replaced the home pack's, tags and all
+    let outcome = sandbox.run(&["bible", "patience"]).success();
+    assert!(outcome.stdout.contains("Wait on the LORD. (Psalm 27:14)"), "{}", outcome.stdout);
+    let outcome = sandbox.run(&["bible", "refactor"]).success();
+    assert!(outcome.stdout.contains("[humility] Pride goeth before destruction."), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("tagged 'refactor'"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_malformed_verse_pack_is_passed_over_with_a_warning() {
+    let sandbox = with_packs("bible-packs-malformed");
+    sandbox.write("packs/more.json", r#"{"verses": [{"key": "humility"}]}"#);
+    let outcome = sandbox.run(&["bible", "--list-sources"]).success();
+    assert!(outcome.stderr.contains(r#"verse 1: the verse "humility" has no "text"; its verses were passed over"#), "{}", outcome.stderr);
+    assert!(outcome.stdout.contains("not loaded: Malformed verse pack"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("9 verses are known"), "{}", outcome.stdout);
+}
+
+#[test]
+fn bible_succeeds_when_a_verse_is_found_and_fails_when_none_is() {
+    let sandbox = Sandbox::new("bible-status");
+    let outcome = sandbox.run(&["bible", "error"]).success();
+    assert!(outcome.stdout.contains("For all have sinned"), "{}", outcome.stdout);
+    // A near miss is still found
+    sandbox.run(&["bible", "eror"]).success();
+
+    let outcome = sandbox.run(&["bible", "zzzzqqq"]).expect(1);
+    assert!(outcome.stdout.contains("No direct verse found for this topic."), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_misspelt_topic_gets_the_guidance_of_the_topic_it_was_taken_for() {
+    let sandbox = Sandbox::new("bible-guidance");
+    let concurrency = "Where two or three threads are gathered together";
+    let general = "The path of righteous code is illuminated";
+    for topic in ["concurrency", "concurency", "proces"] {
+        let outcome = sandbox.run(&["bible", topic]).success();
+        assert!(outcome.stdout.contains(concurrency) && !outcome.stdout.contains(general), "{}: {}", topic, outcome.stdout);
+    }
+    let outcome = sandbox.run(&["bible", "eror"]).success();
+    assert!(outcome.stdout.contains("errors are treated as sins to be confessed"), "{}", outcome.stdout);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::Sandbox;
+
+/// The line a run printed just before judgment day.
+fn last_printed(stdout: &str) -> &str {
+    stdout.lines().take_while(|line| !line.is_empty()).last().unwrap_or_default()
+}
+
+#[test]
+fn cast_lots_repeats_under_the_same_seed() {
+    let sandbox = Sandbox::new("builtins-lots");
+    sandbox.write("s.divine", "print(castLots(6), castLots(6), castLots(6), castLots(6))\n");
+    let first = sandbox.run(&["run", "s.divine", "--seed", "7"]).success();
+    let again = sandbox.run(&["run", "s.divine", "--seed", "7"]).success();
+    let lots = last_printed(&first.stdout);
+    assert_eq!(lots, last_printed(&again.stdout));
+    assert!(lots.split(' ').all(|lot| (1..=6).contains(&lot.parse::<u32>().unwrap())), "{}", lots);
+}
+
+#[test]
+fn len_counts_arrays_and_strings_and_light_is_true() {
+    let sandbox = Sandbox::new("builtins-len");
+    sandbox.write("s.divine", "print(len([1, 2, 3]), len(\"abcd\"), createLight())\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert_eq!(last_printed(&outcome.stdout), "3 4 true");
+
+    sandbox.write("s.divine", "print(len(1))\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(6);
+    assert!(outcome.stderr.contains("len requires an array or a string, but received a number"), "{}", outcome.stderr);
+}
+
+#[test]
+fn the_scribe_writes_only_in_dev_mode() {
+    let sandbox = Sandbox::new("builtins-scribe");
+    sandbox.write("s.divine", "scribe(\"out.txt\", \"inscribed\")\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(6);
+    assert!(outcome.stderr.contains("only in dev mode may the scribe write 'out.txt'"), "{}", outcome.stderr);
+    assert!(!sandbox.path().join("out.txt").exists());
+
+    sandbox.run(&["--dev", "run", "s.divine"]).success();
+    assert_eq!(sandbox.read("out.txt"), "inscribed");
+}
+
+#[test]
+fn an_unknown_function_names_the_nearest_builtin() {
+    let sandbox = Sandbox::new("builtins-nearest");
+    sandbox.write("s.divine", "print(castLot(6))\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(6);
+    assert!(outcome.stderr.contains("no such blessed function 'castLot' (line 1); did you mean 'castLots'?"), "{}", outcome.stderr);
+}
+
+#[test]
+fn the_bible_documents_every_builtin() {
+    let sandbox = Sandbox::new("builtins-bible");
+    // No verse speaks of functions, so the search fails, but the guidance still follows.
+    let outcome = sandbox.run(&["bible", "function"]).expect(1);
+    for signature in ["createLight()", "castLots(n)", "amen()", "scribe(path, text)", "len(x)", "listen(prompt)", "number(x)"] {
+        assert!(outcome.stdout.contains(signature), "{} is not documented:\n{}", signature, outcome.stdout);
+    }
+}
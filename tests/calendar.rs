@@ -0,0 +1,46 @@
+//! The liturgical calendar, pretending today is a day it keeps.
+
+mod common;
+
+use common::Sandbox;
+
+const CALENDAR: &str = r#"{"feast_days": {"07-04": "the Founding"}, "lent": {"from": "12-30", "to": "01-02"}}"#;
+
+#[test]
+fn anoint_tells_the_season() {
+    let sandbox = Sandbox::new("calendar-anoint");
+    sandbox.write("commandments.config", CALENDAR);
+    for (day, season) in [
+        ("2026-07-04", "Today is the feast of the Founding; venial sins weigh nothing at judgment"),
+        ("2026-12-25", "Today is the feast of Christmas"),
+        ("2026-12-31", "It is Lent, until 2 January; judgment is sterner and long functions are a sin"),
+        ("2026-08-01", "It is ordinary time"),
+    ] {
+        let outcome = sandbox.run(&["--dev", "--pretend-today", day, "anoint"]);
+        assert!(outcome.stdout.contains(season), "{}\n{}", day, outcome.stdout);
+    }
+}
+
+#[test]
+fn a_feast_lifts_the_weight_of_venial_sins() {
+    let sandbox = Sandbox::new("calendar-feast");
+    sandbox.write("commandments.config", CALENDAR);
+    sandbox.write("s.divine", "bless genesis() {\n  var x = 1\n}\n");
+    let outcome = sandbox.run(&["--dev", "--pretend-today", "2026-07-04", "run", "s.divine"]).success();
+    assert!(outcome.stdout.contains("🎉 Today is the feast of the Founding!"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("1 venial sin weighed against you, leaving 100% of grace"), "{}", outcome.stdout);
+
+    let outcome = sandbox.run(&["--dev", "--pretend-today", "2026-08-01", "run", "s.divine"]).success();
+    assert!(!outcome.stdout.contains("🎉"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("1 venial sin weighed against you, leaving 90% of grace"), "{}", outcome.stdout);
+}
+
+#[test]
+fn in_lent_a_long_function_is_a_sin() {
+    let sandbox = Sandbox::new("calendar-lent");
+    sandbox.write("commandments.config", CALENDAR);
+    sandbox.write("s.divine", format!("bless genesis() {{\n{}}}\n", "  print(1)\n".repeat(30)));
+    let outcome = sandbox.run(&["--dev", "--pretend-today", "2026-12-31", "confess", "s.divine"]).expect(1);
+    assert!(outcome.stdout.contains("Function 'genesis' spans 31 lines; in Lent, functions keep to 30"), "{}", outcome.stdout);
+    sandbox.run(&["--dev", "--pretend-today", "2026-08-01", "confess", "s.divine"]).success();
+}
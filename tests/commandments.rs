@@ -0,0 +1,85 @@
+mod common;
+
+use common::Sandbox;
+
+#[test]
+fn a_commandment_of_the_wrong_type_is_warned_of_by_every_command() {
+    let sandbox = Sandbox::new("mistyped-commandment");
+    sandbox.write("commandments.config", r#"{"sabbath_mode": "false", "sabath_day": "sat"}"#);
+    sandbox.write("s.divine", "let psalm = 23;\n");
+
+    for args in [&["run", "s.divine"][..], &["confess", "s.divine"], &["prophesy", "s.divine"]] {
+        let outcome = sandbox.run(args).success();
+        let warnings: Vec<&str> = outcome.stderr.lines().filter(|line| line.contains("Warning")).collect();
+        assert_eq!(warnings.len(), 2, "{:?}\n{}", args, outcome.stderr);
+        assert!(warnings[0].contains(r#"sabbath_mode should be true or false, but is "false""#), "{}", warnings[0]);
+        assert!(warnings[1].contains(r#""sabath_day" is no commandment; did you mean "sabbath_day"?"#), "{}", warnings[1]);
+    }
+}
+
+#[test]
+fn a_custom_commandment_names_its_sins_with_its_own_id() {
+    let sandbox = Sandbox::new("custom-commandment");
+    sandbox.write("commandments.config", r#"{"custom_commandments": [{"id": "no-mammon", "pattern": "mammon", "severity": "mortal"}]}"#);
+    sandbox.write("s.divine", "let mammon = 1;\n");
+
+    let outcome = sandbox.run(&["--porcelain", "confess", "s.divine"]).expect(2);
+    assert_eq!(outcome.stdout, "sin mortal no-mammon s.divine:1\n");
+}
+
+/// The source `anoint` gives for each setting, as name=source.
+fn sources(outcome: &common::Outcome) -> Vec<String> {
+    let report: serde_json::Value = serde_json::from_str(&outcome.stdout).unwrap();
+    report["settings"].as_object().unwrap().iter()
+        .map(|(name, setting)| format!("{}={}", name, setting["source"].as_str().unwrap()))
+        .collect()
+}
+
+#[test]
+fn the_commandments_give_settings_below_the_environment_and_above_the_defaults() {
+    let sandbox = Sandbox::new("commanded-settings");
+    sandbox.write("commandments.config", r#"{"dev": true, "fast": true, "seed": 7, "plain_glyphs": true}"#);
+
+    let outcome = common::Outcome::of(sandbox.divine().env_remove("DIVINEPL_FAST").env("DIVINEPL_SEED", "8")
+        .args(["--dev", "anoint", "--format", "json"]).output().unwrap());
+    assert_eq!(sources(&outcome), [
+        "dev=flag", "override_sabbath=default", "seed=environment", "fast=commandments", "color=environment", "plain_glyphs=commandments",
+    ]);
+    let report: serde_json::Value = serde_json::from_str(&outcome.stdout).unwrap();
+    assert_eq!(report["settings"]["seed"]["value"], "8");
+    assert_eq!(report["settings"]["fast"]["from"], "fast");
+
+    let outcome = common::Outcome::of(sandbox.divine().env_remove("NO_COLOR").env_remove("DIVINEPL_FAST")
+        .args(["anoint", "--format", "json"]).output().unwrap());
+    assert_eq!(sources(&outcome), [
+        "dev=commandments", "override_sabbath=default", "seed=commandments", "fast=commandments", "color=default", "plain_glyphs=commandments",
+    ]);
+}
+
+#[test]
+fn no_config_passes_over_the_commanded_settings() {
+    let sandbox = Sandbox::new("commanded-settings-forgone");
+    sandbox.write("commandments.config", r#"{"fast": false, "color": "always"}"#);
+
+    let outcome = common::Outcome::of(sandbox.divine().env_remove("NO_COLOR")
+        .args(["anoint", "--format", "json"]).output().unwrap());
+    assert!(sources(&outcome).contains(&"color=commandments".to_string()), "{}", outcome.stdout);
+    assert!(sources(&outcome).contains(&"fast=environment".to_string()), "{}", outcome.stdout);
+
+    let outcome = common::Outcome::of(sandbox.divine().env_remove("NO_COLOR")
+        .args(["--no-config", "anoint", "--format", "json"]).output().unwrap());
+    assert!(sources(&outcome).contains(&"color=default".to_string()), "{}", outcome.stdout);
+}
+
+#[test]
+fn anoint_json_is_shorthand_for_the_json_format() {
+    let sandbox = Sandbox::new("anoint-json");
+    sandbox.write("commandments.config", r#"{"seed": 7}"#);
+
+    let shorthand = sandbox.run(&["anoint", "--json"]);
+    assert_eq!(sources(&shorthand), sources(&sandbox.run(&["anoint", "--format", "json"])));
+    assert!(sources(&shorthand).contains(&"seed=commandments".to_string()), "{}", shorthand.stdout);
+
+    let outcome = sandbox.run(&["anoint", "--json", "--format", "text"]).expect(2);
+    assert!(outcome.stderr.contains("cannot be used with"), "{}", outcome.stderr);
+}
@@ -0,0 +1,127 @@
+//! What the command-line tests share: a sandbox directory for each test, and
+//! the divine binary to run in it, kept from the tester's own settings.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use chrono::{Datelike, Duration, Local};
+
+/// A directory of its own for one test, removed when the test is done.
+pub struct Sandbox {
+    dir: PathBuf,
+}
+
+impl Sandbox {
+    pub fn new(name: &str) -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "divinepl-test-{}-{}-{}", name, std::process::id(), COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("home")).unwrap();
+        Self { dir }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes `content` to `file` under the sandbox, making its directories.
+    pub fn write(&self, file: &str, content: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    pub fn read(&self, file: &str) -> String {
+        fs::read_to_string(self.dir.join(file)).unwrap_or_else(|e| panic!("reading {}: {}", file, e))
+    }
+
+    pub fn read_bytes(&self, file: &str) -> Vec<u8> {
+        fs::read(self.dir.join(file)).unwrap_or_else(|e| panic!("reading {}: {}", file, e))
+    }
+
+    /// The divine binary, run in the sandbox on any weekday but the Sabbath,
+    /// without color, and with the sandbox's own home.
+    pub fn divine(&self) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_divine-pl"));
+        command.current_dir(&self.dir);
+        for (key, _) in std::env::vars_os() {
+            let key = key.to_string_lossy().to_string();
+            if key.starts_with("DIVINEPL_") || ["NO_COLOR", "CLICOLOR_FORCE", "LC_ALL", "LC_CTYPE", "RUST_BACKTRACE"].contains(&key.as_str()) {
+                command.env_remove(key);
+            }
+        }
+        command.env("HOME", self.dir.join("home")).env("USERPROFILE", self.dir.join("home"))
+            .env("LANG", "en_US.UTF-8")
+            .env("NO_COLOR", "1")
+            .env("DIVINEPL_FAST", "1")
+            .args(["--sabbath-day", &not_today()]);
+        command
+    }
+
+    /// Runs divine in the sandbox with `args`.
+    pub fn run(&self, args: &[&str]) -> Outcome {
+        Outcome::of(self.divine().args(args).output().unwrap())
+    }
+
+    /// Runs divine in the sandbox with `args`, writing `input` to its stdin.
+    pub fn run_with_input(&self, args: &[&str], input: &str) -> Outcome {
+        let mut child = self.divine().args(args)
+            .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped())
+            .spawn().unwrap();
+        child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        Outcome::of(child.wait_with_output().unwrap())
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// The date of the Sabbath the sandbox keeps: three days from today, so the
+/// Sabbath never stops a test unless it pretends today is this day.
+pub fn sabbath() -> String {
+    (Local::now() + Duration::days(3)).format("%Y-%m-%d").to_string()
+}
+
+/// The weekday of the sandbox's Sabbath.
+fn not_today() -> String {
+    let day = (Local::now() + Duration::days(3)).weekday();
+    day.to_string().to_lowercase()
+}
+
+/// What a run of divine printed, and how it ended.
+pub struct Outcome {
+    pub status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Outcome {
+    pub fn of(output: Output) -> Self {
+        Self {
+            status: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+
+    /// Fails the test, showing everything printed, unless divine exited with `code`.
+    pub fn expect(self, code: i32) -> Self {
+        assert_eq!(self.status, Some(code), "exit status\n--- stdout\n{}\n--- stderr\n{}", self.stdout, self.stderr);
+        self
+    }
+
+    pub fn success(self) -> Self {
+        self.expect(0)
+    }
+}
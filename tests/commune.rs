@@ -0,0 +1,19 @@
+mod common;
+
+use common::Sandbox;
+
+#[test]
+fn variables_and_blessed_functions_outlive_their_entry() {
+    let sandbox = Sandbox::new("commune-persists");
+    let outcome = sandbox.run_with_input(&["commune"], "let x = 2;\nbless double(n) {\n  ascend with n * 2;\n}\ndouble(x)\n:amen\n").success();
+    let echoed: Vec<&str> = outcome.stdout.lines().skip(1).collect();
+    assert_eq!(echoed, ["4", "🕊️ Go in peace."]);
+}
+
+#[test]
+fn an_error_is_reported_and_the_session_continues() {
+    let sandbox = Sandbox::new("commune-error");
+    let outcome = sandbox.run_with_input(&["commune"], "y\nlet y = 3;\ny\n").success();
+    assert!(outcome.stderr.contains("'y' was never declared"), "{}", outcome.stderr);
+    assert!(outcome.stdout.lines().any(|line| line == "3"), "{}", outcome.stdout);
+}
@@ -0,0 +1,172 @@
+//! The confession daemon: several pilgrims, commandments changed on disk while
+//! it keeps vigil, and confession in-process when no daemon answers.
+
+#![cfg(unix)]
+
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use common::Sandbox;
+
+const SCRIPT: &str = "let a = 0\nvar x = 1 // forgiven: DPL003 kept for the old scrolls\nvar y = 2\n";
+
+/// A daemon keeping vigil over a sandbox, told to depart when dropped.
+struct Vigil {
+    child: Child,
+    socket: PathBuf,
+}
+
+impl Vigil {
+    fn keep(sandbox: &Sandbox) -> Self {
+        let socket = sandbox.path().join("vigil.sock");
+        let child = sandbox.divine().args(["daemon", "--socket"]).arg(&socket)
+            .stdout(Stdio::null()).stderr(Stdio::null())
+            .spawn().unwrap();
+        let started = Instant::now();
+        while UnixStream::connect(&socket).is_err() {
+            assert!(started.elapsed() < Duration::from_secs(10), "the daemon never began its vigil");
+            thread::sleep(Duration::from_millis(20));
+        }
+        Self { child, socket }
+    }
+
+    fn connect(&self) -> UnixStream {
+        let stream = UnixStream::connect(&self.socket).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        stream
+    }
+
+    /// Sends one request on a connection of its own and returns the answer.
+    fn ask(&self, request: &str) -> String {
+        ask(&mut self.connect(), request)
+    }
+}
+
+impl Drop for Vigil {
+    fn drop(&mut self) {
+        if let Ok(mut stream) = UnixStream::connect(&self.socket) {
+            let _ = writeln!(stream, r#"{{"cmd":"shutdown"}}"#);
+        }
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_secs(5) {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn ask(stream: &mut UnixStream, request: &str) -> String {
+    writeln!(stream, "{}", request).unwrap();
+    let mut line = String::new();
+    BufReader::new(&*stream).read_line(&mut line).unwrap();
+    line
+}
+
+fn check(path: &Path, source: &str) -> String {
+    let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    format!(r#"{{"cmd":"check","path":"{}","source":"{}"}}"#, escape(&path.to_string_lossy()), escape(source))
+}
+
+/// Writes commandments, making sure their modification time moves on from any before them.
+fn ordain(sandbox: &Sandbox, commandments: &str) {
+    let path = sandbox.path().join("commandments.config");
+    let before = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    sandbox.write("commandments.config", commandments);
+    while before.is_some() && std::fs::metadata(&path).and_then(|m| m.modified()).ok() == before {
+        thread::sleep(Duration::from_millis(10));
+        let file = std::fs::File::options().append(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now()).unwrap();
+    }
+}
+
+#[test]
+fn answers_several_requests_on_one_connection_and_on_many() {
+    let sandbox = Sandbox::new("daemon-several");
+    let script = sandbox.write("s.divine", SCRIPT);
+    let vigil = Vigil::keep(&sandbox);
+
+    let mut stream = vigil.connect();
+    assert!(ask(&mut stream, r#"{"cmd":"ping"}"#).contains(r#""ok":true"#));
+    for _ in 0..3 {
+        let answer = ask(&mut stream, &check(&script, SCRIPT));
+        assert!(answer.contains(r#""ok":true"#), "{}", answer);
+        // The forgiven line is forgiven by the daemon itself
+        assert!(answer.contains(r#""forgiven":1"#), "{}", answer);
+        assert!(!answer.contains(r#""line":2"#) && answer.contains(r#""line":3"#), "{}", answer);
+    }
+    for _ in 0..3 {
+        assert!(vigil.ask(&check(&script, SCRIPT)).contains(r#""ok":true"#));
+    }
+    assert!(vigil.ask(r#"{"cmd":"smite"}"#).contains("unknown command"));
+}
+
+#[test]
+fn a_silent_pilgrim_keeps_no_one_waiting() {
+    let sandbox = Sandbox::new("daemon-silent");
+    let script = sandbox.write("s.divine", SCRIPT);
+    let vigil = Vigil::keep(&sandbox);
+
+    let _silent = vigil.connect();
+    thread::sleep(Duration::from_millis(100));
+    let started = Instant::now();
+    assert!(vigil.ask(&check(&script, SCRIPT)).contains(r#""ok":true"#));
+    assert!(started.elapsed() < Duration::from_secs(5), "answered only after {:?}", started.elapsed());
+}
+
+#[test]
+fn heeds_commandments_changed_on_disk() {
+    let sandbox = Sandbox::new("daemon-config");
+    let script = sandbox.write("s.divine", SCRIPT);
+    ordain(&sandbox, r#"{ "sins": { "DPL003": "mortal" } }"#);
+    let vigil = Vigil::keep(&sandbox);
+
+    let answer = vigil.ask(&check(&script, SCRIPT));
+    assert!(answer.contains(r#""severity":"mortal""#), "{}", answer);
+
+    ordain(&sandbox, r#"{ "sins": { "DPL003": "off" } }"#);
+    let answer = vigil.ask(&check(&script, SCRIPT));
+    assert!(answer.contains(r#""findings":[]"#), "{}", answer);
+
+    ordain(&sandbox, r#"{ "allow_confession": false }"#);
+    let answer = vigil.ask(&check(&script, SCRIPT));
+    assert!(answer.contains("Confession is forbidden"), "{}", answer);
+}
+
+#[test]
+fn confess_through_the_daemon_judges_as_confessing_in_process() {
+    let sandbox = Sandbox::new("daemon-confess");
+    sandbox.write("s.divine", SCRIPT);
+    ordain(&sandbox, r#"{ "sins": { "DPL003": "mortal" } }"#);
+    let in_process = sandbox.run(&["confess", "s.divine", "--format", "json"]).expect(2);
+
+    let vigil = Vigil::keep(&sandbox);
+    let through_daemon = sandbox.divine().env("DIVINEPL_SOCKET", &vigil.socket)
+        .args(["confess", "s.divine", "--daemon-auto", "--format", "json"])
+        .output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&through_daemon.stdout), in_process.stdout);
+    assert_eq!(through_daemon.status.code(), Some(2));
+}
+
+#[test]
+fn confesses_in_process_when_no_daemon_answers() {
+    let sandbox = Sandbox::new("daemon-fallback");
+    sandbox.write("s.divine", SCRIPT);
+    let in_process = sandbox.run(&["confess", "s.divine", "--format", "json"]);
+
+    let fallback = sandbox.divine().env("DIVINEPL_SOCKET", sandbox.path().join("nobody.sock"))
+        .args(["confess", "s.divine", "--daemon-auto", "--format", "json"])
+        .output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&fallback.stdout), in_process.stdout);
+    assert_eq!(fallback.status.code(), in_process.status);
+    assert!(in_process.stdout.contains(r#""forgiven":1"#), "{}", in_process.stdout);
+}
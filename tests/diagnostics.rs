@@ -0,0 +1,130 @@
+mod common;
+
+use common::Sandbox;
+
+#[test]
+fn a_parse_error_underlines_the_token_it_stopped_on() {
+    let sandbox = Sandbox::new("diagnostic-parse");
+    sandbox.write("s.divine", "genesis() {\n  let x = 1 +* 2\n}\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(5);
+    assert_eq!(outcome.stderr, "\
+Divine Error: ParseError: expected an expression at line 2, column 14
+ --> s.divine:2:14
+  |
+2 |   let x = 1 +* 2
+  |              ^
+");
+}
+
+#[test]
+fn a_parse_error_underlines_a_whole_string_it_stopped_on() {
+    let sandbox = Sandbox::new("diagnostic-parse-string");
+    sandbox.write("s.divine", "let x = 1\nx = 2 \"three\"\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(5);
+    assert!(outcome.stderr.ends_with("2 | x = 2 \"three\"\n  |       ^^^^^^^\n"), "{}", outcome.stderr);
+}
+
+#[test]
+fn an_undeclared_name_is_underlined_where_it_is_used() {
+    let sandbox = Sandbox::new("diagnostic-undeclared");
+    sandbox.write("s.divine", "genesis() {\n  print(nothing)\n}\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(6);
+    assert_eq!(outcome.stderr, "\
+Divine Error: HeresyError: 'nothing' was never declared (line 2)
+ --> s.divine:2:9
+  |
+2 |   print(nothing)
+  |         ^^^^^^^
+");
+}
+
+#[test]
+fn a_fault_in_a_called_function_is_pinned_inside_it() {
+    let sandbox = Sandbox::new("diagnostic-nested");
+    sandbox.write("s.divine", "bless fall() {\n  transgress(\"woe\")\n}\ngenesis() {\n  fall()\n}\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(6);
+    assert!(outcome.stderr.contains(" --> s.divine:2:3\n"), "{}", outcome.stderr);
+    assert!(outcome.stderr.ends_with("2 |   transgress(\"woe\")\n  |   ^^^^^^^^^^^^^^^^^\n"), "{}", outcome.stderr);
+}
+
+#[test]
+fn a_missing_module_is_underlined_where_it_is_imported() {
+    let sandbox = Sandbox::new("diagnostic-module");
+    sandbox.write("s.divine", "import module \"absent\"\ngenesis() {\n  print(1)\n}\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(5);
+    assert!(outcome.stderr.contains(" --> s.divine:1:16\n"), "{}", outcome.stderr);
+    assert!(outcome.stderr.ends_with("1 | import module \"absent\"\n  |                ^^^^^^\n"), "{}", outcome.stderr);
+}
+
+#[test]
+fn a_sin_underlines_its_whole_line() {
+    let sandbox = Sandbox::new("diagnostic-sin");
+    sandbox.write("s.divine", "function speak() {\n  print(1)\n}\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(2);
+    assert_eq!(outcome.stderr, "\
+Divine Error: SinError: Function at line 1 lacks divine blessing
+ --> s.divine:1:1
+  |
+1 | function speak() {
+  | ^^^^^^^^^^^^^^^^^^
+");
+}
+
+#[test]
+fn an_error_in_no_script_is_not_pinned() {
+    let sandbox = Sandbox::new("diagnostic-missing");
+    let outcome = sandbox.run(&["run", "missing.divine"]).expect(4);
+    assert_eq!(outcome.stderr, "Divine Error: Failed to read the scripture: missing.divine does not exist\n");
+}
+
+#[test]
+fn nesting_too_deep_is_a_parse_error_not_a_crash() {
+    let sandbox = Sandbox::new("diagnostic-nesting");
+    sandbox.write("s.divine", format!("let x = {}1{}\n", "[".repeat(600), "]".repeat(600)));
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(5);
+    assert!(outcome.stderr.contains("ParseError: nested more than 128 levels deep at line 1, column 137"), "{}", outcome.stderr);
+    // Confession judges what it can parse, and doesn't fall over what it can't
+    sandbox.run(&["confess", "s.divine"]).success();
+}
+
+#[test]
+fn color_never_prints_no_escape_codes_even_when_color_is_forced() {
+    let sandbox = Sandbox::new("diagnostic-color-never");
+    sandbox.write("fault.divine", "genesis() {\n  print(nothing)\n}\n");
+    sandbox.write("sinful.divine", "let x = 1\n");
+    let colored = |when: &str, args: &[&str]| common::Outcome::of(
+        sandbox.divine().env("CLICOLOR_FORCE", "1").args(["--color", when]).args(args).output().unwrap()
+    );
+    for args in [&["run", "fault.divine"][..], &["confess", "sinful.divine"], &["prophesy", "sinful.divine"]] {
+        let always = colored("always", args);
+        assert!(always.stdout.contains("\x1b["), "{:?}: {}", args, always.stdout);
+        let never = colored("never", args);
+        assert!(!never.stdout.contains("\x1b["), "{:?}: {}", args, never.stdout);
+        assert!(!never.stderr.contains("\x1b["), "{:?}: {}", args, never.stderr);
+    }
+    assert!(colored("always", &["run", "fault.divine"]).stderr.contains("\x1b["));
+}
+
+#[test]
+fn a_mistyped_script_suggests_the_one_beside_it() {
+    let sandbox = Sandbox::new("diagnostic-typo");
+    sandbox.write("genesis.divine", "print(\"hi\")\n");
+    let outcome = sandbox.run(&["run", "genesys.divine"]).expect(4);
+    assert_eq!(outcome.stderr, "Divine Error: Failed to read the scripture: genesys.divine does not exist; did you mean genesis.divine?\n");
+    // The same name with a wrong extension is suggested however far apart
+    let outcome = sandbox.run(&["run", "genesis.txt"]).expect(4);
+    assert!(outcome.stderr.ends_with("; did you mean genesis.divine?\n"), "{}", outcome.stderr);
+    // A name close to nothing suggests nothing
+    let outcome = sandbox.run(&["run", "exodus.divine"]).expect(4);
+    assert!(!outcome.stderr.contains("did you mean"), "{}", outcome.stderr);
+}
+
+#[test]
+fn a_script_of_another_kind_is_read_only_when_unorthodox() {
+    let sandbox = Sandbox::new("diagnostic-unorthodox");
+    sandbox.write("hymn.js", "print(\"hi\")\n");
+    let outcome = sandbox.run(&["run", "hymn.js"]).expect(1);
+    assert_eq!(outcome.stderr, "Divine Error: UnorthodoxError: hymn.js is not a .divine or .dpl scripture, and only those are blessed; pass --unorthodox to read it all the same\n");
+    let outcome = sandbox.run(&["--unorthodox", "run", "hymn.js"]).success();
+    assert!(outcome.stdout.contains("\nhi\n"), "{}", outcome.stdout);
+}
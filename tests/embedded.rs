@@ -0,0 +1,46 @@
+mod common;
+
+use std::fs;
+
+use common::Sandbox;
+
+const README: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/embedded/README.md");
+
+/// A sandbox holding the fixture README, with a clean block fenced at line 5,
+/// a sinful one indented in a list item at line 14, and an ignored one at line 22.
+fn psalter(name: &str) -> Sandbox {
+    let sandbox = Sandbox::new(name);
+    sandbox.write("README.md", fs::read(README).unwrap());
+    sandbox
+}
+
+#[test]
+fn sins_in_embedded_blocks_are_told_at_the_documents_lines() {
+    let sandbox = psalter("embedded-confess");
+    let outcome = sandbox.run(&["--porcelain", "confess", "README.md", "--embedded", "markdown"]).expect(2);
+    // Nothing from the ignored block, though it sins mortally
+    assert_eq!(outcome.stdout, "\
+sin venial DPL003 README.md:15
+sin mortal DPL010 README.md:16
+sin mortal DPL010 README.md:17
+");
+
+    let outcome = sandbox.run(&["confess", "README.md", "--embedded", "markdown"]).expect(2);
+    assert!(outcome.stdout.contains("Beginning confession ritual for 2 embedded blocks"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("--> README.md:15:4\n   |\n15 |    var cost = 1;\n   |    ^^^\n"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_run_of_embedded_blocks_fails_for_the_one_that_falls() {
+    let sandbox = psalter("embedded-run");
+    let outcome = sandbox.run(&["run", "README.md", "--embedded", "markdown"]).expect(6);
+    assert!(outcome.stdout.contains("README.md:5: running embedded block..."), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("README.md:14: running embedded block..."), "{}", outcome.stdout);
+    assert!(!outcome.stdout.contains("README.md:22"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("\n23\n"), "{}", outcome.stdout);
+
+    // The error is placed on the document's line and column, past the list item's indentation
+    assert!(outcome.stderr.contains("MoralError: Thou shalt not kill child processes at line 16\n  --> README.md:16:4\n"), "{}", outcome.stderr);
+    assert!(outcome.stderr.contains("16 |    let killProcess = cost;\n   |    ^^^^"), "{}", outcome.stderr);
+    assert!(outcome.stderr.contains("1 embedded block in README.md did not reach salvation (fenced at line 14)"), "{}", outcome.stderr);
+}
@@ -0,0 +1,39 @@
+//! Scripts saved on Windows, with a byte order mark and CRLF line endings,
+//! behave as they would with neither.
+
+mod common;
+
+use common::Sandbox;
+
+const SCRIPT: &[u8] = include_bytes!("fixtures/bom_crlf.divine");
+const SECULAR: &[u8] = include_bytes!("fixtures/bom_crlf.js");
+
+#[test]
+fn a_script_with_a_bom_and_crlf_endings_runs() {
+    let sandbox = Sandbox::new("crlf-run");
+    sandbox.write("s.divine", SCRIPT);
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert!(outcome.stdout.contains("📢 saved"), "{}", outcome.stdout);
+    // The prayer block opened and closed, and the comment behind the mark was no statement
+    assert!(!outcome.stdout.contains("wilderness"), "{}", outcome.stdout);
+    assert!(!outcome.stdout.contains("Lord, bless"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_script_with_a_bom_and_crlf_endings_confesses_no_sin() {
+    let sandbox = Sandbox::new("crlf-confess");
+    sandbox.write("s.divine", SCRIPT);
+    let outcome = sandbox.run(&["confess", "s.divine"]).success();
+    assert!(outcome.stdout.contains("sins: total=0 venial=0 mortal=0"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_miracle_on_crlf_code_keeps_its_endings_unmixed() {
+    let sandbox = Sandbox::new("crlf-miracle");
+    sandbox.write("s.js", SECULAR);
+    sandbox.run(&["miracle", "s.js", "divine.js"]).success();
+    let miracle = sandbox.read("divine.js");
+    assert!(miracle.contains("bless function greet(name) {\r\n  revelation(name);\r\n  ascend with name;\r\n}"), "{:?}", miracle);
+    assert!(!miracle.starts_with('\u{feff}'), "{:?}", miracle);
+    assert!(!miracle.replace("\r\n", "").contains('\n'), "mixed endings: {:?}", miracle);
+}
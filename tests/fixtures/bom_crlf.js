@@ -0,0 +1,5 @@
+﻿// Saved on Windows, behind a byte order mark
+function greet(name) {
+  console.log(name);
+  return name;
+}
@@ -0,0 +1,26 @@
+// Transformed by the Divine Miracle of DivinePL
+// This code has been sanctified from its secular origins
+
+// 🙏 BEGIN PRAYER 🙏
+// Lord, bless this transformed code
+// Guide it to run with divine efficiency
+// Protect it from bugs and runtime errors
+// 🙏 END PRAYER 🙏
+
+// A function to return the total; console.log is only for debugging
+/* throw new Error would be harsher:
+   return nothing, for nothing */
+bless function total(items) {
+  const returnValue = "function return throw new Error";
+  const note = 'console.log(return)';
+  const functional = `return ${items.length} items, function aside`;
+  if (items.length === 0) {
+    confess new Sin("return nothing; function failed");
+  }
+  revelation("total of", items); // return it next
+  ascend with items.reduce((sum, item) => sum + item, 0) + returnValue.length + note.length + functional.length;
+}
+
+
+// End of sanctified code
+// "In the beginning was the code, and the code was with God." - DivinePL 1:1
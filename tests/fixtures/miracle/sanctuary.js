@@ -0,0 +1,13 @@
+// A function to return the total; console.log is only for debugging
+/* throw new Error would be harsher:
+   return nothing, for nothing */
+function total(items) {
+  const returnValue = "function return throw new Error";
+  const note = 'console.log(return)';
+  const functional = `return ${items.length} items, function aside`;
+  if (items.length === 0) {
+    throw new Error("return nothing; function failed");
+  }
+  console.log("total of", items); // return it next
+  return items.reduce((sum, item) => sum + item, 0) + returnValue.length + note.length + functional.length;
+}
@@ -0,0 +1,269 @@
+//! What `miracle` and `secularize` promise of the code they transform. The
+//! miracle of tests/fixtures/miracle/sanctuary.js is checked against a golden
+//! file beside it; run with UPDATE_GOLDEN=1 to write it afresh after a change
+//! meant to alter it.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::Sandbox;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/miracle");
+
+#[test]
+fn keywords_in_strings_and_comments_are_left_as_they_are() {
+    let sandbox = Sandbox::new("miracle-tokens");
+    sandbox.write("sanctuary.js", fs::read(Path::new(FIXTURES).join("sanctuary.js")).unwrap());
+    sandbox.run(&["miracle", "sanctuary.js", "divine.js"]).success();
+
+    let path = Path::new(FIXTURES).join("sanctuary.divine.js");
+    let written = sandbox.read_bytes("divine.js");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, &written).unwrap();
+    }
+    let expected = fs::read(&path).unwrap_or_else(|e| panic!("reading {}: {}", path.display(), e));
+    assert!(written == expected, "divine.js differs from sanctuary.divine.js:\n{}", String::from_utf8_lossy(&written));
+}
+
+#[test]
+fn code_from_standard_input_is_taken_for_javascript() {
+    let sandbox = Sandbox::new("miracle-stdin");
+    let outcome = sandbox.run_with_input(&["miracle", "-", "-"], "function f(x) {\n  return x;\n}\n").success();
+    assert!(outcome.stdout.contains("bless function f(x) {\n  ascend with x;\n}"), "{}", outcome.stdout);
+}
+
+#[test]
+fn code_from_standard_input_can_be_named_another_language() {
+    let sandbox = Sandbox::new("miracle-stdin-language");
+    let outcome = sandbox.run_with_input(&["miracle", "--language", "python", "-", "-"], "def f(x):\n    raise ValueError(x)\n").success();
+    assert!(outcome.stdout.contains("bless def f(x):\n    confess ValueError(x)"), "{}", outcome.stdout);
+    assert!(outcome.stdout.starts_with("# Transformed by the Divine Miracle of DivinePL\n"), "{}", outcome.stdout);
+}
+
+#[test]
+fn the_header_and_footer_are_written_in_the_comments_of_the_language() {
+    let sandbox = Sandbox::new("miracle-comments");
+    sandbox.write("s.py", "def f(x):\n    print(x)\n");
+    sandbox.write("s.rs", "fn f() {\n    println!(\"x\");\n}\n");
+    sandbox.run(&["miracle", "s.py", "divine.py"]).success();
+    sandbox.run(&["miracle", "s.rs", "divine.rs"]).success();
+
+    let python = sandbox.read("divine.py");
+    let (code, frame): (Vec<&str>, Vec<&str>) = python.lines().filter(|line| !line.is_empty())
+        .partition(|line| line.contains("f(x)") || line.starts_with("    "));
+    assert_eq!(code, ["bless def f(x):", "    revelation(x)"]);
+    assert!(frame.iter().all(|line| line.starts_with("# ")), "{}", python);
+
+    let rust = sandbox.read("divine.rs");
+    assert!(rust.lines().filter(|line| line.contains("🙏")).all(|line| line.starts_with("// ")), "{}", rust);
+}
+
+#[test]
+fn a_miracle_performed_again_keeps_one_header() {
+    let sandbox = Sandbox::new("miracle-again");
+    sandbox.write("s.py", "def f(x):\n    return x\n");
+    sandbox.run(&["miracle", "s.py", "once.py"]).success();
+    sandbox.run(&["miracle", "once.py", "twice.py"]).expect(1);
+    sandbox.run(&["miracle", "--retransform", "once.py", "twice.py"]).success();
+    assert_eq!(sandbox.read("twice.py"), sandbox.read("once.py"));
+}
+
+#[test]
+fn a_header_with_the_prayer_outside_a_comment_is_still_known() {
+    let sandbox = Sandbox::new("miracle-former-header");
+    sandbox.write("old.js", "// Transformed by the Divine Miracle of DivinePL\n// This code has been sanctified from its secular origins\n\n\
+        🙏 BEGIN PRAYER 🙏\nLord, bless this transformed code\nGuide it to run with divine efficiency\nProtect it from bugs and runtime errors\n🙏 END PRAYER 🙏\n\n\
+        bless function f() {}\n\n\n// End of sanctified code\n// \"In the beginning was the code, and the code was with God.\" - DivinePL 1:1\n");
+    sandbox.run(&["secularize", "old.js", "secular.js"]).success();
+    assert_eq!(sandbox.read("secular.js"), "function f() {}\n");
+}
+
+#[test]
+fn an_unknown_extension_is_refused_with_the_languages_known() {
+    let sandbox = Sandbox::new("miracle-unknown");
+    sandbox.write("s.rb", "def f; end\n");
+    let outcome = sandbox.run(&["miracle", "s.rb", "out.rb"]).expect(1);
+    assert!(outcome.stderr.contains("python (.py)") && outcome.stderr.contains("--language"), "{}", outcome.stderr);
+}
+
+#[test]
+fn secularizing_a_miracle_returns_the_original_byte_for_byte() {
+    let sandbox = Sandbox::new("miracle-round-trip");
+    let original = "function area(r) {\n  if (r < 0) {\n    throw new Error(\"negative\");\n  }\n  console.log(\"area of\", r);\n  return Math.PI * r * r;\n}\nconst unknown = () => 42;\n";
+    sandbox.write("s.js", original);
+    sandbox.run(&["miracle", "s.js", "divine.js"]).success();
+    assert_ne!(sandbox.read("divine.js"), original);
+
+    let outcome = sandbox.run(&["secularize", "divine.js", "secular.js", "--report"]).success();
+    assert_eq!(sandbox.read_bytes("secular.js"), original.as_bytes());
+    for reversed in ["1  bless function → function", "1  confess new Sin → throw new Error", "1  revelation → console.log", "1  ascend with → return", "0  preach → for"] {
+        assert!(outcome.stdout.contains(reversed), "{}", outcome.stdout);
+    }
+}
+
+#[test]
+fn a_preview_needs_no_output_and_writes_nothing() {
+    let sandbox = Sandbox::new("miracle-preview");
+    let original = "function f(x) {\n  return x;\n}\n";
+    sandbox.write("s.js", original);
+    for flag in ["--preview", "--dry-run"] {
+        let outcome = sandbox.run(&["miracle", "s.js", flag]).success();
+        assert!(outcome.stdout.contains("-function f(x) {\n") && outcome.stdout.contains("+bless function f(x) {\n"), "{}", outcome.stdout);
+        assert!(outcome.stdout.contains(" return: return → ascend with") && outcome.stdout.contains("2 substitutions in s.js (javascript); nothing was written"), "{}", outcome.stdout);
+    }
+    sandbox.run(&["miracle", "s.js", "--preview", "--in-place"]).success();
+    assert_eq!(sandbox.read("s.js"), original);
+    assert_eq!(std::fs::read_dir(sandbox.path()).unwrap().count(), 2, "only s.js and the home were there");
+}
+
+#[test]
+fn a_stat_prints_only_the_substitutions() {
+    let sandbox = Sandbox::new("miracle-stat");
+    sandbox.write("s.js", "function f(x) {\n  console.log(x);\n  return x;\n}\n");
+    let outcome = sandbox.run(&["miracle", "s.js", "--stat"]).success();
+    assert_eq!(outcome.stdout.lines().collect::<Vec<_>>(), [
+        " function: function → bless function   | 1",
+        " console-log: console.log → revelation | 1",
+        " return: return → ascend with          | 1",
+        " 3 substitutions in s.js (javascript); nothing was written",
+    ]);
+}
+
+#[test]
+fn in_place_needs_no_output_and_refuses_one() {
+    let sandbox = Sandbox::new("miracle-in-place");
+    sandbox.write("s.js", "function f(x) {\n  return x;\n}\n");
+    sandbox.run(&["miracle", "s.js"]).expect(2);
+    sandbox.run(&["miracle", "s.js", "out.js", "--in-place"]).expect(2);
+
+    let outcome = sandbox.run(&["miracle", "s.js", "--in-place"]).success();
+    assert!(outcome.stdout.contains("transformed in place in s.js; the original rests in s.js.secular.bak"), "{}", outcome.stdout);
+    assert!(sandbox.read("s.js").contains("bless function f(x) {\n  ascend with x;\n}"));
+}
+
+#[cfg(unix)]
+#[test]
+fn in_place_keeps_the_original_and_its_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = Sandbox::new("miracle-in-place-kept");
+    let original = "#!/usr/bin/env node\nfunction f(x) {\n  return x;\n}\n";
+    let script = sandbox.write("s.js", original);
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o751)).unwrap();
+    sandbox.run(&["miracle", "s.js", "--in-place"]).success();
+
+    let mode = |file: &str| fs::metadata(sandbox.path().join(file)).unwrap().permissions().mode() & 0o777;
+    assert!(sandbox.read("s.js").contains("bless function f(x) {"));
+    assert_eq!(mode("s.js"), 0o751);
+    assert_eq!(sandbox.read_bytes("s.js.secular.bak"), original.as_bytes());
+    assert_eq!(mode("s.js.secular.bak"), 0o751);
+}
+
+#[test]
+fn a_directory_is_transformed_file_by_file_under_the_output() {
+    let sandbox = Sandbox::new("miracle-directory");
+    sandbox.write("secular/a.js", "function f(x) {\n  return x;\n}\n");
+    sandbox.write("secular/lib/b.py", "def g(x):\n    return x\n");
+    sandbox.write("secular/notes.txt", "return\n");
+    sandbox.run(&["miracle", "secular/a.js", "secular/done.js"]).success();
+
+    let outcome = sandbox.run(&["miracle", "secular", "secular/divine"]).success();
+    assert!(outcome.stdout.contains("2 files transformed, 2 skipped (1 already transformed, 1 of a language a miracle doesn't know), 0 failed; 4 substitutions in all"), "{}", outcome.stdout);
+    assert!(sandbox.read("secular/divine/a.js").contains("bless function f(x) {\n  ascend with x;\n}"));
+    assert!(sandbox.read("secular/divine/lib/b.py").contains("bless def g(x):\n    ascend with x"));
+    assert!(!sandbox.path().join("secular/divine/notes.txt").exists() && !sandbox.path().join("secular/divine/done.js").exists());
+
+    // What was written under the directory is not taken for more secular code
+    let outcome = sandbox.run(&["miracle", "secular", "secular/divine", "--extensions", "py"]).success();
+    assert!(outcome.stdout.contains("1 file transformed, 0 skipped"), "{}", outcome.stdout);
+    sandbox.run(&["miracle", "secular", "elsewhere", "--extensions", "rb"]).expect(1);
+}
+
+#[test]
+fn a_directory_transformed_in_place_keeps_each_original() {
+    let sandbox = Sandbox::new("miracle-directory-in-place");
+    sandbox.write("secular/a.js", "function f(x) {\n  return x;\n}\n");
+    sandbox.write("secular/lib/b.py", "def g(x):\n    return x\n");
+    sandbox.write("secular/broken.js", b"function f() {}\n\xff\n");
+
+    let outcome = sandbox.run(&["miracle", "secular", "--in-place"]).expect(1);
+    assert!(outcome.stdout.contains("lib/b.py the original rests in secular/lib/b.py.secular.bak"), "{}", outcome.stdout);
+    assert!(outcome.stderr.contains("Failed to read secular/broken.js"), "{}", outcome.stderr);
+    assert!(outcome.stderr.contains("1 file could not be transformed"), "{}", outcome.stderr);
+    assert_eq!(sandbox.read("secular/a.js.secular.bak"), "function f(x) {\n  return x;\n}\n");
+    assert!(sandbox.read("secular/a.js").contains("bless function f(x) {"));
+    assert!(sandbox.read("secular/lib/b.py").contains("bless def g(x):"));
+}
+
+#[test]
+fn a_liturgy_is_read_as_toml_or_json_by_its_extension() {
+    let sandbox = Sandbox::new("miracle-liturgy");
+    sandbox.write("s.js", "function f() {\n  debugger;\n  log.info(new NotFoundAppError());\n}\n");
+    sandbox.write("liturgy.json", r#"{
+  "disable": ["debugger"],
+  "rules": [
+    { "id": "logger", "match": "log.info", "replacement": "revelation", "language": "javascript" },
+    { "id": "base-error", "match": "AppError", "replacement": "Sin", "word_boundary": false }
+  ]
+}
+"#);
+    sandbox.write("liturgy.toml", r#"disable = ["debugger"]
+
+[[rules]]
+id = "logger"
+match = "log.info"
+replacement = "revelation"
+language = "javascript"
+
+[[rules]]
+id = "base-error"
+match = "AppError"
+replacement = "Sin"
+word_boundary = false
+"#);
+    for liturgy in ["liturgy.json", "liturgy.toml"] {
+        let output = format!("divine-{}.js", liturgy);
+        sandbox.run(&["miracle", "s.js", &output, "--liturgy", liturgy]).success();
+        assert!(sandbox.read(&output).contains("bless function f() {\n  debugger;\n  revelation(new NotFoundSin());\n}"), "{}", sandbox.read(&output));
+    }
+    assert_eq!(sandbox.read("divine-liturgy.json.js"), sandbox.read("divine-liturgy.toml.js"));
+
+    // Each is held to its own syntax, and to the same rules
+    sandbox.write("json.toml", "{ \"disable\": [] }\n");
+    let outcome = sandbox.run(&["miracle", "s.js", "--stat", "--liturgy", "json.toml"]).expect(1);
+    assert!(outcome.stderr.contains("The liturgy json.toml is not valid TOML"), "{}", outcome.stderr);
+    sandbox.write("unknown.toml", "disable = [\"amen\"]\n");
+    let outcome = sandbox.run(&["miracle", "s.js", "--stat", "--liturgy", "unknown.toml"]).expect(1);
+    assert!(outcome.stderr.contains("The liturgy unknown.toml disables 'amen', which is no built-in rule"), "{}", outcome.stderr);
+}
+
+#[test]
+fn a_source_map_traces_each_line_of_a_miracle_to_where_it_came_from() {
+    let sandbox = Sandbox::new("miracle-map");
+    sandbox.write("s.js", "function f(x) {\n  if (x < 0) {\n    throw\n      new Error(\"no\");\n  }\n  return x;\n}\n");
+    sandbox.run(&["miracle", "--map", "s.map.json", "s.js", "divine.js"]).success();
+    assert_eq!(sandbox.read("s.map.json"), concat!(
+        r#"{"source":"s.js","header":{"first_line":1,"last_line":9},"footer":{"first_line":16,"last_line":19},"lines":["#,
+        r#"{"generated_line":10,"original_line":1},{"generated_line":11,"original_line":2},{"generated_line":12,"original_line":3},"#,
+        r#"{"generated_line":13,"original_line":5},{"generated_line":14,"original_line":6},{"generated_line":15,"original_line":7}]}"#, "\n",
+    ));
+    assert_eq!(sandbox.read("divine.js").lines().nth(11), Some("    confess new Sin(\"no\");"));
+
+    let lookup = |line: &str| sandbox.run(&["map-lookup", "s.map.json", line]);
+    assert_eq!(lookup("1").success().stdout, "Line 1 of the miracle is in the header it wrote, and was no line of s.js\n");
+    assert_eq!(lookup("12").success().stdout, "Line 12 of the miracle was line 3 of s.js\n");
+    assert_eq!(lookup("13").success().stdout, "Line 13 of the miracle was line 5 of s.js\n");
+    assert_eq!(lookup("16").success().stdout, "Line 16 of the miracle is in the footer it wrote, and was no line of s.js\n");
+    assert_eq!(lookup("20").expect(1).stderr, "Divine Error: Line 20 is beyond the 19 lines s.map.json maps\n");
+
+    // Secularized with the map, each line goes back to the line it came from
+    sandbox.run(&["secularize", "--map", "s.map.json", "divine.js", "secular.js"]).success();
+    let secular = sandbox.read("secular.js");
+    assert_eq!(secular.lines().collect::<Vec<_>>(), ["function f(x) {", "  if (x < 0) {", "    throw new Error(\"no\");", "", "  }", "  return x;", "}"]);
+
+    sandbox.write("dir/a.js", "function f() {}\n");
+    let outcome = sandbox.run(&["miracle", "--map", "dir.map.json", "dir", "out"]).expect(1);
+    assert!(outcome.stderr.contains("--map writes the source map of a single file, not of a directory"), "{}", outcome.stderr);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::Sandbox;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/modules");
+
+/// A sandbox holding a copy of the project `name` in tests/fixtures/modules.
+fn project(name: &str) -> Sandbox {
+    let sandbox = Sandbox::new(&format!("modules-{}", name));
+    copy(&Path::new(FIXTURES).join(name), Path::new(""), &sandbox);
+    sandbox
+}
+
+fn copy(dir: &Path, relative: &Path, sandbox: &Sandbox) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let entry = entry.unwrap();
+        let relative = relative.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy(&entry.path(), &relative, sandbox);
+        } else {
+            sandbox.write(&relative.to_string_lossy(), fs::read(entry.path()).unwrap());
+        }
+    }
+}
+
+#[test]
+fn a_script_calls_the_blessings_of_the_modules_it_imports() {
+    let sandbox = project("pair");
+    let outcome = sandbox.run(&["-vvv", "run", "genesis.divine"]).success();
+    assert!(outcome.stdout.contains("Peace be with you, pilgrim\n"), "{}", outcome.stdout);
+    // son imports father too, whose welcome son's carry calls
+    assert!(outcome.stdout.contains("Peace be with you, bearer of 3\n"), "{}", outcome.stdout);
+    assert!(!outcome.stdout.contains("a module's own statements never run\n"), "{}", outcome.stdout);
+
+    // father is imported twice but loaded once
+    let loaded: Vec<&str> = outcome.stdout.lines().filter(|line| line.starts_with("📂 Loaded")).collect();
+    assert_eq!(loaded, [
+        "📂 Loaded genesis.divine (1 blessed functions)",
+        "📂 Loaded holy_trinity/father.divine (1 blessed functions)",
+        "📂 Loaded holy_trinity/son.divine (1 blessed functions)",
+    ]);
+}
+
+#[test]
+fn importing_modules_in_a_circle_is_an_ouroboros() {
+    let sandbox = project("circle");
+    let outcome = sandbox.run(&["run", "genesis.divine"]).expect(5);
+    assert!(outcome.stderr.contains(
+        "OuroborosError: circular import genesis.divine → holy_trinity/alpha.divine → holy_trinity/omega.divine → holy_trinity/alpha.divine"
+    ), "{}", outcome.stderr);
+    assert!(!outcome.stdout.contains("the end"), "{}", outcome.stdout);
+}
@@ -0,0 +1,39 @@
+//! How confession ends: the exit status it gives for the sins it found, and
+//! the line that counts them.
+
+mod common;
+
+use common::Sandbox;
+
+/// A sandbox holding a script with no sins, one with a venial sin, and one
+/// with a mortal sin too.
+fn confessional(name: &str) -> Sandbox {
+    let sandbox = Sandbox::new(name);
+    sandbox.write("clean.divine", "bless genesis() {\n  print(1)\n}\n");
+    sandbox.write("venial.divine", "var x = 1\nbless genesis() {\n  print(x)\n}\n");
+    sandbox.write("mortal.divine", "var x = 1\nbless genesis() {\n  kill(x)\n}\n");
+    sandbox
+}
+
+#[test]
+fn confession_exits_by_the_gravest_sin_found() {
+    let sandbox = confessional("penance-default");
+    for (script, code, counts) in [
+        ("clean.divine", 0, "sins: total=0 venial=0 mortal=0 forgiven=0"),
+        ("venial.divine", 1, "sins: total=1 venial=1 mortal=0 forgiven=0"),
+        ("mortal.divine", 2, "sins: total=2 venial=1 mortal=1 forgiven=0"),
+    ] {
+        let outcome = sandbox.run(&["confess", script]).expect(code);
+        assert_eq!(outcome.stdout.lines().last(), Some(counts), "{}", outcome.stdout);
+    }
+}
+
+#[test]
+fn the_penance_threshold_chooses_which_sins_fail() {
+    let sandbox = confessional("penance-threshold");
+    for (threshold, codes) in [("none", [0, 0, 0]), ("venial", [0, 1, 2]), ("mortal", [0, 0, 2])] {
+        for (script, code) in ["clean.divine", "venial.divine", "mortal.divine"].into_iter().zip(codes) {
+            sandbox.run(&["confess", script, "--penance-threshold", threshold]).expect(code);
+        }
+    }
+}
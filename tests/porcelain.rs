@@ -0,0 +1,47 @@
+//! The --porcelain forms are kept from version to version, so each is checked
+//! against a golden file in tests/fixtures/porcelain. Run with UPDATE_GOLDEN=1
+//! to write the golden files afresh after a change meant to alter the form.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::Sandbox;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/porcelain");
+
+/// Runs `divine --porcelain` with `args` on a copy of psalm.divine, expecting
+/// `code`, and compares what it printed with the golden file `golden`.
+fn golden(golden: &str, args: &[&str], code: i32) {
+    let sandbox = Sandbox::new(&format!("porcelain-{}", golden));
+    sandbox.write("psalm.divine", fs::read(Path::new(FIXTURES).join("psalm.divine")).unwrap());
+    let outcome = sandbox.run(&[&["--porcelain"], args].concat()).expect(code);
+
+    let path = Path::new(FIXTURES).join(golden);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, &outcome.stdout).unwrap();
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| panic!("reading {}: {}", path.display(), e));
+    assert_eq!(outcome.stdout, expected, "stdout differs from {}\n--- stderr\n{}", golden, outcome.stderr);
+}
+
+#[test]
+fn run_keeps_its_porcelain_form() {
+    golden("run.out", &["run", "psalm.divine", "--seed", "7"], 0);
+}
+
+#[test]
+fn confess_keeps_its_porcelain_form() {
+    golden("confess.out", &["confess", "psalm.divine"], 1);
+}
+
+#[test]
+fn prophesy_keeps_its_porcelain_form() {
+    golden("prophesy.out", &["prophesy", "psalm.divine", "--seed", "7"], 0);
+}
+
+#[test]
+fn prophesy_of_a_directory_keeps_its_porcelain_form() {
+    golden("prophesy-directory.out", &["prophesy", ".", "--seed", "7"], 0);
+}
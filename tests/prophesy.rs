@@ -0,0 +1,55 @@
+//! What other tools rely on in `prophesy`: its exit status as a gate, and the
+//! reports `--output` writes, of which the Markdown one is checked against a
+//! golden file in tests/fixtures/prophecy. Run with UPDATE_GOLDEN=1 to write
+//! it afresh after a change meant to alter it.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::Sandbox;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/prophecy");
+
+#[test]
+fn the_markdown_report_keeps_its_form() {
+    let sandbox = omens("prophesy-markdown");
+    sandbox.run(&["prophesy", "omens.divine", "--seed", "7", "--output", "vision.md"]).success();
+
+    let path = Path::new(FIXTURES).join("omens.md");
+    let written = sandbox.read_bytes("vision.md");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, &written).unwrap();
+    }
+    let expected = fs::read(&path).unwrap_or_else(|e| panic!("reading {}: {}", path.display(), e));
+    assert!(written == expected, "vision.md differs from omens.md:\n{}", String::from_utf8_lossy(&written));
+}
+
+/// A sandbox holding the omens fixture, whose code alone gives four prophecies,
+/// one each of reliability, maintainability, security and design, of doom 7.
+fn omens(name: &str) -> Sandbox {
+    let sandbox = Sandbox::new(name);
+    sandbox.write("omens.divine", fs::read(Path::new(FIXTURES).join("omens.divine")).unwrap());
+    sandbox
+}
+
+#[test]
+fn fail_on_fails_only_for_a_category_foretold() {
+    let sandbox = omens("prophesy-fail-on");
+    let outcome = sandbox.run(&["prophesy", "omens.divine", "--fail-on", "testing", "--fail-on", "deployment"]).success();
+    assert!(!outcome.stderr.contains("--fail-on"), "{}", outcome.stderr);
+
+    let outcome = sandbox.run(&["prophesy", "omens.divine", "--fail-on", "testing", "--fail-on", "security", "--fail-on", "design"]).expect(1);
+    assert!(outcome.stderr.contains("1 security prophecy was foretold (--fail-on security)"), "{}", outcome.stderr);
+    assert!(outcome.stderr.contains("1 design prophecy was foretold (--fail-on design)"), "{}", outcome.stderr);
+    assert!(!outcome.stderr.contains("--fail-on testing"), "{}", outcome.stderr);
+}
+
+#[test]
+fn max_doom_fails_only_past_it() {
+    let sandbox = omens("prophesy-max-doom");
+    sandbox.run(&["prophesy", "omens.divine", "--max-doom", "7"]).success();
+    let outcome = sandbox.run(&["prophesy", "omens.divine", "--max-doom", "6"]).expect(1);
+    assert!(outcome.stderr.contains("The doom foretold is 7, past --max-doom 6"), "{}", outcome.stderr);
+}
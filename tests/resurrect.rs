@@ -0,0 +1,76 @@
+mod common;
+
+use common::Sandbox;
+
+const SINFUL: &str = "var x = 1\nbless genesis() {\n  print(x)\n}\n";
+const ABSOLVED: &str = "let x = 1\nbless genesis() {\n  print(x)\n}\n";
+
+/// A project with a sinful script, absolved once so that its relic rests in the sepulchre.
+fn absolved(name: &str) -> Sandbox {
+    let sandbox = Sandbox::new(name);
+    sandbox.write("commandments.config", "{}");
+    sandbox.write("s.divine", SINFUL);
+    let outcome = sandbox.run(&["confess", "s.divine", "--absolve"]).success();
+    assert!(outcome.stderr.contains("A relic of"), "{}", outcome.stderr);
+    assert_eq!(sandbox.read("s.divine"), ABSOLVED);
+    sandbox
+}
+
+#[test]
+fn an_absolution_is_undone_without_force() {
+    let sandbox = absolved("resurrect-absolve");
+    let outcome = sandbox.run(&["resurrect", "s.divine"]).success();
+    assert!(outcome.stdout.contains("s.divine has risen again"), "{}", outcome.stdout);
+    assert!(!outcome.stdout.contains("RELICS OF"), "{}", outcome.stdout);
+    assert_eq!(sandbox.read("s.divine"), SINFUL);
+}
+
+#[test]
+fn a_sanctification_is_undone_without_force() {
+    let sandbox = Sandbox::new("resurrect-sanctify");
+    sandbox.write("commandments.config", "{}");
+    sandbox.write("s.divine", "bless genesis(){print(1)}\n");
+    sandbox.run(&["sanctify", "s.divine"]).success();
+    assert_ne!(sandbox.read("s.divine"), "bless genesis(){print(1)}\n");
+    sandbox.run(&["resurrect", "s.divine"]).success();
+    assert_eq!(sandbox.read("s.divine"), "bless genesis(){print(1)}\n");
+}
+
+#[test]
+fn changes_made_after_an_absolution_need_force() {
+    let sandbox = absolved("resurrect-changed");
+    let changed = format!("{}// mine\n", ABSOLVED);
+    sandbox.write("s.divine", &changed);
+
+    let refused = sandbox.run(&["resurrect", "s.divine"]).expect(1);
+    assert!(refused.stderr.contains("has changes that are neither committed nor entombed"), "{}", refused.stderr);
+    assert_eq!(sandbox.read("s.divine"), changed);
+
+    sandbox.run(&["resurrect", "s.divine", "--force"]).success();
+    assert_eq!(sandbox.read("s.divine"), SINFUL);
+    // What --force overwrote was entombed first, and is now the newest relic
+    sandbox.run(&["resurrect", "s.divine", "--relic", "1"]).success();
+    assert_eq!(sandbox.read("s.divine"), changed);
+}
+
+#[test]
+fn the_relics_are_listed_only_when_asked_and_nothing_is_restored() {
+    let sandbox = absolved("resurrect-list");
+    let outcome = sandbox.run(&["resurrect", "s.divine", "--list"]).success();
+    let lines: Vec<&str> = outcome.stdout.lines().collect();
+    assert!(lines[0].contains("RELICS OF s.divine"), "{}", outcome.stdout);
+    assert_eq!(lines.len(), 2, "{}", outcome.stdout);
+    assert!(lines[1].trim_start().starts_with("1  ") && lines[1].ends_with(".divine"), "{}", outcome.stdout);
+    assert_eq!(sandbox.read("s.divine"), ABSOLVED);
+}
+
+#[test]
+fn a_miracle_in_place_is_undone_without_force() {
+    let sandbox = Sandbox::new("resurrect-miracle");
+    sandbox.write("commandments.config", "{}");
+    sandbox.write("s.js", "function f() { return 1; }\n");
+    sandbox.run(&["miracle", "s.js", "--in-place"]).success();
+    assert_ne!(sandbox.read("s.js"), "function f() { return 1; }\n");
+    sandbox.run(&["resurrect", "s.js"]).success();
+    assert_eq!(sandbox.read("s.js"), "function f() { return 1; }\n");
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{Outcome, Sandbox};
+
+/// The lines a run proclaimed, between the ritual and judgment day, without
+/// the warning that there was no genesis.
+fn proclaimed(stdout: &str) -> Vec<&str> {
+    stdout.lines()
+        .skip_while(|line| !line.starts_with("Rest..."))
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter(|line| !line.contains("executing in the wilderness"))
+        .collect()
+}
+
+#[test]
+fn escaped_quotes_stay_in_the_message() {
+    let sandbox = Sandbox::new("escaped-quotes");
+    sandbox.write("s.divine", r#"revelation("He said \"Let there be light\"");"#);
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert_eq!(proclaimed(&outcome.stdout), [r#"📢 He said "Let there be light""#]);
+}
+
+#[test]
+fn a_closing_paren_inside_the_string_does_not_end_the_call() {
+    let sandbox = Sandbox::new("paren-in-string");
+    sandbox.write("s.divine", r#"revelation("close with \")\" then go"); print("(\")");"#);
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert_eq!(proclaimed(&outcome.stdout), [r#"📢 close with ")" then go"#, r#"(")"#]);
+}
+
+#[test]
+fn several_calls_on_one_line_are_made_in_order() {
+    let sandbox = Sandbox::new("several-calls");
+    sandbox.write("s.divine", r#"revelation("one"); print("two"); revelation("three");"#);
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert_eq!(proclaimed(&outcome.stdout), ["📢 one", "two", "📢 three"]);
+}
+
+#[test]
+fn nested_preach_visits_every_pair_in_order() {
+    let sandbox = Sandbox::new("nested-preach");
+    sandbox.write("s.divine", "preach (row in [1, 2]) {\n  preach (cell in [\"a\", \"b\"]) {\n    print(row + cell)\n  }\n}\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert_eq!(proclaimed(&outcome.stdout), ["1a", "1b", "2a", "2b"]);
+}
+
+#[test]
+fn nested_objects_and_arrays_are_read_changed_and_printed_whole() {
+    let sandbox = Sandbox::new("nested-objects");
+    sandbox.write("s.divine", "\
+let church = {name: \"Zion\", members: [{name: \"Ruth\", gifts: [1, 2]}, {name: \"Boaz\", gifts: []}], elders: {head: \"Eli\"}};
+print(church.members[0].name, church.members[0].gifts[1], church.elders.head, church[\"elders\"][\"head\"]);
+church.elders.head = \"Samuel\";
+church.members[1].name = \"Naomi\";
+print(church);
+let grid = [[1, 2], [3, [4, 5]]];
+print(grid[1][1][0], len(grid[1]), grid);
+");
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert_eq!(proclaimed(&outcome.stdout), [
+        "Ruth 2 Eli Eli",
+        "{name: Zion, members: [{name: Ruth, gifts: [1, 2]}, {name: Naomi, gifts: []}], elders: {head: Samuel}}",
+        "4 2 [[1, 2], [3, [4, 5]]]",
+    ]);
+}
+
+#[test]
+fn a_nested_object_is_shared_and_what_it_lacks_is_void() {
+    let sandbox = Sandbox::new("nested-objects-shared");
+    sandbox.write("s.divine", "\
+let church = {members: [{name: \"Ruth\"}]};
+let ruth = church.members[0];
+ruth.name = \"Naomi\";
+print(church.members[0].name, church.members[0].age, church.members[3].name);
+church.members[0].name.first = \"N\";
+");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(6);
+    assert_eq!(proclaimed(&outcome.stdout), ["Naomi void void"]);
+    assert!(outcome.stderr.contains("FormlessError"), "{}", outcome.stderr);
+}
+
+#[test]
+fn ascending_from_a_nested_preach_leaves_both_loops() {
+    let sandbox = Sandbox::new("nested-preach-ascend");
+    sandbox.write("s.divine", "\
+bless find(grid, wanted) {
+  preach (row in grid) {
+    preach (cell in row) {
+      judge (cell == wanted) {
+        ascend with cell
+      }
+      print(cell)
+    }
+  }
+  ascend with \"none\"
+}
+print(find([[1, 2], [3, 4]], 3))
+");
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert_eq!(proclaimed(&outcome.stdout), ["1", "2", "3"]);
+}
+
+#[test]
+fn a_preach_header_of_another_form_is_a_parse_error() {
+    let sandbox = Sandbox::new("preach-of");
+    sandbox.write("s.divine", "preach (x of [1, 2]) {\n  print(x)\n}\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(5);
+    assert!(outcome.stderr.contains("must take the form preach (name in expr)"), "{}", outcome.stderr);
+    assert!(proclaimed(&outcome.stdout).is_empty(), "{}", outcome.stdout);
+}
+
+#[test]
+fn arguments_after_the_script_fall_as_manna() {
+    let sandbox = Sandbox::new("manna");
+    sandbox.write("s.divine", "bless genesis() {\n  print(manna.length, manna[0], manna[1])\n  print(number(manna[2]) + 1, manna[2] + 1)\n}\n");
+    let outcome = sandbox.run(&["run", "s.divine", "--", "alpha", "beta", "41"]).success();
+    assert_eq!(proclaimed(&outcome.stdout), ["3 alpha beta", "42 411"]);
+}
+
+#[test]
+fn number_refuses_what_it_cannot_count() {
+    let sandbox = Sandbox::new("manna-number");
+    sandbox.write("s.divine", "print(number(manna[0]))\n");
+    let outcome = sandbox.run(&["run", "s.divine", "--", "abc"]).expect(6);
+    assert!(outcome.stderr.contains("number cannot count \"abc\""), "{}", outcome.stderr);
+}
+
+#[test]
+fn listen_reads_piped_lines_without_prompting_and_void_at_the_end() {
+    let sandbox = Sandbox::new("listen");
+    sandbox.write("s.divine", "bless genesis() {\n  let name = listen(\"Name? \")\n  judge (name == \"Job\") {\n    print(\"hello\", name)\n  }\n  print(listen(\"More? \"))\n}\n");
+    let outcome = sandbox.run_with_input(&["run", "s.divine"], "  Job  \n").success();
+    assert_eq!(proclaimed(&outcome.stdout), ["hello Job", "void"]);
+    assert!(!outcome.stdout.contains("Name?"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_fast_run_skips_every_ritual_pause() {
+    let sandbox = Sandbox::new("fast");
+    sandbox.write("s.divine", "bless genesis() {\n  print(1)\n  print(2)\n}\n");
+    let mut by_flag = sandbox.divine();
+    by_flag.env_remove("DIVINEPL_FAST").args(["--fast", "run", "s.divine"]);
+    let mut by_env = sandbox.divine();
+    by_env.args(["run", "s.divine"]);
+    for mut command in [by_flag, by_env] {
+        let started = Instant::now();
+        let outcome = Outcome::of(command.output().unwrap()).success();
+        assert!(started.elapsed() < Duration::from_millis(800), "took {:?}", started.elapsed());
+        assert!(outcome.stdout.contains("Creation of light... ✓"), "{}", outcome.stdout);
+        assert_eq!(proclaimed(&outcome.stdout), ["1", "2"]);
+    }
+
+    let help = sandbox.run(&["--help"]).success();
+    assert!(help.stdout.contains("--fast"), "{}", help.stdout);
+}
+
+#[test]
+fn dev_mode_lets_a_mortal_sin_run_and_forgives_it_at_judgment() {
+    let sandbox = Sandbox::new("dev-judgment");
+    sandbox.write("s.divine", "genesis() {\n  let killProcess = 1;\n  print(1)\n}\n");
+
+    sandbox.run(&["run", "s.divine"]).expect(2);
+    let outcome = sandbox.run(&["--dev", "run", "s.divine"]).success();
+    assert!(outcome.stdout.contains("sent to DEBUGGING PURGATORY"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("execution continues by divine mercy"), "{}", outcome.stdout);
+}
+
+#[test]
+fn dev_mode_forgives_a_broken_covenant() {
+    let sandbox = Sandbox::new("dev-covenant");
+    sandbox.write("s.divine", "genesis() {\n  covenant(\"return\")\n}\n");
+
+    let outcome = sandbox.run(&["--dev", "run", "s.divine"]).success();
+    assert!(outcome.stdout.contains("broken promises are forgiven by divine mercy"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_run_that_leaves_covenants_unfulfilled_is_judged() {
+    let sandbox = Sandbox::new("covenant-breach");
+    sandbox.write("s.divine", "genesis() {\n  covenant(\"return\")\n  covenant(\"rest\")\n  covenant(\"rest\")\n  fulfill(\"rest\")\n}\n");
+
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(6);
+    assert!(outcome.stdout.contains("💔 Unfulfilled covenants:\n  line 2: \"return\"\n  line 4: \"rest\"\n"), "{}", outcome.stdout);
+    assert!(outcome.stderr.contains("CovenantBreachError: 2 covenants remain unfulfilled (lines 2, 4)"), "{}", outcome.stderr);
+    assert!(!outcome.stdout.contains("divine mercy"), "{}", outcome.stdout);
+
+    sandbox.write("s.divine", "genesis() {\n  covenant(\"return\")\n  fulfill(\"retrun\")\n  fulfill(\"return\")\n}\n");
+    // Revelation mode keeps a running tally
+    let outcome = sandbox.run(&["run", "--revelation", "s.divine"]).success();
+    assert!(outcome.stdout.contains("Covenant made at line 2: \"return\" (1 open, 0 fulfilled)"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("fulfill(\"retrun\") at line 3 matches no covenant; did you mean \"return\" (line 2)?"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("Covenant from line 2 fulfilled at line 4: \"return\" (0 open, 1 fulfilled)"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_run_with_the_same_seed_prints_the_same_thing_again() {
+    let sandbox = Sandbox::new("seeded-run");
+    sandbox.write("s.divine", "\
+bless genesis() {
+  let lots = [castLots(1000000), castLots(1000000), castLots(1000000)];
+  preach (lot in lots) {
+    print(lot)
+  }
+}
+");
+    let first = sandbox.run(&["run", "s.divine", "--seed", "42"]).success();
+    let second = sandbox.run(&["run", "s.divine", "--seed", "42"]).success();
+    assert_eq!(first.stdout, second.stdout);
+    assert!(!first.stdout.contains("Execution time"), "{}", first.stdout);
+
+    let other = sandbox.run(&["run", "s.divine", "--seed", "43"]).success();
+    assert_ne!(proclaimed(&first.stdout), proclaimed(&other.stdout));
+}
+
+#[test]
+fn a_recursion_limit_too_tall_to_build_is_refused() {
+    let sandbox = Sandbox::new("tall-tower");
+    sandbox.write("s.divine", "genesis() {\n  print(1)\n}\n");
+    let outcome = sandbox.run(&["run", "--recursion-limit", "281474976710656", "s.divine"]).expect(1);
+    assert!(outcome.stderr.contains("TowerOfBabelError: a tower of 281474976710656 levels is too tall to build"), "{}", outcome.stderr);
+}
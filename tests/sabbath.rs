@@ -0,0 +1,70 @@
+//! What the Sabbath forbids, pretending today is the day the sandbox keeps.
+
+mod common;
+
+use common::{sabbath, Outcome, Sandbox};
+
+/// Runs `args` in development mode, as if it were evening on the Sabbath.
+fn on_the_sabbath(sandbox: &Sandbox, args: &[&str]) -> Outcome {
+    on_the_sabbath_at(sandbox, "20:48", args)
+}
+
+/// Runs `args` in development mode, as if it were `time` on the Sabbath, kept
+/// in UTC so that no change of the clocks moves its end.
+fn on_the_sabbath_at(sandbox: &Sandbox, time: &str, args: &[&str]) -> Outcome {
+    let now = format!("{}T{}", sabbath(), time);
+    sandbox.run(&[&["--dev", "--sabbath-timezone", "utc", "--pretend-today", &now][..], args].concat())
+}
+
+#[test]
+fn running_and_transforming_code_wait_for_the_sabbath_to_end() {
+    let sandbox = Sandbox::new("sabbath-labor");
+    sandbox.write("s.divine", "bless genesis() {\n  print(1)\n}\n");
+    sandbox.write("s.js", "function f() {}\n");
+    for args in [&["run", "s.divine"][..], &["miracle", "s.js", "out.js"], &["secularize", "s.js", "out.js"], &["sanctify", "s.divine"], &["testament", "s.divine"]] {
+        let outcome = on_the_sabbath(&sandbox, args).expect(3);
+        assert!(outcome.stderr.contains("RestError: Remember the Sabbath day, to keep it holy"), "{:?}\n{}", args, outcome.stderr);
+    }
+    assert!(!sandbox.path().join("out.js").exists());
+}
+
+#[test]
+fn reading_and_reflection_go_on_with_a_reminder() {
+    let sandbox = Sandbox::new("sabbath-reflection");
+    sandbox.write("s.divine", "bless genesis() {\n  print(1)\n}\n");
+    for args in [&["confess", "s.divine"][..], &["bible", "error"], &["prophesy", "s.divine"], &["new", "psalms"]] {
+        let outcome = on_the_sabbath(&sandbox, args).success();
+        assert!(outcome.stderr.contains("🕯️ It is the Sabbath"), "{:?}\n{}", args, outcome.stderr);
+    }
+}
+
+#[test]
+fn overriding_the_sabbath_lets_the_labor_go_on() {
+    let sandbox = Sandbox::new("sabbath-override");
+    sandbox.write("s.divine", "bless genesis() {\n  print(1)\n}\n");
+    let outcome = on_the_sabbath(&sandbox, &["--override-sabbath", "run", "s.divine"]).success();
+    assert!(outcome.stdout.contains("\n1\n"), "{}", outcome.stdout);
+}
+
+#[test]
+fn only_development_mode_may_pretend_today_is_another_day() {
+    let sandbox = Sandbox::new("sabbath-pretend");
+    sandbox.write("s.divine", "bless genesis() {\n  print(1)\n}\n");
+    let outcome = sandbox.run(&["--pretend-today", &sabbath(), "run", "s.divine"]).expect(1);
+    assert!(outcome.stderr.contains("--pretend-today is only available in development mode (--dev)"), "{}", outcome.stderr);
+    let outcome = sandbox.run(&["--dev", "--pretend-today", "someday", "run", "s.divine"]).expect(1);
+    assert!(outcome.stderr.contains("--pretend-today must be a date written YYYY-MM-DD"), "{}", outcome.stderr);
+}
+
+#[test]
+fn the_rejection_counts_down_to_the_end_of_the_sabbath() {
+    let sandbox = Sandbox::new("sabbath-countdown");
+    sandbox.write("s.divine", "bless genesis() {\n  print(1)\n}\n");
+    let outcome = on_the_sabbath(&sandbox, &["run", "s.divine"]).expect(3);
+    assert!(outcome.stderr.contains("The Sabbath ends in 3 hours and 12 minutes"), "{}", outcome.stderr);
+    assert!(outcome.stderr.contains("Wait until it ends, or pass --dev --override-sabbath."), "{}", outcome.stderr);
+
+    let outcome = on_the_sabbath_at(&sandbox, "23:50", &["run", "s.divine"]).expect(3);
+    assert!(outcome.stderr.contains("The Sabbath ends in 10 minutes"), "{}", outcome.stderr);
+    assert!(outcome.stderr.contains("It is nearly over; wait a little longer."), "{}", outcome.stderr);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::Sandbox;
+
+const UNKEMPT: &str = "\
+🙏 BEGIN PRAYER 🙏
+   Lord,  keep   this  
+🙏 END PRAYER 🙏
+bless speak() {
+print(1)   
+    judge (true) {
+ print(2)
+}
+}
+
+
+
+bless genesis() {
+  speak()
+}
+";
+
+const SANCTIFIED: &str = "\
+🙏 BEGIN PRAYER 🙏
+   Lord,  keep   this  
+🙏 END PRAYER 🙏
+bless speak() {
+  print(1);
+  judge (true) {
+    print(2);
+  }
+}
+
+bless genesis() {
+  speak();
+}
+";
+
+#[test]
+fn sanctify_indents_and_spaces_but_leaves_prayers_alone() {
+    let sandbox = Sandbox::new("sanctify-tidy");
+    sandbox.write("s.divine", UNKEMPT);
+    let outcome = sandbox.run(&["sanctify", "--stdout", "s.divine"]).success();
+    assert_eq!(outcome.stdout, SANCTIFIED);
+    assert_eq!(sandbox.read("s.divine"), UNKEMPT);
+
+    sandbox.run(&["sanctify", "s.divine"]).success();
+    assert_eq!(sandbox.read("s.divine"), SANCTIFIED);
+}
+
+#[test]
+fn check_fails_without_writing_until_the_script_is_sanctified() {
+    let sandbox = Sandbox::new("sanctify-check");
+    sandbox.write("s.divine", UNKEMPT);
+    let outcome = sandbox.run(&["sanctify", "--check", "s.divine"]).expect(1);
+    assert!(outcome.stderr.contains("s.divine is not sanctified"), "{}", outcome.stderr);
+    assert_eq!(sandbox.read("s.divine"), UNKEMPT);
+
+    sandbox.write("s.divine", SANCTIFIED);
+    sandbox.run(&["sanctify", "--check", "s.divine"]).success();
+}
+
+#[test]
+fn every_template_stays_sanctified_once_sanctified() {
+    let sandbox = Sandbox::new("sanctify-templates");
+    for template in ["default", "miracle", "prophet"] {
+        sandbox.run(&["new", template, "--template", template]).success();
+        let scripts = ["genesis.divine", "holy_trinity/father.divine", "holy_trinity/son.divine", "holy_trinity/holy_ghost.divine"];
+        for script in scripts.map(|script| format!("{}/{}", template, script)) {
+            if !sandbox.path().join(&script).exists() {
+                continue;
+            }
+            sandbox.run(&["sanctify", &script]).success();
+            let once = sandbox.read(&script);
+            sandbox.run(&["sanctify", "--check", &script]).success();
+            assert_eq!(sandbox.run(&["sanctify", "--stdout", &script]).success().stdout, once, "{}", script);
+        }
+    }
+}
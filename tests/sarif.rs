@@ -0,0 +1,45 @@
+mod common;
+
+use common::Sandbox;
+use serde_json::Value;
+
+#[test]
+fn a_confession_is_logged_as_sarif() {
+    let sandbox = Sandbox::new("sarif");
+    sandbox.write("mortal.divine", "var x = 1\nbless genesis() {\n  kill(x)\n  var y = 2\n}\n");
+    let outcome = sandbox.run(&["confess", "mortal.divine", "--format", "sarif"]).expect(2);
+    let log: Value = serde_json::from_str(&outcome.stdout).unwrap();
+
+    let mut expected: Value = serde_json::from_str(include_str!("fixtures/confession.sarif")).unwrap();
+    expected["runs"][0]["tool"]["driver"]["version"] = Value::from(env!("CARGO_PKG_VERSION"));
+    assert_eq!(log, expected);
+}
+
+#[test]
+fn every_result_points_at_its_rule_and_every_rule_is_listed_once() {
+    let sandbox = Sandbox::new("sarif-rules");
+    sandbox.write("a.divine", "var x = 1\nfunction f() {\n}\n");
+    sandbox.write("b.divine", "var y = 2\nlet devil = 3\n");
+    let outcome = sandbox.run(&["confess", ".", "--format", "sarif"]).expect(2);
+    let log: Value = serde_json::from_str(&outcome.stdout).unwrap();
+    assert_eq!(log["runs"].as_array().unwrap().len(), 1);
+
+    let run = &log["runs"][0];
+    let rules: Vec<&str> = run["tool"]["driver"]["rules"].as_array().unwrap().iter().map(|rule| rule["id"].as_str().unwrap()).collect();
+    let mut unique = rules.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), rules.len(), "{:?}", rules);
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    for result in results {
+        let index = result["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(result["ruleId"], rules[index]);
+        let expected_level = if rules[index].starts_with("DPL01") { "error" } else { "warning" };
+        assert_eq!(result["level"], expected_level, "{}", result);
+        let location = &result["locations"][0]["physicalLocation"];
+        assert!(["a.divine", "b.divine"].contains(&location["artifactLocation"]["uri"].as_str().unwrap()), "{}", result);
+        assert!(location["region"]["startLine"].as_u64().unwrap() >= 1);
+    }
+}
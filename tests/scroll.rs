@@ -0,0 +1,42 @@
+mod common;
+
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+
+use common::Sandbox;
+
+const INSCRIBED: &str = "let log = scroll.open(\"log.txt\");\nlog.inscribe(\"In the beginning\");\n";
+
+#[test]
+fn a_scroll_is_sealed_when_a_sin_unwinds_past_it() {
+    let sandbox = Sandbox::new("scroll-sin");
+    sandbox.write("s.divine", format!("{}let fallen = nothing();\n", INSCRIBED));
+    let outcome = sandbox.run(&["-v", "run", "s.divine"]).expect(6);
+    assert!(outcome.stdout.contains("The scroll log.txt sealed itself"), "{}", outcome.stdout);
+    assert_eq!(sandbox.read("log.txt"), "In the beginning\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn a_scroll_is_sealed_when_ctrl_c_stops_the_run() {
+    let sandbox = Sandbox::new("scroll-interrupt");
+    // Long enough to still be running when interrupted, but never forever
+    sandbox.write("s.divine", format!(
+        "{}print(\"ready\");\namen();\nlet i = 0;\nwhile (i < 9999) {{\n  i = i + 1;\n  let j = 0;\n  while (j < 9999) {{\n    j = j + 1;\n  }}\n}}\n",
+        INSCRIBED
+    ));
+    let mut child = sandbox.divine().args(["run", "s.divine"])
+        .stdout(Stdio::piped()).stderr(Stdio::piped())
+        .spawn().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    while line.trim() != "ready" {
+        line.clear();
+        assert_ne!(stdout.read_line(&mut line).unwrap(), 0, "the script never became ready");
+    }
+    assert!(std::process::Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap().success());
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130));
+    assert_eq!(sandbox.read("log.txt"), "In the beginning\n");
+}
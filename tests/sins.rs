@@ -0,0 +1,120 @@
+//! What the commandments read: the code of a script, and neither its comments
+//! nor the strings it speaks.
+
+mod common;
+
+use common::Sandbox;
+
+#[test]
+fn killing_a_process_in_code_is_a_sin() {
+    let sandbox = Sandbox::new("sins-kill");
+    sandbox.write("s.divine", "childProcess.kill()\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).expect(2);
+    assert!(outcome.stderr.contains("Thou shalt not kill child processes at line 1"), "{}", outcome.stderr);
+}
+
+#[test]
+fn killing_a_process_in_a_trailing_comment_is_no_sin() {
+    let sandbox = Sandbox::new("sins-trailing-comment");
+    sandbox.write("s.divine", "let light = createLight(); // kill the child Process, as in Genesis 1:3\nprint(light)\n");
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert!(outcome.stdout.contains("\ntrue\n"), "{}", outcome.stdout);
+    let outcome = sandbox.run(&["confess", "s.divine"]).success();
+    assert!(outcome.stdout.contains("sins: total=0 venial=0 mortal=0"), "{}", outcome.stdout);
+}
+
+#[test]
+fn nothing_in_a_block_comment_is_judged() {
+    let sandbox = Sandbox::new("sins-block-comment");
+    sandbox.write("s.divine", "\
+/* Never kill a child Process,
+   and never write let satan = 1 */
+print(\"after\") /* kill */
+");
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert!(outcome.stdout.contains("\nafter\n"), "{}", outcome.stdout);
+    let outcome = sandbox.run(&["confess", "s.divine"]).success();
+    assert!(outcome.stdout.contains("sins: total=0 venial=0 mortal=0"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_comment_marker_inside_a_string_starts_no_comment() {
+    let sandbox = Sandbox::new("sins-slashes-in-string");
+    sandbox.write("s.divine", "print(\"https://example.com\"); childProcess.kill()\n");
+    sandbox.run(&["run", "s.divine"]).expect(2);
+}
+
+#[test]
+fn forbidden_words_inside_strings_are_no_sin() {
+    let sandbox = Sandbox::new("sins-strings");
+    sandbox.write("s.divine", "\
+revelation(\"never kill a child Process manually\")
+let warning = \"let satan = 1\"
+print(\"function speak() { }\")
+print(warning)
+");
+    let outcome = sandbox.run(&["run", "s.divine"]).success();
+    assert!(outcome.stdout.contains("\nlet satan = 1\n"), "{}", outcome.stdout);
+    let outcome = sandbox.run(&["confess", "s.divine"]).success();
+    assert!(outcome.stdout.contains("sins: total=0 venial=0 mortal=0"), "{}", outcome.stdout);
+}
+
+#[test]
+fn prophecy_reads_no_omen_in_a_string() {
+    let sandbox = Sandbox::new("sins-prophecy-strings");
+    sandbox.write("s.divine", "print(\"while the data waits\")\n");
+    let outcome = sandbox.run(&["prophesy", "s.divine"]).success();
+    assert!(!outcome.stdout.contains("Infinite loop risk"), "{}", outcome.stdout);
+    assert!(!outcome.stdout.contains("validation to all data inputs"), "{}", outcome.stdout);
+
+    sandbox.write("s.divine", "while (true) {\n  print(data)\n}\n");
+    let outcome = sandbox.run(&["prophesy", "s.divine"]).success();
+    assert!(outcome.stdout.contains("Infinite loop risk"), "{}", outcome.stdout);
+}
+
+#[test]
+fn only_a_literal_never_assigned_again_is_foretold_to_want_a_covenant() {
+    let sandbox = Sandbox::new("sins-prophecy-covenant");
+    sandbox.write("s.divine", "let count = 0\ncount = count + 1\nlet name = listen(\"?\")\nprint(count, name)\n");
+    let outcome = sandbox.run(&["prophesy", "s.divine"]).success();
+    assert!(!outcome.stdout.contains("declared as 'covenant'"), "{}", outcome.stdout);
+
+    sandbox.write("s.divine", "covenant shepherd = \"the Lord\"\nlet count = 0\ncount = count + 1\nlet psalm = 23\nprint(count, psalm)\n");
+    let outcome = sandbox.run(&["prophesy", "s.divine", "--output", "vision.json"]).success();
+    assert!(outcome.stdout.contains("📜 Future maintainers will appreciate constants such as 'psalm'"), "{}", outcome.stdout);
+
+    // The glyph is for the terminal; the report keeps the text plain
+    let vision: serde_json::Value = serde_json::from_str(&sandbox.read("vision.json")).unwrap();
+    let covenanted = vision.as_array().unwrap().iter().find(|prophecy| prophecy["message"].as_str().unwrap().contains("'covenant'")).unwrap();
+    assert_eq!(covenanted["message"], "Future maintainers will appreciate constants such as 'psalm' declared as 'covenant', since it never changes.");
+    assert_eq!(covenanted["line"], 4);
+    assert!(vision.as_array().unwrap().iter().all(|prophecy| prophecy["message"].as_str().unwrap().is_ascii()), "{}", vision);
+}
+
+#[test]
+fn a_constant_a_sin_points_at_is_shown_with_its_value() {
+    let sandbox = Sandbox::new("sins-folded");
+    sandbox.write("s.divine", "let apiKey = \"sk-\" + \"live-42\"\njudge (0.1 + 0.2 == 0.3) {\n  print(1)\n}\n");
+    let outcome = sandbox.run(&["confess", "s.divine"]).expect(1);
+    assert!(outcome.stdout.contains("\
+Venial Sin[DPL015]: Secret 'apiKey' is written into the scripture
+ --> s.divine:1:14
+  |
+1 | let apiKey = \"sk-\" + \"live-42\"
+  |              ^^^^^^^^^^^^^^^^^ = \"sk-live-42\"
+"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("\
+2 | judge (0.1 + 0.2 == 0.3) {
+  |        ^^^^^^^^^^^^^^^^ = false
+"), "{}", outcome.stdout);
+}
+
+#[test]
+fn what_is_not_constant_is_shown_without_a_value_and_never_called() {
+    let sandbox = Sandbox::new("sins-not-folded");
+    sandbox.write("s.divine", "let scale = 2\njudge (scale * 0.5 == 1) {\n  print(1)\n}\njudge (revelation(\"spoken\") != 0.5) {\n  print(2)\n}\n");
+    let outcome = sandbox.run(&["confess", "s.divine"]).expect(1);
+    assert!(outcome.stdout.contains("2 | judge (scale * 0.5 == 1) {\n  |        ^^^^^^^^^^^^^^^^\n"), "{}", outcome.stdout);
+    assert!(outcome.stdout.contains("5 | judge (revelation(\"spoken\") != 0.5) {\n  |        ^^^^^^^^^^^^^^^^^^^^^^^^^^^\n"), "{}", outcome.stdout);
+    assert!(!outcome.stdout.contains("📢"), "{}", outcome.stdout);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::Sandbox;
+use serde_json::{json, Value};
+
+const OFFERED: &str = "\
+// a comment
+🙏 BEGIN PRAYER 🙏
+Lord
+🙏 END PRAYER 🙏
+bless speak() {
+  revelation(\"hi\")
+}
+
+miracle wonder() {
+  covenant(\"p\")
+  fulfill(\"p\")
+}
+bless genesis() {
+  speak()
+}
+";
+
+fn tithe(sandbox: &Sandbox, args: &[&str]) -> Value {
+    let outcome = sandbox.run(&[&["tithe", "--format", "json"][..], args].concat()).success();
+    serde_json::from_str(&outcome.stdout).unwrap()
+}
+
+#[test]
+fn a_script_gives_its_lines_functions_prayers_and_devotion() {
+    let sandbox = Sandbox::new("tithe-script");
+    sandbox.write("s.divine", OFFERED);
+    let report = tithe(&sandbox, &["s.divine"]);
+    assert_eq!(report["total"], json!({
+        "lines": 15, "code_lines": 10, "devotional_lines": 4,
+        "blessings": 1, "miracles": 1, "genesis": 1, "prayer_blocks": 1, "covenants": 1, "revelations": 1,
+        "devotion_ratio": 0.4,
+    }));
+
+    let outcome = sandbox.run(&["tithe", "s.divine"]).success();
+    assert!(outcome.stdout.contains("Devotion ratio: 0.40 lines of comments and prayers for every line of code"), "{}", outcome.stdout);
+}
+
+#[test]
+fn a_directory_gives_every_script_but_those_ignored() {
+    let sandbox = Sandbox::new("tithe-directory");
+    sandbox.write("s.divine", OFFERED);
+    sandbox.write("fixtures/f.divine", "bless genesis() {\n}\n");
+    sandbox.write("build/b.dpl", "bless genesis() {\n}\n");
+    sandbox.write("commandments.config", r#"{"tithe_ignore": ["build"]}"#);
+
+    let paths = |report: &Value| report["files"].as_array().unwrap().iter().map(|file| file["path"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+    let report = tithe(&sandbox, &["."]);
+    assert_eq!(paths(&report), ["fixtures/f.divine", "s.divine"]);
+    assert_eq!(report["total"]["genesis"], 2);
+
+    let report = tithe(&sandbox, &[".", "--ignore", "fixtures"]);
+    assert_eq!(paths(&report), ["s.divine"]);
+}
+
+#[test]
+fn json_is_shorthand_for_the_json_format() {
+    let sandbox = Sandbox::new("tithe-json");
+    sandbox.write("s.divine", OFFERED);
+    let outcome = sandbox.run(&["tithe", "s.divine", "--json"]).success();
+    assert_eq!(serde_json::from_str::<Value>(&outcome.stdout).unwrap(), tithe(&sandbox, &["s.divine"]));
+
+    let outcome = sandbox.run(&["tithe", "s.divine", "--json", "--format", "text"]).expect(2);
+    assert!(outcome.stderr.contains("cannot be used with"), "{}", outcome.stderr);
+}
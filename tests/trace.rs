@@ -0,0 +1,67 @@
+mod common;
+
+use std::fs;
+
+use common::Sandbox;
+use serde_json::Value;
+
+const BROKEN: &str = "bless genesis() {\n  let x = 1 + 2\n  covenant(\"p\")\n}\n";
+
+/// The lines of the trace at `file`, without their timestamps.
+fn traced(sandbox: &Sandbox, file: &str) -> Vec<String> {
+    sandbox.read(file).lines().map(|line| line.split_once(' ').unwrap().1.to_string()).collect()
+}
+
+#[test]
+fn a_trace_ends_with_the_error_the_run_fell_to() {
+    let sandbox = Sandbox::new("trace-error");
+    sandbox.write("s.divine", BROKEN);
+    sandbox.run(&["run", "s.divine", "--trace", "t.log"]).expect(6);
+    assert_eq!(traced(&sandbox, "t.log"), [
+        "line 2 plain let x = 1 + 2 => 3",
+        "line 3 plain covenant(\"p\")",
+        "error CovenantBreachError: 1 covenant remains unfulfilled (line 3)",
+    ]);
+
+    // The trace is appended to, one JSON object a line
+    sandbox.run(&["run", "s.divine", "--trace", "t.log", "--trace-format", "json"]).expect(6);
+    let last: Value = serde_json::from_str(sandbox.read("t.log").lines().last().unwrap()).unwrap();
+    assert_eq!(last["event"], "error");
+    assert_eq!(last["message"], "CovenantBreachError: 1 covenant remains unfulfilled (line 3)");
+    assert_eq!(sandbox.read("t.log").lines().count(), 6);
+}
+
+#[test]
+fn a_failed_judgment_is_traced_before_its_error() {
+    let sandbox = Sandbox::new("trace-judgment");
+    sandbox.write("s.divine", format!("bless genesis() {{\n{}}}\n", "  var a = 1\n".repeat(6)));
+    sandbox.run(&["run", "s.divine", "--trace", "t.log"]).expect(6);
+    let lines = traced(&sandbox, "t.log");
+    let [.., judgment, error] = lines.as_slice() else { panic!("{:?}", lines) };
+    assert!(judgment.starts_with("judgment sent to debugging purgatory: 6 venial sins"), "{}", judgment);
+    assert!(error.starts_with("error Your code requires purification before it can be saved"), "{}", error);
+}
+
+#[test]
+fn a_trace_without_a_path_is_kept_as_an_artifact() {
+    let sandbox = Sandbox::new("trace-artifact");
+    sandbox.write("commandments.config", "{}");
+    sandbox.write("ok.divine", "bless genesis() {\n  print(1)\n}\n");
+    sandbox.write("s.divine", BROKEN);
+
+    let outcome = sandbox.run(&["run", "ok.divine", "--trace"]).success();
+    assert!(outcome.stdout.contains("📜 Trace kept in "), "{}", outcome.stdout);
+    sandbox.run(&["run", "s.divine", "--trace", "--trace-format", "json"]).expect(6);
+
+    let kept = |verdict: &str| -> Vec<String> {
+        fs::read_dir(sandbox.path().join(".divinepl").join(verdict)).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect()
+    };
+    let heaven = kept("heaven");
+    assert!(matches!(heaven.as_slice(), [name] if name.contains("-ok-") && name.ends_with(".trace.txt")), "{:?}", heaven);
+    let purgatory = kept("purgatory");
+    assert!(matches!(purgatory.as_slice(), [name] if name.contains("-s-") && name.ends_with(".trace.jsonl")), "{:?}", purgatory);
+    let trace = sandbox.read(&format!(".divinepl/purgatory/{}", purgatory[0]));
+    assert!(trace.lines().last().unwrap().contains("\"event\":\"error\""), "{}", trace);
+}
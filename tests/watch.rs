@@ -0,0 +1,34 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use common::{Outcome, Sandbox};
+
+/// Watches with `args` until the first run is done, then presses Ctrl-C.
+fn interrupted_watch(sandbox: &Sandbox, args: &[&str]) -> Outcome {
+    let child = sandbox.divine().args(args).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    thread::sleep(Duration::from_millis(1500));
+    Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    Outcome::of(child.wait_with_output().unwrap())
+}
+
+#[test]
+fn ctrl_c_ends_a_run_watch_in_peace() {
+    let sandbox = Sandbox::new("watch-run");
+    sandbox.write("s.divine", r#"print("watched");"#);
+    let outcome = interrupted_watch(&sandbox, &["run", "--watch", "s.divine"]).success();
+    assert!(outcome.stdout.contains("watched"), "{}", outcome.stdout);
+    assert!(outcome.stdout.ends_with("🕊️ The watch is ended. Go in peace.\n"), "{}", outcome.stdout);
+}
+
+#[test]
+fn ctrl_c_ends_a_confession_watch_in_peace() {
+    let sandbox = Sandbox::new("watch-confess");
+    sandbox.write("s.divine", r#"print("watched");"#);
+    let outcome = interrupted_watch(&sandbox, &["confess", "--watch", "s.divine"]).success();
+    assert!(outcome.stdout.ends_with("🕊️ The watch is ended. Go in peace.\n"), "{}", outcome.stdout);
+}